@@ -0,0 +1,33 @@
+//! `glob!()` parses its input (the pattern as a string literal along with the
+//! options) into the same pattern, options and tokens as compiled at runtime,
+//! or reports the same error.
+
+#![no_main]
+
+use goglob_common::GlobPattern;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((options, pattern, _)) = goglob_fuzz::split(data) else {
+        return;
+    };
+    let Some(input) = goglob_fuzz::macro_input(data[0], pattern) else {
+        return;
+    };
+    match (
+        goglob_fuzz::macro_tokens(input),
+        GlobPattern::new_with(pattern, &options),
+    ) {
+        (Ok((tokens, macro_pattern, macro_options)), Ok(compiled)) => {
+            assert_eq!(macro_pattern, pattern);
+            assert_eq!(macro_options, options);
+            assert_eq!(tokens, compiled.tokens());
+        }
+        (Err(macro_error), Err(runtime_error)) => {
+            assert_eq!(macro_error, runtime_error.to_string());
+        }
+        (macro_result, runtime_result) => {
+            panic!("glob!() gives {macro_result:?}, runtime gives {runtime_result:?}")
+        }
+    }
+});
@@ -0,0 +1,28 @@
+//! The scanner never panics, and reports errors within the pattern.
+
+#![no_main]
+
+use goglob_common::{error::ErrorType, GlobPattern};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((options, pattern, _)) = goglob_fuzz::split(data) else {
+        return;
+    };
+    match GlobPattern::new_with(pattern, &options) {
+        Ok(compiled) => {
+            assert!(!compiled.tokens().is_empty());
+            let _ = compiled.dump().with_source(pattern).to_string();
+        }
+        Err(error) => {
+            if !matches!(
+                error.error_type(),
+                ErrorType::EmptyPattern | ErrorType::PatternTooLong
+            ) {
+                assert!(error.position() < pattern.len(), "{error}");
+                assert!(pattern.is_char_boundary(error.position()), "{error}");
+            }
+            let _ = error.render(pattern);
+        }
+    }
+});
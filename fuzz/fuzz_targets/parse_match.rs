@@ -0,0 +1,27 @@
+//! Every way of matching a compiled pattern agrees on whether a name matches.
+
+#![no_main]
+
+use goglob_common::GlobPattern;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((options, pattern, name)) = goglob_fuzz::split(data) else {
+        return;
+    };
+    let Ok(compiled) = GlobPattern::new_with(pattern, &options) else {
+        return;
+    };
+
+    let matched = compiled.matches(name);
+    assert_eq!(compiled.captures(name).is_some(), matched);
+    assert_eq!(compiled.match_detailed(name).is_match(), matched);
+    assert_eq!(compiled.matches_with_fuel(name, usize::MAX), Ok(matched));
+    assert_eq!(compiled.simplify().matches(name), matched);
+
+    let mut stream = compiled.stream_matcher();
+    let (first, second) = name.split_at(name.floor_char_boundary(name.len() / 2));
+    stream.feed(first);
+    stream.feed(second);
+    assert_eq!(stream.finish(), matched);
+});
@@ -0,0 +1,84 @@
+//! Helpers shared by the fuzz targets, which all take an options byte
+//! followed by a pattern and, for matching targets, a `\0` and a name.
+
+// The parsing of `glob!()`'s input, which goglob-proc-macro can't export.
+#[allow(dead_code)]
+#[path = "../../goglob-proc-macro/src/parse.rs"]
+mod parse;
+#[allow(dead_code)]
+#[path = "../../goglob-proc-macro/src/scan.rs"]
+mod scan;
+
+use goglob_common::{
+    options::{CaseFold, MatchOptions},
+    syntax::Syntax,
+    GlobToken,
+};
+use proc_macro2::{Literal, TokenStream};
+
+/// Options encoded in `byte`, `0` being the defaults.
+pub fn options(byte: u8) -> MatchOptions {
+    let syntax = match byte & 0b11 {
+        0 => Syntax::GoPathMatch,
+        1 => Syntax::GoFilepathMatch,
+        2 => Syntax::Glob7,
+        _ => Syntax::Gitignore,
+    };
+    let case_fold = match (byte >> 2) & 0b11 {
+        0 => CaseFold::Sensitive,
+        1 => CaseFold::Ascii,
+        _ => CaseFold::Unicode,
+    };
+    MatchOptions::new()
+        .syntax(syntax)
+        .case_fold(case_fold)
+        .escapes(byte & 0b1_0000 == 0)
+        .dotfiles(byte & 0b10_0000 == 0)
+}
+
+/// Split the fuzzer's input into options, a pattern and a name (empty if
+/// there is no `\0` after the pattern), if it's UTF-8.
+pub fn split(data: &[u8]) -> Option<(MatchOptions, &str, &str)> {
+    let (&byte, rest) = data.split_first()?;
+    let rest = std::str::from_utf8(rest).ok()?;
+    let (pattern, name) = rest.split_once('\0').unwrap_or((rest, ""));
+    Some((options(byte), pattern, name))
+}
+
+/// Input of a `glob!()` invocation of `pattern` with the options encoded in
+/// `byte`, as a raw string literal if its seventh bit is set (and `pattern`
+/// can be written as one), or `None` if it can't be lexed.
+pub fn macro_input(byte: u8, pattern: &str) -> Option<TokenStream> {
+    let syntax =
+        ["go_path_match", "go_filepath_match", "glob7", "gitignore"][usize::from(byte & 0b11)];
+    let case_fold = ["sensitive", "ascii", "unicode", "unicode"][usize::from((byte >> 2) & 0b11)];
+    let escapes = byte & 0b1_0000 == 0;
+    let dotfiles = byte & 0b10_0000 == 0;
+    let literal = if byte & 0b100_0000 != 0 && !pattern.contains("\"#") {
+        format!("r#\"{pattern}\"#")
+    } else {
+        Literal::string(pattern).to_string()
+    };
+    format!(
+        "{literal}, syntax = {syntax}, case_fold = {case_fold}, \
+         escapes = {escapes}, dotfiles = {dotfiles}"
+    )
+    .parse()
+    .ok()
+}
+
+/// Tokens `glob!()` compiles its `input` into, along with its pattern and
+/// options, or its compile error.
+pub fn macro_tokens(input: TokenStream) -> Result<(Vec<GlobToken>, String, MatchOptions), String> {
+    let mut tokens = Vec::new();
+    match scan::glob_tokens_from(input, &mut tokens) {
+        Ok((pattern, options)) => {
+            let options = options
+                .iter()
+                .fold(MatchOptions::new(), |options, option| option.apply(options));
+            Ok((tokens, pattern, options))
+        }
+        Err(scan::Error::GlobTokenError(_, e)) => Err(e.to_string()),
+        Err(scan::Error::ParseError(parse::ParseError(_, msg))) => Err(msg.to_string()),
+    }
+}
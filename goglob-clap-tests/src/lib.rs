@@ -0,0 +1,56 @@
+//! # Do not use this crate!
+//!
+//! See the `goglob` crate instead.
+//!
+//! (This crate facilitates testing `goglob`'s clap integration with
+//! `cargo test`. It offers no functionality to the end user)
+
+pub fn stub_sub(a: usize, b: usize) -> usize {
+    a - b
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{error::ErrorKind, value_parser, Arg, ArgAction, Command};
+    use goglob::GlobPattern;
+
+    fn command() -> Command {
+        Command::new("tool").arg(
+            Arg::new("include")
+                .long("include")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(GlobPattern)),
+        )
+    }
+
+    #[test]
+    fn clap_value_parser() {
+        let matches = command()
+            .try_get_matches_from(["tool", "--include", "*.rs", "--include", "src/*"])
+            .unwrap();
+        let patterns: Vec<&GlobPattern> = matches.get_many("include").unwrap().collect();
+        assert_eq!(patterns.len(), 2);
+        assert!(patterns[0].matches("lib.rs"));
+        assert!(patterns[1].matches("src/lib.rs"));
+    }
+
+    #[test]
+    fn clap_value_parser_error() {
+        let error = command()
+            .try_get_matches_from(["tool", "--include", "ab[cd"])
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+        assert!(error.to_string().contains(
+            "invalid pattern for '--include <include>': \
+             character class opened with '[' at 2 isn't closed\n  ab[cd\n    ^"
+        ));
+    }
+
+    #[test]
+    fn clap_from_str() {
+        let pattern: GlobPattern = "a*".parse().unwrap();
+        assert!(pattern.matches("abc"));
+        assert!("a[".parse::<GlobPattern>().is_err());
+    }
+}
@@ -0,0 +1,844 @@
+use crate::error::CliError;
+use std::ffi::OsString;
+use std::str::FromStr;
+
+pub(crate) const USAGE: &str = "\
+Usage: goglob [OPTIONS] <PATTERN>...
+       goglob <COMMAND> [ARGS]...
+
+Print every name read from standard input matching any of the given patterns.
+
+Commands:
+  explain <PATTERN>   print how the pattern is compiled and what it can match
+      --dot               print the states of the pattern as a Graphviz graph
+  diff <A> <B>        print a name matched by only one of the patterns, if any
+  find <PATTERN>      print every file matching the pattern
+      --base <DIR>        directory the pattern is relative to
+      --no-hidden         don't match names starting with '.' using wildcards
+      --max-depth <N>     don't descend more than N directories
+      --no-follow         don't descend into symbolic links
+      --exclude <PATTERN> skip the paths (relative to the base) matching
+                          PATTERN, along with everything inside them
+      --min-size <BYTES>  only print files of at least BYTES bytes
+      --max-size <BYTES>  only print files of at most BYTES bytes
+      --newer-than <DAYS> only print files modified in the last DAYS days
+      --older-than <DAYS> only print files not modified in the last DAYS days
+      -0, --null          separate paths by NUL instead of newline
+  to-regex <PATTERN>...
+                      print the anchored regex equivalent to each pattern
+      --file <FILE>       also convert every line of FILE ('-' for stdin)
+  from-like <LIKE>... print the pattern equivalent to each SQL LIKE pattern
+      --file <FILE>       also convert every line of FILE ('-' for stdin)
+      --escape <CHAR>     escape character of the LIKE patterns (default '\\')
+  rename <FROM> <TO>  rename every file matching FROM to TO, filling the '*',
+                      '?' and '{N}' of TO with the text matched by FROM
+      --base <DIR>        directory the patterns are relative to
+      -n, --dry-run       only print what would be renamed
+  bench <PATTERN>... --names <FILE>
+                      match every line of FILE against the patterns, printing
+                      match counts, throughput and the slowest names
+      --patterns <FILE>   also benchmark every line of FILE ('-' for stdin)
+      --iterations <N>    match every name N times, keeping the fastest
+                          (default 10)
+      --worst <N>         print the N slowest names (default 5)
+  check <PATTERN>...  exit with status 1 if any pattern is invalid, pointing
+                      at the error in each one on stderr
+  completions <SHELL> print the completion script for SHELL (bash, zsh or
+                      fish)
+
+Options:
+  -v, --invert-match  print the names which don't match instead
+  -0, --null          names are separated by NUL instead of newline
+      --color         highlight the parts of each name matched by literals
+                      and by wildcards (or character classes)
+  -h, --help          print this help and exit
+  -V, --version       print the version and exit
+";
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum Command {
+    Filter(FilterArgs),
+    Explain(ExplainArgs),
+    Diff(String, String),
+    Find(FindArgs),
+    ToRegex(ConvertArgs),
+    FromLike(ConvertArgs),
+    Rename(RenameArgs),
+    Bench(BenchArgs),
+    Check(Vec<String>),
+    Completions(Shell),
+    Help,
+    Version,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub(crate) struct FilterArgs {
+    pub(crate) patterns: Vec<String>,
+    pub(crate) invert: bool,
+    pub(crate) null: bool,
+    pub(crate) color: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct ExplainArgs {
+    pub(crate) pattern: String,
+    pub(crate) dot: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct FindArgs {
+    pub(crate) pattern: String,
+    pub(crate) base: Option<String>,
+    pub(crate) hidden: bool,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) follow_symlinks: bool,
+    pub(crate) excludes: Vec<String>,
+    pub(crate) min_size: Option<u64>,
+    pub(crate) max_size: Option<u64>,
+    pub(crate) newer_than: Option<u64>,
+    pub(crate) older_than: Option<u64>,
+    pub(crate) null: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct ConvertArgs {
+    pub(crate) inputs: Vec<String>,
+    pub(crate) file: Option<String>,
+    pub(crate) escape: Option<char>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct RenameArgs {
+    pub(crate) source: String,
+    pub(crate) destination: String,
+    pub(crate) base: Option<String>,
+    pub(crate) dry_run: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct BenchArgs {
+    pub(crate) patterns: Vec<String>,
+    pub(crate) patterns_file: Option<String>,
+    pub(crate) names: String,
+    pub(crate) iterations: usize,
+    pub(crate) worst: usize,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+fn into_utf8(arg: OsString) -> Result<String, CliError> {
+    arg.into_string()
+        .map_err(|arg| CliError::Usage(format!("argument {arg:?} isn't valid UTF-8")))
+}
+
+pub(crate) fn parse_args<I: IntoIterator<Item = OsString>>(args: I) -> Result<Command, CliError> {
+    let mut args = args.into_iter().map(into_utf8).peekable();
+    match args.peek() {
+        Some(Ok(command)) if command == "explain" => {
+            args.next();
+            parse_explain_args(args)
+        }
+        Some(Ok(command)) if command == "diff" => {
+            args.next();
+            parse_diff_args(args)
+        }
+        Some(Ok(command)) if command == "find" => {
+            args.next();
+            parse_find_args(args)
+        }
+        Some(Ok(command)) if command == "to-regex" => {
+            args.next();
+            parse_convert_args(args, false)
+        }
+        Some(Ok(command)) if command == "from-like" => {
+            args.next();
+            parse_convert_args(args, true)
+        }
+        Some(Ok(command)) if command == "rename" => {
+            args.next();
+            parse_rename_args(args)
+        }
+        Some(Ok(command)) if command == "bench" => {
+            args.next();
+            parse_bench_args(args)
+        }
+        Some(Ok(command)) if command == "check" => {
+            args.next();
+            parse_check_args(args)
+        }
+        Some(Ok(command)) if command == "completions" => {
+            args.next();
+            parse_completions_args(args)
+        }
+        _ => parse_filter_args(args),
+    }
+}
+
+/// Collect the positional arguments of a subcommand, handling `--help` and `--`.
+fn parse_positional<I: Iterator<Item = Result<String, CliError>>>(
+    args: I,
+) -> Result<Option<Vec<String>>, CliError> {
+    let mut positional = Vec::new();
+    let mut only_positional = false;
+    for arg in args {
+        let arg = arg?;
+        match arg.as_str() {
+            "--" if !only_positional => only_positional = true,
+            "-h" | "--help" if !only_positional => return Ok(None),
+            option if !only_positional && option.starts_with('-') && option != "-" => {
+                return Err(CliError::Usage(format!("unknown option '{option}'")))
+            }
+            _ => positional.push(arg),
+        }
+    }
+    Ok(Some(positional))
+}
+
+fn parse_explain_args<I: Iterator<Item = Result<String, CliError>>>(
+    args: I,
+) -> Result<Command, CliError> {
+    let mut pattern = None;
+    let mut dot = false;
+    let mut only_positional = false;
+    for arg in args {
+        let arg = arg?;
+        let option = if only_positional { "" } else { arg.as_str() };
+        match option {
+            "--" => only_positional = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "--dot" => dot = true,
+            option if option.starts_with('-') && option != "-" => {
+                return Err(CliError::Usage(format!("unknown option '{option}'")))
+            }
+            _ if pattern.is_none() => pattern = Some(arg),
+            _ => {
+                return Err(CliError::Usage(
+                    "explain requires exactly one pattern".into(),
+                ))
+            }
+        }
+    }
+
+    let pattern =
+        pattern.ok_or_else(|| CliError::Usage("explain requires exactly one pattern".into()))?;
+    Ok(Command::Explain(ExplainArgs { pattern, dot }))
+}
+
+fn parse_diff_args<I: Iterator<Item = Result<String, CliError>>>(
+    args: I,
+) -> Result<Command, CliError> {
+    let Some(positional) = parse_positional(args)? else {
+        return Ok(Command::Help);
+    };
+    match <[String; 2]>::try_from(positional) {
+        Ok([left, right]) => Ok(Command::Diff(left, right)),
+        Err(_) => Err(CliError::Usage("diff requires exactly two patterns".into())),
+    }
+}
+
+fn parse_find_args<I: Iterator<Item = Result<String, CliError>>>(
+    mut args: I,
+) -> Result<Command, CliError> {
+    let mut pattern = None;
+    let mut base = None;
+    let mut hidden = true;
+    let mut max_depth = None;
+    let mut follow_symlinks = true;
+    let mut excludes = Vec::new();
+    let mut min_size = None;
+    let mut max_size = None;
+    let mut newer_than = None;
+    let mut older_than = None;
+    let mut null = false;
+    let mut only_positional = false;
+    while let Some(arg) = args.next() {
+        let arg = arg?;
+        let option = if only_positional { "" } else { arg.as_str() };
+        match option {
+            "--" => only_positional = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "--exclude" => {
+                let value = args.next().ok_or_else(|| missing_value("--exclude"))??;
+                excludes.push(value);
+            }
+            "--base" => {
+                let value = args.next().ok_or_else(|| missing_value("--base"))??;
+                base = Some(value);
+            }
+            "--max-depth" => max_depth = Some(number_value(&mut args, "--max-depth")?),
+            "--min-size" => min_size = Some(number_value(&mut args, "--min-size")?),
+            "--max-size" => max_size = Some(number_value(&mut args, "--max-size")?),
+            "--newer-than" => newer_than = Some(number_value(&mut args, "--newer-than")?),
+            "--older-than" => older_than = Some(number_value(&mut args, "--older-than")?),
+            "--no-hidden" => hidden = false,
+            "--no-follow" => follow_symlinks = false,
+            "-0" | "--null" => null = true,
+            option if option.starts_with('-') && option != "-" => {
+                return Err(CliError::Usage(format!("unknown option '{option}'")))
+            }
+            _ if pattern.is_none() => pattern = Some(arg),
+            _ => return Err(CliError::Usage("find requires exactly one pattern".into())),
+        }
+    }
+
+    let pattern =
+        pattern.ok_or_else(|| CliError::Usage("find requires exactly one pattern".into()))?;
+    Ok(Command::Find(FindArgs {
+        pattern,
+        base,
+        hidden,
+        max_depth,
+        follow_symlinks,
+        excludes,
+        min_size,
+        max_size,
+        newer_than,
+        older_than,
+        null,
+    }))
+}
+
+/// Parse the arguments of `to-regex` (or `from-like` if `like`).
+fn parse_convert_args<I: Iterator<Item = Result<String, CliError>>>(
+    mut args: I,
+    like: bool,
+) -> Result<Command, CliError> {
+    let mut convert = ConvertArgs {
+        inputs: Vec::new(),
+        file: None,
+        escape: like.then_some('\\'),
+    };
+    let mut only_positional = false;
+    while let Some(arg) = args.next() {
+        let arg = arg?;
+        let option = if only_positional { "" } else { arg.as_str() };
+        match option {
+            "--" => only_positional = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "--file" => {
+                let value = args.next().ok_or_else(|| missing_value("--file"))??;
+                convert.file = Some(value);
+            }
+            "--escape" if like => {
+                let value = args.next().ok_or_else(|| missing_value("--escape"))??;
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => convert.escape = Some(c),
+                    (None, _) => convert.escape = None,
+                    _ => {
+                        return Err(CliError::Usage(format!(
+                            "invalid value '{value}' for '--escape'"
+                        )))
+                    }
+                }
+            }
+            option if option.starts_with('-') && option != "-" => {
+                return Err(CliError::Usage(format!("unknown option '{option}'")))
+            }
+            _ => convert.inputs.push(arg),
+        }
+    }
+
+    if convert.inputs.is_empty() && convert.file.is_none() {
+        return Err(CliError::Usage(
+            "at least one pattern or '--file' is required".into(),
+        ));
+    }
+    Ok(if like {
+        Command::FromLike(convert)
+    } else {
+        Command::ToRegex(convert)
+    })
+}
+
+fn parse_rename_args<I: Iterator<Item = Result<String, CliError>>>(
+    mut args: I,
+) -> Result<Command, CliError> {
+    let mut positional = Vec::new();
+    let mut base = None;
+    let mut dry_run = false;
+    let mut only_positional = false;
+    while let Some(arg) = args.next() {
+        let arg = arg?;
+        let option = if only_positional { "" } else { arg.as_str() };
+        match option {
+            "--" => only_positional = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "--base" => {
+                let value = args.next().ok_or_else(|| missing_value("--base"))??;
+                base = Some(value);
+            }
+            "-n" | "--dry-run" => dry_run = true,
+            option if option.starts_with('-') && option != "-" => {
+                return Err(CliError::Usage(format!("unknown option '{option}'")))
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let [source, destination]: [String; 2] = positional.try_into().map_err(|_| {
+        CliError::Usage("rename requires a source and a destination pattern".into())
+    })?;
+    Ok(Command::Rename(RenameArgs {
+        source,
+        destination,
+        base,
+        dry_run,
+    }))
+}
+
+fn parse_bench_args<I: Iterator<Item = Result<String, CliError>>>(
+    mut args: I,
+) -> Result<Command, CliError> {
+    let mut patterns = Vec::new();
+    let mut patterns_file = None;
+    let mut names = None;
+    let mut iterations = 10;
+    let mut worst = 5;
+    let mut only_positional = false;
+    while let Some(arg) = args.next() {
+        let arg = arg?;
+        let option = if only_positional { "" } else { arg.as_str() };
+        match option {
+            "--" => only_positional = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "--patterns" => {
+                let value = args.next().ok_or_else(|| missing_value("--patterns"))??;
+                patterns_file = Some(value);
+            }
+            "--names" => {
+                let value = args.next().ok_or_else(|| missing_value("--names"))??;
+                names = Some(value);
+            }
+            "--iterations" => iterations = number_value(&mut args, "--iterations")?,
+            "--worst" => worst = number_value(&mut args, "--worst")?,
+            option if option.starts_with('-') && option != "-" => {
+                return Err(CliError::Usage(format!("unknown option '{option}'")))
+            }
+            _ => patterns.push(arg),
+        }
+    }
+
+    if patterns.is_empty() && patterns_file.is_none() {
+        return Err(CliError::Usage(
+            "at least one pattern or '--patterns' is required".into(),
+        ));
+    }
+    let names = names.ok_or_else(|| CliError::Usage("bench requires '--names'".into()))?;
+    if iterations == 0 {
+        return Err(CliError::Usage(
+            "invalid value '0' for '--iterations'".into(),
+        ));
+    }
+    Ok(Command::Bench(BenchArgs {
+        patterns,
+        patterns_file,
+        names,
+        iterations,
+        worst,
+    }))
+}
+
+fn parse_check_args<I: Iterator<Item = Result<String, CliError>>>(
+    args: I,
+) -> Result<Command, CliError> {
+    let Some(patterns) = parse_positional(args)? else {
+        return Ok(Command::Help);
+    };
+    if patterns.is_empty() {
+        return Err(CliError::Usage("at least one pattern is required".into()));
+    }
+    Ok(Command::Check(patterns))
+}
+
+fn parse_completions_args<I: Iterator<Item = Result<String, CliError>>>(
+    args: I,
+) -> Result<Command, CliError> {
+    let Some(positional) = parse_positional(args)? else {
+        return Ok(Command::Help);
+    };
+    match <[String; 1]>::try_from(positional) {
+        Ok([shell]) => match shell.as_str() {
+            "bash" => Ok(Command::Completions(Shell::Bash)),
+            "zsh" => Ok(Command::Completions(Shell::Zsh)),
+            "fish" => Ok(Command::Completions(Shell::Fish)),
+            _ => Err(CliError::Usage(format!("unsupported shell '{shell}'"))),
+        },
+        Err(_) => Err(CliError::Usage(
+            "completions requires exactly one shell".into(),
+        )),
+    }
+}
+
+fn missing_value(option: &str) -> CliError {
+    CliError::Usage(format!("option '{option}' requires a value"))
+}
+
+/// Parse the next argument as the numeric value of `option`.
+fn number_value<T, I>(args: &mut I, option: &str) -> Result<T, CliError>
+where
+    T: FromStr,
+    I: Iterator<Item = Result<String, CliError>>,
+{
+    let value = args.next().ok_or_else(|| missing_value(option))??;
+    value
+        .parse()
+        .map_err(|_| CliError::Usage(format!("invalid value '{value}' for '{option}'")))
+}
+
+fn parse_filter_args<I: Iterator<Item = Result<String, CliError>>>(
+    args: I,
+) -> Result<Command, CliError> {
+    let mut filter = FilterArgs::default();
+    let mut only_positional = false;
+    for arg in args {
+        let arg = arg?;
+        if only_positional || !arg.starts_with('-') || arg == "-" {
+            filter.patterns.push(arg);
+            continue;
+        }
+        match arg.as_str() {
+            "--" => only_positional = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--invert-match" => filter.invert = true,
+            "--null" => filter.null = true,
+            "--color" => filter.color = true,
+            long if long.starts_with("--") => {
+                return Err(CliError::Usage(format!("unknown option '{long}'")))
+            }
+            short => {
+                // Allow grouping short flags (e.g. '-v0')
+                for flag in short.chars().skip(1) {
+                    match flag {
+                        'h' => return Ok(Command::Help),
+                        'V' => return Ok(Command::Version),
+                        'v' => filter.invert = true,
+                        '0' => filter.null = true,
+                        flag => return Err(CliError::Usage(format!("unknown option '-{flag}'"))),
+                    }
+                }
+            }
+        }
+    }
+
+    if filter.patterns.is_empty() {
+        return Err(CliError::Usage("at least one pattern is required".into()));
+    }
+    Ok(Command::Filter(filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::{
+        parse_args, BenchArgs, Command, ConvertArgs, ExplainArgs, FilterArgs, FindArgs, RenameArgs,
+        Shell,
+    };
+    use std::ffi::OsString;
+
+    fn parse(args: &[&str]) -> Option<Command> {
+        parse_args(args.iter().map(OsString::from)).ok()
+    }
+
+    #[test]
+    fn parse_filter_args() {
+        assert_eq!(
+            parse(&["a*/b"]),
+            Some(Command::Filter(FilterArgs {
+                patterns: vec!["a*/b".into()],
+                invert: false,
+                null: false,
+                color: false,
+            }))
+        );
+        assert_eq!(
+            parse(&["-v0", "a*", "--", "-b*"]),
+            Some(Command::Filter(FilterArgs {
+                patterns: vec!["a*".into(), "-b*".into()],
+                invert: true,
+                null: true,
+                color: false,
+            }))
+        );
+        assert_eq!(
+            parse(&["--null", "a*", "--invert-match"]),
+            Some(Command::Filter(FilterArgs {
+                patterns: vec!["a*".into()],
+                invert: true,
+                null: true,
+                color: false,
+            }))
+        );
+        assert_eq!(
+            parse(&["--color", "-v", "a*"]),
+            Some(Command::Filter(FilterArgs {
+                patterns: vec!["a*".into()],
+                invert: true,
+                null: false,
+                color: true,
+            }))
+        );
+        assert_eq!(parse(&["a*", "--help"]), Some(Command::Help));
+        assert_eq!(parse(&["-V"]), Some(Command::Version));
+        assert_eq!(parse(&[]), None);
+        assert_eq!(parse(&["-v"]), None);
+        assert_eq!(parse(&["-x", "a*"]), None);
+        assert_eq!(parse(&["--unknown", "a*"]), None);
+    }
+
+    #[test]
+    fn parse_explain_args() {
+        assert_eq!(
+            parse(&["explain", "a*b"]),
+            Some(Command::Explain(ExplainArgs {
+                pattern: "a*b".into(),
+                dot: false,
+            }))
+        );
+        assert_eq!(
+            parse(&["explain", "--", "-a*b"]),
+            Some(Command::Explain(ExplainArgs {
+                pattern: "-a*b".into(),
+                dot: false,
+            }))
+        );
+        assert_eq!(
+            parse(&["explain", "--dot", "a*b"]),
+            Some(Command::Explain(ExplainArgs {
+                pattern: "a*b".into(),
+                dot: true,
+            }))
+        );
+        assert_eq!(
+            parse(&["explain", "--", "--dot"]),
+            Some(Command::Explain(ExplainArgs {
+                pattern: "--dot".into(),
+                dot: false,
+            }))
+        );
+        assert_eq!(parse(&["explain", "--dot"]), None);
+        assert_eq!(parse(&["explain", "--help"]), Some(Command::Help));
+        assert_eq!(parse(&["explain"]), None);
+        assert_eq!(parse(&["explain", "a", "b"]), None);
+        assert_eq!(
+            parse(&["--", "explain"]),
+            Some(Command::Filter(FilterArgs {
+                patterns: vec!["explain".into()],
+                invert: false,
+                null: false,
+                color: false,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_diff_args() {
+        assert_eq!(
+            parse(&["diff", "*.rs", "--", "-*"]),
+            Some(Command::Diff("*.rs".into(), "-*".into()))
+        );
+        assert_eq!(parse(&["diff", "a", "--help"]), Some(Command::Help));
+        assert_eq!(parse(&["diff", "a"]), None);
+        assert_eq!(parse(&["diff", "a", "b", "c"]), None);
+    }
+
+    #[test]
+    fn parse_find_args() {
+        assert_eq!(
+            parse(&["find", "src/*/*.rs", "--base", ".", "--no-hidden"]),
+            Some(Command::Find(FindArgs {
+                pattern: "src/*/*.rs".into(),
+                base: Some(".".into()),
+                hidden: false,
+                max_depth: None,
+                follow_symlinks: true,
+                excludes: vec![],
+                min_size: None,
+                max_size: None,
+                newer_than: None,
+                older_than: None,
+                null: false,
+            }))
+        );
+        assert_eq!(
+            parse(&["find", "--max-depth", "2", "--no-follow", "-0", "--", "-*"]),
+            Some(Command::Find(FindArgs {
+                pattern: "-*".into(),
+                base: None,
+                hidden: true,
+                max_depth: Some(2),
+                follow_symlinks: false,
+                excludes: vec![],
+                min_size: None,
+                max_size: None,
+                newer_than: None,
+                older_than: None,
+                null: true,
+            }))
+        );
+        assert_eq!(
+            parse(&["find", "*", "--exclude", "target", "--exclude", "*.o"]),
+            Some(Command::Find(FindArgs {
+                pattern: "*".into(),
+                base: None,
+                hidden: true,
+                max_depth: None,
+                follow_symlinks: true,
+                excludes: vec!["target".into(), "*.o".into()],
+                min_size: None,
+                max_size: None,
+                newer_than: None,
+                older_than: None,
+                null: false,
+            }))
+        );
+        assert_eq!(
+            parse(&["find", "*.log", "--min-size", "1", "--older-than", "7"]),
+            Some(Command::Find(FindArgs {
+                pattern: "*.log".into(),
+                base: None,
+                hidden: true,
+                max_depth: None,
+                follow_symlinks: true,
+                excludes: vec![],
+                min_size: Some(1),
+                max_size: None,
+                newer_than: None,
+                older_than: Some(7),
+                null: false,
+            }))
+        );
+        assert_eq!(parse(&["find"]), None);
+        assert_eq!(parse(&["find", "a", "b"]), None);
+        assert_eq!(parse(&["find", "a", "--base"]), None);
+        assert_eq!(parse(&["find", "a", "--max-depth", "x"]), None);
+        assert_eq!(parse(&["find", "a", "--exclude"]), None);
+        assert_eq!(parse(&["find", "a", "--max-size", "-1"]), None);
+    }
+
+    #[test]
+    fn parse_convert_args() {
+        assert_eq!(
+            parse(&["to-regex", "a*", "b?", "--file", "-"]),
+            Some(Command::ToRegex(ConvertArgs {
+                inputs: vec!["a*".into(), "b?".into()],
+                file: Some("-".into()),
+                escape: None,
+            }))
+        );
+        assert_eq!(
+            parse(&["from-like", "a%"]),
+            Some(Command::FromLike(ConvertArgs {
+                inputs: vec!["a%".into()],
+                file: None,
+                escape: Some('\\'),
+            }))
+        );
+        assert_eq!(
+            parse(&["from-like", "--escape", "!", "--", "-a%"]),
+            Some(Command::FromLike(ConvertArgs {
+                inputs: vec!["-a%".into()],
+                file: None,
+                escape: Some('!'),
+            }))
+        );
+        assert_eq!(parse(&["from-like", "-h"]), Some(Command::Help));
+        assert_eq!(parse(&["to-regex"]), None);
+        assert_eq!(parse(&["to-regex", "--escape", "!", "a*"]), None);
+        assert_eq!(parse(&["from-like", "--escape", "ab", "a%"]), None);
+    }
+
+    #[test]
+    fn parse_rename_args() {
+        assert_eq!(
+            parse(&["rename", "*.jpeg", "*.jpg", "-n"]),
+            Some(Command::Rename(RenameArgs {
+                source: "*.jpeg".into(),
+                destination: "*.jpg".into(),
+                base: None,
+                dry_run: true,
+            }))
+        );
+        assert_eq!(
+            parse(&["rename", "--base", "photos", "--", "-*", "*"]),
+            Some(Command::Rename(RenameArgs {
+                source: "-*".into(),
+                destination: "*".into(),
+                base: Some("photos".into()),
+                dry_run: false,
+            }))
+        );
+        assert_eq!(parse(&["rename", "*.jpeg"]), None);
+        assert_eq!(parse(&["rename", "a", "b", "c"]), None);
+    }
+
+    #[test]
+    fn parse_bench_args() {
+        assert_eq!(
+            parse(&["bench", "*.rs", "src/*", "--names", "names.txt"]),
+            Some(Command::Bench(BenchArgs {
+                patterns: vec!["*.rs".into(), "src/*".into()],
+                patterns_file: None,
+                names: "names.txt".into(),
+                iterations: 10,
+                worst: 5,
+            }))
+        );
+        assert_eq!(
+            parse(&[
+                "bench",
+                "--patterns",
+                "rules.txt",
+                "--names",
+                "-",
+                "--iterations",
+                "3",
+                "--worst",
+                "1",
+            ]),
+            Some(Command::Bench(BenchArgs {
+                patterns: vec![],
+                patterns_file: Some("rules.txt".into()),
+                names: "-".into(),
+                iterations: 3,
+                worst: 1,
+            }))
+        );
+        assert_eq!(parse(&["bench", "--help"]), Some(Command::Help));
+        assert_eq!(parse(&["bench", "--names", "names.txt"]), None);
+        assert_eq!(parse(&["bench", "*.rs"]), None);
+        assert_eq!(parse(&["bench", "*.rs", "--names"]), None);
+        assert_eq!(
+            parse(&["bench", "*", "--names", "-", "--iterations", "0"]),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_check_args() {
+        assert_eq!(
+            parse(&["check", "*.rs", "--", "-a["]),
+            Some(Command::Check(vec!["*.rs".into(), "-a[".into()]))
+        );
+        assert_eq!(parse(&["check", "-h"]), Some(Command::Help));
+        assert_eq!(parse(&["check"]), None);
+        assert_eq!(parse(&["check", "--quiet", "*.rs"]), None);
+    }
+
+    #[test]
+    fn parse_completions_args() {
+        assert_eq!(
+            parse(&["completions", "zsh"]),
+            Some(Command::Completions(Shell::Zsh))
+        );
+        assert_eq!(parse(&["completions", "--help"]), Some(Command::Help));
+        assert_eq!(parse(&["completions"]), None);
+        assert_eq!(parse(&["completions", "tcsh"]), None);
+        assert_eq!(parse(&["completions", "bash", "fish"]), None);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{args::BenchArgs, convert::read_lines, error::CliError};
+use goglob::{GlobPattern, GlobSet};
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// Every pattern to benchmark, from the command line and then the patterns
+/// file.
+fn compile_patterns(args: &BenchArgs) -> Result<(Vec<String>, GlobSet), CliError> {
+    let mut patterns: Vec<_> = args.patterns.iter().map(|p| (None, p.clone())).collect();
+    if let Some(file) = &args.patterns_file {
+        let lines = read_lines(file)?;
+        patterns.extend(
+            lines
+                .into_iter()
+                .map(|(location, line)| (Some(location), line)),
+        );
+    }
+    let set = patterns
+        .iter()
+        .map(|(location, pattern)| {
+            GlobPattern::new(pattern).map_err(|e| {
+                let e = CliError::Pattern(pattern.clone(), e);
+                match location {
+                    Some(location) => CliError::At(location.clone(), Box::new(e)),
+                    None => e,
+                }
+            })
+        })
+        .collect::<Result<GlobSet, _>>()?;
+    Ok((patterns.into_iter().map(|(_, p)| p).collect(), set))
+}
+
+/// Match every name of the names file against the patterns in `args` as many
+/// times as requested, reporting how many names each pattern matched, the
+/// throughput and the names which took the longest to match.
+///
+/// Each name is timed on its own, keeping its fastest time across iterations
+/// so that the numbers are as reproducible as possible.
+pub(crate) fn run_bench<W: Write>(args: &BenchArgs, mut output: W) -> Result<bool, CliError> {
+    let (patterns, set) = compile_patterns(args)?;
+    let names: Vec<_> = read_lines(&args.names)?
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect();
+
+    let mut fastest = vec![Duration::MAX; names.len()];
+    let mut matched = 0;
+    for iteration in 0..args.iterations {
+        for (name, fastest) in names.iter().zip(&mut fastest) {
+            let start = Instant::now();
+            let matches = set.matches(name);
+            *fastest = (*fastest).min(start.elapsed());
+            if iteration == 0 && matches {
+                matched += 1;
+            }
+        }
+    }
+    let total: Duration = fastest.iter().sum();
+
+    writeln!(output, "patterns: {}", patterns.len())?;
+    let coverage = set.coverage(&names);
+    for (pattern, hits) in patterns.iter().zip(coverage.hits()) {
+        writeln!(output, "  {hits:>8}  {pattern}")?;
+    }
+    writeln!(output, "names: {}", names.len())?;
+    writeln!(output, "matched: {matched}")?;
+    writeln!(
+        output,
+        "time: {total:.1?} per pass (fastest of {} iterations)",
+        args.iterations
+    )?;
+    if !total.is_zero() {
+        let throughput = names.len() as f64 / total.as_secs_f64();
+        writeln!(output, "throughput: {throughput:.0} names/s")?;
+    }
+
+    let mut slowest: Vec<_> = names.iter().zip(&fastest).collect();
+    slowest.sort_by(|(_, a), (_, b)| b.cmp(a));
+    writeln!(output, "slowest names:")?;
+    for (name, time) in slowest.into_iter().take(args.worst) {
+        writeln!(output, "  {time:>8.1?}  {name}")?;
+    }
+    output.flush()?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{args::BenchArgs, bench::run_bench};
+    use std::fs;
+
+    #[test]
+    fn bench_names() {
+        let dir = std::env::temp_dir();
+        let names = dir.join(format!("goglob-cli-bench-names-{}", std::process::id()));
+        let patterns = dir.join(format!("goglob-cli-bench-rules-{}", std::process::id()));
+        fs::write(&names, "lib.rs\nsrc/main.rs\nREADME.md\r\n\nCargo.toml\n").unwrap();
+        fs::write(&patterns, "*.md\n\n*.toml\n").unwrap();
+
+        let mut args = BenchArgs {
+            patterns: vec!["*.rs".into(), "src/*".into()],
+            patterns_file: Some(patterns.to_str().unwrap().into()),
+            names: names.to_str().unwrap().into(),
+            iterations: 3,
+            worst: 2,
+        };
+        let mut output = Vec::new();
+        assert!(run_bench(&args, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(
+            lines[..7],
+            [
+                "patterns: 4",
+                "         1  *.rs",
+                "         1  src/*",
+                "         1  *.md",
+                "         1  *.toml",
+                "names: 4",
+                "matched: 4",
+            ]
+        );
+        assert!(lines[7].starts_with("time: "));
+        let slowest = lines.iter().position(|l| *l == "slowest names:").unwrap();
+        assert_eq!(lines.len(), slowest + 3);
+
+        fs::write(&patterns, "*.md\na[\n").unwrap();
+        let err = run_bench(&args, Vec::new()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{}:2: invalid pattern 'a[': character class opened with '[' at 1 isn't closed",
+                args.patterns_file.as_ref().unwrap()
+            )
+        );
+
+        args.patterns_file = None;
+        fs::remove_file(&names).unwrap();
+        let err = run_bench(&args, Vec::new()).err().unwrap();
+        assert!(err.to_string().starts_with(&args.names));
+        fs::remove_file(&patterns).unwrap();
+    }
+}
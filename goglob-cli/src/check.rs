@@ -0,0 +1,52 @@
+use crate::error::CliError;
+use goglob::GlobPattern;
+use std::io::Write;
+
+/// Compile every one of `patterns`, describing why each invalid one is
+/// (pointing at the offending character) on `diagnostics`, and return whether
+/// they're all valid.
+pub(crate) fn run_check<W: Write>(
+    patterns: &[String],
+    mut diagnostics: W,
+) -> Result<bool, CliError> {
+    let mut valid = true;
+    for pattern in patterns {
+        if let Err(e) = GlobPattern::new(pattern) {
+            writeln!(
+                diagnostics,
+                "goglob: invalid pattern: {}",
+                e.render(pattern)
+            )?;
+            valid = false;
+        }
+    }
+    diagnostics.flush()?;
+    Ok(valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::check::run_check;
+
+    fn check(patterns: &[&str]) -> (bool, String) {
+        let patterns: Vec<_> = patterns.iter().map(|p| p.to_string()).collect();
+        let mut diagnostics = Vec::new();
+        let valid = run_check(&patterns, &mut diagnostics).unwrap();
+        (valid, String::from_utf8(diagnostics).unwrap())
+    }
+
+    #[test]
+    fn check_patterns() {
+        assert_eq!(check(&["*.rs", "src/[a-z]*"]), (true, String::new()));
+        assert_eq!(
+            check(&["*.rs", "ab[cd", "\\"]),
+            (
+                false,
+                "goglob: invalid pattern: character class opened with '[' at 2 isn't closed\n  \
+                 ab[cd\n    ^\n\
+                 goglob: invalid pattern: illegal use of '\\' at 0: end of pattern\n  \\\n  ^\n"
+                    .into()
+            )
+        );
+    }
+}
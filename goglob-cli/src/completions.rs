@@ -0,0 +1,113 @@
+use crate::{args::Shell, error::CliError};
+use std::io::Write;
+
+const BASH: &str = r#"_goglob() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local command="${COMP_WORDS[1]}" options
+    case "$prev" in
+        --base|--file|--names|--patterns)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            return
+            ;;
+    esac
+    case "$command" in
+        explain) options="--dot --help" ;;
+        diff|check) options="--help" ;;
+        find) options="--base --no-hidden --max-depth --no-follow --exclude --min-size
+                       --max-size --newer-than --older-than --null --help" ;;
+        to-regex) options="--file --help" ;;
+        from-like) options="--file --escape --help" ;;
+        rename) options="--base --dry-run --help" ;;
+        bench) options="--names --patterns --iterations --worst --help" ;;
+        completions) options="--help" ;;
+        *) options="--invert-match --null --color --help --version" ;;
+    esac
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "$options" -- "$cur"))
+    elif [[ $COMP_CWORD -eq 1 ]]; then
+        COMPREPLY=($(compgen -W "explain diff find to-regex from-like rename bench check
+                                 completions" -- "$cur"))
+    elif [[ "$command" == completions && $COMP_CWORD -eq 2 ]]; then
+        COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur"))
+    fi
+}
+complete -F _goglob goglob
+"#;
+
+const FISH: &str = r#"complete -c goglob -f
+complete -c goglob -n __fish_use_subcommand -a explain -d 'print how the pattern is compiled'
+complete -c goglob -n __fish_use_subcommand -a diff -d 'print a name matched by only one pattern'
+complete -c goglob -n __fish_use_subcommand -a find -d 'print every file matching the pattern'
+complete -c goglob -n __fish_use_subcommand -a to-regex -d 'print the equivalent regex'
+complete -c goglob -n __fish_use_subcommand -a from-like -d 'convert SQL LIKE patterns'
+complete -c goglob -n __fish_use_subcommand -a rename -d 'rename every file matching a pattern'
+complete -c goglob -n __fish_use_subcommand -a bench -d 'benchmark patterns against names'
+complete -c goglob -n __fish_use_subcommand -a check -d 'check that patterns are valid'
+complete -c goglob -n __fish_use_subcommand -a completions -d 'print shell completions'
+complete -c goglob -n __fish_use_subcommand -s v -l invert-match -d 'print the names which don\'t match'
+complete -c goglob -n __fish_use_subcommand -s 0 -l null -d 'names are separated by NUL'
+complete -c goglob -n __fish_use_subcommand -l color -d 'highlight what matched each name'
+complete -c goglob -n __fish_use_subcommand -s V -l version -d 'print the version'
+complete -c goglob -s h -l help -d 'print help'
+complete -c goglob -n '__fish_seen_subcommand_from explain' -l dot -d 'print a Graphviz graph'
+complete -c goglob -n '__fish_seen_subcommand_from find rename' -l base -r -F -d 'base directory'
+complete -c goglob -n '__fish_seen_subcommand_from find' -l no-hidden -d 'skip hidden names'
+complete -c goglob -n '__fish_seen_subcommand_from find' -l max-depth -x -d 'maximum depth'
+complete -c goglob -n '__fish_seen_subcommand_from find' -l no-follow -d 'skip symbolic links'
+complete -c goglob -n '__fish_seen_subcommand_from find' -l exclude -x -d 'skip matching paths'
+complete -c goglob -n '__fish_seen_subcommand_from find' -l min-size -x -d 'minimum size'
+complete -c goglob -n '__fish_seen_subcommand_from find' -l max-size -x -d 'maximum size'
+complete -c goglob -n '__fish_seen_subcommand_from find' -l newer-than -x -d 'maximum age'
+complete -c goglob -n '__fish_seen_subcommand_from find' -l older-than -x -d 'minimum age'
+complete -c goglob -n '__fish_seen_subcommand_from find' -s 0 -l null -d 'separate by NUL'
+complete -c goglob -n '__fish_seen_subcommand_from to-regex from-like' -l file -r -F -d 'input file'
+complete -c goglob -n '__fish_seen_subcommand_from from-like' -l escape -x -d 'escape character'
+complete -c goglob -n '__fish_seen_subcommand_from rename' -s n -l dry-run -d 'only print renames'
+complete -c goglob -n '__fish_seen_subcommand_from bench' -l names -r -F -d 'file of names'
+complete -c goglob -n '__fish_seen_subcommand_from bench' -l patterns -r -F -d 'file of patterns'
+complete -c goglob -n '__fish_seen_subcommand_from bench' -l iterations -x -d 'times to match'
+complete -c goglob -n '__fish_seen_subcommand_from bench' -l worst -x -d 'slowest names shown'
+complete -c goglob -n '__fish_seen_subcommand_from completions' -a 'bash zsh fish'
+"#;
+
+/// Write the completion script for `shell` into `output`. The one for zsh is
+/// the bash one, loaded through `bashcompinit`.
+pub(crate) fn run_completions<W: Write>(shell: Shell, mut output: W) -> Result<bool, CliError> {
+    match shell {
+        Shell::Bash => output.write_all(BASH.as_bytes())?,
+        Shell::Zsh => {
+            writeln!(output, "autoload -U +X bashcompinit && bashcompinit")?;
+            output.write_all(BASH.as_bytes())?;
+        }
+        Shell::Fish => output.write_all(FISH.as_bytes())?,
+    }
+    output.flush()?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{args::Shell, completions::run_completions};
+
+    #[test]
+    fn completions_cover_commands() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut output = Vec::new();
+            assert!(run_completions(shell, &mut output).unwrap());
+            let script = String::from_utf8(output).unwrap();
+            for command in [
+                "explain",
+                "diff",
+                "find",
+                "to-regex",
+                "from-like",
+                "rename",
+                "bench",
+                "check",
+                "completions",
+            ] {
+                assert!(script.contains(command), "{shell:?} {command}");
+            }
+        }
+    }
+}
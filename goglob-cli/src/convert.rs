@@ -0,0 +1,122 @@
+use crate::{args::ConvertArgs, error::CliError};
+use goglob::{convert::like_to_pattern, GlobPattern};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+/// Every non-empty line of `file` (standard input if `-`), along with its
+/// location (e.g. `rules.txt:3`).
+pub(crate) fn read_lines(file: &str) -> Result<Vec<(String, String)>, CliError> {
+    let reader: Box<dyn BufRead> = if file == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(file).map_err(|e| {
+            CliError::At(file.to_string(), Box::new(CliError::Io(e)))
+        })?))
+    };
+    let mut lines = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        if !line.is_empty() {
+            lines.push((format!("{file}:{}", i + 1), line.to_string()));
+        }
+    }
+    Ok(lines)
+}
+
+/// Every input to convert, along with its location if read from a file.
+fn collect_inputs(args: &ConvertArgs) -> Result<Vec<(Option<String>, String)>, CliError> {
+    let mut inputs: Vec<_> = args.inputs.iter().map(|i| (None, i.clone())).collect();
+    if let Some(file) = &args.file {
+        let lines = read_lines(file)?;
+        inputs.extend(
+            lines
+                .into_iter()
+                .map(|(location, line)| (Some(location), line)),
+        );
+    }
+    Ok(inputs)
+}
+
+fn convert_each<W, F>(args: &ConvertArgs, mut output: W, convert: F) -> Result<bool, CliError>
+where
+    W: Write,
+    F: Fn(&str) -> Result<String, CliError>,
+{
+    for (location, input) in collect_inputs(args)? {
+        let converted = convert(&input).map_err(|e| match location {
+            Some(location) => CliError::At(location, Box::new(e)),
+            None => e,
+        })?;
+        writeln!(output, "{converted}")?;
+    }
+    output.flush()?;
+    Ok(true)
+}
+
+pub(crate) fn run_to_regex<W: Write>(args: &ConvertArgs, output: W) -> Result<bool, CliError> {
+    convert_each(args, output, |pattern| {
+        GlobPattern::new(pattern)
+            .map(|p| p.to_regex())
+            .map_err(|e| CliError::Pattern(pattern.to_string(), e))
+    })
+}
+
+pub(crate) fn run_from_like<W: Write>(args: &ConvertArgs, output: W) -> Result<bool, CliError> {
+    convert_each(args, output, |like| {
+        let pattern = like_to_pattern(like, args.escape);
+        // Make sure the result is valid, even though it always should be.
+        GlobPattern::new(&pattern).map_err(|e| CliError::Pattern(pattern.clone(), e))?;
+        Ok(pattern)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        args::ConvertArgs,
+        convert::{run_from_like, run_to_regex},
+    };
+    use std::fs;
+
+    #[test]
+    fn convert_to_regex() {
+        let path = std::env::temp_dir().join(format!("goglob-cli-regex-{}", std::process::id()));
+        fs::write(&path, "*.rs\r\n\nsrc/?\n").unwrap();
+        let file = path.to_str().unwrap().to_string();
+
+        let args = ConvertArgs {
+            inputs: vec!["a[b-d]".into()],
+            file: Some(file.clone()),
+            escape: None,
+        };
+        let mut output = Vec::new();
+        assert!(run_to_regex(&args, &mut output).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "^a[b-d]$\n^[^/]*\\.rs$\n^src/[^/]$\n"
+        );
+
+        fs::write(&path, "*.rs\na[\n").unwrap();
+        let err = run_to_regex(&args, Vec::new()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{file}:2: invalid pattern 'a[': character class opened with '[' at 1 isn't closed"
+            )
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn convert_from_like() {
+        let args = ConvertArgs {
+            inputs: vec!["a%b_".into(), "100!%".into()],
+            file: None,
+            escape: Some('!'),
+        };
+        let mut output = Vec::new();
+        assert!(run_from_like(&args, &mut output).unwrap());
+        assert_eq!(String::from_utf8(output).unwrap(), "a*b?\n100%\n");
+    }
+}
@@ -0,0 +1,54 @@
+use crate::error::CliError;
+use goglob::GlobPattern;
+use std::io::Write;
+
+pub(crate) fn run_diff<W: Write>(left: &str, right: &str, mut output: W) -> Result<bool, CliError> {
+    let compile =
+        |pattern: &str| GlobPattern::new(pattern).map_err(|e| CliError::Pattern(pattern.into(), e));
+    let (left_pattern, right_pattern) = (compile(left)?, compile(right)?);
+
+    let Some(witness) = left_pattern.witness(&right_pattern) else {
+        writeln!(output, "'{left}' and '{right}' match the same names")?;
+        output.flush()?;
+        return Ok(false);
+    };
+    let (matching, other) = if witness.matched_by_left() {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    writeln!(
+        output,
+        "{:?} is matched by '{matching}' but not by '{other}'",
+        witness.name()
+    )?;
+    output.flush()?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::run_diff;
+
+    fn diff(left: &str, right: &str) -> (String, bool) {
+        let mut output = Vec::new();
+        let found = run_diff(left, right, &mut output).unwrap();
+        (String::from_utf8(output).unwrap(), found)
+    }
+
+    #[test]
+    fn diff_patterns() {
+        assert_eq!(
+            diff("*.rs", "*.r?"),
+            (
+                "\".ra\" is matched by '*.r?' but not by '*.rs'\n".into(),
+                true
+            )
+        );
+        assert_eq!(
+            diff("a[b]*", "ab**"),
+            ("'a[b]*' and 'ab**' match the same names\n".into(), false)
+        );
+        assert!(run_diff("[", "a", Vec::new()).is_err());
+    }
+}
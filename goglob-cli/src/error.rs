@@ -0,0 +1,27 @@
+use goglob::error::Error as GlobError;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
+
+#[derive(Debug)]
+pub(crate) enum CliError {
+    Usage(String),
+    Pattern(String, GlobError),
+    Io(io::Error),
+    /// Error found at the given location (e.g. `rules.txt:3`) of an input file.
+    At(String, Box<CliError>),
+}
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            CliError::Usage(msg) => write!(f, "{msg}\n\nTry 'goglob --help' for more information."),
+            CliError::Pattern(pattern, e) => write!(f, "invalid pattern '{pattern}': {e}"),
+            CliError::Io(e) => write!(f, "{e}"),
+            CliError::At(location, e) => write!(f, "{location}: {e}"),
+        }
+    }
+}
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{args::ExplainArgs, error::CliError};
+use goglob::{token::GlobToken, GlobPattern};
+use std::io::Write;
+
+/// Lints of the tokens of `pattern`, each quoting the characters of the
+/// source the token was compiled from.
+fn lint_tokens(pattern: &GlobPattern) -> Vec<String> {
+    let mut lints = Vec::new();
+    let spans = pattern.token_spans();
+    for (i, (token, span)) in pattern.tokens().iter().zip(spans).enumerate() {
+        let GlobToken::CharClass(cc) = token else {
+            continue;
+        };
+        let source = &pattern.as_str()[span.clone()];
+        if let (false, [cct]) = (cc.is_negated(), cc.types()) {
+            let range = cct.as_range();
+            if range.start() == range.end() {
+                lints.push(format!(
+                    "token {i} at {span:?}: {source} only matches {:?}, which could be written as a literal",
+                    range.start()
+                ));
+            }
+        }
+        if cc.matches_next("/").is_some() {
+            lints.push(format!(
+                "token {i} at {span:?}: {source} can match '/', unlike '*' and '?'"
+            ));
+        }
+    }
+    lints
+}
+
+pub(crate) fn run_explain<W: Write>(args: &ExplainArgs, mut output: W) -> Result<bool, CliError> {
+    let pattern = &args.pattern;
+    let compiled =
+        GlobPattern::new(pattern).map_err(|e| CliError::Pattern(pattern.to_string(), e))?;
+    if args.dot {
+        output.write_all(compiled.to_dot().as_bytes())?;
+        output.flush()?;
+        return Ok(true);
+    }
+
+    write!(output, "{}", compiled.dump())?;
+    match compiled.literal_prefix() {
+        Some(prefix) => writeln!(output, "literal prefix: {prefix:?}")?,
+        None => writeln!(output, "literal prefix: (none)")?,
+    }
+    match compiled.literal_suffix() {
+        Some(suffix) => writeln!(output, "literal suffix: {suffix:?}")?,
+        None => writeln!(output, "literal suffix: (none)")?,
+    }
+    writeln!(output, "min length: {}", compiled.min_len())?;
+    match compiled.max_len() {
+        Some(max_len) => writeln!(output, "max length: {max_len}")?,
+        None => writeln!(output, "max length: unbounded")?,
+    }
+    let lints = lint_tokens(&compiled);
+    if lints.is_empty() {
+        writeln!(output, "lints: (none)")?;
+    } else {
+        writeln!(output, "lints:")?;
+        for lint in lints {
+            writeln!(output, "  {lint}")?;
+        }
+    }
+    output.flush()?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{args::ExplainArgs, explain::run_explain};
+
+    fn explain_with(pattern: &str, dot: bool) -> String {
+        let args = ExplainArgs {
+            pattern: pattern.into(),
+            dot,
+        };
+        let mut output = Vec::new();
+        run_explain(&args, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn explain(pattern: &str) -> String {
+        explain_with(pattern, false)
+    }
+
+    #[test]
+    fn explain_pattern() {
+        assert_eq!(
+            explain("a*b?[c-f]"),
+            "\
+pattern: a*b?[c-f]
+tokens:
+    0  0..1  Literal \"a\"
+    1  1..2  SeqWildcard
+    2  2..3  Literal \"b\"
+    3  3..4  SingleWildcard
+    4  4..9  CharClass [c-f]
+literal prefix: \"a\"
+literal suffix: (none)
+min length: 4
+max length: unbounded
+lints: (none)
+"
+        );
+    }
+
+    #[test]
+    fn explain_lints() {
+        assert_eq!(
+            explain("x[y][^z]"),
+            "\
+pattern: x[y][^z]
+tokens:
+    0  0..1  Literal \"x\"
+    1  1..4  CharClass [y]
+    2  4..8  CharClass [^z]
+literal prefix: \"x\"
+literal suffix: (none)
+min length: 3
+max length: 3
+lints:
+  token 1 at 1..4: [y] only matches 'y', which could be written as a literal
+  token 2 at 4..8: [^z] can match '/', unlike '*' and '?'
+"
+        );
+        assert!(explain("a[\\y]").ends_with(
+            "lints:\n  token 1 at 1..5: [\\y] only matches 'y', which could be written as a literal\n"
+        ));
+    }
+
+    #[test]
+    fn explain_dot() {
+        let dot = explain_with("a*", true);
+        assert!(dot.starts_with("digraph pattern {\n"));
+        assert!(dot.contains("  1 -> 2 [label=\"ε\"];\n"));
+        assert!(!dot.contains("lints"));
+    }
+}
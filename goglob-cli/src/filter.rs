@@ -0,0 +1,192 @@
+use crate::{args::FilterArgs, error::CliError};
+use goglob::{GlobPattern, GlobSet};
+use std::io::{BufRead, Result as IoResult, Write};
+
+/// Style of the text matched by literals: green.
+const LITERAL_STYLE: &str = "\x1b[32m";
+/// Style of the text matched by wildcards and character classes: underlined
+/// yellow.
+const WILDCARD_STYLE: &str = "\x1b[4;33m";
+const RESET_STYLE: &str = "\x1b[0m";
+
+pub(crate) fn compile_patterns(patterns: &[String]) -> Result<GlobSet, CliError> {
+    patterns
+        .iter()
+        .map(|p| GlobPattern::new(p).map_err(|e| CliError::Pattern(p.clone(), e)))
+        .collect()
+}
+
+/// Write `name` highlighting the text matched by the literals of the first
+/// pattern of `set` matching it and that matched by its wildcards.
+fn write_highlighted<W: Write>(output: &mut W, set: &GlobSet, name: &str) -> IoResult<()> {
+    let spans = set
+        .iter()
+        .find_map(|pattern| pattern.capture_spans(name))
+        .unwrap_or_default();
+    let mut end = 0;
+    for span in spans.into_iter().filter(|span| !span.is_empty()) {
+        if span.start > end {
+            write!(
+                output,
+                "{LITERAL_STYLE}{}{RESET_STYLE}",
+                &name[end..span.start]
+            )?;
+        }
+        write!(
+            output,
+            "{WILDCARD_STYLE}{}{RESET_STYLE}",
+            &name[span.clone()]
+        )?;
+        end = span.end;
+    }
+    if end < name.len() {
+        write!(output, "{LITERAL_STYLE}{}{RESET_STYLE}", &name[end..])?;
+    }
+    Ok(())
+}
+
+/// Copy every record of `input` matching the patterns in `args` (or not matching,
+/// if inverted) into `output`, returning whether any record was written.
+pub(crate) fn run_filter<R: BufRead, W: Write>(
+    args: &FilterArgs,
+    mut input: R,
+    mut output: W,
+) -> Result<bool, CliError> {
+    let set = compile_patterns(&args.patterns)?;
+    let separator = if args.null { b'\0' } else { b'\n' };
+
+    let mut any = false;
+    let mut record = Vec::new();
+    loop {
+        record.clear();
+        if input.read_until(separator, &mut record)? == 0 {
+            break;
+        }
+        let mut name = record.as_slice();
+        if let Some(stripped) = name.strip_suffix(&[separator]) {
+            name = stripped;
+        }
+        if !args.null {
+            if let Some(stripped) = name.strip_suffix(b"\r") {
+                name = stripped;
+            }
+        }
+
+        // Names which aren't valid UTF-8 can never match.
+        let utf8_name = std::str::from_utf8(name).ok();
+        let matched = utf8_name.is_some_and(|name| set.matches(name));
+        if matched != args.invert {
+            match utf8_name {
+                Some(name) if matched && args.color => write_highlighted(&mut output, &set, name)?,
+                _ => output.write_all(name)?,
+            }
+            output.write_all(&[separator])?;
+            any = true;
+        }
+    }
+    output.flush()?;
+    Ok(any)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{args::FilterArgs, filter::run_filter};
+
+    fn filter(patterns: &[&str], invert: bool, null: bool, input: &[u8]) -> (bool, Vec<u8>) {
+        let args = FilterArgs {
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            invert,
+            null,
+            color: false,
+        };
+        let mut output = Vec::new();
+        let any = run_filter(&args, input, &mut output).unwrap();
+        (any, output)
+    }
+
+    #[test]
+    fn filter_lines() {
+        let input = b"abc/b\r\na/c/b\nlib.rs\n\xffa\nmain.rs";
+        assert_eq!(
+            filter(&["a*/b"], false, false, input),
+            (true, b"abc/b\n".to_vec())
+        );
+        assert_eq!(
+            filter(&["a*/b", "*.rs"], false, false, input),
+            (true, b"abc/b\nlib.rs\nmain.rs\n".to_vec())
+        );
+        assert_eq!(
+            filter(&["a*/b", "*.rs"], true, false, input),
+            (true, b"a/c/b\n\xffa\n".to_vec())
+        );
+        assert_eq!(filter(&["x*"], false, false, input), (false, vec![]));
+    }
+
+    #[test]
+    fn filter_null_separated() {
+        let input = b"a\nb\0lib.rs\0main.rs\0";
+        assert_eq!(
+            filter(&["*.rs"], false, true, input),
+            (true, b"lib.rs\0main.rs\0".to_vec())
+        );
+        assert_eq!(
+            filter(&["*.rs"], true, true, input),
+            (true, b"a\nb\0".to_vec())
+        );
+    }
+
+    #[test]
+    fn filter_invalid_pattern() {
+        let args = FilterArgs {
+            patterns: vec!["*.rs".into(), "a[".into()],
+            invert: false,
+            null: false,
+            color: false,
+        };
+        let err = run_filter(&args, &b""[..], Vec::new()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "invalid pattern 'a[': character class opened with '[' at 1 isn't closed"
+        );
+    }
+
+    #[test]
+    fn filter_color() {
+        let args = FilterArgs {
+            patterns: vec!["x*".into(), "src/*.rs".into(), "*.md".into()],
+            invert: false,
+            null: false,
+            color: true,
+        };
+        let mut output = Vec::new();
+        assert!(run_filter(
+            &args,
+            &b"src/lib.rs
+src/.rs
+lib.rs
+"[..],
+            &mut output
+        )
+        .unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\x1b[32msrc/\x1b[0m\x1b[4;33mlib\x1b[0m\x1b[32m.rs\x1b[0m\n\
+             \x1b[32msrc/.rs\x1b[0m\n"
+        );
+
+        let args = FilterArgs {
+            invert: true,
+            ..args
+        };
+        let mut output = Vec::new();
+        assert!(run_filter(
+            &args,
+            &b"src/lib.rs
+lib.rs
+"[..],
+            &mut output
+        )
+        .unwrap());
+        assert_eq!(output, b"lib.rs\n");
+    }
+}
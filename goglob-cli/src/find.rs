@@ -0,0 +1,98 @@
+use crate::{args::FindArgs, error::CliError};
+use goglob::{
+    fs::{glob_in, WalkOptions},
+    GlobPattern, GlobSet,
+};
+use std::io::Write;
+use std::time::{Duration, SystemTime};
+
+pub(crate) fn run_find<W: Write>(args: &FindArgs, mut output: W) -> Result<bool, CliError> {
+    let pattern =
+        GlobPattern::new(&args.pattern).map_err(|e| CliError::Pattern(args.pattern.clone(), e))?;
+    let excludes = args
+        .excludes
+        .iter()
+        .map(|p| GlobPattern::new(p).map_err(|e| CliError::Pattern(p.clone(), e)))
+        .collect::<Result<GlobSet, _>>()?;
+    let options = WalkOptions::new()
+        .hidden(args.hidden)
+        .max_depth(args.max_depth)
+        .follow_symlinks(args.follow_symlinks)
+        .excludes((!excludes.is_empty()).then_some(excludes))
+        .min_size(args.min_size)
+        .max_size(args.max_size)
+        .modified_after(args.newer_than.map(days_ago))
+        .modified_before(args.older_than.map(days_ago));
+    let separator = if args.null { '\0' } else { '\n' };
+
+    let paths = glob_in(args.base.as_deref().unwrap_or(""), &pattern, &options);
+    for path in &paths {
+        write!(output, "{}{separator}", path.display())?;
+    }
+    output.flush()?;
+    Ok(!paths.is_empty())
+}
+
+/// Time `days` days ago.
+fn days_ago(days: u64) -> SystemTime {
+    let ago = Duration::from_secs(days.saturating_mul(24 * 60 * 60));
+    SystemTime::now()
+        .checked_sub(ago)
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{args::FindArgs, find::run_find};
+    use std::fs;
+
+    #[test]
+    fn find_files() {
+        let root = std::env::temp_dir().join(format!("goglob-cli-find-{}", std::process::id()));
+        fs::create_dir_all(root.join("src/.cache")).unwrap();
+        fs::write(root.join("src/lib.rs"), "").unwrap();
+        fs::write(root.join("src/.cache/x.rs"), "").unwrap();
+
+        let mut args = FindArgs {
+            pattern: "src/*/*.rs".into(),
+            base: Some(root.to_str().unwrap().into()),
+            hidden: true,
+            max_depth: None,
+            follow_symlinks: true,
+            excludes: vec![],
+            min_size: None,
+            max_size: None,
+            newer_than: None,
+            older_than: None,
+            null: true,
+        };
+        let mut output = Vec::new();
+        assert!(run_find(&args, &mut output).unwrap());
+        let expected = format!("{}\0", root.join("src/.cache/x.rs").display());
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+        args.hidden = false;
+        let mut output = Vec::new();
+        assert!(!run_find(&args, &mut output).unwrap());
+        assert!(output.is_empty());
+
+        args.pattern = "src/*".into();
+        args.excludes = vec!["*/*.rs".into()];
+        let mut output = Vec::new();
+        assert!(!run_find(&args, &mut output).unwrap());
+        args.excludes = vec!["[".into()];
+        assert!(run_find(&args, &mut output).is_err());
+
+        args.pattern = "src/*.rs".into();
+        args.excludes = vec![];
+        args.newer_than = Some(1);
+        assert!(run_find(&args, &mut output).unwrap());
+        args.min_size = Some(1);
+        assert!(!run_find(&args, &mut output).unwrap());
+        args.min_size = None;
+        args.older_than = Some(1);
+        assert!(!run_find(&args, &mut output).unwrap());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,58 @@
+//! Command-line interface for `goglob`.
+//!
+//! See the crate's `README.md` for usage.
+
+mod args;
+mod bench;
+mod check;
+mod completions;
+mod convert;
+mod diff;
+mod error;
+mod explain;
+mod filter;
+mod find;
+mod rename;
+
+use crate::{
+    args::{parse_args, Command, USAGE},
+    error::CliError,
+};
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+fn run() -> Result<bool, CliError> {
+    match parse_args(std::env::args_os().skip(1))? {
+        Command::Filter(args) => filter::run_filter(&args, io::stdin().lock(), io::stdout().lock()),
+        Command::Explain(args) => explain::run_explain(&args, io::stdout().lock()),
+        Command::Diff(left, right) => diff::run_diff(&left, &right, io::stdout().lock()),
+        Command::Find(args) => find::run_find(&args, io::stdout().lock()),
+        Command::ToRegex(args) => convert::run_to_regex(&args, io::stdout().lock()),
+        Command::FromLike(args) => convert::run_from_like(&args, io::stdout().lock()),
+        Command::Rename(args) => rename::run_rename(&args, io::stdout().lock()),
+        Command::Bench(args) => bench::run_bench(&args, io::stdout().lock()),
+        Command::Check(patterns) => check::run_check(&patterns, io::stderr().lock()),
+        Command::Completions(shell) => completions::run_completions(shell, io::stdout().lock()),
+        Command::Help => {
+            io::stdout().write_all(USAGE.as_bytes())?;
+            Ok(true)
+        }
+        Command::Version => {
+            writeln!(io::stdout(), "goglob {}", env!("CARGO_PKG_VERSION"))?;
+            Ok(true)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        // Downstream consumer went away (e.g. `goglob '*' | head`)
+        Err(CliError::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("goglob: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{args::RenameArgs, error::CliError};
+use goglob::{
+    fs::{glob_in, WalkOptions},
+    GlobPattern, GlobRenamer,
+};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Pair every file matching the source pattern with its new path.
+fn plan_renames(args: &RenameArgs) -> Result<Vec<(PathBuf, PathBuf)>, CliError> {
+    let source =
+        GlobPattern::new(&args.source).map_err(|e| CliError::Pattern(args.source.clone(), e))?;
+    let renamer = GlobRenamer::new(source, &args.destination)
+        .map_err(|e| CliError::Pattern(args.destination.clone(), e))?;
+
+    let base = Path::new(args.base.as_deref().unwrap_or(""));
+    let mut renames = Vec::new();
+    for path in glob_in(base, renamer.source(), &WalkOptions::new()) {
+        // Absolute patterns are matched against the whole path.
+        let name = if args.source.starts_with('/') {
+            path.as_path()
+        } else {
+            path.strip_prefix(base).unwrap_or(&path)
+        };
+        let Some(renamed) = name.to_str().and_then(|name| renamer.rename(name)) else {
+            continue;
+        };
+        let renamed = base.join(renamed);
+        if renamed != path {
+            renames.push((path, renamed));
+        }
+    }
+    Ok(renames)
+}
+
+/// Make sure no file is overwritten, either because it already exists or
+/// because several files would be renamed to the same path.
+fn check_conflicts(renames: &[(PathBuf, PathBuf)]) -> Result<(), CliError> {
+    let sources: HashSet<_> = renames.iter().map(|(from, _)| from).collect();
+    let mut destinations = HashSet::new();
+    for (from, to) in renames {
+        let exists = fs::symlink_metadata(to).is_ok() && !sources.contains(to);
+        if exists || !destinations.insert(to) {
+            return Err(CliError::Usage(format!(
+                "renaming '{}' would overwrite '{}'",
+                from.display(),
+                to.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn run_rename<W: Write>(args: &RenameArgs, mut output: W) -> Result<bool, CliError> {
+    let renames = plan_renames(args)?;
+    check_conflicts(&renames)?;
+    for (from, to) in &renames {
+        writeln!(output, "{} -> {}", from.display(), to.display())?;
+        if !args.dry_run {
+            fs::rename(from, to)
+                .map_err(|e| CliError::At(from.display().to_string(), Box::new(CliError::Io(e))))?;
+        }
+    }
+    output.flush()?;
+    Ok(!renames.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{args::RenameArgs, rename::run_rename};
+    use std::fs;
+
+    #[test]
+    fn rename_files() {
+        let root = std::env::temp_dir().join(format!("goglob-cli-rename-{}", std::process::id()));
+        fs::create_dir_all(root.join("photos")).unwrap();
+        fs::write(root.join("photos/cat.jpeg"), "").unwrap();
+        fs::write(root.join("photos/dog.jpeg"), "").unwrap();
+        fs::write(root.join("photos/notes.txt"), "").unwrap();
+        let base = root.to_str().unwrap().to_string();
+
+        let mut args = RenameArgs {
+            source: "*/*.jpeg".into(),
+            destination: "*/*.jpg".into(),
+            base: Some(base.clone()),
+            dry_run: true,
+        };
+        let mut output = Vec::new();
+        assert!(run_rename(&args, &mut output).unwrap());
+        let expected = format!(
+            "{base}/photos/cat.jpeg -> {base}/photos/cat.jpg\n\
+             {base}/photos/dog.jpeg -> {base}/photos/dog.jpg\n"
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+        assert!(root.join("photos/cat.jpeg").exists());
+
+        args.dry_run = false;
+        assert!(run_rename(&args, Vec::new()).unwrap());
+        assert!(root.join("photos/cat.jpg").exists());
+        assert!(!root.join("photos/cat.jpeg").exists());
+
+        // Both would end up as 'photos/pet.jpg'
+        args.source = "*/*.jpg".into();
+        args.destination = "*/pet.jpg".into();
+        assert!(run_rename(&args, Vec::new()).is_err());
+        assert!(root.join("photos/dog.jpg").exists());
+
+        args.destination = "*/*/*.jpg".into();
+        assert!(run_rename(&args, Vec::new()).is_err());
+
+        args.destination = "{1}/{2}.JPG".into();
+        assert!(run_rename(&args, Vec::new()).unwrap());
+        assert!(root.join("photos/dog.JPG").exists());
+
+        args.destination = "*/{3}.jpg".into();
+        assert!(run_rename(&args, Vec::new()).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,131 @@
+//! [arbitrary](https://docs.rs/arbitrary) support for fuzzing rule handling
+//! with valid patterns and names matching them.
+
+use crate::{
+    charcls::{CharClass, CharClassType},
+    literal::Literal,
+    options::MatchOptions,
+    GlobPattern, GlobToken,
+};
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+/// Maximum number of tokens of generated patterns.
+const MAX_TOKENS: usize = 16;
+/// Maximum number of characters generated for each `*`.
+const MAX_SEQ_LEN: usize = 8;
+
+impl<'a> Arbitrary<'a> for GlobToken {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=3)? {
+            0 => {
+                let mut literal = String::arbitrary(u)?;
+                if literal.is_empty() {
+                    literal.push(char::arbitrary(u)?);
+                }
+                GlobToken::Literal(Literal::new(literal))
+            }
+            1 => {
+                let negated = bool::arbitrary(u)?;
+                let mut types = Vec::new();
+                for _ in 0..u.int_in_range(1..=4)? {
+                    let (a, b) = (char::arbitrary(u)?, char::arbitrary(u)?);
+                    types.push(CharClassType::try_from(a.min(b)..=a.max(b)).unwrap());
+                }
+                GlobToken::CharClass(CharClass::new(negated, types))
+            }
+            2 => GlobToken::SeqWildcard,
+            _ => GlobToken::SingleWildcard,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for GlobPattern {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // Patterns can't be empty.
+        let mut tokens = vec![GlobToken::arbitrary(u)?];
+        for _ in 0..u.arbitrary_len::<GlobToken>()?.min(MAX_TOKENS - 1) {
+            tokens.push(GlobToken::arbitrary(u)?);
+        }
+        Ok(GlobPattern::normalized(tokens, MatchOptions::new()))
+    }
+}
+
+fn arbitrary_non_slash(u: &mut Unstructured<'_>) -> Result<char> {
+    match char::arbitrary(u)? {
+        '/' => Ok('_'),
+        c => Ok(c),
+    }
+}
+
+/// Generate a name guaranteed to match `pattern` from the fuzzer's input.
+///
+/// Fails with [`Error::IncorrectFormat`] if no name can match the pattern (e.g.
+/// `[^\x00-\u{10FFFF}]`) or none was found from the given input, including
+/// when the pattern doesn't match the name built from its tokens after all,
+/// as matching commits to the earliest position where a class following a
+/// `*` matches (e.g. `ab/c` for `*[^x]*`).
+pub fn matching_name(pattern: &GlobPattern, u: &mut Unstructured<'_>) -> Result<String> {
+    let mut name = String::new();
+    for token in pattern.tokens() {
+        match token {
+            GlobToken::Literal(l) => name.push_str(l.as_ref()),
+            GlobToken::SingleWildcard => name.push(arbitrary_non_slash(u)?),
+            GlobToken::SeqWildcard => {
+                for _ in 0..u.int_in_range(0..=MAX_SEQ_LEN)? {
+                    name.push(arbitrary_non_slash(u)?);
+                }
+            }
+            GlobToken::AnyDirs => {
+                for _ in 0..u.int_in_range(0..=2)? {
+                    for _ in 0..u.int_in_range(0..=MAX_SEQ_LEN)? {
+                        name.push(arbitrary_non_slash(u)?);
+                    }
+                    name.push('/');
+                }
+            }
+            GlobToken::CharClass(cc) if !cc.is_negated() => {
+                let range = u.choose(cc.types())?.as_range();
+                let c = u.int_in_range(u32::from(*range.start())..=u32::from(*range.end()))?;
+                // Surrogates are the only values in a char range which aren't chars.
+                name.push(char::from_u32(c).unwrap_or(*range.start()));
+            }
+            GlobToken::CharClass(cc) => {
+                let c = char::arbitrary(u)?;
+                let c = [c, '\0', 'a', '/', char::MAX]
+                    .into_iter()
+                    .find(|c| cc.matches_next(c.encode_utf8(&mut [0; 4])).is_some())
+                    .ok_or(Error::IncorrectFormat)?;
+                name.push(c);
+            }
+        }
+    }
+    match pattern.matches(&name) {
+        true => Ok(name),
+        false => Err(Error::IncorrectFormat),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arbitrary::matching_name;
+    use crate::GlobPattern;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn arbitrary_matching_name() {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i * 7919 % 251) as u8).collect();
+        let mut u = Unstructured::new(&data);
+        let mut generated = 0;
+        while !u.is_empty() {
+            let Ok(pattern) = GlobPattern::arbitrary(&mut u) else {
+                break;
+            };
+            let Ok(name) = matching_name(&pattern, &mut u) else {
+                continue;
+            };
+            assert!(pattern.matches(&name), "{pattern:?} doesn't match {name:?}");
+            generated += 1;
+        }
+        assert!(generated > 0);
+    }
+}
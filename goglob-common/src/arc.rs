@@ -0,0 +1,95 @@
+//! Patterns with shared ownership, cheap to hand out to many tasks or
+//! threads.
+
+use crate::GlobPattern;
+use alloc::sync::Arc;
+use core::ops::Deref;
+
+/// Handle to a [`GlobPattern`] whose clones share its tokens, so cloning it
+/// only bumps an atomic reference count instead of copying every token and
+/// literal.
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use goglob_common::GlobPattern;
+/// use std::thread;
+///
+/// let pattern = GlobPattern::new("src/*.rs").unwrap().shared();
+/// let handles: Vec<_> = ["src/lib.rs", "src/main.rs"]
+///     .into_iter()
+///     .map(|name| {
+///         let pattern = pattern.clone();
+///         thread::spawn(move || pattern.matches(name))
+///     })
+///     .collect();
+/// for handle in handles {
+///     assert!(handle.join().unwrap());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ArcGlobPattern(Arc<GlobPattern>);
+impl ArcGlobPattern {
+    /// Share `pattern` between its handles.
+    #[inline]
+    pub fn new(pattern: GlobPattern) -> Self {
+        Self(Arc::new(pattern))
+    }
+
+    /// Whether `self` and `other` are handles to the very same pattern.
+    #[inline]
+    pub fn ptr_eq(&self, other: &ArcGlobPattern) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Owned pattern, which is only copied if other handles to it remain.
+    #[inline]
+    pub fn into_pattern(self) -> GlobPattern {
+        Arc::unwrap_or_clone(self.0)
+    }
+}
+impl Deref for ArcGlobPattern {
+    type Target = GlobPattern;
+
+    #[inline]
+    fn deref(&self) -> &GlobPattern {
+        &self.0
+    }
+}
+impl From<GlobPattern> for ArcGlobPattern {
+    #[inline]
+    fn from(pattern: GlobPattern) -> Self {
+        Self::new(pattern)
+    }
+}
+
+impl GlobPattern {
+    /// Move the pattern behind a handle whose clones share it (see
+    /// [`ArcGlobPattern`]).
+    #[inline]
+    pub fn shared(self) -> ArcGlobPattern {
+        ArcGlobPattern::new(self)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{arc::ArcGlobPattern, GlobPattern};
+
+    #[test]
+    fn arc_glob_pattern() {
+        let pattern = GlobPattern::new("*.[ch]").unwrap();
+        let shared = pattern.clone().shared();
+        let clone = shared.clone();
+        assert!(clone.ptr_eq(&shared));
+        assert_eq!(clone.tokens().as_ptr(), shared.tokens().as_ptr());
+        assert!(clone.matches("main.c"));
+        assert!(!ArcGlobPattern::from(pattern.clone()).ptr_eq(&shared));
+        assert_eq!(*shared, pattern);
+
+        let tokens = shared.tokens().as_ptr();
+        drop(clone);
+        let owned = shared.into_pattern();
+        assert_eq!(owned.tokens().as_ptr(), tokens);
+    }
+}
@@ -0,0 +1,184 @@
+//! Nondeterministic automatons following the matching rules of patterns.
+
+use crate::{charcls::CharClass, GlobPattern, GlobToken};
+use alloc::{collections::BTreeSet, vec::Vec};
+
+/// Single step of a pattern, with literals split into their characters.
+#[derive(Debug, Clone)]
+pub(crate) enum Atom<'p> {
+    Char(char),
+    Class(&'p CharClass),
+    Single,
+    Seq,
+    AnyDirs,
+}
+
+/// Nondeterministic automaton following the matching rules of a pattern.
+///
+/// States are an atom index, along with whether a `**/` is in the middle of a
+/// directory name; a pattern is matched once the last atom is passed.
+#[derive(Debug, Clone)]
+pub(crate) struct Automaton<'p> {
+    pub(crate) atoms: Vec<Atom<'p>>,
+    pub(crate) pattern: &'p GlobPattern,
+    pub(crate) separator: char,
+}
+pub(crate) type States = BTreeSet<(usize, bool)>;
+impl<'p> Automaton<'p> {
+    pub(crate) fn new(pattern: &'p GlobPattern) -> Self {
+        let mut atoms = Vec::new();
+        for token in pattern.tokens() {
+            match token {
+                GlobToken::Literal(l) => atoms.extend(l.as_ref().chars().map(Atom::Char)),
+                GlobToken::CharClass(cc) => atoms.push(Atom::Class(cc)),
+                GlobToken::SingleWildcard => atoms.push(Atom::Single),
+                GlobToken::SeqWildcard => atoms.push(Atom::Seq),
+                GlobToken::AnyDirs => atoms.push(Atom::AnyDirs),
+            }
+        }
+        Self {
+            atoms,
+            pattern,
+            separator: pattern.options().effective_separator(),
+        }
+    }
+
+    pub(crate) fn start(&self) -> States {
+        self.closure([(0, false)])
+    }
+
+    pub(crate) fn accepts(&self, states: &States) -> bool {
+        states.contains(&(self.atoms.len(), false))
+    }
+
+    /// Whether `c` at the start of a segment makes it hidden, which wildcards
+    /// can't match.
+    pub(crate) fn hides(&self, c: char) -> bool {
+        c == '.' && !self.pattern.options().dotfiles
+    }
+
+    /// States reached from `states` by consuming `c`, with `hidden` if `c`
+    /// starts a hidden name.
+    pub(crate) fn step(&self, states: &States, c: char, hidden: bool) -> States {
+        let options = self.pattern.options();
+        let mut next = Vec::new();
+        for &(i, in_dir) in states {
+            if in_dir {
+                next.push((i, c != self.separator));
+                continue;
+            }
+            let Some(atom) = self.atoms.get(i) else {
+                continue;
+            };
+            match atom {
+                // A `*` can't match even nothing before a hidden name
+                Atom::Char(_) if hidden && i > 0 && matches!(self.atoms[i - 1], Atom::Seq) => {}
+                Atom::Char(l) if options.case_fold.eq(*l, c) => next.push((i + 1, false)),
+                Atom::Char(_) => {}
+                _ if hidden => {}
+                Atom::Class(cc) if cc.matches_decoded(Some(c), options.case_fold) => {
+                    next.push((i + 1, false))
+                }
+                Atom::Class(_) => {}
+                Atom::Single if c != self.separator => next.push((i + 1, false)),
+                Atom::Seq if c != self.separator => next.push((i, false)),
+                Atom::Single | Atom::Seq => {}
+                Atom::AnyDirs => next.push((i, c != self.separator)),
+            }
+        }
+        self.closure(next)
+    }
+
+    /// Add the states reachable without consuming anything, i.e. past any
+    /// wildcard matching nothing.
+    pub(crate) fn closure<I: IntoIterator<Item = (usize, bool)>>(&self, states: I) -> States {
+        let mut closure = States::new();
+        for (mut i, in_dir) in states {
+            closure.insert((i, in_dir));
+            while !in_dir && matches!(self.atoms.get(i), Some(Atom::Seq | Atom::AnyDirs)) {
+                i += 1;
+                closure.insert((i, false));
+            }
+        }
+        closure
+    }
+}
+
+/// Characters worth trying: those the automatons treat specially along with
+/// their neighbours (standing for the characters treated like them) and case
+/// variants.
+pub(crate) fn alphabet(automatons: &[&Automaton<'_>]) -> Vec<char> {
+    let mut special = BTreeSet::new();
+    for automaton in automatons {
+        let fold = automaton.pattern.options().case_fold;
+        special.insert(automaton.separator);
+        special.insert('.');
+        for atom in &automaton.atoms {
+            match atom {
+                Atom::Char(c) => special.extend(fold.variants(*c)),
+                Atom::Class(cc) => {
+                    for cct in cc.types() {
+                        let range = cct.as_range();
+                        special.extend(fold.variants(*range.start()));
+                        special.extend(fold.variants(*range.end()));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // 'a' stands for most characters, and is tried first along with the other
+    // letters and digits so the witness reads nicely
+    let mut alphabet = BTreeSet::from(['a']);
+    for c in special {
+        alphabet.insert(c);
+        alphabet.extend(char::from_u32(c as u32 + 1));
+        alphabet.extend((c as u32).checked_sub(1).and_then(char::from_u32));
+    }
+    let mut alphabet: Vec<char> = alphabet.into_iter().collect();
+    alphabet.sort_by_key(|c| (!c.is_ascii_alphabetic(), !c.is_ascii_digit(), *c));
+    alphabet
+}
+
+/// Whether the automaton matches exactly the same names as
+/// [`GlobPattern::matches()`].
+///
+/// Like golang's `path.Match`, matching only tries the earliest position
+/// where the tokens following a `*` match when more wildcards follow them,
+/// while the automaton tries every position. That's only the same if the
+/// tokens can't match a separator (the `*` can't skip one to reach a later
+/// position) unless they're followed by `**/` (which can only skip whole
+/// directories), in which case they must contain a literal separator (only
+/// matching at a single position).
+pub(crate) fn is_exact(pattern: &GlobPattern) -> bool {
+    let options = pattern.options();
+    let separator = options.effective_separator();
+    let tokens = pattern.tokens();
+    for (i, token) in tokens.iter().enumerate() {
+        if !matches!(token, GlobToken::SeqWildcard) {
+            continue;
+        }
+        let mut chunk = tokens[i + 1..].iter();
+        let mut literal_separator = false;
+        let mut class_separator = false;
+        let end = loop {
+            match chunk.next() {
+                Some(GlobToken::Literal(l)) => {
+                    literal_separator |= l.as_ref().contains(separator);
+                }
+                Some(GlobToken::CharClass(cc)) => {
+                    class_separator |= cc.matches_decoded(Some(separator), options.case_fold);
+                }
+                Some(GlobToken::SingleWildcard) => {}
+                end => break end,
+            }
+        };
+        match end {
+            Some(GlobToken::SeqWildcard) if class_separator => return false,
+            Some(GlobToken::AnyDirs) if class_separator || !literal_separator => return false,
+            _ => {}
+        }
+    }
+    true
+}
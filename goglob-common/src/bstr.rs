@@ -0,0 +1,120 @@
+//! [bstr](https://docs.rs/bstr) integration, capturing and filtering lines of
+//! byte strings which may not be valid UTF-8 (e.g. paths and lines produced
+//! by git).
+//!
+//! As with [`GlobPattern::matches_bytes()`], bytes which can't be decoded as
+//! UTF-8 are each treated as a single character which isn't `/`: they're
+//! matched by `?`, `*` and negated character classes, but never by literals
+//! or non-negated classes.
+
+use crate::GlobPattern;
+use alloc::vec::Vec;
+use bstr::{BStr, ByteSlice};
+#[cfg(feature = "std")]
+use {bstr::BString, std::io};
+
+impl GlobPattern {
+    /// Byte string counterpart of [`captures()`][GlobPattern::captures].
+    pub fn captures_bytes<'a>(&self, name: &'a [u8]) -> Option<Vec<&'a BStr>> {
+        let mut captures = Vec::new();
+        self.match_tokens(name, Some(&mut captures))
+            .then(|| captures.into_iter().map(BStr::new).collect())
+    }
+
+    /// Iterate over the lines of `haystack` (terminated by `\n` or `\r\n`)
+    /// matching the pattern, without their terminators.
+    pub fn filter_byte_str_lines<'a>(
+        &'a self,
+        haystack: &'a [u8],
+    ) -> impl Iterator<Item = &'a BStr> + 'a {
+        haystack
+            .lines()
+            .filter(move |line| self.matches_bytes(line))
+            .map(BStr::new)
+    }
+
+    /// Filter lines read with [`BufReadExt::byte_lines()`][bstr::io::BufReadExt::byte_lines],
+    /// keeping the ones matching the pattern as well as any I/O error.
+    #[cfg(feature = "std")]
+    pub fn filter_byte_lines<'a, I>(
+        &'a self,
+        lines: I,
+    ) -> impl Iterator<Item = io::Result<BString>> + 'a
+    where
+        I: IntoIterator<Item = io::Result<Vec<u8>>>,
+        I::IntoIter: 'a,
+    {
+        lines.into_iter().filter_map(move |line| match line {
+            Ok(line) => self.matches_bytes(&line).then(|| Ok(line.into())),
+            Err(e) => Some(Err(e)),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{fixed::FixedGlobPattern, GlobPattern};
+    use bstr::{BStr, ByteSlice};
+    use goglob_testutil::GO_MATCH_VECTORS;
+
+    #[test]
+    fn matches_bytes_go_match_test() {
+        for vector in GO_MATCH_VECTORS {
+            let Some(expected) = vector.expected else {
+                continue;
+            };
+            let pattern = GlobPattern::new(vector.pattern).unwrap();
+            assert_eq!(
+                pattern.matches_bytes(vector.name.as_bytes()),
+                expected,
+                "({}, {})",
+                vector.pattern,
+                vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn matches_bytes_invalid_utf8() {
+        let pattern = GlobPattern::new("caf?.txt").unwrap();
+        assert!(pattern.matches_bytes(b"caf\xe9.txt"));
+        assert!(pattern.matches_bytes(BStr::new("café.txt")));
+        assert!(!pattern.matches_bytes(b"caf\xe9\xe9.txt"));
+
+        let pattern = GlobPattern::new("*/[^a]").unwrap();
+        assert!(pattern.matches_bytes(b"\xff\xfe/\x80"));
+        assert!(!GlobPattern::new("[a-z]").unwrap().matches_bytes(b"\xe9"));
+
+        let pattern = GlobPattern::new("*.*").unwrap();
+        assert_eq!(
+            pattern.captures_bytes(b"\xffa.b.c"),
+            Some(vec![BStr::new(b"\xffa"), BStr::new("b.c")])
+        );
+
+        let pattern = FixedGlobPattern::<4, 16>::new("?[^/]").unwrap();
+        assert!(pattern.matches_bytes(b"\xc3\xa9\xc3"));
+        assert!(!pattern.matches_bytes(b"\xc3\xa9/"));
+    }
+
+    #[test]
+    fn filter_byte_str_lines() {
+        let pattern = GlobPattern::new("*.rs").unwrap();
+        let input = b"lib.rs\r\nREADME.md\nm\xffin.rs\nsrc/lib.rs\n";
+        let lines: Vec<_> = pattern.filter_byte_str_lines(input).collect();
+        assert_eq!(lines, [BStr::new("lib.rs"), BStr::new(b"m\xffin.rs")]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn filter_byte_lines() {
+        use bstr::io::BufReadExt;
+
+        let pattern = GlobPattern::new("*.rs").unwrap();
+        let input = b"lib.rs\r\nREADME.md\nm\xffin.rs\nsrc/lib.rs\n";
+        let lines: Vec<_> = pattern
+            .filter_byte_lines(input.as_slice().byte_lines())
+            .map(|line| line.unwrap())
+            .collect();
+        assert_eq!(lines, [b"lib.rs".as_bstr(), b"m\xffin.rs".as_bstr()]);
+    }
+}
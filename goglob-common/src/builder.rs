@@ -0,0 +1,287 @@
+//! Configurable compilation of [`GlobPattern`]s.
+
+use crate::{
+    complexity::ComplexityLimits, limits::ParserLimits, options::MatchOptions, syntax::Syntax,
+    GlobPattern, Result,
+};
+
+/// Compiles patterns with non-default settings, such as another [`Syntax`].
+///
+/// The same builder can compile any number of patterns.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct GlobPatternBuilder {
+    options: MatchOptions,
+    limits: ComplexityLimits,
+    parser_limits: ParserLimits,
+}
+impl GlobPatternBuilder {
+    /// Default settings, compiling patterns exactly like
+    /// [`GlobPattern::new()`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            options: MatchOptions::new(),
+            limits: ComplexityLimits::new(),
+            parser_limits: ParserLimits::new(),
+        }
+    }
+
+    /// Dialect patterns are parsed as.
+    #[inline]
+    pub fn syntax(mut self, syntax: Syntax) -> Self {
+        self.options = self.options.syntax(syntax);
+        self
+    }
+
+    /// Characters acting as the `*` and `?` wildcards, for foreign dialects
+    /// (e.g. `%` and `_` for SQL `LIKE`-style patterns).
+    #[inline]
+    pub fn wildcards(mut self, seq_wildcard: char, single_wildcard: char) -> Self {
+        self.options = self
+            .options
+            .seq_wildcard(seq_wildcard)
+            .single_wildcard(single_wildcard);
+        self
+    }
+
+    /// Character escaping the next one, or `None` to disable escapes
+    /// altogether.
+    #[inline]
+    pub fn escape_char(mut self, escape_char: Option<char>) -> Self {
+        self.options = match escape_char {
+            Some(c) => self.options.escapes(true).escape_char(c),
+            None => self.options.escapes(false),
+        };
+        self
+    }
+
+    /// Whether `[` opens a character class (as it does by default), instead
+    /// of being a literal character.
+    #[inline]
+    pub fn char_classes(mut self, char_classes: bool) -> Self {
+        self.options = self.options.char_classes(char_classes);
+        self
+    }
+
+    /// Replace every setting with `options`.
+    #[inline]
+    pub fn options(mut self, options: MatchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Bounds on the complexity of compiled patterns, for patterns coming from
+    /// untrusted sources. None by default.
+    #[inline]
+    pub fn limits(mut self, limits: ComplexityLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Hard limits enforced by the parser, each failing with a dedicated
+    /// [error type][crate::error::ErrorType]. None by default.
+    #[inline]
+    pub fn parser_limits(mut self, parser_limits: ParserLimits) -> Self {
+        self.parser_limits = parser_limits;
+        self
+    }
+
+    /// Compile `pattern` with the configured settings, returning an
+    /// [error][crate::error::Error] if it's syntactically invalid or exceeds
+    /// the [limits][Self::limits] or [parser limits][Self::parser_limits].
+    #[inline]
+    pub fn build<S: AsRef<str>>(&self, pattern: S) -> Result<GlobPattern> {
+        GlobPattern::compile(
+            pattern.as_ref(),
+            &self.options,
+            &self.limits,
+            &self.parser_limits,
+        )
+    }
+}
+
+impl GlobPattern {
+    /// Start configuring how to compile a pattern (e.g.
+    /// `GlobPattern::builder().syntax(Syntax::Glob7).build("[!a]*")`).
+    #[inline]
+    pub fn builder() -> GlobPatternBuilder {
+        GlobPatternBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        builder::GlobPatternBuilder,
+        error::{Error, ErrorType},
+        options::MatchOptions,
+        syntax::Syntax,
+        GlobPattern,
+    };
+
+    fn matches(syntax: Syntax, pattern: &str, name: &str) -> bool {
+        GlobPattern::builder()
+            .syntax(syntax)
+            .build(pattern)
+            .unwrap()
+            .matches(name)
+    }
+
+    fn error(syntax: Syntax, pattern: &str) -> Error {
+        GlobPattern::builder()
+            .syntax(syntax)
+            .build(pattern)
+            .unwrap_err()
+    }
+
+    #[test]
+    fn builder_default_syntax() {
+        let builder = GlobPatternBuilder::default();
+        assert_eq!(
+            builder.build("a[^b]*").unwrap(),
+            GlobPattern::new("a[^b]*").unwrap()
+        );
+        assert!(matches(Syntax::GoFilepathMatch, "a\\*", "a*") != cfg!(windows));
+        assert!(matches(Syntax::GoPathMatch, "[!a]", "!"));
+        assert!(!matches(Syntax::GoPathMatch, "a/**/b", "a/b"));
+        let error = error(Syntax::GoPathMatch, "[]a]");
+        assert!(matches!(error.error_type(), ErrorType::UnescapedChar(']')));
+        assert_eq!(error.position(), 1);
+    }
+
+    #[test]
+    fn builder_glob7_syntax() {
+        assert!(matches(Syntax::Glob7, "[!a]", "b"));
+        assert!(!matches(Syntax::Glob7, "[!a]", "a"));
+        assert!(matches(Syntax::Glob7, "[^a]", "b"));
+        assert!(matches(Syntax::Glob7, "[]a]", "]"));
+        assert!(matches(Syntax::Glob7, "[!]a]", "b"));
+        assert!(matches(Syntax::Glob7, "[-a]", "-"));
+        assert!(matches(Syntax::Glob7, "[a-]", "-"));
+        assert!(matches(Syntax::Glob7, "[a-c-]", "b"));
+        assert!(matches(Syntax::Glob7, "a[b", "a[b"));
+        assert!(matches(Syntax::Glob7, "?[b", "a[b"));
+        assert!(matches(Syntax::Glob7, "a]b", "a]b"));
+        assert!(matches(Syntax::Glob7, "a[\\]b", "a[]b"));
+        assert!(matches(Syntax::Glob7, "[\\]]", "]"));
+        assert!(!matches(Syntax::Glob7, "a/**/b", "a/b"));
+    }
+
+    #[test]
+    fn builder_gitignore_syntax() {
+        assert!(matches(Syntax::Gitignore, "[!a]", "b"));
+        let error = error(Syntax::Gitignore, "a[b");
+        assert!(matches!(error.error_type(), ErrorType::UnclosedCharClass));
+        assert_eq!(error.position(), 1);
+
+        for name in ["a", "x/a", "x/y/a"] {
+            assert!(matches(Syntax::Gitignore, "**/a", name), "{name}");
+        }
+        assert!(!matches(Syntax::Gitignore, "**/a", "xa"));
+        for name in ["a/b", "a/x/b", "a/x/y/b"] {
+            assert!(matches(Syntax::Gitignore, "a/**/b", name), "{name}");
+        }
+        assert!(!matches(Syntax::Gitignore, "a/**/b", "ab"));
+        assert!(!matches(Syntax::Gitignore, "a/**/b", "a/xb"));
+        for name in ["a/x", "a/x/y"] {
+            assert!(matches(Syntax::Gitignore, "a/**", name), "{name}");
+        }
+        assert!(!matches(Syntax::Gitignore, "a/**", "b/x"));
+        assert!(matches(Syntax::Gitignore, "**", "a/b/c"));
+        assert!(matches(Syntax::Gitignore, "a/**/**/b", "a/x/y/b"));
+
+        // '**/' can't just match as little as possible
+        assert!(matches(Syntax::Gitignore, "**/a/*", "a/x/a/y"));
+        assert!(matches(Syntax::Gitignore, "a/**/*.rs", "a/x/y/z.rs"));
+        assert!(!matches(Syntax::Gitignore, "a/**/*.rs", "a/x/y/z.rs/w"));
+
+        // Other '**' are regular '*'
+        assert!(matches(Syntax::Gitignore, "a**", "abc"));
+        assert!(!matches(Syntax::Gitignore, "a**", "ab/c"));
+        assert!(!matches(Syntax::Gitignore, "**a/b", "x/ya/b"));
+
+        let pattern = GlobPattern::builder()
+            .syntax(Syntax::Gitignore)
+            .build("src/**/*.rs")
+            .unwrap();
+        assert_eq!(pattern.captures("src/a/b/c.rs"), Some(vec!["a/b/", "c"]));
+        assert_eq!(pattern.segments().len(), 3);
+        assert_eq!(pattern.min_len(), 7);
+        assert_eq!(pattern.max_len(), None);
+    }
+
+    #[test]
+    fn builder_custom_metachars() {
+        let like = GlobPattern::builder()
+            .wildcards('%', '_')
+            .escape_char(Some('!'))
+            .char_classes(false);
+        let pattern = like.build("report_20%![x]!%.csv").unwrap();
+        assert!(pattern.matches("report-2024[x]%.csv"));
+        assert!(!pattern.matches("report-2024[x]-.csv"));
+        assert!(like.build("a*?\\b").unwrap().matches("a*?\\b"));
+        assert_eq!(
+            like.build("%.rs").unwrap().tokens(),
+            GlobPattern::new("*.rs").unwrap().tokens()
+        );
+        let error = like.build("a!").unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::IllegalEscape));
+
+        let no_escapes = GlobPattern::builder().escape_char(None);
+        assert!(no_escapes.build("a\\*").unwrap().matches("a\\b"));
+        let gitignore = GlobPattern::builder()
+            .syntax(Syntax::Gitignore)
+            .wildcards('#', '?');
+        assert!(gitignore.build("##/a#").unwrap().matches("x/y/ab"));
+        assert!(GlobPattern::builder()
+            .char_classes(false)
+            .build("a]")
+            .unwrap()
+            .matches("a]"));
+    }
+
+    #[test]
+    fn builder_conflicting_metachars() {
+        let conflict = |builder: &GlobPatternBuilder| {
+            let error = builder.build("a").unwrap_err();
+            assert_eq!(error.position(), usize::MAX);
+            match error.error_type() {
+                ErrorType::ConflictingMetachar(c) => *c,
+                error_type => panic!("{error_type} isn't about the options"),
+            }
+        };
+        let builder = GlobPattern::builder();
+        for (conflicting, c) in [
+            (builder.clone().wildcards('*', '*'), '*'),
+            (builder.clone().wildcards('?', '?'), '?'),
+            (builder.clone().wildcards('/', '?'), '/'),
+            (builder.clone().wildcards('*', '/'), '/'),
+            (builder.clone().escape_char(Some('*')), '*'),
+            (builder.clone().escape_char(Some('?')), '?'),
+            (builder.clone().wildcards('[', '?'), '['),
+            (builder.clone().wildcards('*', ']'), ']'),
+            (builder.clone().escape_char(Some(']')), ']'),
+        ] {
+            assert_eq!(conflict(&conflicting), c);
+        }
+        let error = builder.clone().wildcards('?', '?').build("a").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "metacharacter ? has more than one meaning"
+        );
+
+        // Only characters which are special with these options may clash
+        let plain = builder.escape_char(None).char_classes(false);
+        assert!(plain.wildcards('\\', '[').build("a\\").is_ok());
+        let options = MatchOptions::new().separator(':').seq_wildcard('/');
+        assert!(GlobPattern::new_with("a/b", &options)
+            .unwrap()
+            .matches("axyb"));
+        let escaped = MatchOptions::new().separator('\\');
+        assert!(GlobPattern::new_with("a\\\\*", &escaped)
+            .unwrap()
+            .matches("a\\b"));
+        let conflicting = GlobPattern::builder().options(options.single_wildcard(':'));
+        assert_eq!(conflict(&conflicting), ':');
+    }
+}
@@ -0,0 +1,219 @@
+//! Patterns matching arbitrary bytes rather than text.
+
+use crate::{
+    error::{Error, ErrorType},
+    matching::Haystack,
+    options::{CaseFold, MatchOptions},
+    GlobPattern, Result,
+};
+use alloc::{string::String, vec::Vec};
+
+/// Shell pattern matching byte strings, with the same syntax and behaviour as
+/// [`GlobPattern`] except that every byte is a character of its own: `?`
+/// matches any single byte (but `/`), literals and classes match byte values
+/// (`[\x80-\xff]` matches any byte with the high bit set) and neither the
+/// pattern nor the names need to be valid UTF-8.
+///
+/// This is meant for protocols and filesystems where names are opaque bytes,
+/// for which treating them as Unicode text would be wrong (e.g. `?` matching
+/// a multi-byte character). Names which happen to be UTF-8 text can be
+/// matched too, every non-ASCII character being several bytes.
+///
+/// ```
+/// use goglob_common::bytes::BytesGlobPattern;
+///
+/// let pattern = BytesGlobPattern::new(b"caf??.txt").unwrap();
+/// assert!(pattern.matches("café.txt"));
+/// assert!(pattern.matches(b"caf\xff\x00.txt"));
+///
+/// let pattern = BytesGlobPattern::new(b"[\x00-\x1f]*").unwrap();
+/// assert!(pattern.matches(b"\x1bname"));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BytesGlobPattern {
+    /// Pattern with every byte decoded as the character with the same value
+    /// (i.e. as Latin-1), matched against names decoded the same way.
+    pattern: GlobPattern,
+}
+impl BytesGlobPattern {
+    /// Compile the given `pattern`, returning an [error][crate::error::Error]
+    /// (positioned at a byte offset in `pattern`) if it's syntactically
+    /// invalid.
+    #[inline]
+    pub fn new<B: AsRef<[u8]>>(pattern: B) -> Result<Self> {
+        Self::new_with(pattern, &MatchOptions::new())
+    }
+
+    /// Same as [`new()`][Self::new], but parsing and matching `pattern`
+    /// according to `options` instead of the defaults. Only ASCII letters
+    /// match regardless of case with [`CaseFold::Unicode`], as with
+    /// [`CaseFold::Ascii`], since bytes have no meaning beyond ASCII.
+    pub fn new_with<B: AsRef<[u8]>>(pattern: B, options: &MatchOptions) -> Result<Self> {
+        let mut options = *options;
+        if options.case_fold == CaseFold::Unicode {
+            options.case_fold = CaseFold::Ascii;
+        }
+        let decoded: String = pattern.as_ref().iter().map(|&b| char::from(b)).collect();
+        GlobPattern::new_with(&decoded, &options)
+            .map(|pattern| Self { pattern })
+            .map_err(|e| byte_offset(e, &decoded))
+    }
+
+    /// Options the pattern was compiled with.
+    #[inline]
+    pub fn options(&self) -> &MatchOptions {
+        self.pattern.options()
+    }
+
+    /// Report whether the byte string `name` matches the compiled pattern.
+    #[inline]
+    pub fn matches<B: AsRef<[u8]>>(&self, name: B) -> bool {
+        self.pattern.match_tokens(Bytes(name.as_ref()), None)
+    }
+
+    /// Byte string counterpart of [`GlobPattern::captures()`]: the bytes
+    /// matched by each wildcard and character class, in order, if `name`
+    /// matches.
+    pub fn captures<'a>(&self, name: &'a [u8]) -> Option<Vec<&'a [u8]>> {
+        let mut captures = Vec::new();
+        self.pattern
+            .match_tokens(Bytes(name), Some(&mut captures))
+            .then(|| captures.into_iter().map(|capture| capture.0).collect())
+    }
+}
+
+/// Move the position of an error in the decoded pattern `decoded` to the
+/// offset of the same character in the original bytes.
+fn byte_offset(error: Error, decoded: &str) -> Error {
+    if let ErrorType::PatternTooLong = error.error_type() {
+        // Not a position in the pattern
+        return error;
+    }
+    match decoded.get(..error.position()) {
+        Some(before) => Error::new(*error.error_type(), before.chars().count()),
+        None => error,
+    }
+}
+
+/// Name whose every byte is a character with the same value.
+#[derive(Clone, Copy)]
+struct Bytes<'a>(&'a [u8]);
+impl Haystack for Bytes<'_> {
+    #[inline]
+    fn len(self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    fn next_char(self) -> Option<(Option<char>, Self)> {
+        let (&b, rest) = self.0.split_first()?;
+        Some((Some(char::from(b)), Bytes(rest)))
+    }
+
+    fn strip_literal(self, literal: &[u8]) -> Option<Self> {
+        let literal = core::str::from_utf8(literal).ok()?;
+        let mut rest = self.0;
+        for c in literal.chars() {
+            let (&b, after) = rest.split_first()?;
+            if char::from(b) != c {
+                return None;
+            }
+            rest = after;
+        }
+        Some(Bytes(rest))
+    }
+
+    #[inline]
+    fn split_at(self, mid: usize) -> (Self, Self) {
+        let (left, right) = self.0.split_at(mid);
+        (Bytes(left), Bytes(right))
+    }
+
+    fn find_char(self, c: char) -> Option<usize> {
+        let b = u8::try_from(c).ok()?;
+        self.0.iter().position(|&other| other == b)
+    }
+
+    fn ends_with_char(self, c: char) -> bool {
+        u8::try_from(c).is_ok_and(|b| self.0.last() == Some(&b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bytes::BytesGlobPattern,
+        error::ErrorType,
+        options::{CaseFold, MatchOptions},
+    };
+    use goglob_testutil::GO_MATCH_VECTORS;
+
+    #[test]
+    fn bytes_glob_pattern_go_match_test() {
+        // Only ASCII vectors, as '?' matches a single byte
+        for vector in GO_MATCH_VECTORS {
+            let Some(expected) = vector.expected else {
+                continue;
+            };
+            if !vector.pattern.is_ascii() || !vector.name.is_ascii() {
+                continue;
+            }
+            let pattern = BytesGlobPattern::new(vector.pattern).unwrap();
+            assert_eq!(
+                pattern.matches(vector.name),
+                expected,
+                "({}, {})",
+                vector.pattern,
+                vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn bytes_glob_pattern_matches() {
+        let pattern = BytesGlobPattern::new(b"\xff*[\x80-\x90]?").unwrap();
+        assert!(pattern.matches(b"\xff\x00\x85a"));
+        assert!(pattern.matches(b"\xffabc\x90\xff"));
+        assert!(!pattern.matches(b"\xff\x91a"));
+        assert!(!pattern.matches(b"\xff\x85/"));
+        assert!(!pattern.matches("ÿ\u{85}a"));
+
+        let pattern = BytesGlobPattern::new("é").unwrap();
+        assert!(pattern.matches("é"));
+        assert!(!pattern.matches(b"\xe9"));
+        assert!(BytesGlobPattern::new(b"?").unwrap().matches(b"\xe9"));
+        assert!(!BytesGlobPattern::new(b"?").unwrap().matches("é"));
+
+        let pattern = BytesGlobPattern::new(b"*.\\*").unwrap();
+        assert_eq!(
+            pattern.captures(b"a\xc3.*"),
+            Some(vec![b"a\xc3".as_slice()])
+        );
+        assert_eq!(pattern.captures(b"a.b"), None);
+    }
+
+    #[test]
+    fn bytes_glob_pattern_options() {
+        let options = MatchOptions::new().case_fold(CaseFold::Unicode);
+        let pattern = BytesGlobPattern::new_with(b"A\xc9*", &options).unwrap();
+        assert_eq!(pattern.options().case_fold, CaseFold::Ascii);
+        assert!(pattern.matches(b"a\xc9b"));
+        assert!(!pattern.matches(b"a\xe9b"));
+
+        let options = MatchOptions::new().dotfiles(false);
+        let pattern = BytesGlobPattern::new_with(b"*", &options).unwrap();
+        assert!(!pattern.matches(b".\xff"));
+    }
+
+    #[test]
+    fn bytes_glob_pattern_errors() {
+        let error = BytesGlobPattern::new(b"\xff\xfe[a").unwrap_err();
+        assert_eq!(
+            error.error_type().type_desc(),
+            ErrorType::UnclosedCharClass.type_desc()
+        );
+        assert_eq!(error.position(), 2);
+        let error = BytesGlobPattern::new(b"\xff]").unwrap_err();
+        assert_eq!(error.position(), 1);
+    }
+}
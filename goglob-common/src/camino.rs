@@ -0,0 +1,37 @@
+//! [camino](https://docs.rs/camino) integration, matching [`Utf8Path`]s
+//! directly instead of going through [`Utf8Path::as_str()`].
+
+use crate::GlobPattern;
+use camino::Utf8Path;
+
+impl GlobPattern {
+    /// Report whether `path` matches the compiled shell pattern.
+    ///
+    /// The path is matched as-is (unless
+    /// [`MatchOptions::normalize_paths()`][crate::options::MatchOptions::normalize_paths]
+    /// is enabled, as with [`matches_path()`][Self::matches_path]), so its
+    /// components must be separated by `/` to be matched against the segments
+    /// of the pattern.
+    #[inline]
+    pub fn matches_utf8_path<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        self.matches(self.normalize_path(path.as_ref().as_str()))
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{options::MatchOptions, GlobPattern};
+    use camino::{Utf8Path, Utf8PathBuf};
+
+    #[test]
+    fn matches_utf8_path() {
+        let pattern = GlobPattern::new("src/*.rs").unwrap();
+        assert!(pattern.matches_utf8_path(Utf8Path::new("src/lib.rs")));
+        assert!(pattern.matches_utf8_path(Utf8PathBuf::from("src/main.rs")));
+        assert!(!pattern.matches_utf8_path(Utf8Path::new("src/bin/main.rs")));
+
+        let options = MatchOptions::new().normalize_paths(true);
+        let pattern = GlobPattern::new_with("src/*.rs", &options).unwrap();
+        assert!(pattern.matches_utf8_path(Utf8Path::new("./src//lib.rs")));
+    }
+}
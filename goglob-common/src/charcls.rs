@@ -1,10 +1,10 @@
 mod sealed {
     #![allow(non_camel_case_types)]
 
-    use std::cmp::Ordering;
-    use std::fmt::{self, Formatter, Write};
-    use std::hash::{Hash, Hasher};
-    use std::ops::RangeInclusive;
+    use core::cmp::Ordering;
+    use core::fmt::{self, Formatter, Write};
+    use core::hash::{Hash, Hasher};
+    use core::ops::RangeInclusive;
 
     #[derive(Default, Copy, Clone, Eq)]
     #[repr(transparent)]
@@ -37,7 +37,7 @@ mod sealed {
     }
     impl PartialOrd<char_sealed> for char_sealed {
         fn partial_cmp(&self, other: &char_sealed) -> Option<Ordering> {
-            self.0.partial_cmp(&other.0)
+            Some(self.cmp(other))
         }
 
         fn lt(&self, other: &char_sealed) -> bool {
@@ -79,12 +79,12 @@ mod sealed {
     }
     impl PartialEq<char_sealed> for char_sealed {
         fn eq(&self, other: &char_sealed) -> bool {
-            (&self.0).eq(&other.0)
+            self.0.eq(&other.0)
         }
     }
     impl PartialEq<char> for char_sealed {
         fn eq(&self, other: &char) -> bool {
-            (&self.0).eq(other)
+            self.0.eq(other)
         }
     }
     impl Hash for char_sealed {
@@ -106,7 +106,7 @@ mod sealed {
         }
     }
     impl fmt::Display for char_sealed {
-        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
             f.write_char(self.0)
         }
     }
@@ -116,12 +116,12 @@ mod sealed {
     pub struct RangeInclusive_char_sealed(pub RangeInclusive<char>);
     impl PartialEq<RangeInclusive_char_sealed> for RangeInclusive_char_sealed {
         fn eq(&self, other: &RangeInclusive_char_sealed) -> bool {
-            (&self.0).eq(&other.0)
+            self.0 == other.0
         }
     }
     impl PartialEq<RangeInclusive<char>> for RangeInclusive_char_sealed {
         fn eq(&self, other: &RangeInclusive<char>) -> bool {
-            (&self.0).eq(other)
+            self.0 == *other
         }
     }
     impl Hash for RangeInclusive_char_sealed {
@@ -144,21 +144,24 @@ mod sealed {
     }
 }
 
-use crate::charcls::sealed::{char_sealed, RangeInclusive_char_sealed};
-use core::ops::RangeInclusive;
-use std::borrow::Cow;
+use crate::{
+    charcls::sealed::{char_sealed, RangeInclusive_char_sealed},
+    options::CaseFold,
+};
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use core::fmt::{self, Formatter, Write};
+use core::ops::{Deref, RangeInclusive};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CharClass {
     negated: bool,
-    matches: Cow<'static, [CharClassType]>,
+    matches: Table,
 }
 impl CharClass {
-    pub fn new(negated: bool, mut matches: Vec<CharClassType>) -> Self {
-        matches.shrink_to_fit();
+    pub fn new(negated: bool, matches: Vec<CharClassType>) -> Self {
         Self {
             negated,
-            matches: Cow::Owned(matches),
+            matches: Table::Shared(matches.into()),
         }
     }
 
@@ -166,24 +169,236 @@ impl CharClass {
         self.negated
     }
 
+    /// Entries (single characters or ranges) listed in the class, in order.
+    pub fn types(&self) -> &[CharClassType] {
+        &self.matches
+    }
+
+    /// Class matching what either `self` or `other` matches.
+    pub fn union(&self, other: &CharClass) -> CharClass {
+        let ranges = merged([self.matched_ranges(), other.matched_ranges()].concat());
+        Self::from_matched(ranges, self.negated || other.negated)
+    }
+
+    /// Class matching what both `self` and `other` match, which may be empty
+    /// (i.e. match nothing).
+    pub fn intersection(&self, other: &CharClass) -> CharClass {
+        self.negation().union(&other.negation()).negation()
+    }
+
+    /// Class matching what `self` matches but `other` doesn't (e.g. `[a-z]`
+    /// minus `[aeiou]` is `[b-df-hj-np-tv-z]`), which may be empty.
+    pub fn difference(&self, other: &CharClass) -> CharClass {
+        self.intersection(&other.negation())
+    }
+
+    /// Class matching exactly what `self` doesn't.
+    pub fn negation(&self) -> CharClass {
+        Self::from_matched(complement(&self.matched_ranges()), !self.negated)
+    }
+
+    /// Sorted, non-overlapping and non-adjacent ranges of the characters the
+    /// class lists.
+    pub(crate) fn listed_ranges(&self) -> Vec<(char, char)> {
+        merged(
+            self.matches
+                .iter()
+                .map(|cct| {
+                    let range = cct.as_range();
+                    (*range.start(), *range.end())
+                })
+                .collect(),
+        )
+    }
+    /// Sorted, non-overlapping and non-adjacent ranges of the characters the
+    /// class matches.
+    fn matched_ranges(&self) -> Vec<(char, char)> {
+        match self.negated {
+            false => self.listed_ranges(),
+            true => complement(&self.listed_ranges()),
+        }
+    }
+    /// Normalized class matching the characters in `ranges`, and undecodable
+    /// bytes if `negated` (which only negated classes match).
+    fn from_matched(ranges: Vec<(char, char)>, negated: bool) -> CharClass {
+        let ranges = match negated {
+            false => ranges,
+            true => complement(&ranges),
+        };
+        Self::from_ranges(negated, ranges)
+    }
+    /// Class listing the given non-empty `ranges`.
+    pub(crate) fn from_ranges(negated: bool, ranges: Vec<(char, char)>) -> CharClass {
+        let matches = ranges
+            .into_iter()
+            .map(|(start, end)| {
+                if start == end {
+                    CharClassType::from(start)
+                } else {
+                    CharClassType::try_from(start..=end).expect("ranges are never empty")
+                }
+            })
+            .collect();
+        CharClass::new(negated, matches)
+    }
+
     pub fn matches_next<'a>(&self, name: &'a str) -> Option<&'a str> {
         name.strip_prefix(|c| self.matches_char(c))
     }
+    /// Whether the class matches a character (or any of its variants under
+    /// `fold`), or an undecodable byte if `None` (which only negated classes
+    /// do).
+    pub(crate) fn matches_decoded(&self, character: Option<char>, fold: CaseFold) -> bool {
+        character.map_or(self.negated, |c| {
+            fold.variants(c).into_iter().any(|c| self.contains(c)) != self.negated
+        })
+    }
     fn matches_char(&self, character: char) -> bool {
-        self.matches.iter().any(|cct| cct.matches(character)) != self.negated
+        self.contains(character) != self.negated
+    }
+    fn contains(&self, character: char) -> bool {
+        self.matches.iter().any(|cct| cct.matches(character))
+    }
+
+    /// Whether the class only lists ASCII characters.
+    pub(crate) fn is_ascii(&self) -> bool {
+        types_are_ascii(&self.matches)
+    }
+
+    /// Same as [`is_ascii()`][Self::is_ascii] for classes compiled into the
+    /// binary by the `glob!` macro, `false` for any other.
+    pub(crate) const fn is_static_ascii(&self) -> bool {
+        match self.matches {
+            Table::Static(matches) => types_are_ascii(matches),
+            Table::Shared(_) => false,
+        }
+    }
+
+    /// Whether the class was compiled into the binary by the `glob!` macro.
+    pub(crate) fn is_static(&self) -> bool {
+        matches!(self.matches, Table::Static(_))
+    }
+
+    /// Whether `self` and `other` use the very same table of entries (see
+    /// [`ClassTables`]).
+    #[cfg(all(test, feature = "parser"))]
+    pub(crate) fn shares_table(&self, other: &CharClass) -> bool {
+        core::ptr::eq(self.matches.as_ptr(), other.matches.as_ptr())
+    }
+
+    /// Write the class in pattern syntax, escaping its special characters with
+    /// `escape` (if any). With `posix` classes, a leading `!` is escaped too.
+    pub(crate) fn fmt_escaped(
+        &self,
+        f: &mut Formatter<'_>,
+        escape: Option<char>,
+        posix: bool,
+    ) -> fmt::Result {
+        let write_escaped = |f: &mut Formatter<'_>, c: char, first: bool| {
+            let special = matches!(c, '-' | ']')
+                || Some(c) == escape
+                || (first && (c == '^' || (c == '!' && posix)));
+            match escape {
+                Some(escape) if special => f.write_char(escape)?,
+                _ => {}
+            }
+            f.write_char(c)
+        };
+
+        f.write_char('[')?;
+        if self.negated {
+            f.write_char('^')?;
+        }
+        for (i, cct) in self.matches.iter().enumerate() {
+            let range = cct.as_range();
+            write_escaped(f, *range.start(), i == 0 && !self.negated)?;
+            if let CharClassType::Range(_) = cct {
+                f.write_char('-')?;
+                write_escaped(f, *range.end(), false)?;
+            }
+        }
+        f.write_char(']')
+    }
+}
+
+/// Entries of a [`CharClass`], either compiled into the binary by the `glob!`
+/// macro or allocated at runtime and possibly shared with other classes.
+#[derive(Clone)]
+enum Table {
+    Static(&'static [CharClassType]),
+    Shared(Arc<[CharClassType]>),
+}
+impl Deref for Table {
+    type Target = [CharClassType];
+
+    #[inline]
+    fn deref(&self) -> &[CharClassType] {
+        match self {
+            Table::Static(matches) => matches,
+            Table::Shared(matches) => matches,
+        }
+    }
+}
+impl PartialEq for Table {
+    #[inline]
+    fn eq(&self, other: &Table) -> bool {
+        **self == **other
+    }
+}
+impl Eq for Table {}
+impl fmt::Debug for Table {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Tables of entries shared by the character classes listing the same ones
+/// (e.g. the `[0-9]` of thousands of machine-generated patterns), so that
+/// each is only stored once.
+#[derive(Debug, Default)]
+pub(crate) struct ClassTables(BTreeMap<Vec<(char, char)>, Arc<[CharClassType]>>);
+impl ClassTables {
+    /// Make `class` use the table of the classes interned before it which
+    /// list the same entries, if any. Tables compiled into the binary are
+    /// left alone, as they don't take any memory.
+    pub(crate) fn intern(&mut self, class: &mut CharClass) {
+        let table = match &class.matches {
+            Table::Static(_) => return,
+            Table::Shared(table) => table,
+        };
+        let entries = table
+            .iter()
+            .map(|cct| {
+                let range = cct.as_range();
+                (*range.start(), *range.end())
+            })
+            .collect();
+        let shared = self.0.entry(entries).or_insert_with(|| table.clone());
+        class.matches = Table::Shared(shared.clone());
+    }
+
+    /// Number of distinct tables interned.
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+impl fmt::Display for CharClass {
+    /// Render the class back into pattern syntax (e.g. `[^a-c\-]`).
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_escaped(f, Some('\\'), false)
     }
 }
 impl IntoIterator for CharClass {
     type Item = CharClassType;
-    type IntoIter = std::vec::IntoIter<CharClassType>;
+    type IntoIter = alloc::vec::IntoIter<CharClassType>;
 
-    // `into_owned()` is necessary as the underlying object is dropped.
+    // `to_vec()` is necessary as the underlying table may be shared.
     // CharClass' `into_iter(self)` is only used internally by
     // `goglob-proc-macro`, meaning these heap allocations only happen
     // at compile-time anyway, so it shouldn't be of much concern.
     #[allow(clippy::unnecessary_to_owned)]
     fn into_iter(self) -> Self::IntoIter {
-        self.matches.into_owned().into_iter()
+        self.matches.to_vec().into_iter()
     }
 }
 
@@ -193,6 +408,17 @@ pub enum CharClassType {
     Range(sealed::RangeInclusive_char_sealed),
 }
 impl CharClassType {
+    /// Characters matched by this entry as an inclusive range (a single
+    /// character `c` being `c..=c`).
+    pub fn as_range(&self) -> RangeInclusive<char> {
+        match self {
+            CharClassType::Single(sealed::char_sealed(c)) => *c..=*c,
+            CharClassType::Range(sealed::RangeInclusive_char_sealed(range_match)) => {
+                range_match.clone()
+            }
+        }
+    }
+
     fn matches(&self, character: char) -> bool {
         match self {
             CharClassType::Single(sealed::char_sealed(char_match)) => *char_match == character,
@@ -212,7 +438,7 @@ impl TryFrom<RangeInclusive<char>> for CharClassType {
 
     fn try_from(range_match: RangeInclusive<char>) -> Result<Self, Self::Error> {
         (!range_match.is_empty())
-            .then(|| range_match)
+            .then_some(range_match)
             .map(sealed::RangeInclusive_char_sealed)
             .map(Self::Range)
             .ok_or(())
@@ -229,6 +455,71 @@ impl From<RangeInclusive_char_sealed> for RangeInclusive<char> {
     }
 }
 
+/// Whether every entry of `types` only lists ASCII characters.
+const fn types_are_ascii(types: &[CharClassType]) -> bool {
+    let mut i = 0;
+    while i < types.len() {
+        let last = match &types[i] {
+            CharClassType::Single(c) => c.0,
+            CharClassType::Range(range) => *range.0.end(),
+        };
+        if !last.is_ascii() {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Sort `ranges` and merge the overlapping or adjacent ones.
+fn merged(mut ranges: Vec<(char, char)>) -> Vec<(char, char)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(char, char)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, last_end)) if char_after(*last_end).is_none_or(|next| start <= next) => {
+                *last_end = (*last_end).max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Ranges of the characters not in the sorted and merged `ranges`.
+fn complement(ranges: &[(char, char)]) -> Vec<(char, char)> {
+    let mut complement = Vec::with_capacity(ranges.len() + 1);
+    let mut next = Some('\0');
+    for &(start, end) in ranges {
+        if let (Some(first), Some(last)) = (next, char_before(start)) {
+            if first <= last {
+                complement.push((first, last));
+            }
+        }
+        next = char_after(end);
+    }
+    if let Some(first) = next {
+        complement.push((first, char::MAX));
+    }
+    complement
+}
+
+/// Character right after `c`, skipping surrogates.
+fn char_after(c: char) -> Option<char> {
+    match c {
+        '\u{d7ff}' => Some('\u{e000}'),
+        c => char::from_u32(c as u32 + 1),
+    }
+}
+
+/// Character right before `c`, skipping surrogates.
+fn char_before(c: char) -> Option<char> {
+    match c {
+        '\u{e000}' => Some('\u{d7ff}'),
+        c => char::from_u32((c as u32).checked_sub(1)?),
+    }
+}
+
 /// Internal workspace-only function employed by `goglob-proc-macro`.
 ///
 /// The procedural macro will insert calls to this function in the end-user's project,
@@ -236,7 +527,7 @@ impl From<RangeInclusive_char_sealed> for RangeInclusive<char> {
 pub const fn from_static(negated: bool, matches: &'static [CharClassType]) -> CharClass {
     CharClass {
         negated,
-        matches: Cow::Borrowed(matches),
+        matches: Table::Static(matches),
     }
 }
 
@@ -320,6 +611,21 @@ mod tests {
         assert_eq!(class.matches_next("f"), Some(""));
     }
 
+    #[test]
+    fn charclass_display() {
+        let class = CharClass::new(
+            false,
+            vec!['a'.into(), ('c'..='e').try_into().unwrap(), '-'.into()],
+        );
+        assert_eq!(class.to_string(), "[ac-e\\-]");
+
+        let class = CharClass::new(true, vec!['^'.into(), (']'..='a').try_into().unwrap()]);
+        assert_eq!(class.to_string(), "[^^\\]-a]");
+
+        let class = CharClass::new(false, vec!['^'.into(), ('a'..='a').try_into().unwrap()]);
+        assert_eq!(class.to_string(), "[\\^a-a]");
+    }
+
     #[test]
     fn charclasstype_conversion() {
         let class_type: CharClassType = 'a'.into();
@@ -339,6 +645,49 @@ mod tests {
         assert!(!class_type.matches('b'));
 
         let class_type: Result<CharClassType, ()> = ('e'..='a').try_into();
-        let _ = class_type.err().unwrap();
+        class_type.err().unwrap();
+    }
+
+    #[test]
+    fn charclass_set_operations() {
+        let class = |negated, types: &[(char, char)]| {
+            let types = types
+                .iter()
+                .map(|&(start, end)| (start..=end).try_into().unwrap())
+                .collect();
+            CharClass::new(negated, types)
+        };
+        let lower = class(false, &[('a', 'z')]);
+        let vowels = class(
+            false,
+            &[('u', 'u'), ('a', 'a'), ('i', 'i'), ('e', 'e'), ('o', 'o')],
+        );
+
+        assert_eq!(lower.difference(&vowels).to_string(), "[b-df-hj-np-tv-z]");
+        assert_eq!(vowels.union(&lower).to_string(), "[a-z]");
+        assert_eq!(vowels.intersection(&lower).to_string(), "[aeiou]");
+        assert_eq!(vowels.negation().to_string(), "[^aeiou]");
+        assert_eq!(lower.negation().negation(), lower);
+        assert!(vowels.difference(&lower).types().is_empty());
+
+        // Adjacent and overlapping entries are merged
+        let digits = class(false, &[('5', '9'), ('0', '4'), ('2', '3')]);
+        assert_eq!(digits.union(&digits).to_string(), "[0-9]");
+
+        // Undecodable bytes are matched only by negated results
+        let not_a = class(true, &[('a', 'a')]);
+        assert_eq!(not_a.union(&vowels).to_string(), "[^]");
+        assert_eq!(not_a.intersection(&vowels).to_string(), "[eiou]");
+        assert_eq!(not_a.difference(&vowels).to_string(), "[^aeiou]");
+        assert_eq!(lower.union(&not_a).negation().to_string(), "[]");
+        assert_eq!(not_a.negation().to_string(), "[a]");
+
+        // Surrogates are skipped
+        let below = class(false, &[('\0', '\u{d7ff}')]);
+        let above = class(false, &[('\u{e000}', char::MAX)]);
+        assert_eq!(below.union(&above), class(false, &[('\0', char::MAX)]));
+        assert_eq!(below.difference(&above), below);
+        assert_eq!(below.negation().negation(), below);
+        assert_eq!(below.negation().intersection(&above), above);
     }
 }
@@ -0,0 +1,49 @@
+//! [clap](https://docs.rs/clap) integration, allowing arguments to be parsed
+//! directly as a [`GlobPattern`] (e.g. `--include <GLOB>`).
+
+use crate::GlobPattern;
+use clap::builder::{TypedValueParser, ValueParserFactory};
+use clap::error::{Error, ErrorKind};
+use clap::{Arg, Command};
+use std::ffi::OsStr;
+
+/// Value parser compiling arguments into a [`GlobPattern`], reporting syntax
+/// errors with a caret pointing at the offending character.
+///
+/// It's used automatically by `clap::value_parser!(GlobPattern)` (and therefore
+/// by `#[derive(Parser)]` fields of type `GlobPattern`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GlobPatternValueParser;
+impl GlobPatternValueParser {
+    #[inline]
+    pub fn new() -> Self {
+        Self
+    }
+}
+impl TypedValueParser for GlobPatternValueParser {
+    type Value = GlobPattern;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+        GlobPattern::new(value).map_err(|e| {
+            let arg = arg.map_or_else(|| "...".into(), ToString::to_string);
+            let message = format!("invalid pattern for '{arg}': {}\n", e.render(value));
+            Error::raw(ErrorKind::ValueValidation, message).with_cmd(cmd)
+        })
+    }
+}
+impl ValueParserFactory for GlobPattern {
+    type Parser = GlobPatternValueParser;
+
+    #[inline]
+    fn value_parser() -> Self::Parser {
+        GlobPatternValueParser
+    }
+}
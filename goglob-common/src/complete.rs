@@ -0,0 +1,209 @@
+//! Completion of partially typed names.
+
+use crate::{
+    automaton::{alphabet, Automaton},
+    GlobPattern,
+};
+use alloc::{collections::BTreeSet, vec::Vec};
+
+/// Whether a partially built name (e.g. the path of a directory being walked)
+/// matches a pattern or could still be extended into a match, as reported by
+/// [`GlobPattern::partial_match()`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PartialMatch {
+    /// The name matches the pattern itself.
+    Match,
+    /// The name doesn't match the pattern, but some extension of it may.
+    Prefix,
+    /// Neither the name nor any extension of it matches the pattern, so e.g.
+    /// a directory walk can skip the whole subtree.
+    NoMatch,
+}
+
+impl GlobPattern {
+    /// Report whether `partial` could still be extended into a name matching
+    /// the pattern (including by appending nothing), e.g. to narrow down
+    /// suggestions while a name is being typed: `sr` could become a match of
+    /// `src/*.rs`, while `src/a/` couldn't.
+    ///
+    /// This has the same caveat as [`witness()`][Self::witness] with
+    /// case-insensitive matching. Also, as matching commits to the earliest
+    /// position where the part of the pattern following a `*` matches if
+    /// other wildcards follow it, patterns where that part contains a class
+    /// matching the separator (e.g. `*[^x]b*`) may report that `partial`
+    /// could be extended when no extension actually matches.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let pattern = GlobPattern::new("src/*.rs").unwrap();
+    /// assert!(pattern.could_match_extension_of("sr"));
+    /// assert!(pattern.could_match_extension_of("src/lib.r"));
+    /// assert!(!pattern.could_match_extension_of("src/a/"));
+    /// assert!(!pattern.could_match_extension_of("tests"));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn could_match_extension_of<S: AsRef<str>>(&self, partial: S) -> bool {
+        self.partial_match(partial) != PartialMatch::NoMatch
+    }
+
+    /// Report whether `partial` matches the pattern, or else whether some
+    /// extension of it may, e.g. to prune subtrees while walking a tree: the
+    /// directory `tests` can be skipped when looking for `src/**/*.rs`, but
+    /// not `src/a`.
+    ///
+    /// This has the same caveats as
+    /// [`could_match_extension_of()`][Self::could_match_extension_of], i.e.
+    /// [`Prefix`][PartialMatch::Prefix] may be reported for a name no
+    /// extension of which matches, but never
+    /// [`NoMatch`][PartialMatch::NoMatch] for one which has a matching
+    /// extension.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::{complete::PartialMatch, syntax::Syntax, GlobPattern};
+    ///
+    /// let pattern = GlobPattern::builder()
+    ///     .syntax(Syntax::Gitignore)
+    ///     .build("src/**/*.rs")
+    ///     .unwrap();
+    /// assert_eq!(pattern.partial_match("src/a/lib.rs"), PartialMatch::Match);
+    /// assert_eq!(pattern.partial_match("src/a"), PartialMatch::Prefix);
+    /// assert_eq!(pattern.partial_match("tests"), PartialMatch::NoMatch);
+    /// # }
+    /// ```
+    pub fn partial_match<S: AsRef<str>>(&self, partial: S) -> PartialMatch {
+        let partial = partial.as_ref();
+        if self.matches(partial) {
+            PartialMatch::Match
+        } else if self.extension_could_match(partial) {
+            PartialMatch::Prefix
+        } else {
+            PartialMatch::NoMatch
+        }
+    }
+
+    /// Whether the automaton of the pattern can reach its end from the
+    /// states `partial` leads to.
+    fn extension_could_match(&self, partial: &str) -> bool {
+        let automaton = Automaton::new(self);
+        let mut states = automaton.start();
+        let mut segment_start = true;
+        for c in partial.chars() {
+            if states.is_empty() {
+                return false;
+            }
+            states = automaton.step(&states, c, segment_start && automaton.hides(c));
+            segment_start = c == automaton.separator;
+        }
+
+        // Whether the end can be reached doesn't depend on the other states,
+        // so each one is searched on its own
+        let alphabet = alphabet(&[&automaton]);
+        let mut seen = BTreeSet::new();
+        let mut pending: Vec<_> = states.into_iter().map(|s| (s, segment_start)).collect();
+        while let Some((state, segment_start)) = pending.pop() {
+            if !seen.insert((state, segment_start)) {
+                continue;
+            }
+            let state = automaton.closure([state]);
+            if automaton.accepts(&state) {
+                return true;
+            }
+            for &c in &alphabet {
+                let next = automaton.step(&state, c, segment_start && automaton.hides(c));
+                pending.extend(next.into_iter().map(|s| (s, c == automaton.separator)));
+            }
+        }
+        false
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        builder::GlobPatternBuilder, complete::PartialMatch, options::MatchOptions, syntax::Syntax,
+        GlobPattern,
+    };
+
+    #[test]
+    fn could_match_extension_of() {
+        let pattern = GlobPattern::new("src/*.[ch]").unwrap();
+        for partial in ["", "s", "src/", "src/main", "src/main.", "src/main.c"] {
+            assert!(pattern.could_match_extension_of(partial), "{partial}");
+        }
+        for partial in ["x", "src/a/", "src/main.c/", "srd"] {
+            assert!(!pattern.could_match_extension_of(partial), "{partial}");
+        }
+
+        let hidden = GlobPatternBuilder::new().options(MatchOptions::new().dotfiles(false));
+        let pattern = hidden.build("a/*").unwrap();
+        assert!(pattern.could_match_extension_of("a/"));
+        assert!(!pattern.could_match_extension_of("a/."));
+        assert!(hidden.build("?.").unwrap().could_match_extension_of(""));
+        assert!(!hidden.build("[^x]").unwrap().could_match_extension_of("."));
+
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        let pattern = gitignore.build("src/**/*.rs").unwrap();
+        assert!(pattern.could_match_extension_of("src/a/b/lib"));
+        assert!(pattern.could_match_extension_of("src/a/b/lib.rs/c"));
+        assert!(!pattern.could_match_extension_of("tests/a"));
+        let pattern = GlobPattern::new("[^\0-\u{10ffff}]").unwrap();
+        assert!(!pattern.could_match_extension_of(""));
+    }
+
+    #[test]
+    fn partial_match() {
+        let pattern = GlobPattern::new("src/*.[ch]").unwrap();
+        for (partial, expected) in [
+            ("", PartialMatch::Prefix),
+            ("src/main.c", PartialMatch::Match),
+            ("src/main.", PartialMatch::Prefix),
+            ("src/main.c/", PartialMatch::NoMatch),
+            ("srd", PartialMatch::NoMatch),
+        ] {
+            assert_eq!(pattern.partial_match(partial), expected, "{partial}");
+        }
+        let pattern = GlobPattern::new("src*").unwrap();
+        assert_eq!(pattern.partial_match("src"), PartialMatch::Match);
+        assert_eq!(pattern.partial_match("src/"), PartialMatch::NoMatch);
+    }
+
+    #[test]
+    fn could_match_extension_of_exhaustive() {
+        fn strings(alphabet: &[&str], len: usize) -> Vec<String> {
+            let mut strings = vec![String::new()];
+            for _ in 0..len {
+                let longer: Vec<_> = strings
+                    .iter()
+                    .flat_map(|s| alphabet.iter().map(move |a| format!("{s}{a}")))
+                    .collect();
+                strings.extend(longer);
+            }
+            strings.sort();
+            strings.dedup();
+            strings
+        }
+
+        // No partial name with a matching extension is ever rejected
+        let names = strings(&["a", "b", "/", "."], 4);
+        let hidden = GlobPatternBuilder::new().options(MatchOptions::new().dotfiles(false));
+        for pattern in strings(&["a", "/", "*", "?", "[^b]", "."], 3) {
+            for builder in [&GlobPatternBuilder::new(), &hidden] {
+                let Ok(pattern) = builder.build(&pattern) else {
+                    continue;
+                };
+                for name in names.iter().filter(|name| pattern.matches(name)) {
+                    for (i, _) in name.char_indices() {
+                        assert!(
+                            pattern.could_match_extension_of(&name[..i]),
+                            "{pattern:?} {name:?} {i}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
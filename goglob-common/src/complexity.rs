@@ -0,0 +1,170 @@
+//! Bounds on patterns supplied by untrusted sources.
+//!
+//! Memory used by a compiled pattern grows with its number of tokens and
+//! character class entries, while the cost of matching grows with its number
+//! of `*` (each one potentially trying every position of the name).
+
+#[cfg(feature = "parser")]
+use crate::{
+    error::{Error, ErrorType},
+    Result,
+};
+use crate::{GlobPattern, GlobToken};
+
+/// Size of a compiled pattern, as returned by [`GlobPattern::complexity()`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Complexity {
+    tokens: usize,
+    class_entries: usize,
+    stars: usize,
+}
+impl Complexity {
+    /// Number of compiled tokens (see [`GlobPattern::tokens()`]).
+    pub fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    /// Number of single characters and ranges in every character class,
+    /// combined.
+    pub fn class_entries(&self) -> usize {
+        self.class_entries
+    }
+
+    /// Number of `*` and `**/`.
+    pub fn stars(&self) -> usize {
+        self.stars
+    }
+}
+
+/// Limits enforced by [`GlobPatternBuilder::limits()`][crate::builder::GlobPatternBuilder::limits].
+///
+/// Patterns exceeding any of them fail to compile with
+/// [`ErrorType::ComplexityExceeded`], pointing at the first element over the
+/// limit.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ComplexityLimits {
+    pub(crate) max_tokens: Option<usize>,
+    pub(crate) max_class_entries: Option<usize>,
+    pub(crate) max_stars: Option<usize>,
+}
+impl ComplexityLimits {
+    /// No limits at all.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            max_tokens: None,
+            max_class_entries: None,
+            max_stars: None,
+        }
+    }
+
+    /// Maximum number of compiled tokens, or `None` for no limit.
+    #[inline]
+    pub fn max_tokens(mut self, max_tokens: Option<usize>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Maximum number of character class entries (across every class), or
+    /// `None` for no limit.
+    #[inline]
+    pub fn max_class_entries(mut self, max_class_entries: Option<usize>) -> Self {
+        self.max_class_entries = max_class_entries;
+        self
+    }
+
+    /// Maximum number of `*` and `**/`, or `None` for no limit.
+    #[inline]
+    pub fn max_stars(mut self, max_stars: Option<usize>) -> Self {
+        self.max_stars = max_stars;
+        self
+    }
+
+    /// Fail at `pos` if `count` exceeds `max`.
+    #[cfg(feature = "parser")]
+    pub(crate) fn check(max: Option<usize>, count: usize, pos: usize) -> Result<()> {
+        match max {
+            Some(max) if count > max => Err(Error::new(ErrorType::ComplexityExceeded, pos)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl GlobPattern {
+    /// Size of the compiled pattern, e.g. to decide whether to accept it.
+    pub fn complexity(&self) -> Complexity {
+        let mut complexity = Complexity {
+            tokens: self.tokens().len(),
+            ..Complexity::default()
+        };
+        for token in self.tokens() {
+            match token {
+                GlobToken::CharClass(cc) => complexity.class_entries += cc.types().len(),
+                GlobToken::SeqWildcard | GlobToken::AnyDirs => complexity.stars += 1,
+                GlobToken::Literal(_) | GlobToken::SingleWildcard => {}
+            }
+        }
+        complexity
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        complexity::ComplexityLimits,
+        error::{Error, ErrorType},
+        syntax::Syntax,
+        GlobPattern,
+    };
+
+    fn build(pattern: &str, limits: ComplexityLimits) -> Result<GlobPattern, Error> {
+        GlobPattern::builder().limits(limits).build(pattern)
+    }
+
+    #[test]
+    fn complexity() {
+        let complexity = GlobPattern::new("a*b?[c-ex]*").unwrap().complexity();
+        assert_eq!(complexity.tokens(), 6);
+        assert_eq!(complexity.class_entries(), 2);
+        assert_eq!(complexity.stars(), 2);
+
+        let pattern = GlobPattern::builder()
+            .syntax(Syntax::Gitignore)
+            .build("src/**")
+            .unwrap();
+        assert_eq!(pattern.complexity().stars(), 2);
+    }
+
+    #[test]
+    fn complexity_limits() {
+        let limits = ComplexityLimits::new()
+            .max_tokens(Some(5))
+            .max_class_entries(Some(3))
+            .max_stars(Some(2));
+        assert!(build("a*b*[abc]", limits.clone()).is_ok());
+
+        let error = build("a*b*c*", limits.clone()).unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::ComplexityExceeded));
+        assert_eq!(error.position(), 5);
+
+        let error = build("a?b?c?", limits.clone()).unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::ComplexityExceeded));
+        assert_eq!(error.position(), 5);
+
+        let error = build("[ab][c-ez]", limits.clone()).unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::ComplexityExceeded));
+        assert_eq!(error.position(), 8);
+        assert_eq!(
+            error.to_string(),
+            "pattern exceeds the complexity limits at 8"
+        );
+
+        // Limits are enforced while scanning, so whichever error comes first
+        // is reported
+        let error = build("a*b*c*[", limits.clone()).unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::ComplexityExceeded));
+        let error = build("a*b*[", limits).unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::UnclosedCharClass));
+        assert!(build("a*b*c*d*", ComplexityLimits::new()).is_ok());
+    }
+}
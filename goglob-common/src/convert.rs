@@ -0,0 +1,143 @@
+//! Conversions between shell patterns and other pattern languages.
+
+#[cfg(feature = "parser")]
+use crate::Result;
+use crate::{charcls::CharClass, GlobPattern, GlobToken};
+use alloc::string::String;
+
+/// Characters with a special meaning in regular expressions, as escaped by
+/// most regex engines (including the `regex` crate and PCRE).
+const REGEX_META: &str = "\\.+*?()|[]{}^$#&-~";
+
+/// Escape `literal` so that it can be embedded in a pattern and only ever match
+/// itself (e.g. `a*b` becomes `a\*b`).
+pub fn escape(literal: &str) -> String {
+    let mut result = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        push_escaped(&mut result, c);
+    }
+    result
+}
+fn push_escaped(pattern: &mut String, c: char) {
+    if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+        pattern.push('\\');
+    }
+    pattern.push(c);
+}
+
+/// Translate a SQL `LIKE` pattern into shell pattern syntax: `%` becomes `*`,
+/// `_` becomes `?` and every other character is matched literally. If
+/// `escape_char` is given, it escapes the character following it (e.g. `\%`).
+///
+/// Note that `%` and `_` match any character in SQL whereas `*` and `?` never
+/// match `/`, so the translation is only exact for names without `/`.
+pub fn like_to_pattern(like: &str, escape_char: Option<char>) -> String {
+    let mut result = String::with_capacity(like.len());
+    let mut chars = like.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // A trailing escape character is taken literally.
+            c if Some(c) == escape_char => push_escaped(&mut result, chars.next().unwrap_or(c)),
+            '%' => result.push('*'),
+            '_' => result.push('?'),
+            c => push_escaped(&mut result, c),
+        }
+    }
+    result
+}
+
+fn push_regex_escaped(regex: &mut String, c: char) {
+    if REGEX_META.contains(c) {
+        regex.push('\\');
+    }
+    regex.push(c);
+}
+
+fn push_regex_class(regex: &mut String, cc: &CharClass) {
+    regex.push('[');
+    if cc.is_negated() {
+        regex.push('^');
+    }
+    for cct in cc.types() {
+        let range = cct.as_range();
+        push_regex_escaped(regex, *range.start());
+        if range.start() != range.end() {
+            regex.push('-');
+            push_regex_escaped(regex, *range.end());
+        }
+    }
+    regex.push(']');
+}
+
+impl GlobPattern {
+    /// Translate the pattern into an equivalent, anchored regular expression
+    /// (e.g. `a*.rs` into `^a[^/]*\.rs$`).
+    ///
+    /// The result only uses syntax shared by virtually every regex engine
+    /// (character classes, `*` repetition and `\`-escaping, plus an optional
+    /// group for `**/`).
+    pub fn to_regex(&self) -> String {
+        let mut regex = String::from("^");
+        for token in self.tokens() {
+            match token {
+                GlobToken::Literal(l) => {
+                    for c in l.as_ref().chars() {
+                        push_regex_escaped(&mut regex, c);
+                    }
+                }
+                GlobToken::CharClass(cc) => push_regex_class(&mut regex, cc),
+                GlobToken::SeqWildcard => regex.push_str("[^/]*"),
+                GlobToken::SingleWildcard => regex.push_str("[^/]"),
+                GlobToken::AnyDirs => regex.push_str("(.*/)?"),
+            }
+        }
+        regex.push('$');
+        regex
+    }
+
+    /// Compile a SQL `LIKE` pattern (with `\` as the escape character), see
+    /// [`like_to_pattern()`] for the translation rules.
+    #[cfg(feature = "parser")]
+    pub fn from_like<S: AsRef<str>>(like: S) -> Result<Self> {
+        Self::new(like_to_pattern(like.as_ref(), Some('\\')))
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::convert::{escape, like_to_pattern};
+    use crate::GlobPattern;
+
+    #[test]
+    fn escape_literal() {
+        assert_eq!(escape("a*b?c[d]e\\f"), "a\\*b\\?c\\[d\\]e\\\\f");
+        assert_eq!(escape("plain/path.rs"), "plain/path.rs");
+
+        let literal = "*[x]?\\";
+        assert!(GlobPattern::new(escape(literal)).unwrap().matches(literal));
+    }
+
+    #[test]
+    fn to_regex() {
+        let regex = |p: &str| GlobPattern::new(p).unwrap().to_regex();
+        assert_eq!(regex("abc"), "^abc$");
+        assert_eq!(regex("a*.rs"), "^a[^/]*\\.rs$");
+        assert_eq!(regex("a?b"), "^a[^/]b$");
+        assert_eq!(regex("ab[^b-d]"), "^ab[^b-d]$");
+        assert_eq!(regex("[\\]\\-x]"), "^[\\]\\-x]$");
+        assert_eq!(regex("a\\*b(c)"), "^a\\*b\\(c\\)$");
+    }
+
+    #[test]
+    fn from_like() {
+        assert_eq!(like_to_pattern("a%b_c", None), "a*b?c");
+        assert_eq!(like_to_pattern("100\\%*", Some('\\')), "100%\\*");
+        assert_eq!(like_to_pattern("a!_b!", Some('!')), "a_b!");
+        assert_eq!(like_to_pattern("[x]", None), "\\[x\\]");
+
+        let pattern = GlobPattern::from_like("report_20%.csv").unwrap();
+        assert!(pattern.matches("report_2022.csv"));
+        assert!(pattern.matches("report-20.csv"));
+        assert!(!pattern.matches("report_2022.tsv"));
+    }
+}
@@ -0,0 +1,127 @@
+//! Analysis of which patterns of a set are actually used.
+
+use crate::{set::GlobSet, GlobPattern};
+use alloc::{vec, vec::Vec};
+
+/// How often each pattern of a [`GlobSet`] (or [`RuleSet`][crate::rules::RuleSet])
+/// matched a corpus of names, as returned by [`GlobSet::coverage()`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Coverage {
+    hits: Vec<usize>,
+    names: usize,
+}
+impl Coverage {
+    /// Number of names in the corpus.
+    #[inline]
+    pub fn names(&self) -> usize {
+        self.names
+    }
+
+    /// Number of names matched by each pattern, by pattern index.
+    #[inline]
+    pub fn hits(&self) -> &[usize] {
+        &self.hits
+    }
+
+    /// Number of names matched by the pattern at `index`, or `None` if
+    /// there's no such pattern.
+    #[inline]
+    pub fn hit_count(&self, index: usize) -> Option<usize> {
+        self.hits.get(index).copied()
+    }
+
+    /// Indices of the patterns which matched no name at all (i.e. dead
+    /// rules, as far as the corpus goes), in ascending order.
+    pub fn unused(&self) -> impl Iterator<Item = usize> + '_ {
+        self.indices_with(0)
+    }
+
+    /// Indices of the patterns which matched every name of a non-empty corpus
+    /// (e.g. catch-all rules shadowing more specific ones), in ascending
+    /// order.
+    pub fn universal(&self) -> impl Iterator<Item = usize> + '_ {
+        self.indices_with(self.names)
+            .filter(move |_| self.names > 0)
+    }
+
+    /// Count how many of `names` each of `patterns` matches.
+    pub(crate) fn of<'p, P, I, S>(patterns: P, names: I) -> Self
+    where
+        P: ExactSizeIterator<Item = &'p GlobPattern> + Clone,
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut coverage = Coverage {
+            hits: vec![0; patterns.len()],
+            names: 0,
+        };
+        for name in names {
+            let name = name.as_ref();
+            coverage.names += 1;
+            for (hits, pattern) in coverage.hits.iter_mut().zip(patterns.clone()) {
+                if pattern.matches(name) {
+                    *hits += 1;
+                }
+            }
+        }
+        coverage
+    }
+
+    fn indices_with(&self, hits: usize) -> impl Iterator<Item = usize> + '_ {
+        self.hits
+            .iter()
+            .enumerate()
+            .filter(move |(_, h)| **h == hits)
+            .map(|(i, _)| i)
+    }
+}
+
+impl GlobSet {
+    /// Match every one of `names` against every pattern, counting how many
+    /// names each pattern matched so unused (or catch-all) patterns can be
+    /// pruned.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::set::GlobSet;
+    ///
+    /// let set = GlobSet::compile(["*.rs", "*.py", "*"]).unwrap();
+    /// let coverage = set.coverage(["lib.rs", "main.rs", "README.md"]);
+    /// assert_eq!(coverage.hits(), [2, 0, 3]);
+    /// assert_eq!(coverage.unused().collect::<Vec<_>>(), [1]);
+    /// assert_eq!(coverage.universal().collect::<Vec<_>>(), [2]);
+    /// # }
+    /// ```
+    pub fn coverage<I, S>(&self, names: I) -> Coverage
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Coverage::of(self.iter(), names)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::set::GlobSet;
+
+    #[test]
+    fn glob_set_coverage() {
+        let set = GlobSet::compile(["src/*", "*.md", "target/*", "*"]).unwrap();
+        let names = ["src/lib.rs", "src/main.rs", "README.md", "Cargo.toml"];
+        let coverage = set.coverage(names);
+        assert_eq!(coverage.names(), 4);
+        assert_eq!(coverage.hits(), [2, 1, 0, 2]);
+        assert_eq!(coverage.hit_count(0), Some(2));
+        assert_eq!(coverage.hit_count(4), None);
+        assert_eq!(coverage.unused().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(coverage.universal().count(), 0);
+
+        let coverage = set.coverage(["a.md"]);
+        assert_eq!(coverage.universal().collect::<Vec<_>>(), vec![1, 3]);
+
+        let coverage = set.coverage(Vec::<String>::new());
+        assert_eq!(coverage.unused().count(), 4);
+        assert_eq!(coverage.universal().count(), 0);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::{Error, ErrorType};
+use defmt::{write, Format, Formatter};
+
+impl Format for ErrorType {
+    fn format(&self, f: Formatter<'_>) {
+        match self {
+            ErrorType::ConflictingMetachar(c) => {
+                write!(f, "metacharacter {=char} has more than one meaning", c)
+            }
+            ErrorType::InvalidRangeValues(start, end) => {
+                write!(f, "invalid character range: {=char}-{=char}", start, end)
+            }
+            ErrorType::UnescapedChar(unescaped) => write!(
+                f,
+                "special character {=char} not escaped with '\\'",
+                unescaped
+            ),
+            _ => write!(f, "{=str}", self.type_desc()),
+        }
+    }
+}
+
+impl Format for Error {
+    fn format(&self, f: Formatter<'_>) {
+        match self.position() {
+            usize::MAX => write!(f, "{}", self.error_type()),
+            pos => write!(f, "{} (at {=usize})", self.error_type(), pos),
+        }
+    }
+}
+
+/// Write `c` as it would appear in a pattern, escaping it if necessary.
+pub(crate) fn write_escaped(f: Formatter<'_>, c: char, in_class: bool) {
+    let special = if in_class {
+        matches!(c, '\\' | '-' | ']' | '^')
+    } else {
+        matches!(c, '*' | '?' | '[' | ']' | '\\')
+    };
+    if special {
+        write!(f, "\\");
+    }
+    write!(f, "{=char}", c);
+}
+
+#[cfg(feature = "alloc")]
+impl Format for crate::GlobPattern {
+    /// Render the pattern back into pattern syntax.
+    fn format(&self, f: Formatter<'_>) {
+        use crate::GlobToken;
+
+        for token in self.tokens() {
+            match token {
+                GlobToken::Literal(l) => {
+                    l.as_ref().chars().for_each(|c| write_escaped(f, c, false))
+                }
+                GlobToken::CharClass(cc) => {
+                    write!(f, "[");
+                    if cc.is_negated() {
+                        write!(f, "^");
+                    }
+                    for cct in cc.types() {
+                        let range = cct.as_range();
+                        write_escaped(f, *range.start(), true);
+                        if range.start() != range.end() {
+                            write!(f, "-");
+                            write_escaped(f, *range.end(), true);
+                        }
+                    }
+                    write!(f, "]");
+                }
+                GlobToken::SeqWildcard => write!(f, "*"),
+                GlobToken::SingleWildcard => write!(f, "?"),
+                GlobToken::AnyDirs => write!(f, "**/"),
+            }
+        }
+    }
+}
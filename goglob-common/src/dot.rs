@@ -0,0 +1,101 @@
+//! [Graphviz](https://graphviz.org) rendering of compiled patterns.
+
+use crate::{convert::escape, GlobPattern, GlobToken};
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+impl GlobPattern {
+    /// Render the pattern as a Graphviz `digraph` of the states matching goes
+    /// through, e.g. to visualize it with `dot -Tsvg`: state `i` is reached
+    /// once the first `i` tokens are matched, the name matching if the last
+    /// (double-circled) state is reached at its end.
+    ///
+    /// Edges are labelled with the text they consume, `*` and `**/` looping
+    /// over their state and leaving it with an `ε` edge consuming nothing.
+    /// The restrictions on hidden names (see
+    /// [`MatchOptions::dotfiles()`][crate::options::MatchOptions::dotfiles])
+    /// aren't shown.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let dot = GlobPattern::new("a*").unwrap().to_dot();
+    /// assert!(dot.starts_with("digraph pattern {\n"));
+    /// assert!(dot.contains("  0 -> 1 [label=\"a\"];\n"));
+    /// assert!(dot.contains("  1 -> 1 [label=\"[^/]\"];\n"));
+    /// # }
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let separator = self.options.effective_separator();
+        let not_separator = format!("[^{}]", escape(separator.encode_utf8(&mut [0; 4])));
+
+        let mut dot = String::from("digraph pattern {\n");
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=circle];\n");
+        dot.push_str("  start [shape=point];\n");
+        let _ = writeln!(dot, "  {} [shape=doublecircle];", self.tokens.len());
+        dot.push_str("  start -> 0;\n");
+        for (i, token) in self.tokens.iter().enumerate() {
+            let label = match token {
+                GlobToken::Literal(l) => escape(l.as_ref()),
+                GlobToken::CharClass(cc) => format!("{cc}"),
+                GlobToken::SingleWildcard => not_separator.clone(),
+                GlobToken::SeqWildcard => {
+                    edge(&mut dot, i, i, &not_separator);
+                    String::from("ε")
+                }
+                GlobToken::AnyDirs => {
+                    edge(&mut dot, i, i, &format!("{not_separator}*{separator}"));
+                    String::from("ε")
+                }
+            };
+            edge(&mut dot, i, i + 1, &label);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Append an edge from state `from` to `to` consuming `label`.
+fn edge(dot: &mut String, from: usize, to: usize, label: &str) {
+    let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+    let _ = writeln!(dot, "  {from} -> {to} [label=\"{label}\"];");
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{builder::GlobPatternBuilder, syntax::Syntax, GlobPattern};
+
+    #[test]
+    fn to_dot() {
+        let pattern = GlobPattern::new("a\\*?[^b]*").unwrap();
+        assert_eq!(
+            pattern.to_dot(),
+            "\
+digraph pattern {
+  rankdir=LR;
+  node [shape=circle];
+  start [shape=point];
+  4 [shape=doublecircle];
+  start -> 0;
+  0 -> 1 [label=\"a\\\\*\"];
+  1 -> 2 [label=\"[^/]\"];
+  2 -> 3 [label=\"[^b]\"];
+  3 -> 3 [label=\"[^/]\"];
+  3 -> 4 [label=\"ε\"];
+}
+"
+        );
+    }
+
+    #[test]
+    fn to_dot_any_dirs() {
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        let dot = gitignore.build("**/\"x\"").unwrap().to_dot();
+        assert!(dot.contains("  0 -> 0 [label=\"[^/]*/\"];\n"));
+        assert!(dot.contains("  0 -> 1 [label=\"ε\"];\n"));
+        assert!(dot.contains("  1 -> 2 [label=\"\\\"x\\\"\"];\n"));
+        assert!(dot.contains("  2 [shape=doublecircle];\n"));
+    }
+}
@@ -0,0 +1,293 @@
+//! Human-readable breakdown of compiled patterns, for debugging.
+
+#[cfg(not(feature = "parser"))]
+use crate::convert::escape;
+#[cfg(feature = "parser")]
+use crate::{
+    scan::{self, ScanSink},
+    Result,
+};
+use crate::{GlobPattern, GlobToken};
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
+
+/// Multi-line breakdown of the tokens of a pattern along with their spans,
+/// as returned by [`GlobPattern::dump()`] (and printed by `{:#?}`):
+///
+/// ```text
+/// pattern: ab*[^b-d]
+/// tokens:
+///     0  0..2  Literal "ab"
+///     1  2..3  SeqWildcard
+///     2  3..9  CharClass [^b-d]
+/// ```
+///
+/// Spans are byte ranges in the text the pattern was compiled from, as
+/// returned by [`GlobPattern::token_spans()`]. Without the `parser` feature,
+/// they're byte ranges in the pattern as rendered back from its tokens
+/// instead.
+#[derive(Debug, Clone, Copy)]
+pub struct Dump<'a> {
+    pattern: &'a GlobPattern,
+    #[cfg_attr(not(feature = "parser"), allow(dead_code))]
+    source: Option<&'a str>,
+}
+impl<'a> Dump<'a> {
+    /// Show spans in `source`, another string compiling to the same pattern
+    /// (with its options). This is ignored if it doesn't, e.g. because the
+    /// pattern was modified afterwards.
+    #[inline]
+    pub fn with_source(mut self, source: &'a str) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Pattern text and the span of each token within it.
+    fn spans(&self) -> (String, Vec<Range<usize>>) {
+        #[cfg(feature = "parser")]
+        {
+            if let Some(source) = self.source {
+                let recompiled = GlobPattern::new_with(source, self.pattern.options());
+                if recompiled.is_ok_and(|recompiled| recompiled.tokens() == self.pattern.tokens()) {
+                    if let Some(spans) = scan_spans(source, self.pattern) {
+                        return (String::from(source), spans);
+                    }
+                }
+            }
+            (
+                String::from(self.pattern.as_str()),
+                self.pattern.token_spans(),
+            )
+        }
+        #[cfg(not(feature = "parser"))]
+        self.rendered_spans()
+    }
+
+    /// Pattern rendered back from its tokens and the span of each token
+    /// within it.
+    #[cfg(not(feature = "parser"))]
+    fn rendered_spans(&self) -> (String, Vec<Range<usize>>) {
+        let mut rendered = String::new();
+        let mut starts = Vec::with_capacity(self.pattern.tokens().len());
+        for token in self.pattern.tokens() {
+            starts.push(rendered.len());
+            match token {
+                GlobToken::Literal(l) => rendered.push_str(&escape(l.as_ref())),
+                GlobToken::CharClass(cc) => rendered.push_str(&alloc::format!("{cc}")),
+                GlobToken::SeqWildcard => rendered.push('*'),
+                GlobToken::SingleWildcard => rendered.push('?'),
+                GlobToken::AnyDirs => rendered.push_str("**/"),
+            }
+        }
+        let len = rendered.len();
+        (rendered, spans(starts, len))
+    }
+}
+impl Display for Dump<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (text, spans) = self.spans();
+        let spans: Vec<String> = spans
+            .iter()
+            .map(|span| alloc::format!("{}..{}", span.start, span.end))
+            .collect();
+        let width = spans.iter().map(String::len).max().unwrap_or(0);
+
+        writeln!(f, "pattern: {text}")?;
+        writeln!(f, "tokens:")?;
+        for (i, (token, span)) in self.pattern.tokens().iter().zip(&spans).enumerate() {
+            write!(f, "  {i:>3}  {span:<width$}  ")?;
+            match token {
+                GlobToken::Literal(l) => writeln!(f, "Literal {:?}", l.as_ref())?,
+                GlobToken::CharClass(cc) => writeln!(f, "CharClass {cc}")?,
+                GlobToken::SeqWildcard => writeln!(f, "SeqWildcard")?,
+                GlobToken::SingleWildcard => writeln!(f, "SingleWildcard")?,
+                GlobToken::AnyDirs => writeln!(f, "AnyDirs")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl GlobPattern {
+    /// Readable breakdown of the pattern's tokens (see [`Dump`]).
+    #[inline]
+    pub fn dump(&self) -> Dump<'_> {
+        Dump {
+            pattern: self,
+            source: None,
+        }
+    }
+
+    /// Byte range of each token in the text the pattern was compiled from
+    /// (see [`as_str()`][Self::as_str]), in order, for diagnostics pointing
+    /// back to it.
+    ///
+    /// ```
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let pattern = GlobPattern::new("a\\b*[^b-d]").unwrap();
+    /// assert_eq!(pattern.token_spans(), [0..3, 3..4, 4..10]);
+    /// assert_eq!(&pattern.as_str()[4..10], "[^b-d]");
+    /// ```
+    ///
+    /// Tokens produced by the same characters (e.g. a trailing `**` in
+    /// [`Syntax::Gitignore`][crate::syntax::Syntax::Gitignore], which becomes
+    /// `**/*`) share the same span.
+    #[cfg(feature = "parser")]
+    pub fn token_spans(&self) -> Vec<Range<usize>> {
+        let source = self.as_str();
+        scan_spans(source, self).unwrap_or_else(|| {
+            // Only reachable if the source didn't compile to the tokens, so
+            // every token is reported as coming from the whole of it.
+            alloc::vec![0..source.len(); self.tokens().len()]
+        })
+    }
+}
+
+/// Spans of the tokens of `pattern` in `source`, if it's scanned into as many
+/// tokens with the pattern's options.
+#[cfg(feature = "parser")]
+fn scan_spans(source: &str, pattern: &GlobPattern) -> Option<Vec<Range<usize>>> {
+    let mut sink = SpanSink::default();
+    scan::scan(source, pattern.options(), &mut sink).ok()?;
+    (sink.starts.len() == pattern.tokens().len()).then(|| spans(sink.starts, source.len()))
+}
+
+/// Spans of tokens starting at `starts`, each one ending where the next one
+/// starts (tokens starting at the same position share the same span).
+fn spans(starts: Vec<usize>, len: usize) -> Vec<Range<usize>> {
+    starts
+        .iter()
+        .map(|&start| {
+            let end = starts.iter().copied().find(|&next| next > start);
+            start..end.unwrap_or(len)
+        })
+        .collect()
+}
+
+/// Records the position each token starts at.
+#[cfg(feature = "parser")]
+#[derive(Default)]
+struct SpanSink {
+    starts: Vec<usize>,
+    in_literal: bool,
+}
+#[cfg(feature = "parser")]
+impl ScanSink for SpanSink {
+    fn seq_wildcard(&mut self, pos: usize) -> Result<()> {
+        self.starts.push(pos);
+        Ok(())
+    }
+
+    fn any_dirs(&mut self, pos: usize) -> Result<()> {
+        self.starts.push(pos);
+        Ok(())
+    }
+
+    fn single_wildcard(&mut self, pos: usize) -> Result<()> {
+        self.starts.push(pos);
+        Ok(())
+    }
+
+    fn literal_char(&mut self, _: char, pos: usize) -> Result<()> {
+        if !self.in_literal {
+            self.in_literal = true;
+            self.starts.push(pos);
+        }
+        Ok(())
+    }
+
+    fn end_literal(&mut self) -> Result<()> {
+        self.in_literal = false;
+        Ok(())
+    }
+
+    fn start_class(&mut self, _: bool, pos: usize) -> Result<()> {
+        self.starts.push(pos);
+        Ok(())
+    }
+
+    fn class_char(&mut self, _: char, _: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn class_range(&mut self, _: char, _: char, _: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn end_class(&mut self, _: usize) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{builder::GlobPatternBuilder, syntax::Syntax, GlobPattern};
+
+    #[test]
+    fn dump() {
+        let pattern = GlobPattern::new("a\\b*[^b-d]??").unwrap();
+        let rendered = GlobPattern::from_tokens(pattern.tokens().to_vec(), *pattern.options());
+        assert_eq!(
+            rendered.dump().to_string(),
+            "\
+pattern: ab*[^b-d]??
+tokens:
+    0  0..2    Literal \"ab\"
+    1  2..3    SeqWildcard
+    2  3..9    CharClass [^b-d]
+    3  9..10   SingleWildcard
+    4  10..11  SingleWildcard
+"
+        );
+        assert_eq!(
+            rendered.dump().with_source("a\\b*[^b-d]??").to_string(),
+            "\
+pattern: a\\b*[^b-d]??
+tokens:
+    0  0..3    Literal \"ab\"
+    1  3..4    SeqWildcard
+    2  4..10   CharClass [^b-d]
+    3  10..11  SingleWildcard
+    4  11..12  SingleWildcard
+"
+        );
+        assert_eq!(
+            pattern.dump().to_string(),
+            rendered.dump().with_source(pattern.as_str()).to_string()
+        );
+        // Not the source of the pattern
+        assert_eq!(
+            pattern.dump().with_source("x").to_string(),
+            pattern.dump().to_string()
+        );
+        assert_eq!(format!("{pattern:#?}"), pattern.dump().to_string());
+        assert!(format!("{pattern:?}").starts_with("GlobPattern { tokens: ["));
+    }
+
+    #[test]
+    fn dump_gitignore() {
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        let pattern = gitignore.build("**/a\\*/**").unwrap();
+        assert_eq!(pattern.token_spans(), [0..3, 3..7, 7..9, 7..9]);
+        assert_eq!(
+            pattern.dump().to_string(),
+            "\
+pattern: **/a\\*/**
+tokens:
+    0  0..3  AnyDirs
+    1  3..7  Literal \"a*/\"
+    2  7..9  AnyDirs
+    3  7..9  SeqWildcard
+"
+        );
+        let rendered = GlobPattern::from_tokens(pattern.tokens().to_vec(), *pattern.options());
+        assert!(rendered
+            .dump()
+            .to_string()
+            .starts_with("pattern: **/a\\*/**/*\n"));
+    }
+}
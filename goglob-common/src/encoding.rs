@@ -0,0 +1,189 @@
+//! [encoding_rs](https://docs.rs/encoding_rs) integration, matching names in
+//! legacy encodings (e.g. from archives or old filesystems) by decoding them
+//! on the fly.
+//!
+//! Each character is decoded on its own, so stateful encodings (ISO-2022-JP
+//! and the replacement encoding) aren't supported. Bytes which can't be
+//! decoded are each treated as a single character which isn't `/`, as with
+//! [`matches_bytes()`][GlobPattern::matches_bytes] with the `bstr` feature.
+
+use crate::{matching::Haystack, GlobPattern};
+use encoding_rs::{DecoderResult, Encoding};
+
+/// Longest byte sequence of a single character in any supported encoding
+/// (4 for GB18030 and UTF-16 surrogate pairs).
+const MAX_CHAR_BYTES: usize = 4;
+
+/// Name encoded with `encoding`, decoded one character at a time.
+#[derive(Clone, Copy)]
+struct Encoded<'a> {
+    bytes: &'a [u8],
+    encoding: &'static Encoding,
+}
+impl<'a> Encoded<'a> {
+    fn with_bytes(self, bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            encoding: self.encoding,
+        }
+    }
+
+    /// Iterate over the characters along with their byte offsets.
+    fn char_indices(self) -> impl Iterator<Item = (usize, Option<char>)> + 'a {
+        let mut rest = self;
+        core::iter::from_fn(move || {
+            let offset = self.bytes.len() - rest.bytes.len();
+            let (c, after) = rest.next_char()?;
+            rest = after;
+            Some((offset, c))
+        })
+    }
+}
+impl Haystack for Encoded<'_> {
+    #[inline]
+    fn len(self) -> usize {
+        self.bytes.len()
+    }
+
+    fn next_char(self) -> Option<(Option<char>, Self)> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        // The shortest prefix decoding into exactly one character
+        for width in 1..=MAX_CHAR_BYTES.min(self.bytes.len()) {
+            let mut decoder = self.encoding.new_decoder_without_bom_handling();
+            let mut buf = [0; 8];
+            let (result, _, written) =
+                decoder.decode_to_utf8_without_replacement(&self.bytes[..width], &mut buf, true);
+            if result != DecoderResult::InputEmpty {
+                continue;
+            }
+            let mut chars = core::str::from_utf8(&buf[..written]).ok()?.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                return Some((Some(c), self.with_bytes(&self.bytes[width..])));
+            }
+        }
+        Some((None, self.with_bytes(&self.bytes[1..])))
+    }
+
+    fn strip_literal(self, literal: &[u8]) -> Option<Self> {
+        let literal = core::str::from_utf8(literal).ok()?;
+        let mut rest = self;
+        for expected in literal.chars() {
+            let (c, after) = rest.next_char()?;
+            if c != Some(expected) {
+                return None;
+            }
+            rest = after;
+        }
+        Some(rest)
+    }
+
+    #[inline]
+    fn split_at(self, mid: usize) -> (Self, Self) {
+        let (left, right) = self.bytes.split_at(mid);
+        (self.with_bytes(left), self.with_bytes(right))
+    }
+
+    fn find_char(self, c: char) -> Option<usize> {
+        self.char_indices()
+            .find(|(_, decoded)| *decoded == Some(c))
+            .map(|(offset, _)| offset)
+    }
+
+    fn ends_with_char(self, c: char) -> bool {
+        self.char_indices()
+            .last()
+            .is_some_and(|(_, last)| last == Some(c))
+    }
+}
+
+impl GlobPattern {
+    /// Report whether `name`, encoded with `encoding` (e.g.
+    /// [`encoding_rs::WINDOWS_1252`]), matches the compiled shell pattern,
+    /// decoding it character by character without allocating.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let pattern = GlobPattern::new("caf?.txt").unwrap();
+    /// let name = b"caf\xe9.txt";
+    /// assert!(pattern.matches_bytes_with_encoding(name, encoding_rs::WINDOWS_1252));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn matches_bytes_with_encoding<B: AsRef<[u8]>>(
+        &self,
+        name: B,
+        encoding: &'static Encoding,
+    ) -> bool {
+        let name = Encoded {
+            bytes: name.as_ref(),
+            encoding,
+        };
+        self.match_tokens(name, None)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        options::{CaseFold, MatchOptions},
+        GlobPattern,
+    };
+    use encoding_rs::{Encoding, SHIFT_JIS, UTF_16LE, UTF_8, WINDOWS_1252};
+    use goglob_testutil::GO_MATCH_VECTORS;
+
+    fn matches(pattern: &str, name: &[u8], encoding: &'static Encoding) -> bool {
+        GlobPattern::new(pattern)
+            .unwrap()
+            .matches_bytes_with_encoding(name, encoding)
+    }
+
+    #[test]
+    fn matches_bytes_with_encoding_go_match_test() {
+        for vector in GO_MATCH_VECTORS {
+            let Some(expected) = vector.expected else {
+                continue;
+            };
+            let pattern = GlobPattern::new(vector.pattern).unwrap();
+            let name = vector.name.as_bytes();
+            assert_eq!(
+                pattern.matches_bytes_with_encoding(name, UTF_8),
+                expected,
+                "({}, {})",
+                vector.pattern,
+                vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn matches_bytes_with_encoding() {
+        // 'é' and '€' in windows-1252
+        assert!(matches("caf?.txt", b"caf\xe9.txt", WINDOWS_1252));
+        assert!(matches("[€]*", b"\x80\xe9", WINDOWS_1252));
+        assert!(matches("*é", b"caf\xe9", WINDOWS_1252));
+        assert!(!matches("*é", b"cafe", WINDOWS_1252));
+        assert!(!matches("caf?.txt", b"caf\xe9\xe9.txt", WINDOWS_1252));
+
+        // '日本' in Shift_JIS, then an undecodable byte
+        let name = b"\x93\xfa\x96\x7b/a.txt";
+        assert!(matches("??/*.txt", name, SHIFT_JIS));
+        assert!(matches("日*/a.txt", name, SHIFT_JIS));
+        assert!(!matches("?/*.txt", name, SHIFT_JIS));
+        assert!(matches("?a", b"\xa0a", SHIFT_JIS));
+        assert!(!matches("[a-z]a", b"\xa0a", SHIFT_JIS));
+
+        // 'a/b' and a surrogate pair in UTF-16
+        let name = [b'a', 0, b'/', 0, 0x3d, 0xd8, 0x00, 0xde];
+        assert!(matches("a/?", &name, UTF_16LE));
+        assert!(matches("a/😀", &name, UTF_16LE));
+        assert!(!matches("*", &name, UTF_16LE));
+
+        let options = MatchOptions::new().case_fold(CaseFold::Unicode);
+        let pattern = GlobPattern::new_with("CAFÉ", &options).unwrap();
+        assert!(pattern.matches_bytes_with_encoding(b"caf\xe9", WINDOWS_1252));
+    }
+}
@@ -1,6 +1,11 @@
+#[cfg(feature = "diagnostics")]
+use alloc::string::ToString;
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use core::result::Result as StdResult;
+#[cfg(feature = "std")]
 use std::error::Error as StdError;
-use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
-use std::result::Result as StdResult;
 
 #[derive(Debug)]
 pub struct Error {
@@ -8,69 +13,255 @@ pub struct Error {
     pos: usize,
 }
 impl Error {
-    pub(crate) fn new(error_type: ErrorType, pos: usize) -> Self {
+    pub(crate) const fn new(error_type: ErrorType, pos: usize) -> Self {
         Self { error_type, pos }
     }
 
-    pub(crate) fn empty_pattern() -> Self {
+    pub(crate) const fn empty_pattern() -> Self {
         Self {
             error_type: ErrorType::EmptyPattern,
             pos: usize::MAX,
         }
     }
 
-    pub fn error_type(&self) -> &ErrorType {
+    pub(crate) const fn conflicting_metachar(c: char) -> Self {
+        Self {
+            error_type: ErrorType::ConflictingMetachar(c),
+            pos: usize::MAX,
+        }
+    }
+
+    /// Same error, at a position `offset` bytes further (i.e. for the pattern
+    /// found at `offset` within a larger string).
+    #[cfg(feature = "parser")]
+    pub(crate) fn offset_by(self, offset: usize) -> Self {
+        match self.error_type {
+            // Not a position in the pattern
+            ErrorType::ConflictingMetachar(_)
+            | ErrorType::EmptyPattern
+            | ErrorType::PatternTooLong => self,
+            error_type => Self::new(error_type, self.pos + offset),
+        }
+    }
+
+    pub const fn error_type(&self) -> &ErrorType {
         &self.error_type
     }
 
     pub fn position(&self) -> usize {
         self.pos
     }
+
+    /// Render the error along with the offending `pattern`, pointing at the
+    /// error's position (if any) with a caret:
+    ///
+    /// ```text
+    /// character class opened with '[' at 2 isn't closed
+    ///   ab[cd
+    ///     ^
+    /// ```
+    #[cfg(feature = "diagnostics")]
+    pub fn render(&self, pattern: &str) -> String {
+        self.point_at(self.to_string(), pattern)
+    }
+
+    /// Same as [`render()`][Self::render], describing the error with
+    /// `renderer` (e.g. in another language).
+    #[cfg(feature = "diagnostics")]
+    pub fn render_with<R: ErrorRenderer + ?Sized>(&self, pattern: &str, renderer: &R) -> String {
+        self.point_at(self.display_with(renderer).to_string(), pattern)
+    }
+
+    /// Display the error as described by `renderer` instead of in English.
+    #[inline]
+    pub fn display_with<'a, R: ErrorRenderer + ?Sized>(
+        &'a self,
+        renderer: &'a R,
+    ) -> DisplayWith<'a, R> {
+        DisplayWith {
+            error: self,
+            renderer,
+        }
+    }
+
+    /// Append the offending `pattern` and a caret under the error's position
+    /// to the description `rendered`.
+    #[cfg(feature = "diagnostics")]
+    fn point_at(&self, mut rendered: String, pattern: &str) -> String {
+        if pattern.is_empty() {
+            return rendered;
+        }
+        rendered.push_str(&format!("\n  {pattern}"));
+        if let Some(before) = pattern.get(..self.pos) {
+            let column = before.chars().count();
+            rendered.push_str(&format!("\n  {}^", " ".repeat(column)));
+        }
+        rendered
+    }
 }
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         self.error_type.fmt_with_pos(Some(self.pos), f)
     }
 }
+#[cfg(feature = "std")]
 impl StdError for Error {}
 
+/// Describes errors to end users, e.g. in their language from a message
+/// catalog, in place of the built-in English descriptions.
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use core::fmt::{Formatter, Result};
+/// use goglob_common::{
+///     error::{ErrorRenderer, ErrorType},
+///     GlobPattern,
+/// };
+///
+/// struct Spanish;
+/// impl ErrorRenderer for Spanish {
+///     fn fmt_error(
+///         &self,
+///         error_type: &ErrorType,
+///         pos: Option<usize>,
+///         f: &mut Formatter<'_>,
+///     ) -> Result {
+///         match (error_type, pos) {
+///             (ErrorType::UnclosedCharClass, Some(pos)) => write!(
+///                 f,
+///                 "la clase de caracteres abierta con '[' en {pos} no está cerrada"
+///             ),
+///             // Fall back to English for the others
+///             (error_type, pos) => error_type.fmt_with_pos(pos, f),
+///         }
+///     }
+/// }
+///
+/// let error = GlobPattern::new("ab[cd").unwrap_err();
+/// assert_eq!(
+///     error.display_with(&Spanish).to_string(),
+///     "la clase de caracteres abierta con '[' en 2 no está cerrada"
+/// );
+/// # }
+/// ```
+pub trait ErrorRenderer {
+    /// Write the description of an error of type `error_type` to `f`, at
+    /// position `pos` if known (see [`Error::position()`]).
+    fn fmt_error(
+        &self,
+        error_type: &ErrorType,
+        pos: Option<usize>,
+        f: &mut Formatter<'_>,
+    ) -> FmtResult;
+}
+
+/// [Error] described by an [ErrorRenderer], as returned by
+/// [`Error::display_with()`].
+pub struct DisplayWith<'a, R: ?Sized> {
+    error: &'a Error,
+    renderer: &'a R,
+}
+impl<R: ErrorRenderer + ?Sized> Display for DisplayWith<'_, R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.renderer
+            .fmt_error(&self.error.error_type, Some(self.error.pos), f)
+    }
+}
+
 #[derive(Copy, Clone)]
 pub enum ErrorType {
+    CapacityExceeded,
+    ComplexityExceeded,
+    ConflictingMetachar(char),
     EmptyPattern,
     IllegalEscape,
+    InvalidHostLabel,
+    InvalidPlaceholder,
     InvalidRangeValues(char, char),
+    MisplacedWildcard,
+    MissingValue,
+    PatternTooLong,
+    TooManyClassEntries,
+    TooManyTokens,
     UnclosedCharClass,
+    UnclosedPlaceholder,
     UnescapedChar(char),
 }
 impl ErrorType {
-    pub fn type_desc(&self) -> &'static str {
+    pub const fn type_desc(&self) -> &'static str {
         match self {
+            ErrorType::CapacityExceeded => "pattern exceeds the available capacity",
+            ErrorType::ComplexityExceeded => "pattern exceeds the complexity limits",
+            ErrorType::ConflictingMetachar(_) => "metacharacter has more than one meaning",
             ErrorType::EmptyPattern => "empty pattern",
             ErrorType::IllegalEscape => "illegal use of '\\': end of pattern",
+            ErrorType::InvalidHostLabel => "label isn't a valid internationalized hostname",
+            ErrorType::InvalidPlaceholder => "placeholder doesn't refer to any capture",
             ErrorType::InvalidRangeValues(_, _) => "invalid character range",
+            ErrorType::MisplacedWildcard => "wildcard outside the leftmost label",
+            ErrorType::MissingValue => "placeholder has no value",
+            ErrorType::PatternTooLong => "pattern exceeds the maximum length",
+            ErrorType::TooManyClassEntries => "character class has too many entries",
+            ErrorType::TooManyTokens => "pattern has too many tokens",
             ErrorType::UnclosedCharClass => "character class opened with '[' isn't closed",
+            ErrorType::UnclosedPlaceholder => "placeholder opened with '{' isn't closed",
             ErrorType::UnescapedChar(_) => "special character not escaped with '\\'",
         }
     }
 
+    #[cfg(feature = "alloc")]
     pub fn full_desc(&self) -> String {
         format!("{}", self)
     }
 
     pub fn fmt_with_pos(&self, pos: Option<usize>, f: &mut Formatter<'_>) -> FmtResult {
         match (self, pos) {
+            (ErrorType::CapacityExceeded, Some(pos)) => {
+                write!(f, "pattern exceeds the available capacity at {pos}")
+            }
+            (ErrorType::ComplexityExceeded, Some(pos)) => {
+                write!(f, "pattern exceeds the complexity limits at {pos}")
+            }
+            (ErrorType::ConflictingMetachar(c), _) => {
+                write!(f, "metacharacter {c} has more than one meaning")
+            }
             (ErrorType::IllegalEscape, Some(pos)) => {
                 write!(f, "illegal use of '\\' at {pos}: end of pattern")
             }
+            (ErrorType::InvalidHostLabel, Some(pos)) => {
+                write!(f, "label at {pos} isn't a valid internationalized hostname")
+            }
+            (ErrorType::InvalidPlaceholder, Some(pos)) => {
+                write!(f, "placeholder at {pos} doesn't refer to any capture")
+            }
             (ErrorType::InvalidRangeValues(start, end), Some(pos)) => {
                 write!(f, "invalid charater range at {pos}: {start}-{end}")
             }
             (ErrorType::InvalidRangeValues(start, end), None) => {
                 write!(f, "invalid charater range: {start}-{end}")
             }
+            (ErrorType::MisplacedWildcard, Some(pos)) => {
+                write!(f, "wildcard at {pos} outside the leftmost label")
+            }
+            (ErrorType::MissingValue, Some(pos)) => {
+                write!(f, "placeholder at {pos} has no value")
+            }
+            (ErrorType::PatternTooLong, Some(pos)) => {
+                write!(f, "pattern exceeds the maximum length of {pos} bytes")
+            }
+            (ErrorType::TooManyClassEntries, Some(pos)) => {
+                write!(f, "character class has too many entries at {pos}")
+            }
+            (ErrorType::TooManyTokens, Some(pos)) => {
+                write!(f, "pattern has too many tokens at {pos}")
+            }
             (ErrorType::UnclosedCharClass, Some(pos)) => {
                 write!(f, "character class opened with '[' at {pos} isn't closed")
             }
+            (ErrorType::UnclosedPlaceholder, Some(pos)) => {
+                write!(f, "placeholder opened with '{{' at {pos} isn't closed")
+            }
             (ErrorType::UnescapedChar(unescaped), Some(pos)) => {
                 write!(
                     f,
@@ -98,3 +289,66 @@ impl Display for ErrorType {
 }
 
 pub type Result<T> = StdResult<T, Error>;
+
+/// Matching ran out of its step budget before reaching a result, as returned
+/// by [`GlobPattern::matches_with_fuel()`][crate::GlobPattern::matches_with_fuel].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct FuelExhausted;
+impl Display for FuelExhausted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("matching exceeded its step budget")
+    }
+}
+#[cfg(feature = "std")]
+impl StdError for FuelExhausted {}
+
+#[cfg(all(test, feature = "diagnostics", feature = "parser"))]
+mod tests {
+    use crate::{
+        error::{ErrorRenderer, ErrorType},
+        GlobPattern,
+    };
+    use core::fmt::{Formatter, Result};
+
+    #[test]
+    fn error_render() {
+        let error = GlobPattern::new("ñb[cd").err().unwrap();
+        assert_eq!(
+            error.render("ñb[cd"),
+            "character class opened with '[' at 3 isn't closed\n  ñb[cd\n    ^"
+        );
+
+        let error = GlobPattern::new("").err().unwrap();
+        assert_eq!(error.render(""), "empty pattern");
+    }
+
+    #[test]
+    fn error_render_with() {
+        struct Upper;
+        impl ErrorRenderer for Upper {
+            fn fmt_error(
+                &self,
+                error_type: &ErrorType,
+                pos: Option<usize>,
+                f: &mut Formatter<'_>,
+            ) -> Result {
+                match (error_type, pos) {
+                    (ErrorType::UnclosedCharClass, Some(pos)) => write!(f, "UNCLOSED AT {pos}"),
+                    (ErrorType::EmptyPattern, _) => f.write_str("EMPTY"),
+                    (error_type, pos) => error_type.fmt_with_pos(pos, f),
+                }
+            }
+        }
+
+        let error = GlobPattern::new("ñb[cd").err().unwrap();
+        assert_eq!(error.display_with(&Upper).to_string(), "UNCLOSED AT 3");
+        assert_eq!(
+            error.render_with("ñb[cd", &Upper),
+            "UNCLOSED AT 3\n  ñb[cd\n    ^"
+        );
+        let error = GlobPattern::new("").err().unwrap();
+        assert_eq!(error.render_with("", &Upper), "EMPTY");
+        let error = GlobPattern::new("a\\").err().unwrap();
+        assert_eq!(error.display_with(&Upper).to_string(), error.to_string());
+    }
+}
@@ -0,0 +1,97 @@
+//! Extension trait matching names and paths against patterns, so that call
+//! sites read in the order of the sentence (e.g. `path.matches_glob(&pattern)`).
+
+use crate::{set::GlobSet, GlobPattern};
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// Names and paths which can be matched against a [`GlobPattern`] or a
+/// [`GlobSet`].
+///
+/// ```
+/// # #[cfg(all(feature = "parser", feature = "std"))] {
+/// use goglob_common::{ext::GlobMatched, set::GlobSet, GlobPattern};
+/// use std::path::Path;
+///
+/// let pattern = GlobPattern::new("*.rs").unwrap();
+/// assert!("main.rs".matches_glob(&pattern));
+/// assert!(!String::from("main.c").matches_glob(&pattern));
+///
+/// let set = GlobSet::compile(["src/*.rs", "Cargo.*"]).unwrap();
+/// assert!(Path::new("src/lib.rs").matches_any(&set));
+/// # }
+/// ```
+pub trait GlobMatched {
+    /// Report whether `self` matches `pattern`.
+    fn matches_glob(&self, pattern: &GlobPattern) -> bool;
+
+    /// Report whether `self` matches any pattern in `set`.
+    fn matches_any(&self, set: &GlobSet) -> bool;
+}
+impl GlobMatched for str {
+    #[inline]
+    fn matches_glob(&self, pattern: &GlobPattern) -> bool {
+        pattern.matches(self)
+    }
+
+    #[inline]
+    fn matches_any(&self, set: &GlobSet) -> bool {
+        set.matches(self)
+    }
+}
+impl GlobMatched for String {
+    #[inline]
+    fn matches_glob(&self, pattern: &GlobPattern) -> bool {
+        pattern.matches(self)
+    }
+
+    #[inline]
+    fn matches_any(&self, set: &GlobSet) -> bool {
+        set.matches(self)
+    }
+}
+/// Paths are matched with [`GlobPattern::matches_path()`] and
+/// [`GlobSet::matches_path()`].
+#[cfg(feature = "std")]
+impl GlobMatched for Path {
+    #[inline]
+    fn matches_glob(&self, pattern: &GlobPattern) -> bool {
+        pattern.matches_path(self)
+    }
+
+    #[inline]
+    fn matches_any(&self, set: &GlobSet) -> bool {
+        set.matches_path(self)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{ext::GlobMatched, set::GlobSet, GlobPattern};
+    use alloc::string::String;
+
+    #[test]
+    fn glob_matched() {
+        let pattern = GlobPattern::new("src/*.rs").unwrap();
+        let set = GlobSet::compile(["*.md", "src/*.rs"]).unwrap();
+        for name in ["src/lib.rs", "README.md", "src/bin/main.rs"] {
+            assert_eq!(name.matches_glob(&pattern), pattern.matches(name));
+            assert_eq!(String::from(name).matches_any(&set), set.matches(name));
+        }
+
+        #[cfg(feature = "std")]
+        {
+            use crate::options::MatchOptions;
+            use std::path::{Path, PathBuf};
+
+            assert!(PathBuf::from("src/lib.rs").matches_glob(&pattern));
+            assert!(!Path::new("./src/lib.rs").matches_any(&set));
+            let options = MatchOptions::new().normalize_paths(true);
+            let pattern = GlobPattern::new_with("src/*.rs", &options).unwrap();
+            let set: GlobSet = [pattern.clone()].into_iter().collect();
+            assert!(Path::new("./src/lib.rs").matches_glob(&pattern));
+            assert!(Path::new("./src/lib.rs").matches_any(&set));
+        }
+    }
+}
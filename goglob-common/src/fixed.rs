@@ -0,0 +1,649 @@
+//! Fixed-capacity patterns which never allocate, for targets without `alloc`.
+
+use crate::{
+    error::{Error, ErrorType},
+    matching::{self, Haystack, MatchToken, MismatchSink, Steps, Wildcard},
+    options::MatchOptions,
+    outcome::MismatchReason,
+    scan::{self, ScanSink},
+    Result,
+};
+use core::fmt::{self, Debug, Formatter};
+
+/// Size in bytes of each character class entry (a pair of `u32`s).
+const CLASS_ENTRY_LEN: usize = 8;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum FixedToken {
+    /// UTF-8 text stored in `bytes[start..end]`.
+    Literal {
+        start: usize,
+        end: usize,
+    },
+    /// Entries stored in `bytes[start..end]` as little-endian `(first, last)`
+    /// character pairs.
+    CharClass {
+        negated: bool,
+        start: usize,
+        end: usize,
+    },
+    SeqWildcard,
+    SingleWildcard,
+}
+
+/// Shell pattern matching similar to golang's `path.Match`, storing up to
+/// `TOKENS` tokens and `BYTES` bytes inline instead of on the heap.
+///
+/// `BYTES` holds the UTF-8 text of every literal, plus 8 bytes per character
+/// class entry (e.g. `[a-cx]` takes 16 bytes). Compiling a pattern which
+/// doesn't fit fails with [`ErrorType::CapacityExceeded`], pointing at the
+/// first element which didn't fit.
+///
+/// Matching behaves exactly like [`GlobPattern`][crate::GlobPattern].
+///
+/// Patterns can also be compiled at compile time with
+/// [`const_new()`][Self::const_new], without the `glob!()` procedural macro:
+///
+/// ```
+/// use goglob_common::fixed::FixedGlobPattern;
+///
+/// const RUST_SOURCES: FixedGlobPattern<4, 16> = FixedGlobPattern::const_new("src/*.rs");
+/// assert!(RUST_SOURCES.matches("src/lib.rs"));
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct FixedGlobPattern<const TOKENS: usize, const BYTES: usize> {
+    tokens: [FixedToken; TOKENS],
+    token_count: usize,
+    bytes: [u8; BYTES],
+    byte_count: usize,
+}
+impl<const TOKENS: usize, const BYTES: usize> FixedGlobPattern<TOKENS, BYTES> {
+    /// Compile the given `pattern`, returning an [error][crate::error::Error]
+    /// if it's syntactically invalid or exceeds the capacity.
+    pub fn new(pattern: &str) -> Result<Self> {
+        let mut compiled = Self {
+            tokens: [FixedToken::SeqWildcard; TOKENS],
+            token_count: 0,
+            bytes: [0; BYTES],
+            byte_count: 0,
+        };
+        scan::scan(
+            pattern,
+            &MatchOptions::new(),
+            &mut FixedSink {
+                pattern: &mut compiled,
+                in_literal: false,
+            },
+        )?;
+        Ok(compiled)
+    }
+
+    /// Compile the given `pattern` in a `const` context, panicking (and thus
+    /// failing to build if evaluated at compile time) if it's syntactically
+    /// invalid or exceeds the capacity.
+    ///
+    /// The result is the same as with [`new()`][Self::new], which should be
+    /// preferred at runtime.
+    ///
+    /// ```compile_fail
+    /// use goglob_common::fixed::FixedGlobPattern;
+    ///
+    /// // error: character class opened with '[' isn't closed
+    /// const UNCLOSED: FixedGlobPattern<4, 16> = FixedGlobPattern::const_new("src/[a-z");
+    /// ```
+    pub const fn const_new(pattern: &str) -> Self {
+        match Self::scan_const(pattern) {
+            Ok(compiled) => compiled,
+            Err(error) => panic!("{}", error.error_type().type_desc()),
+        }
+    }
+
+    /// Number of tokens used by the compiled pattern.
+    #[inline]
+    pub fn token_count(&self) -> usize {
+        self.token_count
+    }
+
+    /// Number of bytes used by the compiled pattern.
+    #[inline]
+    pub fn byte_count(&self) -> usize {
+        self.byte_count
+    }
+
+    /// Report whether the `name` matches the compiled shell pattern.
+    pub fn matches(&self, name: &str) -> bool {
+        self.match_tokens(name)
+    }
+
+    /// Report whether the byte string `name`, which may not be valid UTF-8,
+    /// matches the compiled shell pattern (see
+    /// [`GlobPattern::matches_bytes()`][crate::GlobPattern::matches_bytes]).
+    #[inline]
+    pub fn matches_bytes<B: AsRef<[u8]>>(&self, name: B) -> bool {
+        self.match_tokens(name.as_ref())
+    }
+    pub(crate) fn match_tokens<H: Haystack>(&self, name: H) -> bool {
+        self.match_tokens_detailed(name, &mut ())
+    }
+    /// Same as [`match_tokens()`][Self::match_tokens], recording the reasons
+    /// matching failed into `mismatches`.
+    pub(crate) fn match_tokens_detailed<H: Haystack, M: MismatchSink>(
+        &self,
+        name: H,
+        mismatches: &mut M,
+    ) -> bool {
+        let bytes = &self.bytes[..self.byte_count];
+        let tokens = self.tokens[..self.token_count]
+            .iter()
+            .map(|&token| FixedTokenRef { token, bytes });
+        let options = MatchOptions::new();
+        let mut steps = Steps::unlimited();
+        matching::match_tokens(tokens, name, &options, &mut (), mismatches, &mut steps)
+    }
+
+    /// Counterpart of [`scan::scan()`] with the default options which can be
+    /// evaluated at compile time, thus using neither traits nor iterators.
+    const fn scan_const(pattern: &str) -> Result<Self> {
+        /// Unwrap a `Result`, as `?` can't be used in `const fn`s.
+        macro_rules! tri {
+            ($result:expr) => {
+                match $result {
+                    Ok(value) => value,
+                    Err(error) => return Err(error),
+                }
+            };
+        }
+
+        let pattern = pattern.as_bytes();
+        if pattern.is_empty() {
+            return Err(Error::empty_pattern());
+        }
+        let mut compiled = Self {
+            tokens: [FixedToken::SeqWildcard; TOKENS],
+            token_count: 0,
+            bytes: [0; BYTES],
+            byte_count: 0,
+        };
+
+        let mut i = 0;
+        while i < pattern.len() {
+            // Match star wildcards (e.g. '*ab?cd[e-z]*')
+            //                             ^          ^
+            if pattern[i] == b'*' {
+                compiled = tri!(compiled.with_token(FixedToken::SeqWildcard, i));
+                while i < pattern.len() && pattern[i] == b'*' {
+                    i += 1;
+                }
+            }
+
+            // Match literals (e.g. '*ab?cd[e-z]*')
+            //                        ^^ ^^
+            let mut literal = false;
+            while i < pattern.len() {
+                let (start, len) = match pattern[i] {
+                    b']' => return Err(Error::new(ErrorType::UnescapedChar(']'), i)),
+                    b'[' | b'?' | b'*' => break,
+                    b'\\' if i + 1 == pattern.len() => {
+                        return Err(Error::new(ErrorType::IllegalEscape, i))
+                    }
+                    b'\\' => (i + 1, decode(pattern, i + 1).1),
+                    _ => (i, decode(pattern, i).1),
+                };
+                if !literal {
+                    let start = compiled.byte_count;
+                    let token = FixedToken::Literal { start, end: start };
+                    compiled = tri!(compiled.with_token(token, i));
+                    literal = true;
+                }
+                compiled = tri!(compiled.with_bytes(pattern, start, len, i));
+                i = start + len;
+            }
+
+            // Match question-mark wildcards (e.g. '*ab?cd[e-z]*')
+            //                                         ^
+            while i < pattern.len() && pattern[i] == b'?' {
+                compiled = tri!(compiled.with_token(FixedToken::SingleWildcard, i));
+                i += 1;
+            }
+
+            // Match character class (e.g. '*ab?cd[e-z]*')
+            //                                    ^^^^^
+            if i < pattern.len() && pattern[i] == b'[' {
+                let start_i = i;
+                i += 1;
+                let negated = i < pattern.len() && pattern[i] == b'^';
+                if negated {
+                    i += 1;
+                }
+                let start = compiled.byte_count;
+                let token = FixedToken::CharClass {
+                    negated,
+                    start,
+                    end: start,
+                };
+                compiled = tri!(compiled.with_token(token, start_i));
+
+                let mut entries = 0;
+                let mut closed_i = None;
+                let mut start_range = false;
+                let mut in_range: Option<char> = None;
+                while i < pattern.len() {
+                    let char_i = i;
+                    let (mut c, len) = decode(pattern, i);
+                    i += len;
+                    match c {
+                        ']' => {
+                            closed_i = Some(char_i);
+                            break;
+                        }
+                        '-' if !start_range => {
+                            return Err(Error::new(ErrorType::UnescapedChar('-'), char_i))
+                        }
+                        '-' => {
+                            start_range = false;
+                            continue;
+                        }
+                        '\\' if i == pattern.len() => {
+                            return Err(Error::new(ErrorType::IllegalEscape, char_i))
+                        }
+                        '\\' => {
+                            let (escaped, len) = decode(pattern, i);
+                            c = escaped;
+                            i += len;
+                        }
+                        _ => {}
+                    }
+
+                    if let Some(first) = in_range {
+                        if first as u32 > c as u32 {
+                            let error = ErrorType::InvalidRangeValues(first, c);
+                            return Err(Error::new(error, char_i));
+                        }
+                        compiled = tri!(compiled.with_class_entry(first, c, char_i));
+                        entries += 1;
+                        in_range = None;
+                    } else if i < pattern.len() && pattern[i] == b'-' {
+                        in_range = Some(c);
+                        start_range = true;
+                    } else {
+                        compiled = tri!(compiled.with_class_entry(c, c, char_i));
+                        entries += 1;
+                    }
+                }
+
+                // A character class must be closed with a corresponding ']',
+                // and must not be empty (e.g. []abc] or [^]abc])
+                //                              ^          ^
+                match closed_i {
+                    None => return Err(Error::new(ErrorType::UnclosedCharClass, start_i)),
+                    Some(closed_i) if entries == 0 => {
+                        return Err(Error::new(ErrorType::UnescapedChar(']'), closed_i))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        Ok(compiled)
+    }
+
+    /// `const` counterpart of [`push_token()`][Self::push_token].
+    const fn with_token(mut self, token: FixedToken, pos: usize) -> Result<Self> {
+        if self.token_count == TOKENS {
+            return Err(Error::new(ErrorType::CapacityExceeded, pos));
+        }
+        self.tokens[self.token_count] = token;
+        self.token_count += 1;
+        Ok(self)
+    }
+
+    /// `const` counterpart of [`push_bytes()`][Self::push_bytes], copying
+    /// `source[start..start + len]`.
+    const fn with_bytes(
+        mut self,
+        source: &[u8],
+        start: usize,
+        len: usize,
+        pos: usize,
+    ) -> Result<Self> {
+        if self.byte_count + len > BYTES {
+            return Err(Error::new(ErrorType::CapacityExceeded, pos));
+        }
+        let mut k = 0;
+        while k < len {
+            self.bytes[self.byte_count + k] = source[start + k];
+            k += 1;
+        }
+        self.byte_count += len;
+        let end = self.byte_count;
+        self.tokens[self.token_count - 1] = match self.tokens[self.token_count - 1] {
+            FixedToken::Literal { start, .. } => FixedToken::Literal { start, end },
+            FixedToken::CharClass { negated, start, .. } => FixedToken::CharClass {
+                negated,
+                start,
+                end,
+            },
+            _ => panic!("bytes only belong to literals and classes"),
+        };
+        Ok(self)
+    }
+
+    /// `const` counterpart of [`push_class_entry()`][Self::push_class_entry].
+    const fn with_class_entry(self, first: char, last: char, pos: usize) -> Result<Self> {
+        let (first, last) = ((first as u32).to_le_bytes(), (last as u32).to_le_bytes());
+        let entry = [
+            first[0], first[1], first[2], first[3], last[0], last[1], last[2], last[3],
+        ];
+        self.with_bytes(&entry, 0, CLASS_ENTRY_LEN, pos)
+    }
+
+    fn push_token(&mut self, token: FixedToken, pos: usize) -> Result<()> {
+        let slot = self
+            .tokens
+            .get_mut(self.token_count)
+            .ok_or_else(|| Error::new(ErrorType::CapacityExceeded, pos))?;
+        *slot = token;
+        self.token_count += 1;
+        Ok(())
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8], pos: usize) -> Result<()> {
+        let end = self.byte_count + bytes.len();
+        self.bytes
+            .get_mut(self.byte_count..end)
+            .ok_or_else(|| Error::new(ErrorType::CapacityExceeded, pos))?
+            .copy_from_slice(bytes);
+        self.byte_count = end;
+        // Extend the token the bytes belong to.
+        match &mut self.tokens[self.token_count - 1] {
+            FixedToken::Literal { end: token_end, .. }
+            | FixedToken::CharClass { end: token_end, .. } => *token_end = end,
+            _ => unreachable!("bytes only belong to literals and classes"),
+        }
+        Ok(())
+    }
+
+    fn push_class_entry(&mut self, first: char, last: char, pos: usize) -> Result<()> {
+        let mut entry = [0; CLASS_ENTRY_LEN];
+        entry[..4].copy_from_slice(&u32::from(first).to_le_bytes());
+        entry[4..].copy_from_slice(&u32::from(last).to_le_bytes());
+        self.push_bytes(&entry, pos)
+    }
+}
+impl<const TOKENS: usize, const BYTES: usize> Debug for FixedGlobPattern<TOKENS, BYTES> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedGlobPattern")
+            .field("tokens", &&self.tokens[..self.token_count])
+            .field("bytes", &&self.bytes[..self.byte_count])
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const TOKENS: usize, const BYTES: usize> defmt::Format for FixedGlobPattern<TOKENS, BYTES> {
+    /// Render the pattern back into pattern syntax.
+    fn format(&self, f: defmt::Formatter<'_>) {
+        use crate::defmt::write_escaped;
+
+        for token in &self.tokens[..self.token_count] {
+            match *token {
+                FixedToken::Literal { start, end } => {
+                    let literal = core::str::from_utf8(&self.bytes[start..end]).unwrap_or_default();
+                    literal.chars().for_each(|c| write_escaped(f, c, false));
+                }
+                FixedToken::CharClass {
+                    negated,
+                    start,
+                    end,
+                } => {
+                    defmt::write!(f, "[");
+                    if negated {
+                        defmt::write!(f, "^");
+                    }
+                    for entry in self.bytes[start..end].chunks_exact(CLASS_ENTRY_LEN) {
+                        let first = u32::from_le_bytes(entry[..4].try_into().unwrap());
+                        let last = u32::from_le_bytes(entry[4..].try_into().unwrap());
+                        let (first, last) = (
+                            char::from_u32(first).unwrap_or_default(),
+                            char::from_u32(last).unwrap_or_default(),
+                        );
+                        write_escaped(f, first, true);
+                        if first != last {
+                            defmt::write!(f, "-");
+                            write_escaped(f, last, true);
+                        }
+                    }
+                    defmt::write!(f, "]");
+                }
+                FixedToken::SeqWildcard => defmt::write!(f, "*"),
+                FixedToken::SingleWildcard => defmt::write!(f, "?"),
+            }
+        }
+    }
+}
+
+/// Decode the UTF-8 character starting at `bytes[i]` (which must be valid
+/// UTF-8), returning it along with its length.
+const fn decode(bytes: &[u8], i: usize) -> (char, usize) {
+    let first = bytes[i] as u32;
+    let (len, mut c) = match first {
+        0x00..=0x7F => (1, first),
+        0xC0..=0xDF => (2, first & 0x1F),
+        0xE0..=0xEF => (3, first & 0x0F),
+        _ => (4, first & 0x07),
+    };
+    let mut k = 1;
+    while k < len {
+        c = (c << 6) | (bytes[i + k] as u32 & 0x3F);
+        k += 1;
+    }
+    match char::from_u32(c) {
+        Some(c) => (c, len),
+        None => panic!("patterns are valid UTF-8"),
+    }
+}
+
+#[derive(Clone)]
+struct FixedTokenRef<'b> {
+    token: FixedToken,
+    bytes: &'b [u8],
+}
+impl MatchToken for FixedTokenRef<'_> {
+    fn try_matches_next<H: Haystack>(
+        &self,
+        name: H,
+        options: &MatchOptions,
+    ) -> core::result::Result<Option<H>, Wildcard> {
+        match self.token {
+            FixedToken::Literal { start, end } => {
+                Ok(name.strip_literal_folded(&self.bytes[start..end], options.case_fold))
+            }
+            FixedToken::CharClass {
+                negated,
+                start,
+                end,
+            } => {
+                let entries = self.bytes[start..end].chunks_exact(CLASS_ENTRY_LEN);
+                Ok(name.strip_char(|c| {
+                    let Some(c) = c else {
+                        return negated;
+                    };
+                    options.case_fold.variants(c).into_iter().any(|c| {
+                        entries.clone().any(|entry| {
+                            let first = u32::from_le_bytes(entry[..4].try_into().unwrap());
+                            let last = u32::from_le_bytes(entry[4..].try_into().unwrap());
+                            (first..=last).contains(&u32::from(c))
+                        })
+                    }) != negated
+                }))
+            }
+            FixedToken::SingleWildcard => {
+                Ok(name.strip_char(|c| c != Some(options.effective_separator())))
+            }
+            FixedToken::SeqWildcard => Err(Wildcard::Seq),
+        }
+    }
+
+    fn mismatch<H: Haystack>(&self, name: H, options: &MatchOptions) -> (MismatchReason, H) {
+        match self.token {
+            FixedToken::Literal { start, end } => {
+                matching::literal_mismatch(name, &self.bytes[start..end], options.case_fold)
+            }
+            FixedToken::CharClass { .. } => {
+                matching::char_mismatch(name, MismatchReason::CharClass)
+            }
+            FixedToken::SingleWildcard => matching::char_mismatch(name, MismatchReason::Separator),
+            FixedToken::SeqWildcard => unreachable!("wildcards are handled by match_tokens()"),
+        }
+    }
+
+    fn is_capturing(&self) -> bool {
+        !matches!(self.token, FixedToken::Literal { .. })
+    }
+}
+
+/// Stores scanned elements into a [`FixedGlobPattern`].
+struct FixedSink<'p, const TOKENS: usize, const BYTES: usize> {
+    pattern: &'p mut FixedGlobPattern<TOKENS, BYTES>,
+    in_literal: bool,
+}
+impl<const TOKENS: usize, const BYTES: usize> ScanSink for FixedSink<'_, TOKENS, BYTES> {
+    fn seq_wildcard(&mut self, pos: usize) -> Result<()> {
+        self.pattern.push_token(FixedToken::SeqWildcard, pos)
+    }
+
+    fn any_dirs(&mut self, _: usize) -> Result<()> {
+        unreachable!("fixed patterns are always scanned with the default options")
+    }
+
+    fn single_wildcard(&mut self, pos: usize) -> Result<()> {
+        self.pattern.push_token(FixedToken::SingleWildcard, pos)
+    }
+
+    fn literal_char(&mut self, c: char, pos: usize) -> Result<()> {
+        if !self.in_literal {
+            let start = self.pattern.byte_count;
+            let token = FixedToken::Literal { start, end: start };
+            self.pattern.push_token(token, pos)?;
+            self.in_literal = true;
+        }
+        self.pattern
+            .push_bytes(c.encode_utf8(&mut [0; 4]).as_bytes(), pos)
+    }
+
+    fn end_literal(&mut self) -> Result<()> {
+        self.in_literal = false;
+        Ok(())
+    }
+
+    fn start_class(&mut self, negated: bool, pos: usize) -> Result<()> {
+        let start = self.pattern.byte_count;
+        let token = FixedToken::CharClass {
+            negated,
+            start,
+            end: start,
+        };
+        self.pattern.push_token(token, pos)
+    }
+
+    fn class_char(&mut self, c: char, pos: usize) -> Result<()> {
+        self.pattern.push_class_entry(c, c, pos)
+    }
+
+    fn class_range(&mut self, start: char, end: char, pos: usize) -> Result<()> {
+        self.pattern.push_class_entry(start, end, pos)
+    }
+
+    fn end_class(&mut self, _: usize) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{Error, ErrorType};
+    use crate::fixed::FixedGlobPattern;
+    use goglob_testutil::{assert_conformance, ConformanceMatcher, GO_MATCH_VECTORS};
+
+    type Pattern = FixedGlobPattern<16, 64>;
+
+    impl ConformanceMatcher for Pattern {
+        type Error = Error;
+
+        fn compile(pattern: &str) -> Result<Self, Error> {
+            Pattern::new(pattern)
+        }
+
+        fn matches(&self, name: &str) -> bool {
+            Pattern::matches(self, name)
+        }
+    }
+
+    #[test]
+    fn fixed_glob_pattern_go_match_test() {
+        assert_conformance::<Pattern>();
+    }
+
+    #[test]
+    fn fixed_glob_pattern_const_new() {
+        const PATTERN: Pattern = Pattern::const_new("a\\*[^b-dé]?*/ü*");
+        assert_eq!(PATTERN, Pattern::new("a\\*[^b-dé]?*/ü*").unwrap());
+        assert!(PATTERN.matches("a*xyz/üv"));
+        assert!(!PATTERN.matches("a*cyz/üv"));
+
+        let extra = [
+            "ab*[c-e]?",
+            "[\\]a]",
+            "[a-]",
+            "[-a]",
+            "[^^]",
+            "a\\",
+            "[a\\",
+            "**?",
+            "[z-a]",
+            "[é-ü]x",
+            "",
+        ];
+        let vectors = GO_MATCH_VECTORS.iter().map(|vector| vector.pattern);
+        for pattern in vectors.chain(extra) {
+            match (Pattern::scan_const(pattern), Pattern::new(pattern)) {
+                (Ok(scanned), Ok(compiled)) => assert_eq!(scanned, compiled, "{pattern}"),
+                (Err(scanned), Err(compiled)) => {
+                    assert_eq!(scanned.position(), compiled.position(), "{pattern}");
+                    assert_eq!(
+                        scanned.error_type().type_desc(),
+                        compiled.error_type().type_desc()
+                    );
+                }
+                (scanned, compiled) => panic!("{pattern}: {scanned:?} != {compiled:?}"),
+            }
+        }
+
+        for pattern in ["ab*[c-e]?", "ab*[c-ex]", "[", "abcdefghijklm"] {
+            let scanned = FixedGlobPattern::<3, 12>::scan_const(pattern)
+                .err()
+                .unwrap();
+            let compiled = FixedGlobPattern::<3, 12>::new(pattern).err().unwrap();
+            assert_eq!(scanned.position(), compiled.position(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn fixed_glob_pattern_capacity() {
+        let pattern = FixedGlobPattern::<3, 12>::new("ab*[c-e]").unwrap();
+        assert_eq!((pattern.token_count(), pattern.byte_count()), (3, 10));
+        assert!(pattern.matches("abxyd"));
+        assert!(!pattern.matches("abxyf"));
+
+        let error = FixedGlobPattern::<3, 12>::new("ab*[c-e]?").err().unwrap();
+        assert!(matches!(error.error_type(), ErrorType::CapacityExceeded));
+        assert_eq!(error.position(), 8);
+
+        let error = FixedGlobPattern::<4, 12>::new("ab*[c-ex]").err().unwrap();
+        assert!(matches!(error.error_type(), ErrorType::CapacityExceeded));
+        assert_eq!(error.position(), 7);
+
+        // Syntax errors take precedence over capacity as long as they are found first.
+        let error = FixedGlobPattern::<1, 1>::new("[").err().unwrap();
+        assert!(matches!(error.error_type(), ErrorType::UnclosedCharClass));
+    }
+}
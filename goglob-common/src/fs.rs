@@ -0,0 +1,1829 @@
+//! Filesystem globbing similar to golang's `filepath.Glob`.
+//!
+//! Patterns are matched one `/`-separated segment at a time, so only the
+//! directories which can actually contain matches are ever read. A `**/` (see
+//! [`Syntax::Gitignore`][crate::syntax::Syntax::Gitignore]) descends into every
+//! directory below the one it's in, skipping symbolic links leading back to a
+//! directory it's already in so that loops don't make it recurse forever.
+//!
+//! On Windows, a leading drive (`C:\`), UNC share (`\\server\share\`) or
+//! verbatim (`\\?\`) root in the patterns given to [`glob()`],
+//! [`glob_with()`] and [`glob_with_walk()`] is taken as is, only the components
+//! after it being matched, with either `\` or `/` separating them (`\` is
+//! then never an escape). Already compiled patterns, as given to [`glob_in()`],
+//! can't have such a root, which should be the base directory instead.
+
+use crate::{
+    options::{CaseFold, MatchOptions},
+    set::GlobSet,
+    GlobPattern, GlobToken, Result,
+};
+#[cfg(feature = "camino")]
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::SystemTime;
+
+/// Options controlling how the filesystem is walked by [`glob_in()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WalkOptions {
+    hidden: bool,
+    follow_symlinks: bool,
+    max_depth: Option<usize>,
+    expand_tilde: bool,
+    excludes: Option<GlobSet>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    modified_after: Option<SystemTime>,
+    modified_before: Option<SystemTime>,
+    output: PathStyle,
+    cancel: Option<Hook<AtomicBool>>,
+    progress: Option<Hook<dyn WalkProgress>>,
+}
+impl WalkOptions {
+    /// Default options, matching golang's `filepath.Glob` behaviour: hidden
+    /// files are matched like any other, symbolic links are followed and there
+    /// is no depth limit other than the pattern's own. A leading `~` is a
+    /// literal directory name.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            hidden: true,
+            follow_symlinks: true,
+            max_depth: None,
+            expand_tilde: false,
+            excludes: None,
+            min_size: None,
+            max_size: None,
+            modified_after: None,
+            modified_before: None,
+            output: PathStyle::AsProvided,
+            cancel: None,
+            progress: None,
+        }
+    }
+
+    /// Whether wildcards and character classes may match names starting with
+    /// `.`. When disabled, hidden files are only matched by segments which
+    /// explicitly start with a literal `.` (e.g. `.config/*`).
+    #[inline]
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Whether to descend into symbolic links pointing to directories.
+    /// Symbolic links are still reported when they match the final segment.
+    /// A `**/` doesn't descend into links to one of the directories it's
+    /// walking through (as `loop -> ..` would otherwise repeat forever),
+    /// reporting them to [`WalkProgress::symlink_loop()`] instead.
+    #[inline]
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Maximum number of directories to descend below the base directory, or
+    /// `None` for no limit.
+    #[inline]
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Whether a leading `~` segment stands for the current user's home
+    /// directory, and `~user` for that of `user` (e.g. `~/.config/*` or
+    /// `~alice/src/*.rs`), as in shells. Home directories come from `$HOME`
+    /// and the user database (`getpwnam_r()`) on Unix and from `%USERPROFILE%`
+    /// on Windows (other users' being next to it); if one can't be found the
+    /// segment is taken literally, as is an escaped `\~`.
+    #[inline]
+    pub fn expand_tilde(mut self, expand_tilde: bool) -> Self {
+        self.expand_tilde = expand_tilde;
+        self
+    }
+
+    /// Patterns of the paths to leave out, matched against paths relative to
+    /// the base directory (e.g. `target` or `src/*/generated.rs`), or `None`
+    /// to keep every match. Excluded directories aren't read at all, nor are
+    /// those whose contents are all excluded (e.g. by `target/*`, or
+    /// `target/**` in [`Syntax::Gitignore`][crate::syntax::Syntax::Gitignore]),
+    /// which is much cheaper than filtering the results afterwards.
+    #[inline]
+    pub fn excludes(mut self, excludes: Option<GlobSet>) -> Self {
+        self.excludes = excludes;
+        self
+    }
+
+    /// Minimum size in bytes of the matches to report (following symbolic
+    /// links), or `None` for no minimum. As with every metadata filter,
+    /// matches whose metadata can't be read (such as broken symbolic links)
+    /// aren't reported when it's set, and the metadata of each match is only
+    /// read once however many filters there are.
+    #[inline]
+    pub fn min_size(mut self, min_size: Option<u64>) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Maximum size in bytes of the matches to report, or `None` for no
+    /// maximum.
+    #[inline]
+    pub fn max_size(mut self, max_size: Option<u64>) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Only report matches last modified after `time`, if `Some`.
+    #[inline]
+    pub fn modified_after(mut self, time: Option<SystemTime>) -> Self {
+        self.modified_after = time;
+        self
+    }
+
+    /// Only report matches last modified before `time`, if `Some` (e.g. a
+    /// week ago, to find logs older than that).
+    ///
+    /// ```no_run
+    /// use goglob_common::{fs::{glob_in, WalkOptions}, GlobPattern};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let week_ago = SystemTime::now() - Duration::from_secs(7 * 24 * 60 * 60);
+    /// let options = WalkOptions::new().modified_before(Some(week_ago));
+    /// let old_logs = glob_in("/var/log", &GlobPattern::new("*.log").unwrap(), &options);
+    /// ```
+    #[inline]
+    pub fn modified_before(mut self, time: Option<SystemTime>) -> Self {
+        self.modified_before = time;
+        self
+    }
+
+    /// How the resulting paths are written, [as provided][PathStyle::AsProvided]
+    /// by default.
+    #[inline]
+    pub fn output(mut self, output: PathStyle) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Flag to stop walking as soon as it's set (e.g. from another thread when
+    /// the user cancels a search), or `None` to always walk the whole tree.
+    /// The matches found up to that point are still returned.
+    ///
+    /// ```no_run
+    /// use goglob_common::{fs::{glob_in, WalkOptions}, GlobPattern};
+    /// use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+    ///
+    /// let cancel = Arc::new(AtomicBool::new(false));
+    /// let options = WalkOptions::new().cancel(Some(cancel.clone()));
+    /// let search = std::thread::spawn(move || {
+    ///     glob_in("/", &GlobPattern::new("*/*/*.log").unwrap(), &options)
+    /// });
+    /// cancel.store(true, Ordering::Relaxed);
+    /// let partial = search.join().unwrap();
+    /// ```
+    #[inline]
+    pub fn cancel(mut self, cancel: Option<Arc<AtomicBool>>) -> Self {
+        self.cancel = cancel.map(Hook);
+        self
+    }
+
+    /// Receiver of the progress of the walk, or `None` not to report it.
+    #[inline]
+    pub fn progress(mut self, progress: Option<Arc<dyn WalkProgress>>) -> Self {
+        self.progress = progress.map(Hook);
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.0.load(Ordering::Relaxed))
+    }
+
+    fn visited(&self, dir: &Path) {
+        if let Some(progress) = &self.progress {
+            progress.0.visited(dir);
+        }
+    }
+
+    fn found(&self, path: &Path) {
+        if let Some(progress) = &self.progress {
+            progress.0.found(path);
+        }
+    }
+
+    fn symlink_loop(&self, path: &Path) {
+        if let Some(progress) = &self.progress {
+            progress.0.symlink_loop(path);
+        }
+    }
+}
+impl Default for WalkOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Receiver of the progress of a walk, set with [`WalkOptions::progress()`]
+/// (e.g. to display how many directories were searched so far).
+///
+/// Every method has a no-op default implementation. Methods are called
+/// synchronously from the thread walking the filesystem, so they should be
+/// cheap (e.g. increment an atomic counter).
+pub trait WalkProgress: Send + Sync {
+    /// The entries of `dir` were read. Directories whose entries don't need
+    /// to be read (as the pattern names a single one of them) aren't
+    /// reported.
+    fn visited(&self, dir: &Path) {
+        let _ = dir;
+    }
+
+    /// A match was found, `path` being written as it's returned.
+    fn found(&self, path: &Path) {
+        let _ = path;
+    }
+
+    /// `path` wasn't walked into by a `**/` as it leads back to one of the
+    /// directories it's already in (e.g. `loop -> ..`).
+    fn symlink_loop(&self, path: &Path) {
+        let _ = path;
+    }
+}
+
+/// Value shared by every clone of some [`WalkOptions`], which are only equal
+/// if they share the same one.
+struct Hook<T: ?Sized>(Arc<T>);
+impl<T: ?Sized> Clone for Hook<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+impl<T: ?Sized> PartialEq for Hook<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl<T: ?Sized> Eq for Hook<T> {}
+impl<T: ?Sized> core::fmt::Debug for Hook<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Hook({:p})", Arc::as_ptr(&self.0))
+    }
+}
+
+/// How the paths returned by [`glob_in()`] are written (see
+/// [`WalkOptions::output()`]).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum PathStyle {
+    /// The base directory joined with the matching names, so results are
+    /// relative if the base is (e.g. `src/lib.rs` for the base `src`).
+    #[default]
+    AsProvided,
+    /// Just the matching names, relative to the directory the pattern is
+    /// relative to: the base directory, the root for absolute patterns or the
+    /// home directory for a leading `~` (e.g. `lib.rs` for the base `src`, or
+    /// `.` for that directory itself).
+    RelativeToBase,
+    /// Absolute paths, made so by joining relative ones with the current
+    /// directory (without resolving symbolic links, see
+    /// [`std::path::absolute()`]).
+    Absolute,
+}
+
+/// Compile `pattern` and return the paths of every file matching it relative to
+/// the current directory (or to the root if `pattern` starts with `/`), sorted
+/// in lexical order per directory.
+///
+/// As with golang's `filepath.Glob`, I/O errors (such as permission errors
+/// while reading a directory) are ignored, so the only possible error is a
+/// syntactically invalid pattern. Use [`glob_with_walk()`] to walk the
+/// filesystem with other [`WalkOptions`], e.g. to
+/// [exclude][WalkOptions::excludes] directories such as `target`.
+pub fn glob<S: AsRef<str>>(pattern: S) -> Result<Vec<PathBuf>> {
+    glob_with(pattern, &MatchOptions::new())
+}
+
+/// Same as [`glob()`], compiling `pattern` according to `options` (e.g. to
+/// match names case-insensitively).
+pub fn glob_with<S: AsRef<str>>(pattern: S, options: &MatchOptions) -> Result<Vec<PathBuf>> {
+    glob_with_walk(pattern, options, &WalkOptions::new())
+}
+
+/// Same as [`glob_with()`], walking the filesystem according to
+/// `walk_options` (e.g. to [expand a leading `~`][WalkOptions::expand_tilde]).
+///
+/// ```no_run
+/// use goglob_common::{fs::{glob_with_walk, WalkOptions}, options::MatchOptions};
+///
+/// let options = WalkOptions::new().expand_tilde(true);
+/// let configs = glob_with_walk("~/.config/*", &MatchOptions::new(), &options).unwrap();
+/// ```
+pub fn glob_with_walk<S: AsRef<str>>(
+    pattern: S,
+    options: &MatchOptions,
+    walk_options: &WalkOptions,
+) -> Result<Vec<PathBuf>> {
+    let pattern = pattern.as_ref();
+    let pattern = match split_root(pattern) {
+        Some((root, rest)) if cfg!(windows) => {
+            if rest.is_empty() {
+                // Nothing but a root, which is either there or not.
+                let root = PathBuf::from(root);
+                return Ok(fs::metadata(&root).map(|_| root).into_iter().collect());
+            }
+            // A path starting with a root is a Windows one, separated by '\'
+            // (or '/') rather than escaped.
+            let options = options.separator('\\').escapes(false);
+            let pattern = GlobPattern::new_with(rest.replace('/', "\\"), &options)?;
+            return Ok(glob_in(root, &pattern, walk_options));
+        }
+        _ => pattern,
+    };
+    let pattern = GlobPattern::new_with(pattern, options)?;
+    Ok(glob_in("", &pattern, walk_options))
+}
+
+/// Return the paths of every file under `base` matching `pattern`, walking the
+/// filesystem according to `options`.
+///
+/// Resulting paths are `base` joined with the matching names. If `pattern`
+/// starts with `/` it's considered absolute and `base` is ignored, much like
+/// [`Path::join()`]. If it ends with `/` only directories (or symbolic links
+/// to them) match. The same goes for a leading `~` if
+/// [tilde expansion][WalkOptions::expand_tilde] is enabled. Windows roots
+/// such as `C:\` aren't recognized (see the [module docs][self]), `base`
+/// should be one instead.
+#[inline]
+pub fn glob_in<P: AsRef<Path>>(
+    base: P,
+    pattern: &GlobPattern,
+    options: &WalkOptions,
+) -> Vec<PathBuf> {
+    glob_iter(base, pattern, options).collect()
+}
+
+/// Same as [`glob_in()`], but returning the paths lazily (in the same order)
+/// as directories are read, so that walking stops as soon as the iterator is
+/// dropped, e.g. to check whether any file matches.
+///
+/// ```no_run
+/// use goglob_common::{fs::{glob_iter, WalkOptions}, GlobPattern};
+///
+/// let pattern = GlobPattern::new("*/*.rs").unwrap();
+/// let any_rust = glob_iter(".", &pattern, &WalkOptions::new()).next().is_some();
+/// ```
+pub fn glob_iter<P: AsRef<Path>>(
+    base: P,
+    pattern: &GlobPattern,
+    options: &WalkOptions,
+) -> GlobIter {
+    let (base, segments) = split_start(base.as_ref(), pattern, options);
+    let mut iter = GlobIter {
+        base,
+        segments: Vec::new(),
+        options: options.clone(),
+        dir_only: pattern.is_dir_only(),
+        pending: Vec::new(),
+        ready: VecDeque::new(),
+    };
+    let segments = match segments {
+        Some(segments) => segments,
+        None => {
+            // Just '~' (or '~/'), i.e. the home directory itself.
+            let home = iter.base.clone();
+            iter.ready.extend(fs::metadata(&home).map(|_| home));
+            iter.dir_only = false;
+            return iter;
+        }
+    };
+    if !segments.is_empty() {
+        iter.pending.push(Pending::Walk {
+            dir: iter.base.clone(),
+            segment: 0,
+            depth: 0,
+        });
+    }
+    iter.segments = segments;
+    iter
+}
+
+/// Directory `pattern` is matched in (`base`, the root if it's absolute or a
+/// home directory if it starts with an expanded `~`) and the segments left to
+/// match there, or `None` if it's just a home directory, which matches itself.
+fn split_start(
+    base: &Path,
+    pattern: &GlobPattern,
+    options: &WalkOptions,
+) -> (PathBuf, Option<Vec<GlobPattern>>) {
+    let mut segments = pattern.segments();
+    let mut base = base.to_path_buf();
+    // An escaped '\~' is a literal one, just like in shells.
+    let home = (options.expand_tilde && pattern.as_str().starts_with('~'))
+        .then(|| segments.first().and_then(tilde_home))
+        .flatten();
+    if let Some(home) = home {
+        base = home;
+        segments.remove(0);
+        if segments.iter().all(|segment| segment.tokens().is_empty()) {
+            return (base, None);
+        }
+    } else if segments.len() > 1 && segments[0].tokens().is_empty() {
+        base = PathBuf::from("/");
+    }
+    // Repeated (or leading) '/' don't introduce any additional directories.
+    segments.retain(|segment| !segment.tokens().is_empty());
+    (base, Some(segments))
+}
+
+/// Lazy iterator over the paths matching a pattern, returned by
+/// [`glob_iter()`].
+#[derive(Debug)]
+pub struct GlobIter {
+    base: PathBuf,
+    segments: Vec<GlobPattern>,
+    options: WalkOptions,
+    dir_only: bool,
+    /// Directories left to walk, the next one last.
+    pending: Vec<Pending>,
+    /// Matches found but not returned yet.
+    ready: VecDeque<PathBuf>,
+}
+impl Iterator for GlobIter {
+    type Item = PathBuf;
+
+    fn next(&mut self) -> Option<PathBuf> {
+        loop {
+            if self.options.is_cancelled() {
+                return None;
+            }
+            while let Some(path) = self.ready.pop_front() {
+                if !self.dir_only || fs::metadata(fs_path(&path)).is_ok_and(|m| m.is_dir()) {
+                    let path = write_path(&self.base, path, &self.options);
+                    self.options.found(&path);
+                    return Some(path);
+                }
+            }
+            match self.pending.pop()? {
+                Pending::Walk {
+                    dir,
+                    segment,
+                    depth,
+                } => self.walk(&dir, segment, depth),
+                Pending::AnyDirs {
+                    dir,
+                    segment,
+                    depth,
+                    ancestors,
+                } => self.walk_any_dirs(dir, segment, depth, ancestors),
+            }
+        }
+    }
+}
+
+/// Directory a [`GlobIter`] has yet to walk.
+#[derive(Debug)]
+enum Pending {
+    /// Match the entries of `dir` against the `segment`-th segment.
+    Walk {
+        dir: PathBuf,
+        segment: usize,
+        depth: usize,
+    },
+    /// Match `dir` and every directory below it against the `segment`-th
+    /// segment, a `**/`, having walked through `ancestors` to get to `dir`.
+    AnyDirs {
+        dir: PathBuf,
+        segment: usize,
+        depth: usize,
+        ancestors: Vec<DirId>,
+    },
+}
+
+impl GlobSet {
+    /// Return every file under `base` matching any pattern of the set (see
+    /// [`glob_in()`]), each only once along with the indices of the patterns
+    /// matching it, in lexical order.
+    ///
+    /// The filesystem is walked once for all the patterns (once per base
+    /// directory if some are absolute or start with `~`), matching each
+    /// directory's entries against every pattern which may match inside it,
+    /// so directories no pattern can match in are never read and those
+    /// several patterns go through are only read once.
+    ///
+    /// Paths are compared as written (see [`WalkOptions::output()`]), so a
+    /// file matched by both a relative and an absolute pattern is only
+    /// reported once with [`PathStyle::Absolute`]. As with [`glob_in()`],
+    /// Windows roots such as `C:\` aren't recognized in the patterns.
+    ///
+    /// ```no_run
+    /// use goglob_common::{fs::WalkOptions, set::GlobSet};
+    ///
+    /// let set = GlobSet::compile(["src/*.rs", "src/lib.rs"]).unwrap();
+    /// for found in set.glob_in(".", &WalkOptions::new()) {
+    ///     println!("{} (patterns {:?})", found.path().display(), found.patterns());
+    /// }
+    /// ```
+    pub fn glob_in<P: AsRef<Path>>(&self, base: P, options: &WalkOptions) -> Vec<SetMatch> {
+        let base = base.as_ref();
+        let mut found: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+        let mut walks: Vec<SetWalk> = Vec::new();
+        for (index, pattern) in self.iter().enumerate() {
+            let (start, segments) = split_start(base, pattern, options);
+            let segments = match segments {
+                Some(segments) if segments.is_empty() => continue,
+                Some(segments) => segments,
+                None => {
+                    // Just a home directory, matching itself.
+                    if fs::metadata(&start).is_ok() {
+                        let path = write_path(&start, start.clone(), options);
+                        if !found.contains_key(&path) {
+                            options.found(&path);
+                        }
+                        found.entry(path).or_default().push(index);
+                    }
+                    continue;
+                }
+            };
+            let pattern = SetWalkPattern {
+                index,
+                segments,
+                dir_only: pattern.is_dir_only(),
+            };
+            match walks.iter_mut().find(|walk| walk.base == start) {
+                Some(walk) => walk.patterns.push(pattern),
+                None => walks.push(SetWalk {
+                    base: start,
+                    patterns: vec![pattern],
+                }),
+            }
+        }
+        for walk in &walks {
+            walk.run(options, &mut found);
+        }
+        found
+            .into_iter()
+            .map(|(path, mut patterns)| {
+                patterns.sort_unstable();
+                patterns.dedup();
+                SetMatch { path, patterns }
+            })
+            .collect()
+    }
+}
+
+/// File matching patterns of a [`GlobSet`], as returned by
+/// [`GlobSet::glob_in()`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SetMatch {
+    path: PathBuf,
+    patterns: Vec<usize>,
+}
+impl SetMatch {
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Indices of the patterns matching the path, in ascending order.
+    #[inline]
+    pub fn patterns(&self) -> &[usize] {
+        &self.patterns
+    }
+
+    #[inline]
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+}
+
+/// Same as [`glob()`], returning [`Utf8PathBuf`]s.
+#[cfg(feature = "camino")]
+pub fn glob_utf8<S: AsRef<str>>(pattern: S) -> Result<Vec<Utf8PathBuf>> {
+    Ok(glob(pattern)?
+        .into_iter()
+        .filter_map(|path| Utf8PathBuf::from_path_buf(path).ok())
+        .collect())
+}
+
+/// Same as [`glob_in()`], returning [`Utf8PathBuf`]s.
+#[cfg(feature = "camino")]
+pub fn glob_in_utf8<P: AsRef<Utf8Path>>(
+    base: P,
+    pattern: &GlobPattern,
+    options: &WalkOptions,
+) -> Vec<Utf8PathBuf> {
+    glob_in(base.as_ref(), pattern, options)
+        .into_iter()
+        // Names which aren't valid UTF-8 never match, so as long as the base
+        // is valid UTF-8 so are the results.
+        .filter_map(|path| Utf8PathBuf::from_path_buf(path).ok())
+        .collect()
+}
+
+/// Split a Windows root (a drive such as `C:\`, a UNC share such as
+/// `\\server\share\` or a verbatim `\\?\` path of either) off the start of
+/// `pattern`, accepting `/` as well as `\` as separators.
+fn split_root(pattern: &str) -> Option<(&str, &str)> {
+    fn is_separator(c: char) -> bool {
+        c == '\\' || c == '/'
+    }
+    /// Length of the first `count` components of `path` (and their trailing
+    /// separators), which must all be non-empty.
+    fn components(path: &str, count: usize) -> Option<usize> {
+        let mut len = 0;
+        for _ in 0..count {
+            let component = path[len..].split(is_separator).next()?;
+            let separator = path[len + component.len()..].chars().next();
+            if component.is_empty() || separator.is_some_and(|c| !is_separator(c)) {
+                return None;
+            }
+            len += component.len() + separator.map_or(0, char::len_utf8);
+        }
+        Some(len)
+    }
+    fn drive(path: &str) -> Option<usize> {
+        let mut chars = path.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(letter), Some(':'), Some(separator))
+                if letter.is_ascii_alphabetic() && is_separator(separator) =>
+            {
+                Some(3)
+            }
+            _ => None,
+        }
+    }
+
+    let mut chars = pattern.chars();
+    let len = match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some(a), Some(b), Some('?' | '.'), Some(d))
+            if is_separator(a) && is_separator(b) && is_separator(d) =>
+        {
+            // Verbatim (or device) path, followed by either a UNC share or a
+            // drive.
+            let rest = &pattern[4..];
+            let len = match rest.get(..4) {
+                Some(unc)
+                    if unc.eq_ignore_ascii_case("UNC\\") || unc.eq_ignore_ascii_case("UNC/") =>
+                {
+                    4 + components(&rest[4..], 2)?
+                }
+                _ => drive(rest)?,
+            };
+            4 + len
+        }
+        (Some(a), Some(b), _, _) if is_separator(a) && is_separator(b) => {
+            2 + components(&pattern[2..], 2)?
+        }
+        _ => drive(pattern)?,
+    };
+    Some(pattern.split_at(len))
+}
+
+/// Path to use when accessing the filesystem, as an empty path refers to the
+/// current directory.
+fn fs_path(path: &Path) -> &Path {
+    if path.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        path
+    }
+}
+
+/// Home directory a `~` or `~user` segment stands for, if it's one and the
+/// directory can be found.
+fn tilde_home(segment: &GlobPattern) -> Option<PathBuf> {
+    match segment.tokens() {
+        [GlobToken::Literal(l)] => {
+            let user = l.as_ref().strip_prefix('~')?;
+            home_dir((!user.is_empty()).then_some(user))
+        }
+        _ => None,
+    }
+}
+
+/// Home directory of `user`, or of the current user if `None`.
+#[cfg(unix)]
+fn home_dir(user: Option<&str>) -> Option<PathBuf> {
+    match user {
+        None => std::env::var_os("HOME")
+            .filter(|home| !home.is_empty())
+            .map(PathBuf::from),
+        Some(user) => user_home(user),
+    }
+}
+
+/// Home directory of `user`, or of the current user if `None`.
+#[cfg(windows)]
+fn home_dir(user: Option<&str>) -> Option<PathBuf> {
+    let home = std::env::var_os("USERPROFILE")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)?;
+    match user {
+        None => Some(home),
+        // Profiles are usually all in the same directory (e.g. 'C:\Users').
+        Some(user) => Some(home.parent()?.join(user)).filter(|home| home.is_dir()),
+    }
+}
+
+/// Home directory of `user`, or of the current user if `None`.
+#[cfg(not(any(unix, windows)))]
+fn home_dir(_user: Option<&str>) -> Option<PathBuf> {
+    None
+}
+
+/// Home directory of `user` according to the user database.
+#[cfg(unix)]
+fn user_home(user: &str) -> Option<PathBuf> {
+    use std::ffi::{CStr, CString, OsStr};
+    use std::mem::MaybeUninit;
+    use std::os::unix::ffi::OsStrExt;
+
+    let name = CString::new(user).ok()?;
+    let mut buf: Vec<libc::c_char> = vec![0; 1024];
+    loop {
+        let mut entry = MaybeUninit::<libc::passwd>::uninit();
+        let mut result = std::ptr::null_mut();
+        // SAFETY: every pointer is valid for the duration of the call, and
+        // `buf`'s length is the one given.
+        let error = unsafe {
+            libc::getpwnam_r(
+                name.as_ptr(),
+                entry.as_mut_ptr(),
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut result,
+            )
+        };
+        // Retry with a larger buffer if the entry doesn't fit, within reason.
+        if error == libc::ERANGE && buf.len() < 1 << 20 {
+            buf.resize(buf.len() * 2, 0);
+            continue;
+        }
+        if error != 0 || result.is_null() {
+            return None;
+        }
+        // SAFETY: `result` points to `entry`, which the call filled in with
+        // strings stored in `buf`.
+        let dir = unsafe { CStr::from_ptr((*result).pw_dir) };
+        let home = PathBuf::from(OsStr::from_bytes(dir.to_bytes()));
+        return Some(home).filter(|home| !home.as_os_str().is_empty());
+    }
+}
+
+/// Name a segment matches if it's a case-sensitive literal, in which case
+/// there's no need to read the directory.
+fn literal_segment(segment: &GlobPattern) -> Option<&str> {
+    match segment.tokens() {
+        [GlobToken::Literal(l)] if segment.options().case_fold == CaseFold::Sensitive => {
+            Some(l.as_ref())
+        }
+        _ => None,
+    }
+}
+
+impl GlobIter {
+    /// Walk `dir` matching its entries against the `index`-th segment.
+    fn walk(&mut self, dir: &Path, index: usize, depth: usize) {
+        let (base, options) = (&self.base, &self.options);
+        let is_last = index + 1 == self.segments.len();
+        let segment = &self.segments[index];
+        if let [GlobToken::AnyDirs] = segment.tokens() {
+            self.pending.push(Pending::AnyDirs {
+                dir: dir.to_path_buf(),
+                segment: index,
+                depth,
+                ancestors: Vec::new(),
+            });
+            return;
+        }
+        if !is_last
+            && options
+                .max_depth
+                .is_some_and(|max_depth| depth >= max_depth)
+        {
+            return;
+        }
+
+        let mut matches = Vec::new();
+        if let Some(literal) = literal_segment(segment) {
+            // No need to read the whole directory, just check whether it exists.
+            let path = dir.join(literal);
+            if fs::symlink_metadata(fs_path(&path)).is_ok() {
+                matches.push(path);
+            }
+        } else {
+            let entries = match fs::read_dir(fs_path(dir)) {
+                Ok(entries) => entries,
+                Err(_) => return,
+            };
+            options.visited(dir);
+            let explicit_hidden = segment.literal_prefix().is_some_and(|p| p.starts_with('.'));
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                // Names which aren't valid UTF-8 can never match.
+                let name = match file_name.to_str() {
+                    Some(name) => name,
+                    None => continue,
+                };
+                if !options.hidden && !explicit_hidden && name.starts_with('.') {
+                    continue;
+                }
+                if segment.matches(name) {
+                    matches.push(dir.join(name));
+                }
+            }
+            matches.sort();
+        }
+
+        if is_last {
+            matches.retain(|path| is_kept(base, path, options));
+            self.ready.extend(matches);
+            return;
+        }
+        let dirs = matches
+            .into_iter()
+            .filter(|path| is_walkable_dir(path, options) && !is_pruned(base, path, options))
+            .map(|dir| Pending::Walk {
+                dir,
+                segment: index + 1,
+                depth: depth + 1,
+            });
+        let start = self.pending.len();
+        self.pending.extend(dirs);
+        self.pending[start..].reverse();
+    }
+
+    /// Walk a `**/` segment, which matches `dir` itself as well as every
+    /// directory below it (except hidden ones, unless both the options and the
+    /// pattern allow them). `dir` is skipped if it's one of its `ancestors`
+    /// (i.e. reached through a symbolic link loop).
+    fn walk_any_dirs(
+        &mut self,
+        dir: PathBuf,
+        segment: usize,
+        depth: usize,
+        mut ancestors: Vec<DirId>,
+    ) {
+        let (base, options) = (&self.base, &self.options);
+        let id = match dir_id(&dir) {
+            Some(id) if ancestors.contains(&id) => {
+                options.symlink_loop(&dir);
+                return;
+            }
+            id => id,
+        };
+        let hidden = options.hidden && self.segments[segment].options().dotfiles;
+        let mut dirs = Vec::new();
+        if options.max_depth.is_none_or(|max_depth| depth < max_depth) {
+            if let Ok(entries) = fs::read_dir(fs_path(&dir)) {
+                options.visited(&dir);
+                dirs = entries
+                    .flatten()
+                    .map(|entry| entry.file_name())
+                    .filter(|name| hidden || !name.to_string_lossy().starts_with('.'))
+                    .map(|name| dir.join(name))
+                    .filter(|path| is_walkable_dir(path, options))
+                    .filter(|path| !is_pruned(base, path, options))
+                    .collect();
+                dirs.sort();
+            }
+        }
+
+        // Directories below `dir` are walked after `dir` itself.
+        ancestors.extend(id);
+        for path in dirs.into_iter().rev() {
+            self.pending.push(Pending::AnyDirs {
+                dir: path,
+                segment,
+                depth: depth + 1,
+                ancestors: ancestors.clone(),
+            });
+        }
+        if segment + 1 < self.segments.len() {
+            self.pending.push(Pending::Walk {
+                dir,
+                segment: segment + 1,
+                depth,
+            });
+        }
+    }
+}
+
+/// Single walk of the filesystem for the patterns of a [`GlobSet`] matched in
+/// the same directory.
+#[derive(Debug)]
+struct SetWalk {
+    base: PathBuf,
+    patterns: Vec<SetWalkPattern>,
+}
+
+/// Pattern of a [`GlobSet`] matched by a [`SetWalk`].
+#[derive(Debug)]
+struct SetWalkPattern {
+    /// Index of the pattern in the set.
+    index: usize,
+    segments: Vec<GlobPattern>,
+    dir_only: bool,
+}
+
+/// Position reached by a [`SetWalk`] pattern in the directory being walked.
+#[derive(Debug, Clone)]
+struct Cursor {
+    /// Position of the pattern in [`SetWalk::patterns`].
+    pattern: usize,
+    segment: usize,
+    /// Directories walked through to get here if `segment` is a `**/`
+    /// matching the directory, or `None` if its entries are matched against
+    /// it.
+    ancestors: Option<Vec<DirId>>,
+}
+impl Cursor {
+    fn is_same(&self, other: &Cursor) -> bool {
+        self.pattern == other.pattern
+            && self.segment == other.segment
+            && self.ancestors.is_some() == other.ancestors.is_some()
+    }
+}
+
+impl SetWalk {
+    /// Walk the filesystem adding every match to `found`, along with the
+    /// index of the pattern matching it.
+    fn run(&self, options: &WalkOptions, found: &mut BTreeMap<PathBuf, Vec<usize>>) {
+        let cursors = (0..self.patterns.len())
+            .map(|pattern| Cursor {
+                pattern,
+                segment: 0,
+                ancestors: None,
+            })
+            .collect();
+        let mut pending = vec![(self.base.clone(), 0, cursors)];
+        while let Some((dir, depth, cursors)) = pending.pop() {
+            if options.is_cancelled() {
+                return;
+            }
+            let dirs = self.walk(&dir, depth, cursors, options, found);
+            pending.extend(
+                dirs.into_iter()
+                    .rev()
+                    .map(|(path, cursors)| (path, depth + 1, cursors)),
+            );
+        }
+    }
+
+    /// Match the entries of `dir` against every pattern at its `cursors`,
+    /// adding matches to `found` and returning the directories to walk next
+    /// along with the cursors of the patterns to match in them.
+    fn walk(
+        &self,
+        dir: &Path,
+        depth: usize,
+        cursors: Vec<Cursor>,
+        options: &WalkOptions,
+        found: &mut BTreeMap<PathBuf, Vec<usize>>,
+    ) -> BTreeMap<PathBuf, Vec<Cursor>> {
+        let base = &self.base;
+        let below_max_depth = options.max_depth.is_none_or(|max_depth| depth < max_depth);
+
+        // A `**/` matches `dir` itself as well as every directory below it.
+        let (mut segments, mut any_dirs) = (Vec::new(), Vec::new());
+        let mut cursors = cursors;
+        while let Some(cursor) = cursors.pop() {
+            let pattern = &self.patterns[cursor.pattern];
+            let is_any_dirs = matches!(
+                pattern.segments[cursor.segment].tokens(),
+                [GlobToken::AnyDirs]
+            );
+            if !is_any_dirs {
+                if cursor.segment + 1 == pattern.segments.len() || below_max_depth {
+                    segments.push(cursor);
+                }
+                continue;
+            }
+            let mut ancestors = cursor.ancestors.unwrap_or_default();
+            let id = match dir_id(dir) {
+                Some(id) if ancestors.contains(&id) => {
+                    options.symlink_loop(dir);
+                    continue;
+                }
+                id => id,
+            };
+            ancestors.extend(id);
+            if cursor.segment + 1 < pattern.segments.len() {
+                cursors.push(Cursor {
+                    pattern: cursor.pattern,
+                    segment: cursor.segment + 1,
+                    ancestors: None,
+                });
+            }
+            if below_max_depth {
+                any_dirs.push(Cursor {
+                    ancestors: Some(ancestors),
+                    ..cursor
+                });
+            }
+        }
+
+        // Only read the directory if some segment isn't a literal name.
+        let mut candidates: Vec<(PathBuf, &Cursor)> = Vec::new();
+        let mut entries = Vec::new();
+        let read = !any_dirs.is_empty()
+            || segments.iter().any(|cursor| {
+                let pattern = &self.patterns[cursor.pattern];
+                literal_segment(&pattern.segments[cursor.segment]).is_none()
+            });
+        if read {
+            if let Ok(read) = fs::read_dir(fs_path(dir)) {
+                options.visited(dir);
+                entries = read.flatten().map(|entry| entry.file_name()).collect();
+                entries.sort();
+            }
+        }
+        for cursor in &segments {
+            let segment = &self.patterns[cursor.pattern].segments[cursor.segment];
+            if let Some(literal) = literal_segment(segment) {
+                let path = dir.join(literal);
+                if fs::symlink_metadata(fs_path(&path)).is_ok() {
+                    candidates.push((path, cursor));
+                }
+                continue;
+            }
+            let explicit_hidden = segment.literal_prefix().is_some_and(|p| p.starts_with('.'));
+            for name in entries.iter().filter_map(|name| name.to_str()) {
+                if !options.hidden && !explicit_hidden && name.starts_with('.') {
+                    continue;
+                }
+                if segment.matches(name) {
+                    candidates.push((dir.join(name), cursor));
+                }
+            }
+        }
+        for cursor in &any_dirs {
+            let segment = &self.patterns[cursor.pattern].segments[cursor.segment];
+            let hidden = options.hidden && segment.options().dotfiles;
+            for name in &entries {
+                if hidden || !name.to_string_lossy().starts_with('.') {
+                    candidates.push((dir.join(name), cursor));
+                }
+            }
+        }
+
+        // Whether each path is a directory to walk, or kept as a match, is
+        // only checked once however many patterns reach it.
+        let mut dirs: BTreeMap<PathBuf, Option<Vec<Cursor>>> = BTreeMap::new();
+        let mut matches: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+        for (path, cursor) in candidates {
+            let pattern = &self.patterns[cursor.pattern];
+            if cursor.ancestors.is_none() && cursor.segment + 1 == pattern.segments.len() {
+                let dir_only = pattern.dir_only;
+                if !dir_only || fs::metadata(fs_path(&path)).is_ok_and(|m| m.is_dir()) {
+                    matches.entry(path).or_default().push(pattern.index);
+                }
+                continue;
+            }
+            let next = match cursor.ancestors {
+                Some(_) => cursor.clone(),
+                None => Cursor {
+                    pattern: cursor.pattern,
+                    segment: cursor.segment + 1,
+                    ancestors: None,
+                },
+            };
+            let walkable = dirs.entry(path).or_insert_with_key(|path| {
+                (is_walkable_dir(path, options) && !is_pruned(base, path, options)).then(Vec::new)
+            });
+            if let Some(cursors) = walkable {
+                if !cursors.iter().any(|cursor| cursor.is_same(&next)) {
+                    cursors.push(next);
+                }
+            }
+        }
+        for (path, indices) in matches {
+            if is_kept(base, &path, options) {
+                let path = write_path(base, path, options);
+                if !found.contains_key(&path) {
+                    options.found(&path);
+                }
+                found.entry(path).or_default().extend(indices);
+            }
+        }
+        dirs.into_iter()
+            .filter_map(|(path, cursors)| Some((path, cursors?)))
+            .collect()
+    }
+}
+
+/// Write the `path` matching a pattern relative to `base` as `options`
+/// require.
+fn write_path(base: &Path, path: PathBuf, options: &WalkOptions) -> PathBuf {
+    match options.output {
+        PathStyle::AsProvided => path,
+        PathStyle::RelativeToBase => match path.strip_prefix(base) {
+            Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => path,
+        },
+        PathStyle::Absolute => std::path::absolute(fs_path(&path)).unwrap_or(path),
+    }
+}
+
+/// Identity of a directory, the same for every path leading to it.
+#[cfg(unix)]
+type DirId = (u64, u64);
+/// Identity of a directory, the same for every path leading to it.
+#[cfg(not(unix))]
+type DirId = PathBuf;
+
+/// Identity of the directory at `path` (following symbolic links), if it can
+/// be found: its device and inode numbers.
+#[cfg(unix)]
+fn dir_id(path: &Path) -> Option<DirId> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(fs_path(path)).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Identity of the directory at `path` (following symbolic links), if it can
+/// be found: its canonical path.
+#[cfg(not(unix))]
+fn dir_id(path: &Path) -> Option<DirId> {
+    fs::canonicalize(fs_path(path)).ok()
+}
+
+fn is_walkable_dir(path: &Path, options: &WalkOptions) -> bool {
+    let metadata = if options.follow_symlinks {
+        fs::metadata(fs_path(path))
+    } else {
+        fs::symlink_metadata(fs_path(path))
+    };
+    metadata.map(|m| m.is_dir()).unwrap_or(false)
+}
+
+/// Whether the match at `path` (below `base`) is reported, i.e. it isn't
+/// excluded by `options` and passes their metadata filters.
+fn is_kept(base: &Path, path: &Path, options: &WalkOptions) -> bool {
+    let filtered = options.min_size.is_some()
+        || options.max_size.is_some()
+        || options.modified_after.is_some()
+        || options.modified_before.is_some();
+    if options.excludes.is_none() && !filtered {
+        return true;
+    }
+
+    let metadata = fs::metadata(fs_path(path)).ok();
+    if let (Some(excludes), Some(name)) = (&options.excludes, relative_name(base, path)) {
+        let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+        if excludes.matches_entry(name, is_dir) {
+            return false;
+        }
+    }
+    if !filtered {
+        return true;
+    }
+    let metadata = match metadata {
+        Some(metadata) => metadata,
+        None => return false,
+    };
+    let size = metadata.len();
+    let modified = metadata.modified().ok();
+    options.min_size.is_none_or(|min_size| size >= min_size)
+        && options.max_size.is_none_or(|max_size| size <= max_size)
+        && options
+            .modified_after
+            .is_none_or(|time| modified.is_some_and(|modified| modified > time))
+        && options
+            .modified_before
+            .is_none_or(|time| modified.is_some_and(|modified| modified < time))
+}
+
+/// Whether the directory at `path` (below `base`) or everything inside it is
+/// excluded by `options`, so that it doesn't need to be read.
+fn is_pruned(base: &Path, path: &Path, options: &WalkOptions) -> bool {
+    let (excludes, name) = match (&options.excludes, relative_name(base, path)) {
+        (Some(excludes), Some(name)) => (excludes, name),
+        _ => return false,
+    };
+    excludes.iter().any(|exclude| {
+        exclude.matches_entry(&name, true)
+            // A last segment matching the empty name (such as `*`) matches
+            // every name in the directory.
+            || (exclude.options().dotfiles && exclude.matches(format!("{name}/")))
+    })
+}
+
+/// `/`-separated path of `path` relative to `base`, if it's valid UTF-8.
+fn relative_name(base: &Path, path: &Path) -> Option<String> {
+    let names = path
+        .strip_prefix(base)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(names.join("/"))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::fs::{glob_in, WalkOptions};
+    use crate::{
+        options::{CaseFold, MatchOptions},
+        syntax::Syntax,
+        GlobPattern,
+    };
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// Temporary directory tree removed when dropped.
+    pub(crate) struct TempTree(pub(crate) PathBuf);
+    impl TempTree {
+        pub(crate) fn new(name: &str, files: &[&str]) -> Self {
+            let root =
+                std::env::temp_dir().join(format!("goglob-test-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            for file in files {
+                let path = root.join(file);
+                if file.ends_with('/') {
+                    fs::create_dir_all(&path).unwrap();
+                } else {
+                    fs::create_dir_all(path.parent().unwrap()).unwrap();
+                    fs::write(&path, file).unwrap();
+                }
+            }
+            Self(root)
+        }
+
+        pub(crate) fn glob(&self, pattern: &str, options: &WalkOptions) -> Vec<String> {
+            let pattern = GlobPattern::new(pattern).unwrap();
+            glob_in(&self.0, &pattern, options)
+                .into_iter()
+                .map(|p| self.relative(&p))
+                .collect()
+        }
+
+        pub(crate) fn relative(&self, path: &Path) -> String {
+            path.strip_prefix(&self.0)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .replace('\\', "/")
+        }
+    }
+    impl Drop for TempTree {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const FILES: &[&str] = &[
+        "Cargo.toml",
+        "src/lib.rs",
+        "src/main.rs",
+        "src/bin/tool.rs",
+        "src/.hidden.rs",
+        ".config/settings.toml",
+        "target/",
+    ];
+
+    #[test]
+    fn glob_in_segments() {
+        let tree = TempTree::new("glob-in-segments", FILES);
+        let options = WalkOptions::new();
+        assert_eq!(
+            tree.glob("src/*.rs", &options),
+            vec!["src/.hidden.rs", "src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(tree.glob("*/*/*.rs", &options), vec!["src/bin/tool.rs"]);
+        assert_eq!(tree.glob("Cargo.toml", &options), vec!["Cargo.toml"]);
+        assert_eq!(tree.glob("src//lib.rs", &options), vec!["src/lib.rs"]);
+        assert_eq!(tree.glob("*/", &options), vec![".config", "src", "target"]);
+        assert_eq!(tree.glob("src/*/", &options), vec!["src/bin"]);
+        assert_eq!(tree.glob("Cargo.toml/", &options), Vec::<String>::new());
+        assert_eq!(tree.glob("missing/*", &options), Vec::<String>::new());
+        assert_eq!(
+            tree.glob("*", &options),
+            vec![".config", "Cargo.toml", "src", "target"]
+        );
+    }
+
+    #[test]
+    fn glob_in_any_dirs() {
+        let tree = TempTree::new("glob-in-any-dirs", FILES);
+        let glob = |pattern: &str, options: &WalkOptions| {
+            let pattern = GlobPattern::builder()
+                .syntax(Syntax::Gitignore)
+                .build(pattern)
+                .unwrap();
+            let paths = glob_in(&tree.0, &pattern, options);
+            paths.iter().map(|p| tree.relative(p)).collect::<Vec<_>>()
+        };
+        let options = WalkOptions::new();
+        assert_eq!(
+            glob("**/*.rs", &options),
+            vec![
+                "src/.hidden.rs",
+                "src/lib.rs",
+                "src/main.rs",
+                "src/bin/tool.rs"
+            ]
+        );
+        assert_eq!(glob("src/**/tool.rs", &options), vec!["src/bin/tool.rs"]);
+        assert_eq!(glob("src/**", &options).len(), 5);
+        assert_eq!(
+            glob("**/*.toml", &options.clone().hidden(false)),
+            vec!["Cargo.toml"]
+        );
+        assert_eq!(
+            glob("**/*.rs", &options.max_depth(Some(1))),
+            vec!["src/.hidden.rs", "src/lib.rs", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn glob_in_options() {
+        let tree = TempTree::new("glob-in-options", FILES);
+        let options = WalkOptions::new().hidden(false);
+        assert_eq!(
+            tree.glob("src/*.rs", &options),
+            vec!["src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(
+            tree.glob("*", &options),
+            vec!["Cargo.toml", "src", "target"]
+        );
+        assert_eq!(tree.glob(".*/*", &options), vec![".config/settings.toml"]);
+
+        let options = WalkOptions::new().max_depth(Some(1));
+        assert_eq!(tree.glob("*/*.rs", &options).len(), 3);
+        assert_eq!(tree.glob("*/*/*.rs", &options), Vec::<String>::new());
+    }
+
+    #[test]
+    fn glob_in_match_options() {
+        let tree = TempTree::new("glob-in-match-options", FILES);
+        let glob = |pattern: &str, options: &MatchOptions| {
+            let pattern = GlobPattern::new_with(pattern, options).unwrap();
+            let paths = glob_in(&tree.0, &pattern, &WalkOptions::new());
+            paths.iter().map(|p| tree.relative(p)).collect::<Vec<_>>()
+        };
+        let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+        assert_eq!(glob("SRC/Lib.RS", &options), vec!["src/lib.rs"]);
+        assert_eq!(glob("cargo.*", &options), vec!["Cargo.toml"]);
+
+        let options = MatchOptions::new().dotfiles(false);
+        assert_eq!(
+            glob("src/*.rs", &options),
+            vec!["src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(glob("*/*.toml", &options), Vec::<String>::new());
+        assert_eq!(glob(".*/*.toml", &options), vec![".config/settings.toml"]);
+        let options = options.syntax(Syntax::Gitignore);
+        assert_eq!(glob("**/*.toml", &options), vec!["Cargo.toml"]);
+    }
+
+    #[cfg(feature = "camino")]
+    #[test]
+    fn glob_in_utf8() {
+        use crate::fs::glob_in_utf8;
+        use camino::Utf8Path;
+
+        let tree = TempTree::new("glob-in-utf8", FILES);
+        let base = Utf8Path::from_path(&tree.0).unwrap();
+        let pattern = GlobPattern::new("src/*.rs").unwrap();
+        let options = WalkOptions::new().hidden(false);
+        assert_eq!(
+            glob_in_utf8(base, &pattern, &options),
+            vec![base.join("src/lib.rs"), base.join("src/main.rs")]
+        );
+    }
+
+    #[test]
+    fn glob_in_excludes() {
+        let tree = TempTree::new("glob-in-excludes", FILES);
+        let excludes = |patterns: &[&str]| {
+            let excludes = patterns
+                .iter()
+                .map(|p| {
+                    GlobPattern::builder()
+                        .syntax(Syntax::Gitignore)
+                        .build(p)
+                        .unwrap()
+                })
+                .collect();
+            WalkOptions::new().excludes(Some(excludes))
+        };
+        let gitignore = |pattern: &str, options: &WalkOptions| {
+            let pattern = GlobPattern::builder()
+                .syntax(Syntax::Gitignore)
+                .build(pattern)
+                .unwrap();
+            let paths = glob_in(&tree.0, &pattern, options);
+            paths.iter().map(|p| tree.relative(p)).collect::<Vec<_>>()
+        };
+
+        let options = excludes(&["src/bin", "*/.*"]);
+        assert_eq!(
+            gitignore("**/*.rs", &options),
+            vec!["src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(tree.glob("*/*/*.rs", &options), Vec::<String>::new());
+        assert_eq!(
+            tree.glob("src/*", &options),
+            vec!["src/lib.rs", "src/main.rs"]
+        );
+
+        let options = excludes(&["src/**", "target/"]);
+        assert_eq!(
+            tree.glob("*", &options),
+            vec![".config", "Cargo.toml", "src"]
+        );
+        assert_eq!(tree.glob("src/*.rs", &options), Vec::<String>::new());
+        assert_eq!(gitignore("**/*.rs", &options), Vec::<String>::new());
+
+        let options = excludes(&["Cargo.toml/"]);
+        assert_eq!(tree.glob("Cargo.toml", &options), vec!["Cargo.toml"]);
+    }
+
+    #[test]
+    fn glob_iter_lazy() {
+        use crate::fs::glob_iter;
+
+        let tree = TempTree::new("glob-iter-lazy", &["a/x", "b/x", "c/x"]);
+        let pattern = GlobPattern::new("*/x").unwrap();
+        let mut paths = glob_iter(&tree.0, &pattern, &WalkOptions::new());
+        assert_eq!(tree.relative(&paths.next().unwrap()), "a/x");
+        // Directories are only read once the previous matches are returned
+        fs::remove_file(tree.0.join("b/x")).unwrap();
+        let rest: Vec<_> = paths.map(|p| tree.relative(&p)).collect();
+        assert_eq!(rest, vec!["c/x"]);
+
+        let pattern = GlobPattern::new("*/").unwrap();
+        let paths = glob_iter(&tree.0, &pattern, &WalkOptions::new());
+        assert_eq!(paths.count(), 3);
+    }
+
+    #[test]
+    fn glob_in_output() {
+        use crate::fs::PathStyle;
+
+        let tree = TempTree::new("glob-in-output", FILES);
+        let pattern = GlobPattern::new("src/*.rs").unwrap();
+        let options = WalkOptions::new().hidden(false);
+        assert_eq!(
+            glob_in(&tree.0, &pattern, &options),
+            vec![tree.0.join("src/lib.rs"), tree.0.join("src/main.rs")]
+        );
+
+        let relative = options.clone().output(PathStyle::RelativeToBase);
+        assert_eq!(
+            glob_in(&tree.0, &pattern, &relative),
+            vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/main.rs")]
+        );
+        let pattern = GlobPattern::new("src/bin/").unwrap();
+        assert_eq!(
+            glob_in(&tree.0, &pattern, &relative),
+            vec![PathBuf::from("src/bin")]
+        );
+
+        let absolute = options.output(PathStyle::Absolute);
+        let paths = glob_in("", &GlobPattern::new("*").unwrap(), &absolute);
+        assert!(!paths.is_empty());
+        assert!(paths.iter().all(|path| path.is_absolute()));
+        let current = std::env::current_dir().unwrap();
+        assert!(paths.iter().all(|path| path.starts_with(&current)));
+    }
+
+    #[test]
+    fn glob_set_glob_in() {
+        use crate::set::GlobSet;
+
+        let tree = TempTree::new("glob-set-glob-in", FILES);
+        let set = GlobSet::new(
+            ["src/**/*.rs", "src/lib.rs", "*/*.rs", "*.toml"]
+                .iter()
+                .map(|p| {
+                    GlobPattern::builder()
+                        .syntax(Syntax::Gitignore)
+                        .build(p)
+                        .unwrap()
+                }),
+        );
+        let options = WalkOptions::new().hidden(false);
+        let found: Vec<_> = set
+            .glob_in(&tree.0, &options)
+            .into_iter()
+            .map(|found| (tree.relative(found.path()), found.patterns().to_vec()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Cargo.toml".into(), vec![3]),
+                ("src/bin/tool.rs".into(), vec![0]),
+                ("src/lib.rs".into(), vec![0, 1, 2]),
+                ("src/main.rs".into(), vec![0, 2]),
+            ]
+        );
+        assert!(GlobSet::new([]).glob_in(&tree.0, &options).is_empty());
+    }
+
+    #[test]
+    fn glob_set_glob_in_single_walk() {
+        use crate::set::GlobSet;
+        use std::collections::BTreeMap;
+
+        let tree = TempTree::new("glob-set-glob-in-single-walk", FILES);
+        let patterns = [
+            "**/*.rs",
+            "**/**/bin/*",
+            "src/*/",
+            "*/*.toml",
+            "src/**/",
+            "Cargo.toml",
+            "*",
+            "missing/**/*",
+        ];
+        let set = GlobSet::new(patterns.iter().map(|p| {
+            GlobPattern::builder()
+                .syntax(Syntax::Gitignore)
+                .build(p)
+                .unwrap()
+        }));
+        for options in [
+            WalkOptions::new(),
+            WalkOptions::new().hidden(false),
+            WalkOptions::new().max_depth(Some(1)),
+        ] {
+            // Same as walking once per pattern.
+            let mut expected: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+            for (index, pattern) in set.iter().enumerate() {
+                for path in glob_in(&tree.0, pattern, &options) {
+                    let indices = expected.entry(path).or_default();
+                    if !indices.contains(&index) {
+                        indices.push(index);
+                    }
+                }
+            }
+            let found: BTreeMap<_, _> = set
+                .glob_in(&tree.0, &options)
+                .into_iter()
+                .map(|found| (found.path().to_path_buf(), found.patterns().to_vec()))
+                .collect();
+            assert_eq!(found, expected, "{options:?}");
+        }
+    }
+
+    #[test]
+    fn glob_in_metadata() {
+        use std::time::{Duration, SystemTime};
+
+        let tree = TempTree::new("glob-in-metadata", &["a.log", "b.log", "c.log"]);
+        let day = Duration::from_secs(24 * 60 * 60);
+        let now = SystemTime::now();
+        for (name, size, age) in [("a.log", 10, 0), ("b.log", 100, 3), ("c.log", 1000, 10)] {
+            let file = fs::File::options()
+                .write(true)
+                .open(tree.0.join(name))
+                .unwrap();
+            file.set_len(size).unwrap();
+            file.set_modified(now - day * age).unwrap();
+        }
+
+        let options = WalkOptions::new();
+        assert_eq!(tree.glob("*.log", &options).len(), 3);
+        let sized = options.clone().min_size(Some(100));
+        assert_eq!(tree.glob("*.log", &sized), vec!["b.log", "c.log"]);
+        let sized = sized.max_size(Some(999));
+        assert_eq!(tree.glob("*.log", &sized), vec!["b.log"]);
+
+        let old = options.clone().modified_before(Some(now - day * 7));
+        assert_eq!(tree.glob("*.log", &old), vec!["c.log"]);
+        let recent = options.clone().modified_after(Some(now - day * 7));
+        assert_eq!(tree.glob("*.log", &recent), vec!["a.log", "b.log"]);
+        let recent = recent.min_size(Some(50));
+        assert_eq!(tree.glob("*.log", &recent), vec!["b.log"]);
+        assert_eq!(tree.glob("missing.log", &recent), Vec::<String>::new());
+    }
+
+    #[test]
+    fn glob_in_cancel_progress() {
+        use crate::fs::WalkProgress;
+        use crate::set::GlobSet;
+        use std::sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        };
+
+        /// Counts the progress, cancelling the walk after `limit` matches.
+        struct Counter {
+            visited: AtomicUsize,
+            found: AtomicUsize,
+            cancel: Arc<AtomicBool>,
+            limit: usize,
+        }
+        impl WalkProgress for Counter {
+            fn visited(&self, _dir: &Path) {
+                self.visited.fetch_add(1, Ordering::Relaxed);
+            }
+
+            fn found(&self, _path: &Path) {
+                if self.found.fetch_add(1, Ordering::Relaxed) + 1 == self.limit {
+                    self.cancel.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+
+        let tree = TempTree::new("glob-in-cancel-progress", FILES);
+        let walk = |limit| {
+            let cancel = Arc::new(AtomicBool::new(false));
+            let counter = Arc::new(Counter {
+                visited: AtomicUsize::new(0),
+                found: AtomicUsize::new(0),
+                cancel: cancel.clone(),
+                limit,
+            });
+            let options = WalkOptions::new()
+                .cancel(Some(cancel))
+                .progress(Some(counter.clone()));
+            (counter, options)
+        };
+
+        let (counter, options) = walk(usize::MAX);
+        assert_eq!(tree.glob("*/*.rs", &options).len(), 3);
+        // The base directory, then `src` (`.config` and `target` hold no
+        // `.rs` file, but still need to be read).
+        assert_eq!(counter.visited.load(Ordering::Relaxed), 4);
+        assert_eq!(counter.found.load(Ordering::Relaxed), 3);
+        assert_eq!(tree.glob("src/lib.rs", &options), vec!["src/lib.rs"]);
+        assert_eq!(counter.visited.load(Ordering::Relaxed), 4);
+        assert_eq!(options, options.clone());
+        assert_ne!(options, WalkOptions::new());
+
+        let (counter, options) = walk(2);
+        assert_eq!(tree.glob("*/*.rs", &options).len(), 2);
+        assert_eq!(tree.glob("*", &options), Vec::<String>::new());
+        let set = GlobSet::compile(["*/*.rs", "*"]).unwrap();
+        assert!(set.glob_in(&tree.0, &options).is_empty());
+        assert_eq!(counter.found.load(Ordering::Relaxed), 2);
+
+        let (counter, options) = walk(1);
+        assert_eq!(set.glob_in(&tree.0, &options).len(), 4);
+        assert_eq!(counter.found.load(Ordering::Relaxed), 4);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn glob_in_symlinks() {
+        let tree = TempTree::new("glob-in-symlinks", FILES);
+        std::os::unix::fs::symlink(tree.0.join("src"), tree.0.join("link")).unwrap();
+        let options = WalkOptions::new();
+        assert_eq!(tree.glob("l*/*.rs", &options).len(), 3);
+
+        let options = WalkOptions::new().follow_symlinks(false);
+        assert_eq!(tree.glob("l*/*.rs", &options), Vec::<String>::new());
+        assert_eq!(tree.glob("l*", &options), vec!["link"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn glob_in_symlink_loops() {
+        use crate::fs::WalkProgress;
+        use crate::set::GlobSet;
+        use std::sync::{Arc, Mutex};
+
+        /// Records the symbolic link loops which were skipped.
+        #[derive(Default)]
+        struct Loops(Mutex<Vec<PathBuf>>);
+        impl WalkProgress for Loops {
+            fn symlink_loop(&self, path: &Path) {
+                self.0.lock().unwrap().push(path.to_path_buf());
+            }
+        }
+
+        let tree = TempTree::new("glob-in-symlink-loops", FILES);
+        std::os::unix::fs::symlink("..", tree.0.join("src/bin/up")).unwrap();
+        std::os::unix::fs::symlink("loop", tree.0.join("src/loop")).unwrap();
+        let loops = Arc::new(Loops::default());
+        let options = WalkOptions::new().progress(Some(loops.clone()));
+        let take_loops = || {
+            let loops = core::mem::take(&mut *loops.0.lock().unwrap());
+            loops.iter().map(|p| tree.relative(p)).collect::<Vec<_>>()
+        };
+        let glob = |pattern: &str| {
+            let pattern = GlobPattern::builder()
+                .syntax(Syntax::Gitignore)
+                .build(pattern)
+                .unwrap();
+            let paths = glob_in(&tree.0, &pattern, &options);
+            paths.iter().map(|p| tree.relative(p)).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            glob("src/**/*.rs"),
+            vec![
+                "src/.hidden.rs",
+                "src/lib.rs",
+                "src/main.rs",
+                "src/bin/tool.rs"
+            ]
+        );
+        assert_eq!(take_loops(), vec!["src/bin/up"]);
+        assert_eq!(glob("**/up"), vec!["src/bin/up"]);
+        assert_eq!(take_loops(), vec!["src/bin/up"]);
+        assert_eq!(glob("src/bin/up/bin/*.rs"), vec!["src/bin/up/bin/tool.rs"]);
+        assert_eq!(glob("src/loop/*"), Vec::<String>::new());
+        assert_eq!(take_loops(), Vec::<String>::new());
+
+        let set = GlobSet::new(["src/**/*.rs", "src/**/tool.rs"].iter().map(|p| {
+            GlobPattern::builder()
+                .syntax(Syntax::Gitignore)
+                .build(p)
+                .unwrap()
+        }));
+        assert_eq!(set.glob_in(&tree.0, &options).len(), 4);
+        assert_eq!(take_loops(), vec!["src/bin/up", "src/bin/up"]);
+    }
+
+    #[test]
+    fn split_root() {
+        use crate::fs::split_root;
+
+        assert_eq!(split_root("C:\\Users\\*"), Some(("C:\\", "Users\\*")));
+        assert_eq!(split_root("d:/src/*.rs"), Some(("d:/", "src/*.rs")));
+        assert_eq!(split_root("C:\\"), Some(("C:\\", "")));
+        assert_eq!(
+            split_root("\\\\server\\share\\dir\\*"),
+            Some(("\\\\server\\share\\", "dir\\*"))
+        );
+        assert_eq!(
+            split_root("//server/share/*"),
+            Some(("//server/share/", "*"))
+        );
+        assert_eq!(
+            split_root("\\\\?\\C:\\Windows\\*.exe"),
+            Some(("\\\\?\\C:\\", "Windows\\*.exe"))
+        );
+        assert_eq!(
+            split_root("\\\\?\\UNC\\server\\share\\*"),
+            Some(("\\\\?\\UNC\\server\\share\\", "*"))
+        );
+        assert_eq!(split_root("C:relative"), None);
+        assert_eq!(split_root("src/*.rs"), None);
+        assert_eq!(split_root("/usr/*"), None);
+        assert_eq!(split_root("\\\\server"), None);
+        assert_eq!(split_root("\\\\server\\\\share"), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn glob_windows_root() {
+        use crate::fs::{glob, glob_with};
+
+        let tree = TempTree::new("glob-windows-root", FILES);
+        let expected = vec!["src/.hidden.rs", "src/lib.rs", "src/main.rs"];
+        let relative = |paths: Vec<PathBuf>| -> Vec<String> {
+            paths.iter().map(|p| tree.relative(p)).collect()
+        };
+        let options = MatchOptions::new().syntax(Syntax::GoFilepathMatch);
+        let pattern = format!("{}\\src\\*.rs", tree.0.display());
+        assert_eq!(relative(glob_with(&pattern, &options).unwrap()), expected);
+        assert_eq!(relative(glob(&pattern).unwrap()), expected);
+        let pattern = format!("{}/src/*.rs", tree.0.display());
+        assert_eq!(relative(glob(&pattern).unwrap()), expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn glob_in_tilde() {
+        let tree = TempTree::new("glob-in-tilde", &["home/src/lib.rs", "~/src/main.rs"]);
+        // No other test reads $HOME
+        std::env::set_var("HOME", tree.0.join("home"));
+        let options = WalkOptions::new().expand_tilde(true);
+        assert_eq!(tree.glob("~/src/*.rs", &options), vec!["home/src/lib.rs"]);
+        assert_eq!(tree.glob("~", &options), vec!["home"]);
+        assert_eq!(
+            tree.glob("~no-such-user-goglob/*", &options),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            tree.glob("~/src/*.rs", &WalkOptions::new()),
+            vec!["~/src/main.rs"]
+        );
+        assert_eq!(tree.glob("\\~/src/*.rs", &options), vec!["~/src/main.rs"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn user_home() {
+        use crate::fs::user_home;
+
+        assert!(user_home("root").is_some());
+        assert_eq!(user_home("no-such-user-goglob"), None);
+        assert_eq!(user_home("nul\0user"), None);
+    }
+}
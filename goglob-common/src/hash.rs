@@ -0,0 +1,156 @@
+//! Hashes of patterns which don't change across releases.
+
+use crate::{options::CaseFold, GlobPattern, GlobToken};
+use alloc::string::String;
+use core::hash::{Hash, Hasher};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl GlobPattern {
+    /// 64-bit hash of the pattern and the options affecting how it matches,
+    /// which is guaranteed to stay the same across `goglob` releases (unlike
+    /// [`Hash`][core::hash::Hash] implementations), e.g. to key on-disk
+    /// caches.
+    ///
+    /// The hash is the 64-bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/)
+    /// hash of the UTF-8 bytes of:
+    ///
+    /// 1. The pattern rendered back into pattern syntax: literal characters
+    ///    (with `*`, `?`, `[`, `]` and `\` preceded by `\`), `*`, `?`, `**/`
+    ///    (any number of directories) and character classes as `[`, followed
+    ///    by `^` if negated, their characters and `-`-separated ranges (with
+    ///    `\`, `-`, `]` and `^` preceded by `\`) and `]`.
+    /// 2. A `\0` byte.
+    /// 3. `s`, `a` or `u` for [sensitive][CaseFold::Sensitive],
+    ///    [ASCII][CaseFold::Ascii] or [Unicode][CaseFold::Unicode] case
+    ///    folding.
+    /// 4. The separator.
+    /// 5. `1` if wildcards match dotfiles, `0` otherwise.
+    ///
+    /// The pattern is rendered from its compiled form, so patterns written
+    /// differently but compiling to the same tokens (e.g. `a\b` and `ab`, or
+    /// the same pattern in different syntaxes) have the same hash.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let pattern = GlobPattern::new("*.rs").unwrap();
+    /// assert_eq!(pattern.stable_hash_u64(), 0xe127_d928_bd90_498f);
+    /// let escaped = GlobPattern::new("*.r\\s").unwrap();
+    /// assert_eq!(escaped.stable_hash_u64(), pattern.stable_hash_u64());
+    /// # }
+    /// ```
+    pub fn stable_hash_u64(&self) -> u64 {
+        let options = self.options();
+        let mut canonical = String::new();
+        for token in self.tokens() {
+            match token {
+                GlobToken::Literal(l) => l
+                    .as_ref()
+                    .chars()
+                    .for_each(|c| push_escaped(&mut canonical, c, false)),
+                GlobToken::CharClass(cc) => {
+                    canonical.push('[');
+                    if cc.is_negated() {
+                        canonical.push('^');
+                    }
+                    for cct in cc.types() {
+                        let range = cct.as_range();
+                        push_escaped(&mut canonical, *range.start(), true);
+                        if range.start() != range.end() {
+                            canonical.push('-');
+                            push_escaped(&mut canonical, *range.end(), true);
+                        }
+                    }
+                    canonical.push(']');
+                }
+                GlobToken::SeqWildcard => canonical.push('*'),
+                GlobToken::SingleWildcard => canonical.push('?'),
+                GlobToken::AnyDirs => canonical.push_str("**/"),
+            }
+        }
+        canonical.push('\0');
+        canonical.push(match options.case_fold {
+            CaseFold::Sensitive => 's',
+            CaseFold::Ascii => 'a',
+            CaseFold::Unicode => 'u',
+        });
+        canonical.push(options.effective_separator());
+        canonical.push(if options.dotfiles { '1' } else { '0' });
+
+        canonical.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        })
+    }
+}
+
+/// Consistent with [`Eq`], equal patterns having the same
+/// [stable hash][GlobPattern::stable_hash_u64()], so that patterns can be used
+/// as keys of a `HashMap` (e.g. deserialized from a config file).
+impl Hash for GlobPattern {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.stable_hash_u64());
+    }
+}
+
+/// Push `c` as it would appear in a pattern, escaping it if necessary.
+fn push_escaped(canonical: &mut String, c: char, in_class: bool) {
+    let special = if in_class {
+        matches!(c, '\\' | '-' | ']' | '^')
+    } else {
+        matches!(c, '*' | '?' | '[' | ']' | '\\')
+    };
+    if special {
+        canonical.push('\\');
+    }
+    canonical.push(c);
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        builder::GlobPatternBuilder,
+        options::{CaseFold, MatchOptions},
+        syntax::Syntax,
+        GlobPattern,
+    };
+
+    fn hash(pattern: &str) -> u64 {
+        GlobPattern::new(pattern).unwrap().stable_hash_u64()
+    }
+
+    #[test]
+    fn stable_hash_u64_values() {
+        // These must never change, as they may be stored on disk
+        assert_eq!(hash("*.rs"), 0xe127_d928_bd90_498f);
+        assert_eq!(hash("a"), 0xd36d_c1fc_d86d_5a0d);
+        assert_eq!(hash("[^a-c\\]]?"), 0xd029_2561_35fc_0ecd);
+    }
+
+    #[test]
+    fn stable_hash_u64_equivalence() {
+        assert_eq!(hash("a\\b"), hash("ab"));
+        assert_ne!(hash("a*"), hash("a\\*"));
+        assert_ne!(hash("[ab]"), hash("[a-b]"));
+
+        let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+        let folded = GlobPattern::new_with("*.rs", &options).unwrap();
+        assert_ne!(folded.stable_hash_u64(), hash("*.rs"));
+        let hidden = MatchOptions::new().dotfiles(false);
+        let hidden = GlobPattern::new_with("*.rs", &hidden).unwrap();
+        assert_ne!(hidden.stable_hash_u64(), hash("*.rs"));
+
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        assert_eq!(
+            gitignore.build("*.rs").unwrap().stable_hash_u64(),
+            hash("*.rs")
+        );
+        assert_ne!(
+            gitignore.build("**/a").unwrap().stable_hash_u64(),
+            hash("*/a")
+        );
+    }
+}
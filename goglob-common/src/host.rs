@@ -0,0 +1,288 @@
+//! Hostname patterns following TLS certificate and DNS wildcard conventions.
+
+use crate::{
+    error::{Error, ErrorType},
+    literal::Literal,
+    options::{CaseFold, MatchOptions},
+    GlobPattern, GlobToken, Result,
+};
+use alloc::{string::String, vec::Vec};
+
+/// Pattern matching hostnames the way TLS certificates and DNS wildcards do:
+/// names are split into `.`-separated labels, compared regardless of ASCII
+/// case, and `*` is the only wildcard.
+///
+/// A label consisting of `*` matches exactly one non-empty label, while a `*`
+/// within a label (as in `api-*.example.com`) matches any part of a single
+/// label. `?`, `[` and `\` are matched literally. A single trailing `.` (as in
+/// fully qualified names) is ignored in both patterns and names.
+///
+/// With the `idna` feature, internationalized names can be matched regardless
+/// of whether they're written in Unicode or in their ASCII (punycode) form
+/// (see [`HostPatternBuilder::idna()`]).
+///
+/// ```
+/// use goglob_common::host::HostPattern;
+///
+/// let pattern = HostPattern::new("*.Example.com").unwrap();
+/// assert!(pattern.matches("www.example.COM"));
+/// assert!(pattern.matches("www.example.com."));
+/// assert!(!pattern.matches("a.www.example.com"));
+/// assert!(!pattern.matches("example.com"));
+/// assert!(!pattern.matches(".example.com"));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HostPattern {
+    /// Pattern with `.` as the separator, each wildcard label being `?*`.
+    pattern: GlobPattern,
+    /// Whether names are converted to their ASCII form before matching.
+    idna: bool,
+}
+impl HostPattern {
+    /// Compile the hostname `pattern`, which may contain wildcards in any
+    /// label (e.g. `*.*.example.com`).
+    #[inline]
+    pub fn new<S: AsRef<str>>(pattern: S) -> Result<Self> {
+        HostPatternBuilder::new().build(pattern)
+    }
+
+    /// Same as [`new()`][Self::new], but returning an
+    /// [error][crate::error::Error] if a wildcard appears outside the leftmost
+    /// label, as required for certificates (RFC 6125): `*.example.com` and
+    /// `api-*.example.com` are accepted, `www.*.com` isn't.
+    #[inline]
+    pub fn new_leftmost_only<S: AsRef<str>>(pattern: S) -> Result<Self> {
+        HostPatternBuilder::new().leftmost_only(true).build(pattern)
+    }
+
+    /// Start configuring how to compile a hostname pattern (e.g.
+    /// `HostPattern::builder().leftmost_only(true).build("*.example.com")`).
+    #[inline]
+    pub fn builder() -> HostPatternBuilder {
+        HostPatternBuilder::new()
+    }
+
+    fn compile(pattern: &str, builder: &HostPatternBuilder) -> Result<Self> {
+        let trimmed = pattern.strip_suffix('.').unwrap_or(pattern);
+        if trimmed.is_empty() {
+            return Err(Error::empty_pattern());
+        }
+
+        let mut tokens = Vec::new();
+        let mut offset = 0;
+        for (i, label) in trimmed.split('.').enumerate() {
+            if i > 0 {
+                tokens.push(GlobToken::Literal(Literal::new(String::from("."))));
+            }
+            if let Some(wildcard) = label.find('*').filter(|_| builder.leftmost_only && i > 0) {
+                return Err(Error::new(ErrorType::MisplacedWildcard, offset + wildcard));
+            }
+            let next = offset + label.len() + 1;
+            #[cfg(feature = "idna")]
+            let label = &*match builder.idna {
+                true => idna_label(label)
+                    .map_err(|()| Error::new(ErrorType::InvalidHostLabel, offset))?,
+                false => alloc::borrow::Cow::Borrowed(label),
+            };
+            offset = next;
+
+            if !label.is_empty() && label.chars().all(|c| c == '*') {
+                tokens.push(GlobToken::SingleWildcard);
+                tokens.push(GlobToken::SeqWildcard);
+                continue;
+            }
+            for (j, part) in label.split('*').enumerate() {
+                if j > 0 {
+                    tokens.push(GlobToken::SeqWildcard);
+                }
+                if !part.is_empty() {
+                    tokens.push(GlobToken::Literal(Literal::new(String::from(part))));
+                }
+            }
+        }
+
+        let options = MatchOptions::new()
+            .separator('.')
+            .case_fold(CaseFold::Ascii)
+            .escapes(false);
+        Ok(Self {
+            pattern: GlobPattern::normalized(tokens, options),
+            idna: builder.idna,
+        })
+    }
+
+    /// Report whether the hostname `name` matches the pattern.
+    #[inline]
+    pub fn matches<S: AsRef<str>>(&self, name: S) -> bool {
+        self._matches(name.as_ref())
+    }
+    fn _matches(&self, name: &str) -> bool {
+        let name = name.strip_suffix('.').unwrap_or(name);
+        #[cfg(feature = "idna")]
+        if self.idna {
+            return idna::domain_to_ascii(name).is_ok_and(|name| self.pattern.matches(name));
+        }
+        self.pattern.matches(name)
+    }
+
+    /// Underlying pattern, with `.` as the separator and ASCII case folding,
+    /// e.g. to be added to a [`GlobSet`][crate::set::GlobSet] (which won't
+    /// ignore trailing `.`s in names, nor convert them to ASCII).
+    #[inline]
+    pub fn as_glob_pattern(&self) -> &GlobPattern {
+        &self.pattern
+    }
+}
+
+/// Compiles [`HostPattern`]s with non-default settings.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct HostPatternBuilder {
+    leftmost_only: bool,
+    idna: bool,
+}
+impl HostPatternBuilder {
+    /// Default settings, compiling patterns exactly like
+    /// [`HostPattern::new()`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            leftmost_only: false,
+            idna: false,
+        }
+    }
+
+    /// Whether wildcards are only allowed in the leftmost label (see
+    /// [`HostPattern::new_leftmost_only()`]). Disabled by default.
+    #[inline]
+    pub fn leftmost_only(mut self, leftmost_only: bool) -> Self {
+        self.leftmost_only = leftmost_only;
+        self
+    }
+
+    /// Whether the labels of patterns and names are converted to their ASCII
+    /// form with IDNA (UTS #46) before matching, so that `*.bücher.example`
+    /// and `*.xn--bcher-kva.example` match the same names. Labels with
+    /// wildcards must then be ASCII, and names which can't be converted never
+    /// match. Disabled by default.
+    #[cfg(feature = "idna")]
+    #[inline]
+    pub fn idna(mut self, idna: bool) -> Self {
+        self.idna = idna;
+        self
+    }
+
+    /// Compile `pattern` with the configured settings, returning an
+    /// [error][crate::error::Error] if it's invalid with them.
+    #[inline]
+    pub fn build<S: AsRef<str>>(&self, pattern: S) -> Result<HostPattern> {
+        HostPattern::compile(pattern.as_ref(), self)
+    }
+}
+
+/// ASCII form of the pattern label `label`, which must be ASCII if it has
+/// wildcards.
+#[cfg(feature = "idna")]
+fn idna_label(label: &str) -> core::result::Result<alloc::borrow::Cow<'_, str>, ()> {
+    if label.contains('*') {
+        return match label.is_ascii() {
+            true => Ok(alloc::borrow::Cow::Borrowed(label)),
+            false => Err(()),
+        };
+    }
+    idna::domain_to_ascii(label)
+        .map(alloc::borrow::Cow::Owned)
+        .map_err(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ErrorType, host::HostPattern};
+
+    #[test]
+    fn host_pattern_matches() {
+        let pattern = HostPattern::new("*.example.com").unwrap();
+        assert!(pattern.matches("a.example.com"));
+        assert!(pattern.matches("A-B.EXAMPLE.com."));
+        assert!(!pattern.matches("a.b.example.com"));
+        assert!(!pattern.matches("example.com"));
+        assert!(!pattern.matches(".example.com"));
+        assert!(!pattern.matches("a.example.com.."));
+
+        let pattern = HostPattern::new("api-*.*.example.com.").unwrap();
+        assert!(pattern.matches("api-.eu.example.com"));
+        assert!(pattern.matches("api-v2.eu.example.com"));
+        assert!(!pattern.matches("api.eu.example.com"));
+        assert!(!pattern.matches("api-v2.example.com"));
+
+        let pattern = HostPattern::new("**.?[a]").unwrap();
+        assert!(pattern.matches("x.?[A]"));
+        assert!(!pattern.matches("x.ba"));
+
+        let pattern = HostPattern::new("localhost").unwrap();
+        assert!(pattern.matches("LocalHost"));
+        assert!(!pattern.matches("localhost.localdomain"));
+        assert!(pattern.as_glob_pattern().matches("LOCALHOST"));
+    }
+
+    #[test]
+    fn host_pattern_leftmost_only() {
+        assert!(HostPattern::new_leftmost_only("*.example.com").is_ok());
+        assert!(HostPattern::new_leftmost_only("w*w.example.com").is_ok());
+        assert!(HostPattern::new("www.*.com").is_ok());
+
+        let error = HostPattern::new_leftmost_only("www.ex*.com").unwrap_err();
+        assert_eq!(error.position(), 6);
+        assert_eq!(
+            error.error_type().type_desc(),
+            ErrorType::MisplacedWildcard.type_desc()
+        );
+        assert!(HostPattern::new("").is_err());
+        assert!(HostPattern::new(".").is_err());
+    }
+
+    #[test]
+    fn host_pattern_builder() {
+        let pattern = HostPattern::builder()
+            .leftmost_only(true)
+            .build("*.example.com")
+            .unwrap();
+        assert_eq!(
+            pattern,
+            HostPattern::new_leftmost_only("*.example.com").unwrap()
+        );
+        assert!(HostPattern::builder().build("www.*.com").is_ok());
+        assert!(HostPattern::builder()
+            .leftmost_only(true)
+            .build("www.*.com")
+            .is_err());
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn host_pattern_idna() {
+        let idna = HostPattern::builder().idna(true);
+        for pattern in [
+            "*.bücher.example",
+            "*.xn--bcher-kva.example",
+            "*.BÜCHER.example.",
+        ] {
+            let pattern = idna.build(pattern).unwrap();
+            assert!(pattern.matches("www.bücher.example"));
+            assert!(pattern.matches("www.xn--bcher-kva.example"));
+            assert!(pattern.matches("WWW.Bücher.Example."));
+            assert!(!pattern.matches("www.bucher.example"));
+            assert!(!pattern.matches("a.www.bücher.example"));
+        }
+        assert!(idna.build("bü*.example").unwrap_err().position() == 0);
+        let error = idna.build("www.xn--a.example").unwrap_err();
+        assert_eq!(error.position(), 4);
+        assert_eq!(
+            error.error_type().type_desc(),
+            ErrorType::InvalidHostLabel.type_desc()
+        );
+
+        let pattern = HostPattern::new("*.bücher.example").unwrap();
+        assert!(pattern.matches("www.bücher.example"));
+        assert!(!pattern.matches("www.xn--bcher-kva.example"));
+    }
+}
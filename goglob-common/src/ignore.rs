@@ -0,0 +1,390 @@
+//! Per-directory `.gitignore`-style ignore files, layered as git does.
+//!
+//! Each ignore file applies to the directory it's in and everything below it,
+//! its patterns being matched against paths relative to that directory. Files
+//! in deeper directories take precedence over those above them, and within a
+//! file later patterns take precedence over earlier ones.
+
+use crate::{
+    rules::{Rule, RuleSet},
+    set::GlobSet,
+    syntax::Syntax,
+    GlobPattern, GlobToken,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::string::String;
+
+/// Ignore files found in a directory tree, read as the paths below them are
+/// checked (e.g. while walking the tree), along with global rules applying to
+/// the whole tree with the lowest precedence (such as git's
+/// `core.excludesFile`).
+///
+/// As with git, paths inside an ignored directory are ignored too, even if a
+/// negated pattern (`!pattern`) matches them. I/O errors, such as an unreadable
+/// ignore file, are ignored as if the file didn't exist.
+///
+/// ```no_run
+/// use goglob_common::{ignore::IgnoreTree, GlobPattern};
+///
+/// let mut tree = IgnoreTree::new("repo").global(GlobPattern::parse_gitignore("*.swp\n"));
+/// if !tree.is_ignored("src/lib.rs", false) {
+///     // ...
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct IgnoreTree {
+    root: PathBuf,
+    file_name: String,
+    global: RuleSet,
+    /// Rules of the ignore file of each directory read so far (relative to
+    /// the root), empty if there's none.
+    dirs: HashMap<PathBuf, RuleSet>,
+}
+impl IgnoreTree {
+    /// Tree rooted at `root`, with an ignore file named `.gitignore` in any
+    /// directory and no global rules.
+    #[inline]
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            file_name: String::from(".gitignore"),
+            global: RuleSet::new(),
+            dirs: HashMap::new(),
+        }
+    }
+
+    /// Name of the ignore files to look for (e.g. `.ignore`).
+    #[inline]
+    pub fn file_name<S: Into<String>>(mut self, file_name: S) -> Self {
+        self.file_name = file_name.into();
+        self.dirs.clear();
+        self
+    }
+
+    /// Rules applying to paths relative to the root, with a lower precedence
+    /// than every ignore file in the tree (e.g. parsed from git's
+    /// `core.excludesFile` with [`GlobPattern::parse_gitignore()`]).
+    #[inline]
+    pub fn global(mut self, rules: RuleSet) -> Self {
+        self.global = rules;
+        self
+    }
+
+    /// Directory the tree is rooted at.
+    #[inline]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Report whether the directory (if `is_dir`) or other file at `path`,
+    /// either relative to the root or starting with it, is ignored by the
+    /// ignore files of the directories it's in or by the global rules.
+    ///
+    /// Paths which aren't valid UTF-8 (or lead outside the root) are never
+    /// ignored.
+    pub fn is_ignored<P: AsRef<Path>>(&mut self, path: P, is_dir: bool) -> bool {
+        let path = path.as_ref();
+        let path = path.strip_prefix(&self.root).unwrap_or(path);
+        let mut components = Vec::new();
+        for component in path.components() {
+            match component {
+                Component::Normal(name) => match name.to_str() {
+                    Some(name) => components.push(name),
+                    None => return false,
+                },
+                Component::CurDir => {}
+                _ => return false,
+            }
+        }
+        // A directory being ignored hides everything inside it.
+        (0..components.len())
+            .any(|i| self.decide(&components[..=i], is_dir || i + 1 < components.len()))
+    }
+
+    /// Whether the entry at `components` is ignored by the deepest ignore file
+    /// (or global rules) with a pattern matching it.
+    fn decide(&mut self, components: &[&str], is_dir: bool) -> bool {
+        for depth in (0..components.len()).rev() {
+            let name = components[depth..].join("/");
+            let rules = self.rules(&components[..depth]);
+            if let Some(index) = rules.matching_rule_entry(&name, is_dir) {
+                return !rules.get(index).is_some_and(Rule::is_negated);
+            }
+        }
+        self.global.matches_entry(components.join("/"), is_dir)
+    }
+
+    /// Rules of the ignore file in the directory at `components`, reading it
+    /// if it hasn't been yet.
+    fn rules(&mut self, components: &[&str]) -> &RuleSet {
+        let dir: PathBuf = components.iter().collect();
+        let root = &self.root;
+        let file_name = &self.file_name;
+        self.dirs.entry(dir).or_insert_with_key(|dir| {
+            fs::read_to_string(root.join(dir).join(file_name))
+                .map(GlobPattern::parse_gitignore)
+                .unwrap_or_default()
+        })
+    }
+}
+
+impl GlobPattern {
+    /// Parse the contents of a `.gitignore` file into a [`RuleSet`] matching
+    /// paths relative to its directory (with
+    /// [`RuleSet::matches_entry()`]), as git does.
+    ///
+    /// Blank lines and lines starting with `#` are skipped, as are lines
+    /// which aren't valid patterns. Trailing spaces are trimmed unless
+    /// escaped with `\`. Patterns starting with `!` are negated, and patterns
+    /// without a `/` other than a trailing one match names at any depth (e.g.
+    /// `*.o` is `**/*.o`), while the others are relative to the directory
+    /// (a leading `/` being dropped). Patterns use [`Syntax::Gitignore`].
+    ///
+    /// ```
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let rules = GlobPattern::parse_gitignore("# Build output\n/target/\n*.o\n!keep.o\n");
+    /// assert!(rules.matches_entry("target", true));
+    /// assert!(!rules.matches_entry("src/target", true));
+    /// assert!(rules.matches_entry("src/main.o", false));
+    /// assert!(!rules.matches_entry("src/keep.o", false));
+    /// ```
+    pub fn parse_gitignore<S: AsRef<str>>(contents: S) -> RuleSet {
+        let builder = GlobPattern::builder().syntax(Syntax::Gitignore);
+        contents
+            .as_ref()
+            .lines()
+            .filter_map(|line| {
+                let line = trim_unescaped_spaces(line);
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let (line, negated) = match line.strip_prefix('!') {
+                    Some(line) => (line, true),
+                    None => (line, false),
+                };
+                let pattern = match line.trim_end_matches('/').contains('/') {
+                    true => builder.build(line.strip_prefix('/').unwrap_or(line)),
+                    false => builder.build(format!("**/{line}")),
+                };
+                Some(Rule::new(pattern.ok()?, negated))
+            })
+            .collect()
+    }
+}
+
+impl RuleSet {
+    /// Render the rules into the contents of a `.gitignore` file, one line
+    /// per rule, which [`GlobPattern::parse_gitignore()`] parses back into
+    /// the same rules.
+    ///
+    /// Patterns are rendered from their compiled form in
+    /// [`Syntax::Gitignore`]: those starting with `**/` and without any other
+    /// `/` but a trailing one are written without it (e.g. `*.o`), and the
+    /// others are anchored with a leading `/` where needed. A leading `#` or
+    /// `!` and a trailing space are escaped with `\`. Case folding and the
+    /// other options the patterns were compiled with aren't rendered.
+    ///
+    /// ```
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let rules = GlobPattern::parse_gitignore("/target/\n*.o\n!keep.o\n\\#notes\n");
+    /// assert_eq!(rules.to_gitignore(), "/target/\n*.o\n!keep.o\n\\#notes\n");
+    /// assert_eq!(GlobPattern::parse_gitignore(rules.to_gitignore()), rules);
+    /// ```
+    pub fn to_gitignore(&self) -> String {
+        let mut contents = String::new();
+        for rule in self.iter() {
+            push_gitignore_line(&mut contents, rule.pattern(), rule.is_negated());
+        }
+        contents
+    }
+}
+
+impl GlobSet {
+    /// Same as [`RuleSet::to_gitignore()`], rendering each pattern as a rule
+    /// which isn't negated.
+    pub fn to_gitignore(&self) -> String {
+        let mut contents = String::new();
+        for pattern in self {
+            push_gitignore_line(&mut contents, pattern, false);
+        }
+        contents
+    }
+}
+
+/// Push the `.gitignore` line of the rule made of `pattern` (negated if
+/// `negated`) to `contents`.
+fn push_gitignore_line(contents: &mut String, pattern: &GlobPattern, negated: bool) {
+    let mut rendered = String::new();
+    for token in pattern.tokens() {
+        match token {
+            GlobToken::Literal(l) => l
+                .as_ref()
+                .chars()
+                .for_each(|c| push_escaped(&mut rendered, c, false)),
+            GlobToken::CharClass(cc) => {
+                rendered.push('[');
+                if cc.is_negated() {
+                    rendered.push('!');
+                }
+                for cct in cc.types() {
+                    let range = cct.as_range();
+                    push_escaped(&mut rendered, *range.start(), true);
+                    if range.start() != range.end() {
+                        rendered.push('-');
+                        push_escaped(&mut rendered, *range.end(), true);
+                    }
+                }
+                rendered.push(']');
+            }
+            GlobToken::SeqWildcard => rendered.push('*'),
+            GlobToken::SingleWildcard => rendered.push('?'),
+            GlobToken::AnyDirs => rendered.push_str("**/"),
+        }
+    }
+
+    // The parser prepends `**/` to patterns without a '/' but a trailing one,
+    // and strips a leading '/' from the others.
+    let has_slash = |line: &str| line.trim_end_matches('/').contains('/');
+    let line = match rendered.strip_prefix("**/") {
+        Some(rest) if !rest.trim_end_matches('/').is_empty() && !has_slash(rest) => rest,
+        _ if has_slash(&rendered) && !rendered.starts_with('/') => &rendered,
+        _ => {
+            rendered.insert(0, '/');
+            &rendered
+        }
+    };
+
+    if negated {
+        contents.push('!');
+    }
+    if line.starts_with(['#', '!']) {
+        contents.push('\\');
+    }
+    match line.strip_suffix(' ') {
+        Some(line) => {
+            contents.push_str(line);
+            contents.push_str("\\ ");
+        }
+        None => contents.push_str(line),
+    }
+    contents.push('\n');
+}
+
+/// Push `c` as it would appear in a `.gitignore` pattern, escaping it if
+/// necessary.
+fn push_escaped(rendered: &mut String, c: char, in_class: bool) {
+    let special = if in_class {
+        matches!(c, '\\' | '-' | ']' | '^' | '!')
+    } else {
+        matches!(c, '*' | '?' | '[' | ']' | '\\')
+    };
+    if special {
+        rendered.push('\\');
+    }
+    rendered.push(c);
+}
+
+/// `line` without its trailing spaces, except for one preceded by `\`.
+fn trim_unescaped_spaces(line: &str) -> &str {
+    let trimmed = line.trim_end_matches(' ');
+    let backslashes = trimmed.len() - trimmed.trim_end_matches('\\').len();
+    match backslashes % 2 == 1 && trimmed.len() < line.len() {
+        true => &line[..trimmed.len() + 1],
+        false => trimmed,
+    }
+}
+
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use crate::{
+        fs::tests::TempTree, ignore::IgnoreTree, options::MatchOptions, rules::RuleSet,
+        set::GlobSet, syntax::Syntax, GlobPattern,
+    };
+
+    fn gitignore() -> MatchOptions {
+        MatchOptions::new().syntax(Syntax::Gitignore)
+    }
+
+    #[test]
+    fn parse_gitignore() {
+        let rules = GlobPattern::parse_gitignore(
+            "\n# comment\n\\#hash\n*.log \nbuild/\ndoc/*.html\n!/doc/index.html\ntrailing\\ \n[\n",
+        );
+        assert_eq!(rules.len(), 6);
+        assert!(rules.matches_entry("#hash", false));
+        assert!(rules.matches_entry("a/b/debug.log", false));
+        assert!(rules.matches_entry("src/build", true));
+        assert!(!rules.matches_entry("src/build", false));
+        assert!(rules.matches_entry("doc/api.html", false));
+        assert!(!rules.matches_entry("src/doc/api.html", false));
+        assert!(!rules.matches_entry("doc/index.html", false));
+        assert!(rules.matches_entry("trailing ", false));
+        assert!(!rules.matches_entry("trailing", false));
+    }
+
+    #[test]
+    fn to_gitignore_round_trip() {
+        let contents = concat!(
+            "*.log\n/target/\nbuild/\ndoc/*.html\n!/doc/index.html\n",
+            "\\#hash\n\\!bang\n!\\!bang\ntrailing\\ \na/**/b\n**/c/d\n",
+            "[!a-c]?\n[\\!x]\nx[\\]\\-]\n/top\n\\*star\n",
+        );
+        let rules = GlobPattern::parse_gitignore(contents);
+        assert_eq!(rules.len(), 16);
+        let rendered = rules.to_gitignore();
+        assert_eq!(GlobPattern::parse_gitignore(&rendered), rules, "{rendered}");
+        assert!(rendered.starts_with("*.log\n/target/\nbuild/\ndoc/*.html\n!doc/index.html\n"));
+        assert!(rendered.contains("\n\\#hash\n\\!bang\n!\\!bang\ntrailing\\ \n"));
+
+        let set = GlobSet::compile_with(["**/*.rs", "src/*", "#x"], &gitignore()).unwrap();
+        assert_eq!(set.to_gitignore(), "*.rs\nsrc/*\n/#x\n");
+        assert_eq!(RuleSet::new().to_gitignore(), "");
+    }
+
+    #[test]
+    fn ignore_tree_is_ignored() {
+        let tree = TempTree::new(
+            "ignore-tree",
+            &["src/lib.rs", "target/", "docs/", "logs/", "vendor/pkg/"],
+        );
+        let write =
+            |path: &str, contents: &str| std::fs::write(tree.0.join(path), contents).unwrap();
+        write(".gitignore", "/target/\n*.log\n!important.log\nlogs/\n");
+        write("src/.gitignore", "!debug.log\n*.rs\n!lib.rs\n");
+        write("vendor/.gitignore", "*\n!keep.txt\n");
+
+        let mut ignore =
+            IgnoreTree::new(&tree.0).global(GlobPattern::parse_gitignore("*.swp\n*.tmp\n"));
+        assert!(ignore.is_ignored("target", true));
+        assert!(ignore.is_ignored("target/debug/app", false));
+        assert!(!ignore.is_ignored("src/target", true));
+        assert!(ignore.is_ignored("a.log", false));
+        assert!(!ignore.is_ignored("important.log", false));
+        assert!(ignore.is_ignored(tree.0.join("docs/x.log"), false));
+
+        // Nested files take precedence over their parents' and global rules
+        assert!(!ignore.is_ignored("src/debug.log", false));
+        assert!(ignore.is_ignored("src/main.rs", false));
+        assert!(!ignore.is_ignored("src/lib.rs", false));
+        assert!(ignore.is_ignored("src/lib.swp", false));
+        assert!(ignore.is_ignored("src/bin/tool.rs", false));
+
+        // Nothing inside an ignored directory can be re-included
+        assert!(ignore.is_ignored("logs/important.log", false));
+        assert!(ignore.is_ignored("vendor/pkg", true));
+        assert!(ignore.is_ignored("vendor/pkg/keep.txt", false));
+        assert!(!ignore.is_ignored("vendor/keep.txt", false));
+        assert!(!ignore.is_ignored("vendor", true));
+
+        assert!(!ignore.is_ignored("../outside.log", false));
+        assert!(!ignore.is_ignored("", true));
+
+        let mut ignore = IgnoreTree::new(&tree.0).file_name(".ignore");
+        assert!(!ignore.is_ignored("a.log", false));
+        assert!(!ignore.is_ignored("a.swp", false));
+    }
+}
@@ -0,0 +1,196 @@
+//! Deduplication of patterns compiled from many independent sources.
+
+use crate::{options::MatchOptions, GlobPattern, Result};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::ops::Index;
+
+/// Handle to a pattern stored in a [`GlobInterner`], cheap to copy and
+/// compare.
+///
+/// Handles are only meaningful to the interner which returned them: two
+/// handles from the same interner are equal if and only if they refer to the
+/// same pattern string compiled with the same options.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct GlobHandle(u32);
+impl GlobHandle {
+    /// Position of the pattern in its interner, in the order patterns were
+    /// first interned.
+    #[inline]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Registry compiling each distinct pattern only once, handing out
+/// [`GlobHandle`]s to the shared compiled pattern.
+///
+/// Meant for applications assembling many rules from independent
+/// configuration fragments, where the same patterns are repeated over and
+/// over. Interning a pattern string (along with its options) which was
+/// already interned returns the existing handle without compiling it again
+/// (reported as a cache hit to the metrics sink, if any).
+///
+/// ```
+/// use goglob_common::interner::GlobInterner;
+///
+/// let mut interner = GlobInterner::new();
+/// let a = interner.intern("*.rs").unwrap();
+/// let b = interner.intern("*.rs").unwrap();
+/// assert_eq!(a, b);
+/// assert_eq!(interner.len(), 1);
+/// assert!(interner[a].matches("lib.rs"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GlobInterner {
+    patterns: Vec<GlobPattern>,
+    handles: BTreeMap<String, Vec<GlobHandle>>,
+}
+impl GlobInterner {
+    /// Empty interner.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct patterns interned.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Whether no pattern was interned yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Return the handle of `pattern`, compiling it with the default options
+    /// if it wasn't interned before. Invalid patterns aren't stored.
+    pub fn intern<S: AsRef<str>>(&mut self, pattern: S) -> Result<GlobHandle> {
+        self.intern_with(pattern, &MatchOptions::new())
+    }
+
+    /// Same as [`intern()`][Self::intern], compiling `pattern` according to
+    /// `options`. The same pattern string interned with different options
+    /// gives different handles.
+    pub fn intern_with<S: AsRef<str>>(
+        &mut self,
+        pattern: S,
+        options: &MatchOptions,
+    ) -> Result<GlobHandle> {
+        let pattern = pattern.as_ref();
+        if let Some(handle) = self.find(pattern, options) {
+            #[cfg(feature = "metrics")]
+            if let Some(sink) = crate::metrics::sink() {
+                sink.cache_hit();
+            }
+            return Ok(handle);
+        }
+
+        let compiled = GlobPattern::new_with(pattern, options)?;
+        let index = u32::try_from(self.patterns.len()).expect("too many interned patterns");
+        let handle = GlobHandle(index);
+        self.patterns.push(compiled);
+        self.handles.entry(pattern.into()).or_default().push(handle);
+        Ok(handle)
+    }
+
+    /// Handle of `pattern` if it was already interned with `options`, without
+    /// interning it otherwise.
+    pub fn find(&self, pattern: &str, options: &MatchOptions) -> Option<GlobHandle> {
+        self.handles
+            .get(pattern)?
+            .iter()
+            .copied()
+            .find(|handle| self.patterns[handle.index()].options() == options)
+    }
+
+    /// Compiled pattern referred to by `handle`, or `None` if it doesn't
+    /// belong to this interner.
+    #[inline]
+    pub fn get(&self, handle: GlobHandle) -> Option<&GlobPattern> {
+        self.patterns.get(handle.index())
+    }
+
+    /// Report whether the `name` matches the pattern referred to by `handle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` doesn't belong to this interner.
+    #[inline]
+    pub fn matches<S: AsRef<str>>(&self, handle: GlobHandle, name: S) -> bool {
+        self[handle].matches(name)
+    }
+
+    /// Iterate over the handles and patterns in the order they were first
+    /// interned.
+    pub fn iter(&self) -> impl Iterator<Item = (GlobHandle, &GlobPattern)> {
+        self.patterns
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| (GlobHandle(index as u32), pattern))
+    }
+}
+impl Index<GlobHandle> for GlobInterner {
+    type Output = GlobPattern;
+
+    /// # Panics
+    ///
+    /// Panics if `handle` doesn't belong to this interner.
+    #[inline]
+    fn index(&self, handle: GlobHandle) -> &GlobPattern {
+        &self.patterns[handle.index()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interner::GlobInterner,
+        options::{CaseFold, MatchOptions},
+    };
+
+    #[test]
+    fn glob_interner_dedup() {
+        let mut interner = GlobInterner::new();
+        assert!(interner.is_empty());
+        let rs = interner.intern("*.rs").unwrap();
+        let toml = interner.intern("*.toml").unwrap();
+        assert_ne!(rs, toml);
+        assert_eq!(interner.intern(String::from("*.rs")).unwrap(), rs);
+        assert_eq!(interner.len(), 2);
+        assert_eq!((rs.index(), toml.index()), (0, 1));
+
+        assert!(interner.matches(rs, "lib.rs"));
+        assert!(!interner.matches(toml, "lib.rs"));
+        assert_eq!(interner.get(toml), Some(&interner[toml]));
+        let handles: Vec<_> = interner.iter().map(|(handle, _)| handle).collect();
+        assert_eq!(handles, vec![rs, toml]);
+    }
+
+    #[test]
+    fn glob_interner_options() {
+        let mut interner = GlobInterner::new();
+        let sensitive = interner.intern("*.RS").unwrap();
+        let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+        let folded = interner.intern_with("*.RS", &options).unwrap();
+        assert_ne!(sensitive, folded);
+        assert_eq!(interner.intern_with("*.RS", &options).unwrap(), folded);
+        assert_eq!(interner.find("*.RS", &options), Some(folded));
+        assert_eq!(interner.find("*.rs", &options), None);
+        assert!(interner.matches(folded, "lib.rs"));
+        assert!(!interner.matches(sensitive, "lib.rs"));
+    }
+
+    #[test]
+    fn glob_interner_errors() {
+        let mut interner = GlobInterner::new();
+        assert!(interner.intern("a[").is_err());
+        assert!(interner.is_empty());
+        assert_eq!(interner.find("a[", &MatchOptions::new()), None);
+
+        let other = GlobInterner::new();
+        let handle = interner.intern("a").unwrap();
+        assert_eq!(other.get(handle), None);
+    }
+}
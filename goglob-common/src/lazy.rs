@@ -0,0 +1,109 @@
+//! Patterns compiled on first use, for `static` items without the procedural
+//! macro.
+
+use crate::{options::MatchOptions, GlobPattern};
+use core::ops::Deref;
+use std::sync::OnceLock;
+
+/// Pattern compiled the first time it's used, so that it can be declared as
+/// a `static` item without the `proc-macro` feature.
+///
+/// Unlike `glob!()`, syntax errors can only be found at runtime: the first
+/// use of an invalid pattern panics, showing where the error is in the
+/// pattern (with the `diagnostics` feature).
+///
+/// ```
+/// use goglob_common::lazy::LazyGlob;
+///
+/// static RUST_FILES: LazyGlob = LazyGlob::new("*.rs");
+///
+/// assert!(RUST_FILES.matches("lib.rs"));
+/// assert!(!RUST_FILES.matches("README.md"));
+/// assert_eq!(RUST_FILES.tokens().len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct LazyGlob {
+    pattern: &'static str,
+    options: MatchOptions,
+    compiled: OnceLock<GlobPattern>,
+}
+impl LazyGlob {
+    /// Pattern compiled from `pattern` on first use.
+    #[inline]
+    pub const fn new(pattern: &'static str) -> Self {
+        Self::new_with(pattern, MatchOptions::new())
+    }
+
+    /// Same as [`new()`][Self::new], compiling the pattern according to
+    /// `options`.
+    #[inline]
+    pub const fn new_with(pattern: &'static str, options: MatchOptions) -> Self {
+        Self {
+            pattern,
+            options,
+            compiled: OnceLock::new(),
+        }
+    }
+
+    /// The pattern as written.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        self.pattern
+    }
+
+    /// Compiled pattern, compiling it if it's the first use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern is syntactically invalid.
+    pub fn get(&self) -> &GlobPattern {
+        self.compiled.get_or_init(|| {
+            GlobPattern::new_with(self.pattern, &self.options).unwrap_or_else(|e| {
+                #[cfg(feature = "diagnostics")]
+                panic!("invalid pattern: {}", e.render(self.pattern));
+                #[cfg(not(feature = "diagnostics"))]
+                panic!("invalid pattern: {e}");
+            })
+        })
+    }
+}
+impl Deref for LazyGlob {
+    type Target = GlobPattern;
+
+    #[inline]
+    fn deref(&self) -> &GlobPattern {
+        self.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{lazy::LazyGlob, options::MatchOptions, syntax::Syntax};
+    use std::{panic, thread};
+
+    static SOURCES: LazyGlob =
+        LazyGlob::new_with("**/*.rs", MatchOptions::new().syntax(Syntax::Gitignore));
+    static INVALID: LazyGlob = LazyGlob::new("ab[cd");
+
+    #[test]
+    fn lazy_glob() {
+        assert_eq!(SOURCES.as_str(), "**/*.rs");
+        let handles: Vec<_> = (0..4)
+            .map(|_| thread::spawn(|| SOURCES.matches("src/bin/main.rs")))
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+        assert!(core::ptr::eq(SOURCES.get(), SOURCES.get()));
+        assert!(!SOURCES.matches("README.md"));
+    }
+
+    #[test]
+    fn lazy_glob_invalid() {
+        let panic = panic::catch_unwind(|| INVALID.matches("abc")).unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("invalid pattern: "), "{message}");
+        #[cfg(feature = "diagnostics")]
+        assert!(message.ends_with("\n  ab[cd\n    ^"), "{message}");
+    }
+}
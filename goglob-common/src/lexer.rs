@@ -0,0 +1,483 @@
+//! Streaming tokenizer, for tools which only need to inspect or validate
+//! patterns without compiling them.
+
+use crate::{
+    error::{Error, ErrorType},
+    options::MatchOptions,
+    syntax::Syntax,
+    Result,
+};
+use core::{
+    iter::{FusedIterator, Peekable},
+    ops::Range,
+    str::CharIndices,
+};
+
+/// Byte range of a [`Token`] within its pattern.
+pub type Span = Range<usize>;
+
+/// Element of a pattern, as yielded by [`PatternLexer`].
+///
+/// Literals are yielded a character at a time (with escapes resolved), and
+/// character classes as their opening, each of their entries and their
+/// closing, so that no token needs to allocate.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Token {
+    /// Any sequence of non-separator characters (`*`, or a run of them).
+    SeqWildcard,
+    /// Any number of directories (`**/` in [`Syntax::Gitignore`]).
+    AnyDirs,
+    /// Any single non-separator character (`?`).
+    SingleWildcard,
+    /// Literal character, possibly escaped (e.g. `a` or `\*`).
+    Literal(char),
+    /// Opening of a character class (`[`, or `[^` if `negated`).
+    ClassStart { negated: bool },
+    /// Single character in a class (e.g. the `a` of `[ab]`).
+    ClassChar(char),
+    /// Range of characters in a class (e.g. `a-z`).
+    ClassRange(char, char),
+    /// Closing of a character class (`]`).
+    ClassEnd,
+}
+
+/// Iterator over the [`Token`]s of a pattern and their [`Span`]s, tokenizing
+/// it lazily without allocating.
+///
+/// A syntactically invalid pattern yields an [error][Error] once the lexer
+/// reaches the offending character, after which iteration stops. A pattern is
+/// valid if and only if [`GlobPattern::new_with()`][crate::GlobPattern::new_with]
+/// would compile it with the same options.
+///
+/// ```
+/// use goglob_common::lexer::{PatternLexer, Token};
+///
+/// let tokens: Vec<_> = PatternLexer::new("a*[^b-d]").map(Result::unwrap).collect();
+/// assert_eq!(
+///     tokens,
+///     [
+///         (Token::Literal('a'), 0..1),
+///         (Token::SeqWildcard, 1..2),
+///         (Token::ClassStart { negated: true }, 2..4),
+///         (Token::ClassRange('b', 'd'), 4..7),
+///         (Token::ClassEnd, 7..8),
+///     ]
+/// );
+///
+/// // Validate without building any token vector
+/// assert!(PatternLexer::new("a[b").any(|token| token.is_err()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PatternLexer<'a> {
+    pattern: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    syntax: Syntax,
+    separator: char,
+    seq: char,
+    single: char,
+    escape: Option<char>,
+    classes: bool,
+    state: State,
+}
+impl<'a> PatternLexer<'a> {
+    /// Tokenize `pattern` with the default options.
+    #[inline]
+    pub fn new(pattern: &'a str) -> Self {
+        Self::new_with(pattern, &MatchOptions::new())
+    }
+
+    /// Tokenize `pattern` according to `options`.
+    pub fn new_with(pattern: &'a str, options: &MatchOptions) -> Self {
+        Self {
+            pattern,
+            chars: pattern.char_indices().peekable(),
+            syntax: options.syntax,
+            separator: options.effective_separator(),
+            seq: options.seq_wildcard,
+            single: options.single_wildcard,
+            escape: options.effective_escapes().then_some(options.escape_char),
+            classes: options.char_classes,
+            state: State::Start,
+        }
+    }
+
+    /// The pattern being tokenized.
+    #[inline]
+    pub fn pattern(&self) -> &'a str {
+        self.pattern
+    }
+
+    fn lex(&mut self) -> Option<Result<(Token, Span)>> {
+        match self.state {
+            State::Start if self.pattern.is_empty() => Some(Err(Error::empty_pattern())),
+            State::Start => {
+                self.state = State::Pattern;
+                self.lex()
+            }
+            State::Pattern => self.lex_pattern(),
+            State::PendingSeq(ref span) => {
+                let span = span.clone();
+                self.state = State::Pattern;
+                Some(Ok((Token::SeqWildcard, span)))
+            }
+            State::Class {
+                start,
+                first,
+                entries,
+            } => Some(self.lex_class(start, first, entries)),
+            State::Done => None,
+        }
+    }
+
+    /// Next token outside of character classes.
+    fn lex_pattern(&mut self) -> Option<Result<(Token, Span)>> {
+        let &(i, c) = self.chars.peek()?;
+        if c == self.seq {
+            return Some(Ok(self.lex_stars(i)));
+        }
+        self.chars.next();
+        if c == self.single {
+            return Some(Ok((Token::SingleWildcard, i..self.offset())));
+        }
+        let c = match c {
+            c if Some(c) == self.escape => {
+                // The escape character escapes the next character, whichever
+                // it may be. If there is no "next character", then it's
+                // considered an illegal escape
+                match self.chars.next() {
+                    Some((_, escaped_char)) => escaped_char,
+                    None => return Some(Err(Error::new(ErrorType::IllegalEscape, i))),
+                }
+            }
+            '[' if self.classes
+                && (!self.syntax.lenient_brackets()
+                    || class_closed(&self.pattern[i + 1..], self.syntax, self.escape)) =>
+            {
+                // Match negation in character class (e.g. '[^A-F]', or
+                //                                           ^
+                // '[!A-F]' with POSIX classes)
+                //   ^
+                let posix = self.syntax.posix_classes();
+                let negated = self
+                    .chars
+                    .next_if(|&(_, c)| c == '^' || (c == '!' && posix))
+                    .is_some();
+                self.state = State::Class {
+                    start: i,
+                    first: true,
+                    entries: 0,
+                };
+                return Some(Ok((Token::ClassStart { negated }, i..self.offset())));
+            }
+            // Without character classes, and in glob(7) when they don't form
+            // a character class, brackets are literal characters
+            '[' => '[',
+            ']' if !self.classes || self.syntax.lenient_brackets() => ']',
+            ']' =>
+            // we are not in a character class (i.e. '[' was never passed)
+            // therefore ']' is illegal and should be explicitly escaped
+            {
+                return Some(Err(Error::new(ErrorType::UnescapedChar(']'), i)))
+            }
+            c => c,
+        };
+        Some(Ok((Token::Literal(c), i..self.offset())))
+    }
+
+    /// Run of star wildcards starting at `i` (e.g. '*ab?cd[e-z]*').
+    ///                                              ^          ^
+    fn lex_stars(&mut self, i: usize) -> (Token, Span) {
+        let mut count = 0;
+        while self.chars.next_if(|&(_, c)| c == self.seq).is_some() {
+            count += 1;
+        }
+        let end = self.offset();
+
+        // In gitignore, '**' spanning a whole segment (e.g. 'a/**/b') matches
+        //                                                      ^^^
+        // any number of directories, including the '/' after it. At the end
+        // of the pattern it matches everything inside the directory, i.e. any
+        // number of directories followed by '*'.
+        let globstar = self.syntax.globstar()
+            && count >= 2
+            && (i == 0 || self.pattern[..i].ends_with(self.separator));
+        match self.chars.peek() {
+            Some(&(_, c)) if globstar && c == self.separator => {
+                self.chars.next();
+                (Token::AnyDirs, i..self.offset())
+            }
+            None if globstar => {
+                self.state = State::PendingSeq(i..end);
+                (Token::AnyDirs, i..end)
+            }
+            _ => (Token::SeqWildcard, i..end),
+        }
+    }
+
+    /// Next entry (or the closing) of the character class opened at `start`.
+    fn lex_class(&mut self, start: usize, first: bool, entries: usize) -> Result<(Token, Span)> {
+        let posix = self.syntax.posix_classes();
+        // A character class must be closed with a corresponding ']'.
+        let (i, c) = self
+            .chars
+            .next()
+            .ok_or_else(|| Error::new(ErrorType::UnclosedCharClass, start))?;
+        let c = match c {
+            ']' if first && posix =>
+            // POSIX classes can't be empty, so a leading ']' is part of the
+            // class (e.g. '[]a]')
+            {
+                ']'
+            }
+            ']' if entries == 0 =>
+            // A character class must not be empty (e.g. []abc] or [^]abc])
+            //                                            ^          ^
+            // For the character class to include a ']' char it must be
+            // explicitly escaped (e.g. [\]abc] or [^\]abc].
+            //                           ^^          ^^
+            {
+                return Err(Error::new(ErrorType::UnescapedChar(']'), i))
+            }
+            ']' => {
+                self.state = State::Pattern;
+                return Ok((Token::ClassEnd, i..self.offset()));
+            }
+            c => self.class_char(i, c, first)?,
+        };
+
+        let token = if starts_range(&self.chars, self.syntax) {
+            // Character range (e.g. [0-9abcdefA-F]
+            //                        ^^^      ^^^
+            self.chars.next();
+            let (j, end) = self
+                .chars
+                .next()
+                .ok_or_else(|| Error::new(ErrorType::UnclosedCharClass, start))?;
+            if end == ']' {
+                return Err(Error::new(ErrorType::UnescapedChar(']'), j));
+            }
+            let end = self.class_char(j, end, false)?;
+            if c > end {
+                return Err(Error::new(ErrorType::InvalidRangeValues(c, end), j));
+            }
+            Token::ClassRange(c, end)
+        } else {
+            Token::ClassChar(c)
+        };
+        self.state = State::Class {
+            start,
+            first: false,
+            entries: entries + 1,
+        };
+        Ok((token, i..self.offset()))
+    }
+
+    /// Character `c` at `i` within a class, other than a closing `]`,
+    /// resolving escapes.
+    fn class_char(&mut self, i: usize, c: char, first: bool) -> Result<char> {
+        match c {
+            '-' if self.syntax.posix_classes()
+                && (first || matches!(self.chars.peek(), Some((_, ']')))) =>
+            // POSIX classes allow a literal '-' at the start or end
+            // (e.g. '[-a]' or '[a-]')
+            {
+                Ok('-')
+            }
+            '-' =>
+            // Illegal uses of '-':
+            //
+            // * As the first character in the class (e.g. [-a][^-z])
+            //                                              ^    ^
+            // * After another '-' (e.g. [a--f])
+            //                              ^
+            // * Immediately after a character range (e.g. [a-f-z])
+            //                                                 ^
+            // If a literal '-' is desired, escape it with a '\' beforehand
+            // (e.g. [a-f\-z][\-a][^\-z])
+            //           ^^   ^^    ^^
+            {
+                Err(Error::new(ErrorType::UnescapedChar('-'), i))
+            }
+            c if Some(c) == self.escape => {
+                // The escape character escapes the next character, whichever
+                // it may be. If there is no "next character", then it's
+                // considered an illegal escape
+                self.chars
+                    .next()
+                    .map(|(_, escaped_char)| escaped_char)
+                    .ok_or_else(|| Error::new(ErrorType::IllegalEscape, i))
+            }
+            // A '^' after the start of the class is a character as-is (at
+            // least according to go's impl), as is anything else
+            c => Ok(c),
+        }
+    }
+
+    /// Offset of the next character, or the end of the pattern.
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.pattern.len(), |&(i, _)| i)
+    }
+}
+impl Iterator for PatternLexer<'_> {
+    type Item = Result<(Token, Span)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.lex();
+        if matches!(item, None | Some(Err(_))) {
+            self.state = State::Done;
+        }
+        item
+    }
+}
+impl FusedIterator for PatternLexer<'_> {}
+
+/// Where the lexer is in the pattern.
+#[derive(Debug, Clone)]
+enum State {
+    /// Nothing was yielded yet.
+    Start,
+    /// Outside of character classes.
+    Pattern,
+    /// Right after the `AnyDirs` of a trailing gitignore `**`, which is
+    /// followed by a `SeqWildcard` with the same span.
+    PendingSeq(Span),
+    /// Inside the character class opened at `start`, before its first entry
+    /// if `first`, after `entries` of them.
+    Class {
+        start: usize,
+        first: bool,
+        entries: usize,
+    },
+    /// After the end of the pattern or an error.
+    Done,
+}
+
+/// Whether the next character in a class is a `-` starting a range, which in
+/// POSIX classes excludes a `-` right before the closing `]` (e.g. `[a-]`).
+fn starts_range(chars: &Peekable<CharIndices>, syntax: Syntax) -> bool {
+    let mut ahead = chars.clone();
+    match ahead.next() {
+        Some((_, '-')) => !(syntax.posix_classes() && matches!(ahead.next(), Some((_, ']')))),
+        _ => false,
+    }
+}
+
+/// Whether the character class starting right after the `[` at the start of
+/// `class` is closed, following the same rules as the lexer (with `escape`
+/// escaping the next character, if any).
+fn class_closed(class: &str, syntax: Syntax, escape: Option<char>) -> bool {
+    let mut chars = class.chars().peekable();
+    chars.next_if(|&c| c == '^' || (c == '!' && syntax.posix_classes()));
+    if syntax.posix_classes() {
+        chars.next_if_eq(&']');
+    }
+    while let Some(c) = chars.next() {
+        match c {
+            ']' => return true,
+            c if Some(c) == escape => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{
+        error::ErrorType,
+        lexer::{PatternLexer, Token},
+        options::MatchOptions,
+        syntax::Syntax,
+    };
+    use alloc::vec::Vec;
+
+    fn tokens(pattern: &str, options: &MatchOptions) -> Vec<Token> {
+        PatternLexer::new_with(pattern, options)
+            .map(|token| token.unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn lexer_tokens() {
+        let options = MatchOptions::new();
+        assert_eq!(
+            tokens("\\*?[^a-c\\]]x", &options),
+            [
+                Token::Literal('*'),
+                Token::SingleWildcard,
+                Token::ClassStart { negated: true },
+                Token::ClassRange('a', 'c'),
+                Token::ClassChar(']'),
+                Token::ClassEnd,
+                Token::Literal('x'),
+            ]
+        );
+        let spans: Vec<_> = PatternLexer::new("é\\*?[a\\]-z]")
+            .map(|token| token.unwrap())
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (Token::Literal('é'), 0..2),
+                (Token::Literal('*'), 2..4),
+                (Token::SingleWildcard, 4..5),
+                (Token::ClassStart { negated: false }, 5..6),
+                (Token::ClassChar('a'), 6..7),
+                (Token::ClassRange(']', 'z'), 7..11),
+                (Token::ClassEnd, 11..12),
+            ]
+        );
+
+        let gitignore = MatchOptions::new().syntax(Syntax::Gitignore);
+        let spans: Vec<_> = PatternLexer::new_with("**/a/**", &gitignore)
+            .map(|token| token.unwrap())
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (Token::AnyDirs, 0..3),
+                (Token::Literal('a'), 3..4),
+                (Token::Literal('/'), 4..5),
+                (Token::AnyDirs, 5..7),
+                (Token::SeqWildcard, 5..7),
+            ]
+        );
+        assert_eq!(
+            tokens("[!]-]a**", &gitignore),
+            [
+                Token::ClassStart { negated: true },
+                Token::ClassChar(']'),
+                Token::ClassChar('-'),
+                Token::ClassEnd,
+                Token::Literal('a'),
+                Token::SeqWildcard,
+            ]
+        );
+    }
+
+    #[test]
+    fn lexer_errors() {
+        let error = |pattern: &str| {
+            let mut lexer = PatternLexer::new(pattern);
+            let error = lexer.find_map(Result::err).unwrap();
+            assert!(lexer.next().is_none());
+            (*error.error_type(), error.position())
+        };
+        assert!(matches!(error("ab[c"), (ErrorType::UnclosedCharClass, 2)));
+        assert!(matches!(error("a\\"), (ErrorType::IllegalEscape, 1)));
+        assert!(matches!(error("a]"), (ErrorType::UnescapedChar(']'), 1)));
+        assert!(matches!(error("[]a]"), (ErrorType::UnescapedChar(']'), 1)));
+        assert!(matches!(
+            error("[a-f-z]"),
+            (ErrorType::UnescapedChar('-'), 4)
+        ));
+        assert!(matches!(
+            error("[z-a]"),
+            (ErrorType::InvalidRangeValues('z', 'a'), 3)
+        ));
+        assert!(matches!(error("[ba-]"), (ErrorType::UnescapedChar(']'), 4)));
+        assert!(matches!(error(""), (ErrorType::EmptyPattern, _)));
+    }
+}
@@ -2,32 +2,255 @@
 //!
 //! See the `goglob` crate instead.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod error;
 pub use crate::error::Result;
 
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "alloc")]
+pub mod arc;
+#[cfg(feature = "parser")]
+pub mod builder;
+#[cfg(feature = "parser")]
+pub mod bytes;
+#[cfg(feature = "alloc")]
 pub mod charcls;
+#[cfg(feature = "alloc")]
+pub mod complete;
+#[cfg(feature = "alloc")]
+pub mod complexity;
+#[cfg(feature = "alloc")]
+pub mod convert;
+#[cfg(feature = "alloc")]
+pub mod coverage;
+#[cfg(feature = "alloc")]
+pub mod dot;
+#[cfg(feature = "alloc")]
+pub mod dump;
+#[cfg(feature = "alloc")]
+pub mod ext;
+pub mod fixed;
+#[cfg(feature = "fs")]
+pub mod fs;
+#[cfg(feature = "alloc")]
+pub mod hash;
+#[cfg(feature = "parser")]
+pub mod host;
+#[cfg(all(feature = "std", feature = "parser"))]
+pub mod ignore;
+#[cfg(feature = "parser")]
+pub mod interner;
+#[cfg(all(feature = "std", feature = "parser"))]
+pub mod lazy;
+pub mod lexer;
+#[cfg(feature = "alloc")]
+pub mod limits;
+#[cfg(feature = "std")]
+pub mod lines;
+#[cfg(feature = "alloc")]
 pub mod literal;
+#[cfg(feature = "alloc")]
+pub mod map;
+pub mod matcher;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "alloc")]
+pub mod minimize;
+pub mod options;
+pub mod outcome;
+#[cfg(feature = "alloc")]
+pub mod partition;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "alloc")]
+pub mod rename;
+#[cfg(feature = "parser")]
+pub mod rulefile;
+#[cfg(feature = "alloc")]
+pub mod rules;
+#[cfg(feature = "alloc")]
+pub mod set;
+#[cfg(feature = "arc-swap")]
+pub mod shared;
+#[cfg(feature = "alloc")]
+pub mod simplify;
+#[cfg(feature = "alloc")]
+pub mod slice;
+#[cfg(feature = "alloc")]
+pub mod specificity;
+#[cfg(feature = "alloc")]
+pub mod stream;
+pub mod syntax;
+#[cfg(feature = "parser")]
+pub mod template;
+#[cfg(feature = "alloc")]
+pub mod witness;
+
+#[cfg(feature = "alloc")]
+mod automaton;
+mod matching;
+#[cfg(feature = "std")]
+mod path;
+#[cfg(feature = "prefilter")]
+mod prefilter;
+mod scan;
+#[cfg(feature = "alloc")]
+mod shape;
 
+#[cfg(feature = "bstr")]
+mod bstr;
+#[cfg(feature = "camino")]
+mod camino;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "defmt")]
+mod defmt;
+#[cfg(feature = "encoding")]
+mod encoding;
 #[cfg(feature = "serde")]
 mod serde;
 
+#[cfg(feature = "alloc")]
 use crate::{
-    charcls::{CharClass as GlobTokenCharClass, CharClassType},
-    error::{Error, ErrorType},
+    charcls::CharClass as GlobTokenCharClass,
+    error::FuelExhausted,
     literal::Literal as GlobTokenLiteral,
+    matching::{Ascii, Haystack, MatchToken, MismatchSink, Steps, Wildcard},
+    options::{CaseFold, MatchOptions},
+    outcome::MismatchReason,
+    shape::Shape,
 };
-use std::{borrow::Cow, result::Result as StdResult};
+#[cfg(feature = "parser")]
+use crate::{
+    charcls::CharClassType,
+    complexity::ComplexityLimits,
+    error::{Error, ErrorType},
+    limits::{LimitedSink, ParserLimits},
+    scan::ScanSink,
+};
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String, vec::Vec};
+#[cfg(feature = "parser")]
+use core::str::FromStr;
+#[cfg(feature = "alloc")]
+use core::{ops::Range, result::Result as StdResult};
 
 /// Shell pattern matching similar to golang's `path.Match`.
 ///
 /// # Further reading
 ///
 /// See the `goglob` crate's documentation for the appropriate syntax.
-#[derive(Debug, Clone, Eq, PartialEq)]
-#[repr(transparent)]
+///
+/// Its alternate debug representation (`{:#?}`) is a readable breakdown of
+/// its tokens, see [`dump()`][Self::dump].
+///
+/// Patterns are equal if they're compiled into the same tokens with the same
+/// options, whatever the text they were compiled from (e.g. `a**` and `a*`).
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
 pub struct GlobPattern {
     tokens: Cow<'static, [GlobToken]>,
+    options: MatchOptions,
+    /// Text the pattern was compiled from, or its tokens rendered back into
+    /// pattern syntax if it was built from them.
+    source: Cow<'static, str>,
+    /// Whether every literal and class is made of ASCII characters alone, so
+    /// that ASCII names can be matched byte by byte.
+    ascii: bool,
+    /// Shape of the pattern, for dedicated matchers of the common ones.
+    shape: Shape,
+}
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for GlobPattern {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            return core::fmt::Display::fmt(&self.dump(), f);
+        }
+        f.debug_struct("GlobPattern")
+            .field("tokens", &self.tokens)
+            .field("options", &self.options)
+            .finish()
+    }
+}
+/// Iterates over the compiled tokens of the pattern, in order (see
+/// [`GlobPattern::into_tokens()`]).
+#[cfg(feature = "alloc")]
+impl IntoIterator for GlobPattern {
+    type Item = GlobToken;
+    type IntoIter = alloc::vec::IntoIter<GlobToken>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_tokens().into_iter()
+    }
+}
+#[cfg(feature = "alloc")]
+impl PartialEq for GlobPattern {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.tokens == other.tokens && self.options == other.options
+    }
+}
+#[cfg(feature = "alloc")]
+impl Eq for GlobPattern {}
+/// Renders the pattern back into pattern syntax, escaping the metacharacters
+/// of its literals and classes, so that the text compiles (with the same
+/// options) to a pattern matching the same names. Metacharacters can't be
+/// escaped if [escapes][crate::options::MatchOptions::escapes] are disabled,
+/// in which case those of literals are written as single-character classes
+/// where possible.
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use goglob_common::GlobPattern;
+///
+/// let pattern = GlobPattern::new("src/\\*[^a\\-z]?.rs").unwrap();
+/// assert_eq!(pattern.to_string(), "src/\\*[^a\\-z]?.rs");
+/// assert_eq!(GlobPattern::new(pattern.to_string()).unwrap(), pattern);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+impl core::fmt::Display for GlobPattern {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use core::fmt::Write;
+
+        let options = &self.options;
+        let escape = options.effective_escapes().then_some(options.escape_char);
+        let posix = options.syntax.posix_classes();
+        for token in self.tokens.iter() {
+            match token {
+                GlobToken::Literal(l) => {
+                    for c in l.as_ref().chars() {
+                        match escape {
+                            _ if !options.is_metachar(c) => f.write_char(c)?,
+                            Some(escape) => write!(f, "{escape}{c}")?,
+                            None if options.char_classes && (c != ']' || posix) => {
+                                write!(f, "[{c}]")?
+                            }
+                            None => f.write_char(c)?,
+                        }
+                    }
+                }
+                GlobToken::CharClass(cc) => cc.fmt_escaped(f, escape, posix)?,
+                GlobToken::SeqWildcard => f.write_char(options.seq_wildcard)?,
+                GlobToken::SingleWildcard => f.write_char(options.single_wildcard)?,
+                GlobToken::AnyDirs => write!(
+                    f,
+                    "{0}{0}{1}",
+                    options.seq_wildcard,
+                    options.effective_separator()
+                )?,
+            }
+        }
+        Ok(())
+    }
 }
+#[cfg(feature = "alloc")]
 impl GlobPattern {
     /// Compile the given `pattern` into tokens at runtime, returning a [`GlobPattern`][Self]
     /// on success or an [error][crate::error::Error] if `pattern` is syntactically invalid.
@@ -36,18 +259,342 @@ impl GlobPattern {
     ///
     /// See the `goglob` crate's documentation for the appropriate syntax, as well as
     /// [goglob::error::Error][crate:error:Error] for possible syntax errors.
+    #[cfg(feature = "parser")]
     #[inline]
     pub fn new<S: AsRef<str>>(pattern: S) -> Result<Self> {
         Self::_new(pattern.as_ref())
     }
+    #[cfg(feature = "parser")]
     fn _new(pattern: &str) -> Result<Self> {
+        Self::compile(
+            pattern,
+            &MatchOptions::new(),
+            &ComplexityLimits::new(),
+            &ParserLimits::new(),
+        )
+    }
+
+    /// Same as [`new()`][Self::new], but parsing and matching `pattern`
+    /// according to `options` instead of the defaults.
+    #[cfg(feature = "parser")]
+    #[inline]
+    pub fn new_with<S: AsRef<str>>(pattern: S, options: &MatchOptions) -> Result<Self> {
+        Self::compile(
+            pattern.as_ref(),
+            options,
+            &ComplexityLimits::new(),
+            &ParserLimits::new(),
+        )
+    }
+    #[cfg(feature = "parser")]
+    pub(crate) fn compile(
+        pattern: &str,
+        options: &MatchOptions,
+        limits: &ComplexityLimits,
+        parser_limits: &ParserLimits,
+    ) -> Result<Self> {
         let mut tokens = Vec::new();
-        crate::scan_patterns(pattern, &mut tokens)?;
+        let scanned = parser_limits.check_len(pattern).and_then(|()| {
+            crate::scan_tokens(pattern, options, limits, parser_limits, &mut tokens)
+        });
+        #[cfg(feature = "metrics")]
+        if let Some(sink) = crate::metrics::sink() {
+            match scanned {
+                Ok(()) => sink.compiled(tokens.len()),
+                Err(_) => sink.compile_failed(),
+            }
+        }
+        scanned?;
+        Ok(Self::from_token_vec(
+            tokens,
+            *options,
+            Some(Cow::Owned(pattern.into())),
+        ))
+    }
+
+    /// Pattern made of already compiled `tokens` (e.g. those of another
+    /// pattern, or generated at build time), matched according to `options`.
+    /// This doesn't need the `parser` feature, so pre-compiled patterns can be
+    /// embedded in builds which never parse any at runtime. Adjacent literals
+    /// and `*`s are merged, as the parser would.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::{literal::Literal, options::MatchOptions, GlobPattern, GlobToken};
+    ///
+    /// let tokens = [
+    ///     GlobToken::SeqWildcard,
+    ///     GlobToken::Literal(Literal::new(".rs".to_string())),
+    /// ];
+    /// let pattern = GlobPattern::from_tokens(tokens, MatchOptions::new());
+    /// assert!(pattern.matches("lib.rs"));
+    /// assert_eq!(pattern, GlobPattern::new("*.rs").unwrap());
+    /// # }
+    /// ```
+    pub fn from_tokens<I>(tokens: I, options: MatchOptions) -> Self
+    where
+        I: IntoIterator<Item = GlobToken>,
+    {
+        Self::normalized(tokens.into_iter().collect(), options)
+    }
+
+    /// Text the pattern was compiled from, for diagnostics or display. That
+    /// of patterns built from tokens (e.g. with [`from_tokens()`][Self::from_tokens]
+    /// or [`segments()`][Self::segments]) is their tokens rendered back into
+    /// pattern syntax, as with [`to_string()`][alloc::string::ToString::to_string].
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let pattern = GlobPattern::new("src/**.rs").unwrap();
+    /// assert_eq!(pattern.as_str(), "src/**.rs");
+    /// assert_eq!(pattern.to_string(), "src/*.rs");
+    /// # }
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Compiled tokens of the pattern, in order.
+    #[inline]
+    pub fn tokens(&self) -> &[GlobToken] {
+        &self.tokens
+    }
+
+    /// Compiled tokens of the pattern, in order, taking ownership of them
+    /// without copying (unless they're `const` data, as with the `glob!()`
+    /// macro), e.g. to rewrite them into another pattern with
+    /// [`from_tokens()`][Self::from_tokens].
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::{options::MatchOptions, GlobPattern, GlobToken};
+    ///
+    /// let pattern = GlobPattern::new("src/*.rs").unwrap();
+    /// let options = *pattern.options();
+    /// let mut tokens = pattern.into_tokens();
+    /// tokens.push(GlobToken::SingleWildcard);
+    /// let pattern = GlobPattern::from_tokens(tokens, options);
+    /// assert!(pattern.matches("src/lib.rsx"));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn into_tokens(self) -> Vec<GlobToken> {
+        self.tokens.into_owned()
+    }
+
+    /// Options the pattern was compiled with.
+    #[inline]
+    pub fn options(&self) -> &MatchOptions {
+        &self.options
+    }
+
+    /// Literal text every matching name must start with, if the pattern starts
+    /// with a literal.
+    pub fn literal_prefix(&self) -> Option<&str> {
+        match self.tokens.first() {
+            Some(GlobToken::Literal(l)) => Some(l.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Literal text every matching name must end with, if the pattern ends
+    /// with a literal.
+    pub fn literal_suffix(&self) -> Option<&str> {
+        match self.tokens.last() {
+            Some(GlobToken::Literal(l)) => Some(l.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Minimum length (in characters) of any name matching the pattern.
+    pub fn min_len(&self) -> usize {
+        self.tokens
+            .iter()
+            .map(|token| match token {
+                GlobToken::Literal(l) => l.as_ref().chars().count(),
+                GlobToken::CharClass(_) | GlobToken::SingleWildcard => 1,
+                GlobToken::SeqWildcard | GlobToken::AnyDirs => 0,
+            })
+            .sum()
+    }
+
+    /// Maximum length (in characters) of any name matching the pattern, or
+    /// `None` if it's unbounded (i.e. the pattern contains a `*`).
+    pub fn max_len(&self) -> Option<usize> {
+        let unbounded = self
+            .tokens
+            .iter()
+            .any(|token| matches!(token, GlobToken::SeqWildcard | GlobToken::AnyDirs));
+        (!unbounded).then(|| self.min_len())
+    }
+
+    /// Whether the pattern matches the empty string, i.e. it's only made of
+    /// `*` and `**/` (the same as a [minimum length][Self::min_len] of zero).
+    /// Validators may want to reject such patterns, as they match every name
+    /// (e.g. `*`, within a single segment).
+    #[inline]
+    pub fn can_match_empty(&self) -> bool {
+        self.min_len() == 0
+    }
 
+    /// Split the pattern into one pattern per `/`-separated path segment (e.g.
+    /// `src/*/*.rs` into `src`, `*` and `*.rs`), each compiled with the same
+    /// options (and thus split on the configured separator instead of `/`).
+    ///
+    /// Leading, trailing or repeated `/` produce empty segments, which only
+    /// match the empty string. A `**/` (see [`Syntax::Gitignore`][crate::syntax::Syntax::Gitignore])
+    /// is a segment of its own, standing for any number of segments.
+    pub fn segments(&self) -> Vec<GlobPattern> {
+        let separator = self.options.effective_separator();
+        let segment = |tokens| Self::from_token_vec(tokens, self.options, None);
+        let mut segments = Vec::new();
+        let mut current = Vec::new();
+        for token in self.tokens.iter() {
+            if let GlobToken::AnyDirs = token {
+                if !current.is_empty() {
+                    segments.push(segment(core::mem::take(&mut current)));
+                }
+                segments.push(segment(alloc::vec![GlobToken::AnyDirs]));
+                continue;
+            }
+            let GlobToken::Literal(l) = token else {
+                current.push(token.clone());
+                continue;
+            };
+            let mut parts = l.as_ref().split(separator);
+            if let Some(first) = parts.next().filter(|part| !part.is_empty()) {
+                current.push(GlobToken::Literal(GlobTokenLiteral::new(first.into())));
+            }
+            for part in parts {
+                segments.push(segment(core::mem::take(&mut current)));
+                if !part.is_empty() {
+                    current.push(GlobToken::Literal(GlobTokenLiteral::new(part.into())));
+                }
+            }
+        }
+        segments.push(segment(current));
+        segments
+    }
+
+    /// Whether each of the [`segments()`][Self::segments] of the pattern can
+    /// match an empty path segment (see [`can_match_empty()`][Self::can_match_empty]),
+    /// e.g. `[false, true, false]` for `src/*/main.rs`, which thus matches
+    /// `src//main.rs`. A `**/` segment can always be empty.
+    pub fn nullable_segments(&self) -> Vec<bool> {
+        self.segments()
+            .iter()
+            .map(GlobPattern::can_match_empty)
+            .collect()
+    }
+
+    /// Join segment patterns (e.g. as returned by [`segments()`][Self::segments])
+    /// back into a path pattern, separating them with `/` (or the configured
+    /// separator), so `src`, `*` and `*.rs` become `src/*/*.rs`.
+    ///
+    /// Tokens are joined as they are, so literals never need escaping (a `*`
+    /// literal in a segment remains one). A segment which is only a `**/`
+    /// already includes its separator. The result uses the options of the
+    /// first segment, and an empty slice gives a pattern only matching the
+    /// empty string.
+    pub fn from_segments(segments: &[GlobPattern]) -> GlobPattern {
+        let options = segments
+            .first()
+            .map_or_else(MatchOptions::new, |segment| segment.options);
+        let separator = options.effective_separator();
+        let mut tokens = Vec::new();
+        let mut after_any_dirs = true;
+        for (i, segment) in segments.iter().enumerate() {
+            if i > 0 && !after_any_dirs {
+                tokens.push(GlobToken::Literal(GlobTokenLiteral::new(separator.into())));
+            }
+            tokens.extend(segment.tokens.iter().cloned());
+            after_any_dirs = matches!(segment.tokens(), [GlobToken::AnyDirs]);
+        }
+        Self::normalized(tokens, options)
+    }
+
+    /// Pattern matching the names this one matches inside the directory
+    /// `dir`, by prepending it as a literal (never interpreted as wildcards),
+    /// so `*.rs` becomes `src/*.rs` for `src`.
+    ///
+    /// A `/` (or the configured separator) is inserted between `dir` and the
+    /// pattern unless either already has one there. An empty `dir` leaves the
+    /// pattern unchanged.
+    pub fn with_prefix_literal<S: AsRef<str>>(&self, dir: S) -> GlobPattern {
+        let dir = dir.as_ref();
+        if dir.is_empty() {
+            return self.clone();
+        }
+        let separator = self.options.effective_separator();
+        let separated = dir.ends_with(separator)
+            || matches!(self.tokens.first(), Some(GlobToken::Literal(l)) if l.as_ref().starts_with(separator));
+        let mut prefix = String::from(dir);
+        if !separated {
+            prefix.push(separator);
+        }
+        let mut tokens = Vec::with_capacity(self.tokens.len() + 1);
+        tokens.push(GlobToken::Literal(GlobTokenLiteral::new(prefix)));
+        tokens.extend(self.tokens.iter().cloned());
+        Self::normalized(tokens, self.options)
+    }
+
+    /// Pattern matching the names this one matches followed by `suffix`, by
+    /// appending it as a literal (never interpreted as wildcards), so `src/*`
+    /// becomes `src/*.rs` for `.rs`. No separator is inserted.
+    pub fn with_suffix_literal<S: AsRef<str>>(&self, suffix: S) -> GlobPattern {
+        let suffix = suffix.as_ref();
+        let mut tokens = self.tokens.to_vec();
+        if !suffix.is_empty() {
+            tokens.push(GlobToken::Literal(GlobTokenLiteral::new(suffix.into())));
+        }
+        Self::normalized(tokens, self.options)
+    }
+
+    /// Build a pattern from generated or joined tokens, merging adjacent
+    /// literals and `*`s so the result is the same as if it were scanned from
+    /// a string.
+    fn normalized(tokens: Vec<GlobToken>, options: MatchOptions) -> Self {
+        let mut normalized: Vec<GlobToken> = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            match (normalized.last_mut(), token) {
+                (Some(GlobToken::SeqWildcard), GlobToken::SeqWildcard) => {}
+                (Some(GlobToken::Literal(last)), GlobToken::Literal(l)) => {
+                    *last =
+                        GlobTokenLiteral::new(alloc::format!("{}{}", last.as_ref(), l.as_ref()));
+                }
+                (_, token) => normalized.push(token),
+            }
+        }
+        Self::from_token_vec(normalized, options, None)
+    }
+    /// Pattern made of `tokens`, compiled from `source` (if known, otherwise
+    /// rendered from the tokens).
+    fn from_token_vec(
+        mut tokens: Vec<GlobToken>,
+        options: MatchOptions,
+        source: Option<Cow<'static, str>>,
+    ) -> Self {
         tokens.shrink_to_fit();
-        Ok(Self {
+        let ascii = tokens.iter().all(|token| match token {
+            GlobToken::Literal(l) => l.is_ascii(),
+            GlobToken::CharClass(cc) => cc.is_ascii(),
+            _ => true,
+        });
+        let shape = Shape::of(&tokens, &options);
+        let mut pattern = Self {
             tokens: Cow::Owned(tokens),
-        })
+            options,
+            source: source.unwrap_or_default(),
+            ascii,
+            shape,
+        };
+        if pattern.source.is_empty() && !pattern.tokens.is_empty() {
+            pattern.source = Cow::Owned(alloc::format!("{pattern}"));
+        }
+        pattern
     }
 
     /// Report whether the `name` matches the compiled shell pattern.
@@ -60,407 +607,885 @@ impl GlobPattern {
         self._matches(name.as_ref())
     }
     fn _matches(&self, name: &str) -> bool {
-        let mut next = name;
-        let mut tokens = self.tokens.iter().peekable();
-        'outer: while let Some(token) = tokens.next() {
-            next = match token.try_matches_next(next) {
-                Ok(Some(next)) => next,
-                Ok(None) => return false,
-                Err(()) => {
-                    // SeqWildcard doesn't implement matches_next. However, it
-                    // can match any number of non-'/' characters (even zero),
-                    // so we must see what matches the remaining tokens up until
-                    // the next SeqWildcard (or the end if no further SeqWildcards
-                    // remain)
-
-                    // If there are no more tokens left, make sure there is no '/'
-                    // in the rest of the string
-                    if tokens.peek().is_none() {
-                        return !next.contains('/');
-                    };
-
-                    // For every remaining position in next until '/', check if
-                    // the remaining tokens until SeqWildcard match.
-                    'star: for (i, c) in next.char_indices() {
-                        let mut tokens_peek = tokens.clone();
-                        let mut next_peek = &next[i..];
-                        let mut fail = false;
-                        let mut finished = true;
-                        'inner: while let Some(token_peek) = tokens_peek.peek() {
-                            next_peek = match token_peek.try_matches_next(next_peek) {
-                                Ok(Some(next_peek)) => next_peek,
-                                Ok(None) => {
-                                    fail = true;
-                                    break 'inner;
-                                }
-                                Err(_) => {
-                                    finished = false;
-                                    break 'inner;
-                                }
-                            };
-                            tokens_peek.next();
-                        }
+        if let Some(matched) = self.shape.matches(&self.tokens, name, &self.options) {
+            // Counted as the fewest steps the token loop could have taken.
+            self.record_match(matched, self.tokens.len());
+            return matched;
+        }
+        if self.ascii && name.is_ascii() {
+            // Same as matching `name`, without decoding characters.
+            return self.match_tokens(Ascii(name.as_bytes()), None);
+        }
+        self.match_tokens(name, None)
+    }
 
-                        if !fail && (!finished || next_peek.is_empty()) {
-                            // Either we correctly matched until the next SeqWildcard,
-                            // or there are no tokens left and the entirety of the
-                            // string is matched. In either case we continue
-                            tokens = tokens_peek;
-                            next = next_peek;
-                            continue 'outer;
-                        }
+    /// Report whether the byte string `name`, which may not be valid UTF-8
+    /// (e.g. a log record), matches the compiled shell pattern, without
+    /// converting it to a `String` first.
+    ///
+    /// Bytes which can't be decoded as UTF-8 are each treated as a single
+    /// character which isn't the separator: they're matched by `?`, `*` and
+    /// negated character classes, but never by literals or other classes.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let pattern = GlobPattern::new("caf?.log").unwrap();
+    /// assert!(pattern.matches_bytes(b"caf\xe9.log"));
+    /// assert!(pattern.matches_bytes("café.log"));
+    /// assert!(!pattern.matches_bytes(b"caf\xe9\xe9.log"));
+    /// assert!(!pattern.matches_bytes(b"caf/.log"));
+    /// # }
+    /// ```
+    pub fn matches_bytes<B: AsRef<[u8]>>(&self, name: B) -> bool {
+        let name = name.as_ref();
+        match core::str::from_utf8(name) {
+            // Same fast paths as text
+            Ok(name) => self._matches(name),
+            Err(_) => self.match_tokens(name, None),
+        }
+    }
+
+    /// Same as [`matches()`][Self::matches], but comparing characters
+    /// according to `fold` instead of the case folding the pattern was
+    /// compiled with, so that the same pattern serves both case-sensitive and
+    /// case-insensitive checks.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::{options::CaseFold, GlobPattern};
+    ///
+    /// let pattern = GlobPattern::new("*.TXT").unwrap();
+    /// assert!(!pattern.matches("notes.txt"));
+    /// assert!(pattern.matches_fold("notes.txt", CaseFold::Ascii));
+    /// assert!(!pattern.matches_fold("notes.txt", CaseFold::Sensitive));
+    /// # }
+    /// ```
+    pub fn matches_fold<S: AsRef<str>>(&self, name: S, fold: CaseFold) -> bool {
+        let options = self.options.case_fold(fold);
+        let mut steps = Steps::unlimited();
+        let matched = matching::match_tokens(
+            self.tokens.iter(),
+            name.as_ref(),
+            &options,
+            &mut (),
+            &mut (),
+            &mut steps,
+        );
+        self.record_match(matched, steps.taken);
+        matched
+    }
 
-                        // Match failed, try from next position.
+    /// Same as [`matches()`][Self::matches], but giving up with
+    /// [`FuelExhausted`] after `max_steps` comparisons of a token against
+    /// the name, to bound the time spent matching untrusted patterns against
+    /// untrusted names. Matching takes at least one step per token of the
+    /// pattern, and more when `*` has to try several positions.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let pattern = GlobPattern::new("*.rs").unwrap();
+    /// let name = "a".repeat(1000);
+    /// assert!(pattern.matches_with_fuel(&name, 100).is_err());
+    /// assert_eq!(pattern.matches_with_fuel("lib.rs", 100), Ok(true));
+    /// # }
+    /// ```
+    pub fn matches_with_fuel<S: AsRef<str>>(
+        &self,
+        name: S,
+        max_steps: usize,
+    ) -> StdResult<bool, FuelExhausted> {
+        let mut steps = Steps::limited(max_steps);
+        let matched = self.match_tokens_counting(name.as_ref(), None, &mut steps);
+        self.record_match(matched, steps.taken);
+        match steps.exhausted() {
+            true => Err(FuelExhausted),
+            false => Ok(matched),
+        }
+    }
 
-                        if c == '/' {
-                            // Found '/', abort
-                            break 'star;
-                        }
-                    }
+    /// Whether the pattern ends with a `/` (or the configured separator), as in
+    /// `build/`, and can thus only match directories (see
+    /// [`matches_entry()`][Self::matches_entry]).
+    pub fn is_dir_only(&self) -> bool {
+        let separator = self.options.effective_separator();
+        matches!(self.tokens.last(), Some(GlobToken::Literal(l)) if l.as_ref().ends_with(separator))
+    }
 
-                    // Exhausted available positions without finding a match.
-                    return false;
-                }
+    /// Report whether the `name` of a directory (if `is_dir`) or of any other
+    /// file matches the pattern, for callers who know what `name` refers to
+    /// without it ending with a `/`.
+    ///
+    /// [Directory-only][Self::is_dir_only] patterns such as `build/` match a
+    /// directory named `build` (with or without a trailing `/`) but nothing
+    /// else, as in `.gitignore`. Other patterns are matched against `name` as
+    /// with [`matches()`][Self::matches].
+    pub fn matches_entry<S: AsRef<str>>(&self, name: S, is_dir: bool) -> bool {
+        let name = name.as_ref();
+        if !self.is_dir_only() {
+            return self._matches(name);
+        }
+        let separator = self.options.effective_separator();
+        match is_dir {
+            false => false,
+            true if name.ends_with(separator) => self._matches(name),
+            true => self._matches(&alloc::format!("{name}{separator}")),
+        }
+    }
+
+    /// Match `name` against the pattern and return the text matched by each
+    /// wildcard and character class (in pattern order), or `None` if it
+    /// doesn't match.
+    ///
+    /// Each `*` captures the shortest text allowing the rest of the pattern to
+    /// match (e.g. `*.*` captures `a` and `b.c` from `a.b.c`).
+    pub fn captures<'a>(&self, name: &'a str) -> Option<Vec<&'a str>> {
+        let mut captures = Vec::new();
+        self.match_tokens(name, Some(&mut captures))
+            .then_some(captures)
+    }
+
+    /// Same as [`captures()`][Self::captures], returning the byte range of
+    /// `name` matched by each wildcard and character class instead of its
+    /// text, e.g. to highlight which parts of a name were matched by literals
+    /// and which by wildcards.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let pattern = GlobPattern::new("src/*.rs").unwrap();
+    /// assert_eq!(pattern.capture_spans("src/lib.rs"), Some(vec![4..7]));
+    /// # }
+    /// ```
+    pub fn capture_spans(&self, name: &str) -> Option<Vec<Range<usize>>> {
+        let captures = self.captures(name)?;
+        // Captures are always subslices of `name`
+        let spans = captures.into_iter().map(|capture| {
+            let start = capture.as_ptr() as usize - name.as_ptr() as usize;
+            start..start + capture.len()
+        });
+        Some(spans.collect())
+    }
+
+    /// Core matching algorithm, optionally recording the text matched by every
+    /// non-literal token into `captures`.
+    fn match_tokens<H: Haystack>(&self, name: H, captures: Option<&mut Vec<H>>) -> bool {
+        let mut steps = Steps::unlimited();
+        let matched = self.match_tokens_counting(name, captures, &mut steps);
+        self.record_match(matched, steps.taken);
+        matched
+    }
+    /// Same as [`match_tokens()`][Self::match_tokens], recording the reasons
+    /// matching failed into `mismatches` instead of any captures.
+    pub(crate) fn match_tokens_detailed<H: Haystack, M: MismatchSink>(
+        &self,
+        name: H,
+        mismatches: &mut M,
+    ) -> bool {
+        let mut steps = Steps::unlimited();
+        let matched = matching::match_tokens(
+            self.tokens.iter(),
+            name,
+            &self.options,
+            &mut (),
+            mismatches,
+            &mut steps,
+        );
+        self.record_match(matched, steps.taken);
+        matched
+    }
+    #[allow(unused_variables)]
+    fn record_match(&self, matched: bool, steps: usize) {
+        #[cfg(feature = "metrics")]
+        if let Some(sink) = crate::metrics::sink() {
+            sink.matched(matched, steps);
+        }
+    }
+    fn match_tokens_counting<H: Haystack>(
+        &self,
+        name: H,
+        captures: Option<&mut Vec<H>>,
+        steps: &mut Steps,
+    ) -> bool {
+        let (tokens, options) = (self.tokens.iter(), &self.options);
+        match captures {
+            Some(captures) => {
+                matching::match_tokens(tokens, name, options, captures, &mut (), steps)
             }
+            None => matching::match_tokens(tokens, name, options, &mut (), &mut (), steps),
         }
-        next.is_empty()
     }
 }
 
+/// Same as [`GlobPattern::new()`], for APIs parsing their values through
+/// [`FromStr`] (e.g. [`str::parse()`], clap or `serde_with::DisplayFromStr`).
+///
+/// ```
+/// use goglob_common::GlobPattern;
+///
+/// let pattern: GlobPattern = "*.rs".parse().unwrap();
+/// assert!(pattern.matches("lib.rs"));
+/// assert!("a[".parse::<GlobPattern>().is_err());
+/// ```
+#[cfg(feature = "parser")]
+impl FromStr for GlobPattern {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(pattern: &str) -> Result<Self> {
+        Self::new(pattern)
+    }
+}
+
+/// Single compiled element of a [`GlobPattern`].
+#[cfg(feature = "alloc")]
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum GlobToken {
+    /// Literal text, with any escapes already resolved (e.g. `ab` or `a\*b`).
     Literal(GlobTokenLiteral),
+    /// Character class (e.g. `[^b-d]`).
     CharClass(GlobTokenCharClass),
+    /// Any sequence of non-`/` characters (`*`).
     SeqWildcard,
+    /// Any single non-`/` character (`?`).
     SingleWildcard,
+    /// Any number of directories, i.e. either nothing or any sequence of
+    /// characters ending with `/` (`**/` in [`Syntax::Gitignore`][crate::syntax::Syntax::Gitignore]).
+    AnyDirs,
 }
-impl GlobToken {
-    fn try_matches_next<'a>(&self, name: &'a str) -> StdResult<Option<&'a str>, ()> {
+#[cfg(feature = "alloc")]
+impl MatchToken for GlobToken {
+    fn try_matches_next<H: Haystack>(
+        &self,
+        name: H,
+        options: &MatchOptions,
+    ) -> StdResult<Option<H>, Wildcard> {
+        let fold = options.case_fold;
+        match self {
+            GlobToken::Literal(l) => Ok(l.matches_next(name, fold)),
+            GlobToken::CharClass(cc) => Ok(name.strip_char(|c| cc.matches_decoded(c, fold))),
+            GlobToken::SingleWildcard => {
+                Ok(name.strip_char(|c| c != Some(options.effective_separator())))
+            }
+            GlobToken::SeqWildcard => Err(Wildcard::Seq),
+            GlobToken::AnyDirs => Err(Wildcard::AnyDirs),
+        }
+    }
+
+    fn mismatch<H: Haystack>(&self, name: H, options: &MatchOptions) -> (MismatchReason, H) {
         match self {
-            GlobToken::Literal(l) => Ok(l.matches_next(name)),
-            GlobToken::CharClass(cc) => Ok(cc.matches_next(name)),
-            GlobToken::SingleWildcard => Ok(name.strip_prefix(|c| c != '/')),
-            GlobToken::SeqWildcard => Err(()),
+            GlobToken::Literal(l) => {
+                matching::literal_mismatch(name, l.as_ref().as_bytes(), options.case_fold)
+            }
+            GlobToken::CharClass(_) => matching::char_mismatch(name, MismatchReason::CharClass),
+            GlobToken::SingleWildcard => matching::char_mismatch(name, MismatchReason::Separator),
+            GlobToken::SeqWildcard | GlobToken::AnyDirs => {
+                unreachable!("wildcards are handled by match_tokens()")
+            }
         }
     }
+
+    fn is_capturing(&self) -> bool {
+        !matches!(self, GlobToken::Literal(_))
+    }
 }
 
 /// Internal workspace-only function employed by `goglob-proc-macro`.
 ///
 /// The procedural macro will insert calls to this function in the end-user's project,
 /// so it must be declared public.
-pub const fn glob_from_tokens(tokens: &'static [GlobToken]) -> GlobPattern {
+#[cfg(feature = "alloc")]
+pub const fn glob_from_tokens(tokens: &'static [GlobToken], source: &'static str) -> GlobPattern {
+    glob_from_tokens_with(tokens, source, MatchOptions::new())
+}
+
+/// Internal workspace-only function employed by `goglob-proc-macro` when
+/// options are given.
+///
+/// The procedural macro will insert calls to this function in the end-user's project,
+/// so it must be declared public.
+#[cfg(feature = "alloc")]
+pub const fn glob_from_tokens_with(
+    tokens: &'static [GlobToken],
+    source: &'static str,
+    options: MatchOptions,
+) -> GlobPattern {
+    let mut ascii = true;
+    let mut i = 0;
+    while i < tokens.len() {
+        ascii &= match &tokens[i] {
+            GlobToken::Literal(l) => l.is_ascii(),
+            GlobToken::CharClass(cc) => cc.is_static_ascii(),
+            _ => true,
+        };
+        i += 1;
+    }
     GlobPattern {
         tokens: Cow::Borrowed(tokens),
+        options,
+        source: Cow::Borrowed(source),
+        ascii,
+        shape: Shape::of(tokens, &options),
     }
 }
 
+/// Internal workspace-only function employed by `goglob-proc-macro` to
+/// generate matching functions: the separator wildcards can't match under
+/// `options`.
+#[cfg(feature = "alloc")]
+pub fn effective_separator(options: &MatchOptions) -> char {
+    options.effective_separator()
+}
+
 /// Internal workspace-only function used locally and in `goglob-proc-macro`.
+#[cfg(feature = "parser")]
 pub fn scan_patterns(pattern: &str, tokens: &mut Vec<GlobToken>) -> Result<()> {
-    if pattern.is_empty() {
-        return Err(Error::empty_pattern());
-    }
-
-    let mut pattern_iter = pattern.char_indices().peekable();
-    while pattern_iter.peek().is_some() {
-        let mut stars = false;
-
-        // Match star wildcards (e.g. '*ab?cd[e-z]*')
-        //                             ^          ^
-        while let Some((_, '*')) = pattern_iter.peek() {
-            stars = true;
-            pattern_iter.next();
-        }
-        if stars {
-            tokens.push(GlobToken::SeqWildcard)
-        }
-
-        // Match literals (e.g. '*ab?cd[e-z]*')
-        //                        ^^ ^^
-        let mut literal_string = String::new();
-        'literal: while let Some((i, c)) = pattern_iter.peek() {
-            let (i, c) = (*i, *c);
-            let c = match c {
-                ']' =>
-                // we are not in a character class (i.e. '[' was never passed)
-                // therefore ']' is illegal and should be explicitly escaped
-                {
-                    return Err(Error::new(ErrorType::UnescapedChar(']'), i))
-                }
-                '[' | '?' | '*' =>
-                // '[' opens a character class
-                // '?' is a single-character wildcard
-                // '*' is a multi-character wildcard
-                // any of these signal an end to the current literal
-                {
-                    break 'literal
-                }
-                '\\' => {
-                    pattern_iter.next();
-
-                    // '\' escapes the next character, whichever it may be.
-                    // If there is no "next character", then it's considered
-                    // an illegal escape
-                    let (_, escaped_char) = pattern_iter
-                        .next()
-                        .ok_or_else(|| Error::new(ErrorType::IllegalEscape, i))?;
-                    escaped_char
-                }
-                c => {
-                    pattern_iter.next();
-                    c
-                }
-            };
+    scan_patterns_with(pattern, &MatchOptions::new(), tokens)
+}
 
-            literal_string.push(c);
-        }
-        if !literal_string.is_empty() {
-            tokens.push(GlobToken::Literal(GlobTokenLiteral::new(literal_string)))
-        }
+/// Internal workspace-only function employed by `goglob-proc-macro` when
+/// options are given.
+#[cfg(feature = "parser")]
+pub fn scan_patterns_with(
+    pattern: &str,
+    options: &MatchOptions,
+    tokens: &mut Vec<GlobToken>,
+) -> Result<()> {
+    scan_tokens(
+        pattern,
+        options,
+        &ComplexityLimits::new(),
+        &ParserLimits::new(),
+        tokens,
+    )
+}
+#[cfg(feature = "parser")]
+fn scan_tokens(
+    pattern: &str,
+    options: &MatchOptions,
+    limits: &ComplexityLimits,
+    parser_limits: &ParserLimits,
+    tokens: &mut Vec<GlobToken>,
+) -> Result<()> {
+    let sink = TokenSink {
+        tokens,
+        limits,
+        literal: String::new(),
+        literal_pos: 0,
+        negated: false,
+        class: Vec::new(),
+        class_pos: 0,
+        class_entries: 0,
+        stars: 0,
+    };
+    scan::scan(pattern, options, &mut LimitedSink::new(sink, parser_limits))
+}
 
-        // Match question-mark wildcards (e.g. '*ab?cd[e-z]*')
-        //                                         ^
-        while let Some((_, '?')) = pattern_iter.peek() {
-            tokens.push(GlobToken::SingleWildcard);
-            pattern_iter.next();
-        }
+/// Collects scanned elements into [`GlobToken`]s, failing as soon as the
+/// pattern exceeds `limits`.
+#[cfg(feature = "parser")]
+struct TokenSink<'t> {
+    tokens: &'t mut Vec<GlobToken>,
+    limits: &'t ComplexityLimits,
+    literal: String,
+    literal_pos: usize,
+    negated: bool,
+    class: Vec<CharClassType>,
+    class_pos: usize,
+    class_entries: usize,
+    stars: usize,
+}
+#[cfg(feature = "parser")]
+impl TokenSink<'_> {
+    fn push_token(&mut self, token: GlobToken, pos: usize) -> Result<()> {
+        ComplexityLimits::check(self.limits.max_tokens, self.tokens.len() + 1, pos)?;
+        self.tokens.push(token);
+        Ok(())
+    }
 
-        // Match character class (e.g. '*ab?cd[e-z]*')
-        //                                    ^^^^^
-        if let Some((i, '[')) = pattern_iter.peek() {
-            let mut types: Vec<CharClassType> = Vec::new();
-            let mut negated = false;
-            let mut closed = false;
-            let i = *i;
-            let start_i = i;
-            let mut closed_i = usize::MAX;
+    fn push_star(&mut self, token: GlobToken, pos: usize) -> Result<()> {
+        self.stars += 1;
+        ComplexityLimits::check(self.limits.max_stars, self.stars, pos)?;
+        self.push_token(token, pos)
+    }
 
-            pattern_iter.next();
+    fn push_class_entry(&mut self, entry: CharClassType, pos: usize) -> Result<()> {
+        self.class_entries += 1;
+        ComplexityLimits::check(self.limits.max_class_entries, self.class_entries, pos)?;
+        self.class.push(entry);
+        Ok(())
+    }
+}
+#[cfg(feature = "parser")]
+impl ScanSink for TokenSink<'_> {
+    fn seq_wildcard(&mut self, pos: usize) -> Result<()> {
+        self.push_star(GlobToken::SeqWildcard, pos)
+    }
 
-            // Match negation in character class (e.g. '[^A-F]')
-            //                                           ^
-            if let Some((_, '^')) = pattern_iter.peek() {
-                pattern_iter.next();
-                negated = true;
-            }
+    fn any_dirs(&mut self, pos: usize) -> Result<()> {
+        self.push_star(GlobToken::AnyDirs, pos)
+    }
 
-            let mut start_range = false;
-            let mut in_range: Option<char> = None;
-            'char_cls: while let Some((i, c)) = pattern_iter.next() {
-                debug_assert!(!start_range || c == '-');
-                let c = match c {
-                    ']' => {
-                        // Close the character range
-                        closed = true;
-                        closed_i = i;
-                        break 'char_cls;
-                    }
-                    '^' =>
-                    // The character class was already started or negated.
-                    // Either way, a '^' here must be interpreted as a
-                    // character as-is (at least according to go's impl).
-                    {
-                        '^'
-                    }
-                    '-' if !start_range =>
-                    // Illegal uses of '-':
-                    //
-                    // * As the first character in the class (e.g. [-a][^-z])
-                    //                                              ^    ^
-                    // * After another '-' (e.g. [a--f])
-                    //                              ^
-                    // * Immediately after a character range (e.g. [a-f-z])
-                    //                                                 ^
-                    // If a literal '-' is desired, escape it with a '\' beforehand
-                    // (e.g. [a-f\-z][\-a][^\-z])
-                    //           ^^   ^^    ^^
-                    {
-                        return Err(Error::new(ErrorType::UnescapedChar('-'), i))
-                    }
-                    '-' => {
-                        // Character range (e.g. [0-9abcdefA-F]
-                        //                        ^^^      ^^^
-                        start_range = false;
-                        continue 'char_cls;
-                    }
-                    '\\' => {
-                        // '\' escapes the next character, whichever it may be.
-                        // If there is no "next character", then it's considered
-                        // an illegal escape
-                        let (_, escaped_char) = pattern_iter
-                            .next()
-                            .ok_or_else(|| Error::new(ErrorType::IllegalEscape, i))?;
-                        escaped_char
-                    }
-                    c => c,
-                };
-                if let Some(start) = in_range {
-                    let end = c;
-                    let range = (start..=end)
-                        .try_into()
-                        .map_err(|_| Error::new(ErrorType::InvalidRangeValues(start, end), i))?;
-                    types.push(range);
-                    in_range = None
-                } else if let Some((_, '-')) = pattern_iter.peek() {
-                    in_range = Some(c);
-                    start_range = true
-                } else {
-                    types.push(c.into())
-                }
-            }
+    fn single_wildcard(&mut self, pos: usize) -> Result<()> {
+        self.push_token(GlobToken::SingleWildcard, pos)
+    }
 
-            // A character class must be closed with a corresponding ']'.
-            if !closed {
-                return Err(Error::new(ErrorType::UnclosedCharClass, start_i));
-            }
+    fn literal_char(&mut self, c: char, pos: usize) -> Result<()> {
+        if self.literal.is_empty() {
+            self.literal_pos = pos;
+        }
+        self.literal.push(c);
+        Ok(())
+    }
 
-            // A character class must not be empty (e.g. []abc] or [^]abc])
-            //                                            ^          ^
-            // For the character class to include a ']' char it must be
-            // explicitly escaped (e.g. [\]abc] or [^\]abc].
-            //                           ^^          ^^
-            if types.is_empty() {
-                return Err(Error::new(ErrorType::UnescapedChar(']'), closed_i));
-            }
+    fn end_literal(&mut self) -> Result<()> {
+        let literal = core::mem::take(&mut self.literal);
+        let token = GlobToken::Literal(GlobTokenLiteral::new(literal));
+        self.push_token(token, self.literal_pos)
+    }
 
-            tokens.push(GlobToken::CharClass(GlobTokenCharClass::new(
-                negated, types,
-            )));
-        }
+    fn start_class(&mut self, negated: bool, pos: usize) -> Result<()> {
+        self.negated = negated;
+        self.class_pos = pos;
+        Ok(())
     }
 
-    Ok(())
+    fn class_char(&mut self, c: char, pos: usize) -> Result<()> {
+        self.push_class_entry(c.into(), pos)
+    }
+
+    fn class_range(&mut self, start: char, end: char, pos: usize) -> Result<()> {
+        let range = (start..=end)
+            .try_into()
+            .map_err(|_| Error::new(ErrorType::InvalidRangeValues(start, end), pos))?;
+        self.push_class_entry(range, pos)
+    }
+
+    fn end_class(&mut self, _: usize) -> Result<()> {
+        let class = core::mem::take(&mut self.class);
+        let token = GlobToken::CharClass(GlobTokenCharClass::new(self.negated, class));
+        self.push_token(token, self.class_pos)
+    }
 }
 
-#[cfg(test)]
-//noinspection DuplicatedCode
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
-    mod aux {
-        use crate::{GlobPattern, Result as GlobPatternResult};
-        use std::fmt::{Display, Formatter};
-
-        #[derive(Clone)]
-        pub struct MatchTest {
-            pattern: String,
-            name: String,
-            expect_match: Result<bool, ()>,
-        }
-        impl MatchTest {
-            const fn _new(pattern: String, name: String, expect_match: Result<bool, ()>) -> Self {
-                Self {
-                    pattern,
-                    name,
-                    expect_match,
+    #[cfg(feature = "parser")]
+    use crate::{
+        builder::GlobPatternBuilder,
+        error::{Error, FuelExhausted},
+        options::CaseFold,
+        syntax::Syntax,
+    };
+    use crate::{literal::Literal, options::MatchOptions, GlobPattern, GlobToken};
+    #[cfg(feature = "parser")]
+    use goglob_testutil::{assert_conformance, ConformanceMatcher};
+
+    #[test]
+    fn glob_pattern_from_tokens_without_parser() {
+        let tokens = [
+            GlobToken::Literal(Literal::new("src/".into())),
+            GlobToken::SeqWildcard,
+            GlobToken::Literal(Literal::new(".rs".into())),
+        ];
+        let pattern = GlobPattern::from_tokens(tokens, MatchOptions::new());
+        assert_eq!(pattern.as_str(), "src/*.rs");
+        assert!(pattern.matches("src/lib.rs"));
+        assert!(!pattern.matches("src/a/lib.rs"));
+        assert!(!pattern.matches("lib.rs"));
+    }
+
+    #[cfg(feature = "parser")]
+    impl ConformanceMatcher for GlobPattern {
+        type Error = Error;
+
+        fn compile(pattern: &str) -> Result<Self, Error> {
+            GlobPattern::new(pattern)
+        }
+
+        fn matches(&self, name: &str) -> bool {
+            GlobPattern::matches(self, name)
+        }
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_introspection() {
+        let pattern = GlobPattern::new("ab*c?[d-f]\\*").unwrap();
+        assert_eq!(pattern.tokens().len(), 6);
+        assert_eq!(pattern.literal_prefix(), Some("ab"));
+        assert_eq!(pattern.literal_suffix(), Some("*"));
+        assert_eq!(pattern.min_len(), 6);
+        assert_eq!(pattern.max_len(), None);
+
+        let pattern = GlobPattern::new("[a-c]☺?").unwrap();
+        assert_eq!(pattern.literal_prefix(), None);
+        assert_eq!(pattern.literal_suffix(), None);
+        assert_eq!(pattern.min_len(), 3);
+        assert_eq!(pattern.max_len(), Some(3));
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_ascii_fast_path() {
+        let options = [
+            MatchOptions::new(),
+            MatchOptions::new().case_fold(CaseFold::Ascii),
+            MatchOptions::new().case_fold(CaseFold::Unicode),
+            MatchOptions::new().dotfiles(false),
+            MatchOptions::new().syntax(Syntax::Gitignore),
+        ];
+        let patterns = [
+            "*.RS",
+            "src/*/[a-c]?.rs",
+            "[^A-Z]*",
+            "**/.*",
+            "a*b*c",
+            "K?",
+            "??",
+        ];
+        let names = [
+            "lib.rs",
+            "src/x/B1.RS",
+            "src/.x/.y",
+            "abc",
+            "kk",
+            "\u{212a}x",
+            "é",
+        ];
+        for options in options {
+            for pattern in patterns {
+                let pattern = GlobPattern::new_with(pattern, &options).unwrap();
+                assert!(pattern.ascii);
+                for name in names {
+                    // Whether or not it's matched byte by byte.
+                    let decoded = pattern.match_tokens(name, None);
+                    assert_eq!(pattern.matches(name), decoded, "{pattern:?} {name}");
                 }
             }
+        }
+        assert!(!GlobPattern::new("é*").unwrap().ascii);
+        assert!(!GlobPattern::new("[a-é]").unwrap().ascii);
+        assert!(GlobPattern::new("[^é]").unwrap().matches("a"));
 
-            pub fn display(&self) -> TestDisplay {
-                let clone = self.clone();
-                TestDisplay { test: clone }
-            }
+        static TOKENS: &[crate::GlobToken] = &[
+            crate::GlobToken::SeqWildcard,
+            crate::GlobToken::Literal(crate::literal::from_static(".rs")),
+        ];
+        let pattern = crate::glob_from_tokens(TOKENS, "*.rs");
+        assert!(pattern.ascii);
+        assert_eq!(pattern.as_str(), "*.rs");
+        assert!(pattern.matches("lib.rs"));
+    }
 
-            pub fn test(&self) -> GlobPatternResult<bool> {
-                GlobPattern::new(self.pattern.clone()).map(|p| p.matches(self.name.clone()))
-            }
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_from_tokens() {
+        let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+        for p in ["*.rs", "a[b-d]?*", "x\\*y", "*a*b*"] {
+            let pattern = GlobPattern::new_with(p, &options).unwrap();
+            let rebuilt = GlobPattern::from_tokens(pattern.tokens().to_vec(), options);
+            assert_eq!(rebuilt, pattern, "{p}");
+        }
+        let split = [
+            crate::GlobToken::Literal(crate::literal::Literal::new("a".into())),
+            crate::GlobToken::Literal(crate::literal::Literal::new("b".into())),
+            crate::GlobToken::SeqWildcard,
+            crate::GlobToken::SeqWildcard,
+        ];
+        let pattern = GlobPattern::from_tokens(split, MatchOptions::new());
+        assert_eq!(pattern, GlobPattern::new("ab*").unwrap());
+    }
 
-            pub fn succeed(self, result: GlobPatternResult<bool>) -> bool {
-                result.map_err(|_| ()) == self.expect_match
-            }
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_can_match_empty() {
+        for (p, expected) in [
+            ("*", true),
+            ("**", true),
+            ("?", false),
+            ("[a-c]", false),
+            ("*a*", false),
+            ("*/", false),
+        ] {
+            let pattern = GlobPattern::new(p).unwrap();
+            assert_eq!(pattern.can_match_empty(), expected, "{p}");
+            assert_eq!(pattern.matches(""), expected, "{p}");
         }
-        #[inline]
-        pub fn make_test<S1: Into<String>, S2: Into<String>>(
-            pattern: S1,
-            name: S2,
-            expect_match: Result<bool, ()>,
-        ) -> MatchTest {
-            MatchTest::_new(pattern.into(), name.into(), expect_match)
+        let pattern = GlobPattern::builder()
+            .syntax(Syntax::Gitignore)
+            .build("**/*")
+            .unwrap();
+        assert!(pattern.can_match_empty());
+        assert!(pattern.matches(""));
+
+        let nullable = |p: &str| GlobPattern::new(p).unwrap().nullable_segments();
+        assert_eq!(nullable("src/*/main.rs"), vec![false, true, false]);
+        assert_eq!(nullable("a//b"), vec![false, true, false]);
+        assert_eq!(nullable("*.rs"), vec![false]);
+        assert_eq!(nullable("*"), vec![true]);
+        let pattern = GlobPattern::builder()
+            .syntax(Syntax::Gitignore)
+            .build("a/**/b")
+            .unwrap();
+        assert_eq!(pattern.nullable_segments(), vec![false, true, false]);
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_segments() {
+        let segments = |p: &str| {
+            GlobPattern::new(p)
+                .unwrap()
+                .segments()
+                .into_iter()
+                .map(|s| s.tokens().len())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(segments("abc"), vec![1]);
+        assert_eq!(segments("src/*/*.rs"), vec![1, 1, 2]);
+        assert_eq!(segments("/usr/lib*/"), vec![0, 1, 2, 0]);
+        assert_eq!(segments("a//b"), vec![1, 0, 1]);
+
+        let segments = GlobPattern::new("a*/b?c/[d-f]").unwrap().segments();
+        assert!(segments[0].matches("abc"));
+        assert!(segments[1].matches("bxc"));
+        assert!(segments[2].matches("e"));
+        assert!(!segments[0].matches("a/"));
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_from_segments() {
+        let roundtrip = |p: &str| {
+            let pattern = GlobPattern::new(p).unwrap();
+            assert_eq!(GlobPattern::from_segments(&pattern.segments()), pattern);
+        };
+        for pattern in ["abc", "src/*/*.rs", "/usr/lib*/", "a//b", "a\\*/[/]"] {
+            roundtrip(pattern);
         }
 
-        pub struct TestDisplay {
-            test: MatchTest,
+        let segments = ["src", "*", "*.rs"].map(|s| GlobPattern::new(s).unwrap());
+        let joined = GlobPattern::from_segments(&segments);
+        assert_eq!(joined, GlobPattern::new("src/*/*.rs").unwrap());
+        assert!(joined.matches("src/a/b.rs"));
+        assert_eq!(GlobPattern::from_segments(&[]).tokens(), []);
+
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        for pattern in ["a/**/b", "**/b", "a/**"] {
+            let pattern = gitignore.build(pattern).unwrap();
+            assert_eq!(GlobPattern::from_segments(&pattern.segments()), pattern);
         }
-        impl Display for TestDisplay {
-            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-                write!(
-                    f,
-                    "({}, {}) expected {:?}",
-                    self.test.pattern, self.test.name, self.test.expect_match,
-                )
-            }
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_with_literals() {
+        let pattern = GlobPattern::new("*.rs").unwrap();
+        let rebased = pattern.with_prefix_literal("my[src]");
+        assert_eq!(rebased, GlobPattern::new("my\\[src\\]/*.rs").unwrap());
+        assert!(rebased.matches("my[src]/lib.rs"));
+        assert_eq!(
+            pattern.with_prefix_literal("src/"),
+            GlobPattern::new("src/*.rs").unwrap()
+        );
+        assert_eq!(pattern.with_prefix_literal(""), pattern);
+        let absolute = GlobPattern::new("/a/*").unwrap();
+        assert_eq!(
+            absolute.with_prefix_literal("root"),
+            GlobPattern::new("root/a/*").unwrap()
+        );
+        let windows = GlobPattern::new_with("*", &MatchOptions::new().separator('\\')).unwrap();
+        assert!(windows.with_prefix_literal("C:").matches("C:\\x"));
+
+        let pattern = GlobPattern::new("src/*").unwrap();
+        assert_eq!(
+            pattern.with_suffix_literal(".r?"),
+            GlobPattern::new("src/*.r\\?").unwrap()
+        );
+        assert_eq!(
+            GlobPattern::new("a").unwrap().with_suffix_literal("b"),
+            GlobPattern::new("ab").unwrap()
+        );
+        assert_eq!(pattern.with_suffix_literal(""), pattern);
+
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        let pattern = gitignore
+            .build("**/*.rs")
+            .unwrap()
+            .with_prefix_literal("src");
+        assert_eq!(pattern, gitignore.build("src/**/*.rs").unwrap());
+        assert!(pattern.matches("src/a/b/lib.rs"));
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_matches_entry() {
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        let pattern = gitignore.build("build/").unwrap();
+        assert!(pattern.is_dir_only());
+        assert!(pattern.matches_entry("build", true));
+        assert!(pattern.matches_entry("build/", true));
+        assert!(!pattern.matches_entry("build", false));
+        assert!(!pattern.matches("build"));
+
+        let pattern = gitignore.build("**/target*/").unwrap();
+        assert!(pattern.matches_entry("a/target-x", true));
+        assert!(!pattern.matches_entry("a/target-x", false));
+
+        let pattern = GlobPattern::new("*.rs").unwrap();
+        assert!(!pattern.is_dir_only());
+        assert!(pattern.matches_entry("lib.rs", false));
+        assert!(pattern.matches_entry("lib.rs", true));
+        assert!(!GlobPattern::new("a/b").unwrap().is_dir_only());
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_matches_fold() {
+        let pattern = GlobPattern::new("straße/*.[a-c]").unwrap();
+        assert!(!pattern.matches("STRASSE/x.B"));
+        assert!(!pattern.matches_fold("STRAßE/x.B", CaseFold::Sensitive));
+        assert!(pattern.matches_fold("STRAßE/x.B", CaseFold::Ascii));
+        assert!(!pattern.matches_fold("STRAßE/X.É", CaseFold::Ascii));
+        assert!(pattern.matches_fold("straße/x.b", CaseFold::Sensitive));
+
+        let options = MatchOptions::new().case_fold(CaseFold::Unicode);
+        let pattern = GlobPattern::new_with("ÄB*", &options).unwrap();
+        #[cfg(feature = "unicode")]
+        assert!(pattern.matches("äbc"));
+        assert!(!pattern.matches_fold("äbc", CaseFold::Sensitive));
+        assert!(!pattern.matches_fold("äbc", CaseFold::Ascii));
+        assert!(pattern.matches_fold("Äbc", CaseFold::Ascii));
+        assert_eq!(pattern.options().case_fold, CaseFold::Unicode);
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_matches_with_fuel() {
+        let pattern = GlobPattern::new("abc").unwrap();
+        assert_eq!(pattern.matches_with_fuel("abc", 1), Ok(true));
+        assert_eq!(pattern.matches_with_fuel("abd", 1), Ok(false));
+        assert_eq!(pattern.matches_with_fuel("abc", 0), Err(FuelExhausted));
+
+        let pattern = GlobPattern::new("*ab").unwrap();
+        let name = "a".repeat(100);
+        assert_eq!(pattern.matches_with_fuel(&name, 50), Err(FuelExhausted));
+        assert_eq!(pattern.matches_with_fuel(&name, 1000), Ok(false));
+
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        let pattern = gitignore.build("**/x/**/y").unwrap();
+        let name = "x/".repeat(50);
+        assert_eq!(pattern.matches_with_fuel(&name, 100), Err(FuelExhausted));
+        assert_eq!(pattern.matches_with_fuel(name + "y", 1000), Ok(true));
+
+        for vector in goglob_testutil::GO_MATCH_VECTORS {
+            let (Some(expected), Ok(pattern)) = (vector.expected, GlobPattern::new(vector.pattern))
+            else {
+                continue;
+            };
+            assert_eq!(pattern.matches_with_fuel(vector.name, 1000), Ok(expected));
         }
     }
 
-    use aux::*;
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_display() {
+        let gitignore = MatchOptions::new().syntax(Syntax::Gitignore);
+        let no_escapes = MatchOptions::new().escapes(false);
+        let sql = MatchOptions::new()
+            .seq_wildcard('%')
+            .single_wildcard('_')
+            .escape_char('!');
+        for (pattern, options, displayed) in [
+            ("a*b?c", MatchOptions::new(), "a*b?c"),
+            (
+                "\\a\\*\\[\\]\\?\\\\",
+                MatchOptions::new(),
+                "a\\*\\[\\]\\?\\\\",
+            ),
+            ("[^a-c\\-\\]x]", MatchOptions::new(), "[^a-c\\-\\]x]"),
+            ("[\\^]", MatchOptions::new(), "[\\^]"),
+            ("**/a/**", gitignore, "**/a/**/*"),
+            ("[!!]\\**[]!]", gitignore, "[^!]\\**[\\]!]"),
+            ("[*][?]a\\", no_escapes, "[*][?]a\\"),
+            ("%_!%*[!!]", sql, "%_!%*[!!]"),
+        ] {
+            let compiled = GlobPattern::new_with(pattern, &options).unwrap();
+            assert_eq!(compiled.to_string(), displayed, "{pattern}");
+            let recompiled = GlobPattern::new_with(compiled.to_string(), &options).unwrap();
+            assert_eq!(recompiled.tokens(), compiled.tokens(), "{pattern}");
+        }
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_into_tokens() {
+        let pattern = GlobPattern::new("a*[bc]").unwrap();
+        let (tokens, ptr) = (pattern.tokens().to_vec(), pattern.tokens().as_ptr());
+        // Clones have their own tokens
+        let owned = pattern.clone().into_tokens();
+        assert_eq!(owned, tokens);
+        assert_ne!(owned.as_ptr(), ptr);
+        assert_eq!(pattern.clone().into_iter().collect::<Vec<_>>(), tokens);
+        let owned = pattern.into_tokens();
+        assert_eq!(owned.as_ptr(), ptr);
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_as_str() {
+        let pattern = GlobPattern::new("src/**/[a-c]*.rs").unwrap();
+        assert_eq!(pattern.as_str(), "src/**/[a-c]*.rs");
+        let segments = pattern.segments();
+        let segments: Vec<_> = segments.iter().map(GlobPattern::as_str).collect();
+        assert_eq!(segments, ["src", "*", "[a-c]*.rs"]);
+
+        let collapsed = GlobPattern::new("a**").unwrap();
+        assert_eq!(collapsed.as_str(), "a**");
+        assert_eq!(collapsed, GlobPattern::new("a*").unwrap());
+        let built = GlobPattern::from_tokens(collapsed.tokens().to_vec(), MatchOptions::new());
+        assert_eq!(built.as_str(), "a*");
+    }
 
+    #[cfg(feature = "parser")]
+    #[test]
+    fn glob_pattern_captures() {
+        fn captures<'a>(p: &str, name: &'a str) -> Option<Vec<&'a str>> {
+            GlobPattern::new(p).unwrap().captures(name)
+        }
+        assert_eq!(captures("*.jpeg", "cat.jpeg"), Some(vec!["cat"]));
+        assert_eq!(captures("*.*", "a.b.c"), Some(vec!["a", "b.c"]));
+        assert_eq!(captures("a?[b-d]*", "axc"), Some(vec!["x", "c", ""]));
+        assert_eq!(
+            captures("*/*b?", "dir/aabbx"),
+            Some(vec!["dir", "aab", "x"])
+        );
+        assert_eq!(captures("abc", "abc"), Some(vec![]));
+        assert_eq!(captures("*.jpeg", "dir/cat.jpeg"), None);
+        assert_eq!(captures("a*", "b"), None);
+
+        let pattern = GlobPattern::new("a?[b-d]*").unwrap();
+        assert_eq!(pattern.capture_spans("aéc"), Some(vec![1..3, 3..4, 4..4]));
+        assert_eq!(pattern.capture_spans("b"), None);
+    }
+
+    #[cfg(feature = "parser")]
     #[test]
     fn glob_pattern_go_match_test() {
-        let tests = [
-            make_test("abc", "abc", Ok(true)),
-            make_test("*", "abc", Ok(true)),
-            make_test("*c", "abc", Ok(true)),
-            make_test("a*", "a", Ok(true)),
-            make_test("a*", "abc", Ok(true)),
-            make_test("a*", "ab/c", Ok(false)),
-            make_test("a*/b", "abc/b", Ok(true)),
-            make_test("a*/b", "a/c/b", Ok(false)),
-            make_test("a*b*c*d*e*/f", "axbxcxdxe/f", Ok(true)),
-            make_test("a*b*c*d*e*/f", "axbxcxdxexxx/f", Ok(true)),
-            make_test("a*b*c*d*e*/f", "axbxcxdxe/xxx/f", Ok(false)),
-            make_test("a*b*c*d*e*/f", "axbxcxdxexxx/fff", Ok(false)),
-            make_test("a*b?c*x", "abxbbxdbxebxczzx", Ok(true)),
-            make_test("a*b?c*x", "abxbbxdbxebxczzy", Ok(false)),
-            make_test("ab[c]", "abc", Ok(true)),
-            make_test("ab[b-d]", "abc", Ok(true)),
-            make_test("ab[e-g]", "abc", Ok(false)),
-            make_test("ab[^c]", "abc", Ok(false)),
-            make_test("ab[^b-d]", "abc", Ok(false)),
-            make_test("ab[^e-g]", "abc", Ok(true)),
-            make_test("a\\*b", "a*b", Ok(true)),
-            make_test("a\\*b", "ab", Ok(false)),
-            make_test("a?b", "a☺b", Ok(true)),
-            make_test("a[^a]b", "a☺b", Ok(true)),
-            make_test("a???b", "a☺b", Ok(false)),
-            make_test("a[^a][^a][^a]b", "a☺b", Ok(false)),
-            make_test("[a-ζ]*", "α", Ok(true)),
-            make_test("*[a-ζ]", "A", Ok(false)),
-            make_test("a?b", "a/b", Ok(false)),
-            make_test("a*b", "a/b", Ok(false)),
-            make_test("[\\]a]", "]", Ok(true)),
-            make_test("[\\-]", "-", Ok(true)),
-            make_test("[x\\-]", "x", Ok(true)),
-            make_test("[x\\-]", "-", Ok(true)),
-            make_test("[x\\-]", "z", Ok(false)),
-            make_test("[\\-x]", "x", Ok(true)),
-            make_test("[\\-x]", "-", Ok(true)),
-            make_test("[\\-x]", "a", Ok(false)),
-            make_test("[]a]", "]", Err(())),
-            make_test("[-]", "-", Err(())),
-            make_test("[x-]", "x", Err(())),
-            make_test("[x-]", "-", Err(())),
-            make_test("[x-]", "z", Err(())),
-            make_test("[-x]", "x", Err(())),
-            make_test("[-x]", "-", Err(())),
-            make_test("[-x]", "a", Err(())),
-            make_test("\\", "a", Err(())),
-            make_test("[a-b-c]", "a", Err(())),
-            make_test("[", "a", Err(())),
-            make_test("[^", "a", Err(())),
-            make_test("[^bc", "a", Err(())),
-            make_test("a[", "a", Err(())),
-            make_test("a[", "ab", Err(())),
-            make_test("a[", "x", Err(())),
-            make_test("a/b[", "x", Err(())),
-            make_test("*x", "xxx", Ok(true)),
-        ];
+        assert_conformance::<GlobPattern>();
+    }
 
-        for (i, test) in tests.into_iter().enumerate() {
-            let display = test.display();
-            let result = test.test();
-            let result_display = format!("{:?}", result);
-            assert!(
-                test.succeed(result),
-                "[Test {i}]: {display}, got {result_display}"
-            )
+    #[cfg(feature = "parser")]
+    #[test]
+    #[cfg(feature = "go-reference")]
+    fn glob_pattern_matches_go_reference() {
+        for seed in 0..4 {
+            goglob_testutil::go::assert_matches_go::<GlobPattern>(seed, 10_000);
         }
     }
 }
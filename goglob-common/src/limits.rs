@@ -0,0 +1,205 @@
+//! Hard limits enforced by the parser, rejecting abusive patterns before (or
+//! while) they're compiled.
+//!
+//! Unlike [`ComplexityLimits`][crate::complexity::ComplexityLimits], each
+//! limit fails with its own [`ErrorType`], and the length limit is checked
+//! before scanning anything.
+
+#[cfg(feature = "parser")]
+use crate::{
+    error::{Error, ErrorType},
+    scan::ScanSink,
+    Result,
+};
+
+/// Limits enforced by a builder's
+/// [`parser_limits()`][crate::builder::GlobPatternBuilder::parser_limits].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ParserLimits {
+    max_len: Option<usize>,
+    max_class_entries: Option<usize>,
+    max_tokens: Option<usize>,
+}
+impl ParserLimits {
+    /// No limits at all.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            max_len: None,
+            max_class_entries: None,
+            max_tokens: None,
+        }
+    }
+
+    /// Maximum length of the pattern in bytes, or `None` for no limit.
+    /// Longer patterns fail with [`ErrorType::PatternTooLong`].
+    #[inline]
+    pub fn max_len(mut self, max_len: Option<usize>) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Maximum number of single characters and ranges in each character
+    /// class, or `None` for no limit. Larger classes fail with
+    /// [`ErrorType::TooManyClassEntries`].
+    #[inline]
+    pub fn max_class_entries(mut self, max_class_entries: Option<usize>) -> Self {
+        self.max_class_entries = max_class_entries;
+        self
+    }
+
+    /// Maximum number of compiled tokens, or `None` for no limit. Patterns
+    /// with more tokens fail with [`ErrorType::TooManyTokens`].
+    #[inline]
+    pub fn max_tokens(mut self, max_tokens: Option<usize>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Fail if `pattern` is too long to be scanned at all.
+    #[cfg(feature = "parser")]
+    pub(crate) fn check_len(&self, pattern: &str) -> Result<()> {
+        match self.max_len {
+            Some(max_len) if pattern.len() > max_len => {
+                Err(Error::new(ErrorType::PatternTooLong, max_len))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Forwards scanned elements to `sink`, failing as soon as they exceed
+/// `limits`.
+#[cfg(feature = "parser")]
+pub(crate) struct LimitedSink<'l, S> {
+    sink: S,
+    limits: &'l ParserLimits,
+    tokens: usize,
+    class_entries: usize,
+    literal_pos: Option<usize>,
+}
+#[cfg(feature = "parser")]
+impl<'l, S: ScanSink> LimitedSink<'l, S> {
+    pub(crate) fn new(sink: S, limits: &'l ParserLimits) -> Self {
+        Self {
+            sink,
+            limits,
+            tokens: 0,
+            class_entries: 0,
+            literal_pos: None,
+        }
+    }
+
+    fn token(&mut self, pos: usize) -> Result<()> {
+        self.tokens += 1;
+        match self.limits.max_tokens {
+            Some(max_tokens) if self.tokens > max_tokens => {
+                Err(Error::new(ErrorType::TooManyTokens, pos))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn class_entry(&mut self, pos: usize) -> Result<()> {
+        self.class_entries += 1;
+        match self.limits.max_class_entries {
+            Some(max_entries) if self.class_entries > max_entries => {
+                Err(Error::new(ErrorType::TooManyClassEntries, pos))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+#[cfg(feature = "parser")]
+impl<S: ScanSink> ScanSink for LimitedSink<'_, S> {
+    fn seq_wildcard(&mut self, pos: usize) -> Result<()> {
+        self.token(pos)?;
+        self.sink.seq_wildcard(pos)
+    }
+
+    fn any_dirs(&mut self, pos: usize) -> Result<()> {
+        self.token(pos)?;
+        self.sink.any_dirs(pos)
+    }
+
+    fn single_wildcard(&mut self, pos: usize) -> Result<()> {
+        self.token(pos)?;
+        self.sink.single_wildcard(pos)
+    }
+
+    fn literal_char(&mut self, c: char, pos: usize) -> Result<()> {
+        if self.literal_pos.is_none() {
+            // A literal is a token as soon as it starts
+            self.token(pos)?;
+            self.literal_pos = Some(pos);
+        }
+        self.sink.literal_char(c, pos)
+    }
+
+    fn end_literal(&mut self) -> Result<()> {
+        self.literal_pos = None;
+        self.sink.end_literal()
+    }
+
+    fn start_class(&mut self, negated: bool, pos: usize) -> Result<()> {
+        self.token(pos)?;
+        self.class_entries = 0;
+        self.sink.start_class(negated, pos)
+    }
+
+    fn class_char(&mut self, c: char, pos: usize) -> Result<()> {
+        self.class_entry(pos)?;
+        self.sink.class_char(c, pos)
+    }
+
+    fn class_range(&mut self, start: char, end: char, pos: usize) -> Result<()> {
+        self.class_entry(pos)?;
+        self.sink.class_range(start, end, pos)
+    }
+
+    fn end_class(&mut self, pos: usize) -> Result<()> {
+        self.sink.end_class(pos)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        error::{Error, ErrorType},
+        limits::ParserLimits,
+        GlobPattern,
+    };
+
+    fn build(pattern: &str, limits: ParserLimits) -> Result<GlobPattern, Error> {
+        GlobPattern::builder().parser_limits(limits).build(pattern)
+    }
+
+    #[test]
+    fn parser_limits() {
+        let limits = ParserLimits::new()
+            .max_len(Some(12))
+            .max_class_entries(Some(2))
+            .max_tokens(Some(4));
+        assert!(build("ab*[a-cx]?", limits.clone()).is_ok());
+
+        let error = build("abcdefghijklm", limits.clone()).unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::PatternTooLong));
+        assert_eq!(error.position(), 12);
+        // Checked before anything else
+        let error = build("[[[[[[[[[[[[[[", limits.clone()).unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::PatternTooLong));
+
+        // The limit applies to each class separately
+        assert!(build("[ab][c-ex]", limits.clone()).is_ok());
+        let error = build("[abc]", limits.clone()).unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::TooManyClassEntries));
+        assert_eq!(error.position(), 3);
+
+        let error = build("a?b?c", limits.clone()).unwrap_err();
+        assert!(matches!(error.error_type(), ErrorType::TooManyTokens));
+        assert_eq!(error.position(), 4);
+        assert_eq!(error.to_string(), "pattern has too many tokens at 4");
+
+        assert!(build("a?b?c?d?[abcdef]", ParserLimits::new()).is_ok());
+    }
+}
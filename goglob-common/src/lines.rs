@@ -0,0 +1,169 @@
+//! Filtering of the lines read from a [`BufRead`], like `grep`.
+
+use crate::GlobPattern;
+use std::{
+    io::{self, BufRead},
+    string::String,
+    vec::Vec,
+};
+
+/// Iterator over the lines read from a [`BufRead`] which match (or, if
+/// inverted, don't match) a pattern, as returned by
+/// [`GlobPattern::filter_lines()`] and [`GlobPattern::reject_lines()`].
+///
+/// Lines are terminated by `\n` or `\r\n`, or by `\0` if
+/// [NUL-separated][Self::nul_separated] (e.g. the output of `find -print0`),
+/// and are returned without their terminator. Lines which aren't valid UTF-8
+/// are returned as [`io::ErrorKind::InvalidData`] errors, like
+/// [`BufRead::lines()`] does, and reading continues with the next one.
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use goglob_common::GlobPattern;
+///
+/// let pattern = GlobPattern::new("*.rs").unwrap();
+/// let input = "lib.rs\r\nREADME.md\nmain.rs\n";
+/// let lines: Vec<_> = pattern
+///     .filter_lines(input.as_bytes())
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(lines, ["lib.rs", "main.rs"]);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FilterLines<'p, R> {
+    pattern: &'p GlobPattern,
+    reader: R,
+    keep: bool,
+    terminator: u8,
+    buf: Vec<u8>,
+}
+impl<'p, R: BufRead> FilterLines<'p, R> {
+    /// Lines read from `reader` matching `pattern`, or not matching it if
+    /// `inverted`.
+    pub fn new(pattern: &'p GlobPattern, reader: R, inverted: bool) -> Self {
+        Self {
+            pattern,
+            reader,
+            keep: !inverted,
+            terminator: b'\n',
+            buf: Vec::new(),
+        }
+    }
+
+    /// Whether lines are terminated by `\0` instead of `\n` or `\r\n`.
+    #[inline]
+    pub fn nul_separated(mut self, nul_separated: bool) -> Self {
+        self.terminator = if nul_separated { b'\0' } else { b'\n' };
+        self
+    }
+
+    /// Take the underlying reader, positioned after the last line returned.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Read the next line into `self.buf`, without its terminator, returning
+    /// whether there was any.
+    fn read_line(&mut self) -> io::Result<bool> {
+        self.buf.clear();
+        if self.reader.read_until(self.terminator, &mut self.buf)? == 0 {
+            return Ok(false);
+        }
+        if self.buf.last() == Some(&self.terminator) {
+            self.buf.pop();
+            if self.terminator == b'\n' && self.buf.last() == Some(&b'\r') {
+                self.buf.pop();
+            }
+        }
+        Ok(true)
+    }
+}
+impl<R: BufRead> Iterator for FilterLines<'_, R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.read_line() {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+            let line = match core::str::from_utf8(&self.buf) {
+                Ok(line) => line,
+                Err(e) => return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e))),
+            };
+            if self.pattern.matches(line) == self.keep {
+                return Some(Ok(String::from(line)));
+            }
+        }
+    }
+}
+
+impl GlobPattern {
+    /// Iterate over the lines read from `reader` matching the pattern (see
+    /// [`FilterLines`]).
+    #[inline]
+    pub fn filter_lines<R: BufRead>(&self, reader: R) -> FilterLines<'_, R> {
+        FilterLines::new(self, reader, false)
+    }
+
+    /// Iterate over the lines read from `reader` which don't match the
+    /// pattern, like `grep -v` (see [`FilterLines`]).
+    #[inline]
+    pub fn reject_lines<R: BufRead>(&self, reader: R) -> FilterLines<'_, R> {
+        FilterLines::new(self, reader, true)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::GlobPattern;
+    use std::io::{self, BufReader};
+
+    fn lines<I: Iterator<Item = io::Result<String>>>(lines: I) -> Vec<String> {
+        lines.map(|line| line.unwrap()).collect()
+    }
+
+    #[test]
+    fn filter_lines() {
+        let pattern = GlobPattern::new("*.rs").unwrap();
+        let input = b"lib.rs\r\nREADME.md\n\nmain.rs\r\nsrc/lib.rs\nlast.rs";
+        assert_eq!(
+            lines(pattern.filter_lines(input.as_slice())),
+            ["lib.rs", "main.rs", "last.rs"]
+        );
+        assert_eq!(
+            lines(pattern.reject_lines(input.as_slice())),
+            ["README.md", "", "src/lib.rs"]
+        );
+        // Lines may span several reads
+        let reader = BufReader::with_capacity(2, input.as_slice());
+        assert_eq!(lines(pattern.filter_lines(reader)).len(), 3);
+        assert!(pattern.filter_lines(io::empty()).next().is_none());
+    }
+
+    #[test]
+    fn filter_reader_nul_separated() {
+        let pattern = GlobPattern::new("*.rs").unwrap();
+        let input = b"lib.rs\0new\nline.rs\0a.rs\r\0b.md\0";
+        let filtered = pattern.filter_lines(input.as_slice()).nul_separated(true);
+        assert_eq!(lines(filtered), ["lib.rs", "new\nline.rs"]);
+        let rejected = pattern.reject_lines(input.as_slice()).nul_separated(true);
+        assert_eq!(lines(rejected), ["a.rs\r", "b.md"]);
+    }
+
+    #[test]
+    fn filter_reader_invalid_utf8() {
+        let pattern = GlobPattern::new("*").unwrap();
+        let input = b"a\n\xff\nb\n";
+        let results: Vec<_> = pattern.filter_lines(input.as_slice()).collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[1].as_ref().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(results[2].as_ref().unwrap(), "b");
+    }
+}
@@ -1,4 +1,8 @@
-use std::borrow::{Borrow, Cow};
+use crate::{matching::Haystack, options::CaseFold};
+use alloc::{
+    borrow::{Borrow, Cow},
+    string::String,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[repr(transparent)]
@@ -8,8 +12,16 @@ impl Literal {
         Self(Cow::Owned(literal))
     }
 
-    pub(crate) fn matches_next<'a>(&self, name: &'a str) -> Option<&'a str> {
-        name.strip_prefix(self.0.as_ref())
+    pub(crate) fn matches_next<H: Haystack>(&self, name: H, fold: CaseFold) -> Option<H> {
+        name.strip_literal_folded(self.0.as_bytes(), fold)
+    }
+
+    /// Whether the literal is only made of ASCII characters.
+    pub(crate) const fn is_ascii(&self) -> bool {
+        match &self.0 {
+            Cow::Borrowed(literal) => literal.is_ascii(),
+            Cow::Owned(literal) => literal.as_str().is_ascii(),
+        }
     }
 }
 impl AsRef<str> for Literal {
@@ -28,25 +40,44 @@ pub const fn from_static(literal: &'static str) -> Literal {
 
 #[cfg(test)]
 mod tests {
-    use crate::literal::{self, Literal};
+    use crate::{
+        literal::{self, Literal},
+        options::CaseFold,
+    };
 
     #[test]
     fn literal_matches_next() {
         let literal: Literal = Literal::new("abcde".into());
-        assert_eq!(literal.matches_next("abcdefg"), Some("fg"));
-        assert_eq!(literal.matches_next("fgabcde"), None);
-        assert_eq!(literal.matches_next("abceefg"), None);
-        assert_eq!(literal.matches_next("abcd"), None);
-        assert_eq!(literal.matches_next("abcde"), Some(""));
+        assert_eq!(
+            literal.matches_next("abcdefg", CaseFold::Sensitive),
+            Some("fg")
+        );
+        assert_eq!(literal.matches_next("fgabcde", CaseFold::Sensitive), None);
+        assert_eq!(literal.matches_next("abceefg", CaseFold::Sensitive), None);
+        assert_eq!(literal.matches_next("abcd", CaseFold::Sensitive), None);
+        assert_eq!(literal.matches_next("abcde", CaseFold::Sensitive), Some(""));
     }
 
     #[test]
     fn literal_matches_next_static() {
         let literal: Literal = literal::from_static("abcde");
-        assert_eq!(literal.matches_next("abcdefg"), Some("fg"));
-        assert_eq!(literal.matches_next("fgabcde"), None);
-        assert_eq!(literal.matches_next("abceefg"), None);
-        assert_eq!(literal.matches_next("abcd"), None);
-        assert_eq!(literal.matches_next("abcde"), Some(""));
+        assert_eq!(
+            literal.matches_next("abcdefg", CaseFold::Sensitive),
+            Some("fg")
+        );
+        assert_eq!(literal.matches_next("fgabcde", CaseFold::Sensitive), None);
+        assert_eq!(literal.matches_next("abceefg", CaseFold::Sensitive), None);
+        assert_eq!(literal.matches_next("abcd", CaseFold::Sensitive), None);
+        assert_eq!(literal.matches_next("abcde", CaseFold::Sensitive), Some(""));
+    }
+
+    #[test]
+    fn literal_matches_next_folded() {
+        let literal: Literal = Literal::new("AbÄ".into());
+        assert_eq!(literal.matches_next("abäc", CaseFold::Sensitive), None);
+        assert_eq!(literal.matches_next("abÄc", CaseFold::Ascii), Some("c"));
+        assert_eq!(literal.matches_next("abäc", CaseFold::Ascii), None);
+        #[cfg(feature = "unicode")]
+        assert_eq!(literal.matches_next("abäc", CaseFold::Unicode), Some("c"));
     }
 }
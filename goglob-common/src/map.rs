@@ -0,0 +1,205 @@
+//! Maps from shell patterns to values, looked up by name, e.g. for routing
+//! tables.
+
+use crate::GlobPattern;
+#[cfg(feature = "parser")]
+use crate::Result;
+use alloc::vec::Vec;
+use core::slice::Iter;
+
+/// Ordered list of shell patterns with an associated value each, looking up
+/// the value of the first pattern matching a name: patterns inserted earlier
+/// take precedence over later ones.
+///
+/// With the `serde` feature, maps can be deserialized from maps (e.g. JSON or
+/// TOML objects) whose keys are pattern strings, in the order they're written.
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use goglob_common::{map::GlobMap, GlobPattern};
+///
+/// let mut routes = GlobMap::new();
+/// routes.insert(GlobPattern::new("/api/v1/*").unwrap(), "legacy");
+/// routes.insert(GlobPattern::new("/api/*/*").unwrap(), "api");
+/// assert_eq!(routes.get("/api/v1/users"), Some(&"legacy"));
+/// assert_eq!(routes.get("/api/v2/users"), Some(&"api"));
+/// assert_eq!(routes.get("/static/app.js"), None);
+/// # }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GlobMap<V> {
+    entries: Vec<(GlobPattern, V)>,
+}
+impl<V> GlobMap<V> {
+    /// Empty map, matching no names.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Compile the pattern of every entry in `entries`, returning a
+    /// [`GlobMap`][Self] on success or the [error][crate::error::Error] of the
+    /// first syntactically invalid pattern.
+    #[cfg(feature = "parser")]
+    pub fn compile<I, S>(entries: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (S, V)>,
+        S: AsRef<str>,
+    {
+        entries
+            .into_iter()
+            .map(|(pattern, value)| Ok((GlobPattern::new(pattern)?, value)))
+            .collect()
+    }
+
+    /// Number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the map contains no entries at all.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the entries in order of precedence.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, (GlobPattern, V)> {
+        self.entries.iter()
+    }
+
+    /// Append an entry with the lowest precedence, returning its index. Names
+    /// matched by an earlier pattern (such as an identical one) keep resolving
+    /// to the earlier entry's value.
+    pub fn insert(&mut self, pattern: GlobPattern, value: V) -> usize {
+        self.entries.push((pattern, value));
+        self.entries.len() - 1
+    }
+
+    /// Value of the first pattern matching `name`, if any.
+    #[inline]
+    pub fn get<S: AsRef<str>>(&self, name: S) -> Option<&V> {
+        self.get_entry(name).map(|(_, value)| value)
+    }
+
+    /// First entry whose pattern matches `name`, if any.
+    pub fn get_entry<S: AsRef<str>>(&self, name: S) -> Option<&(GlobPattern, V)> {
+        let name = name.as_ref();
+        self.entries
+            .iter()
+            .find(|(pattern, _)| pattern.matches(name))
+    }
+
+    /// Values of every pattern matching `name`, in order of precedence.
+    pub fn get_all<'a, S>(&'a self, name: S) -> impl Iterator<Item = &'a V> + 'a
+    where
+        S: AsRef<str> + 'a,
+    {
+        self.entries
+            .iter()
+            .filter(move |(pattern, _)| pattern.matches(name.as_ref()))
+            .map(|(_, value)| value)
+    }
+}
+impl<V> Default for GlobMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<V> FromIterator<(GlobPattern, V)> for GlobMap<V> {
+    fn from_iter<T: IntoIterator<Item = (GlobPattern, V)>>(iter: T) -> Self {
+        let mut entries: Vec<_> = iter.into_iter().collect();
+        entries.shrink_to_fit();
+        Self { entries }
+    }
+}
+impl<V> Extend<(GlobPattern, V)> for GlobMap<V> {
+    fn extend<T: IntoIterator<Item = (GlobPattern, V)>>(&mut self, iter: T) {
+        self.entries.extend(iter);
+    }
+}
+impl<V> IntoIterator for GlobMap<V> {
+    type Item = (GlobPattern, V);
+    type IntoIter = alloc::vec::IntoIter<(GlobPattern, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+impl<'a, V> IntoIterator for &'a GlobMap<V> {
+    type Item = &'a (GlobPattern, V);
+    type IntoIter = Iter<'a, (GlobPattern, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{map::GlobMap, GlobPattern};
+
+    #[test]
+    fn glob_map_get() {
+        let map = GlobMap::compile([("*.rs", 1), ("lib.*", 2), ("*.rs", 3)]).unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("main.rs"), Some(&1));
+        assert_eq!(map.get("lib.rs"), Some(&1));
+        assert_eq!(map.get("lib.py"), Some(&2));
+        assert_eq!(map.get("src/lib.rs"), None);
+        assert_eq!(map.get_all("lib.rs").collect::<Vec<_>>(), vec![&1, &2, &3]);
+        assert_eq!(
+            map.get_entry("lib.py").unwrap().0,
+            GlobPattern::new("lib.*").unwrap()
+        );
+
+        let mut map = GlobMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert(GlobPattern::new("a*").unwrap(), "a"), 0);
+        assert_eq!(map.insert(GlobPattern::new("*").unwrap(), "any"), 1);
+        assert_eq!(map.get("abc"), Some(&"a"));
+        assert_eq!(map.get("bc"), Some(&"any"));
+        assert!(GlobMap::compile([("[a", 1)]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn glob_map_deserialize() {
+        use serde::{
+            de::value::{Error, MapDeserializer},
+            Deserialize,
+        };
+
+        let entries = vec![("/api/v1/*", 1), ("/api/*/*", 2), ("*", 3)];
+        let map =
+            GlobMap::<i32>::deserialize(MapDeserializer::<_, Error>::new(entries.into_iter()))
+                .unwrap();
+        assert_eq!(map.get("/api/v1/users"), Some(&1));
+        assert_eq!(map.get("/api/v2/users"), Some(&2));
+        assert_eq!(map.get("index.html"), Some(&3));
+
+        let entries = vec![("*.rs", 1), ("[a", 2)];
+        let error =
+            GlobMap::<i32>::deserialize(MapDeserializer::<_, Error>::new(entries.into_iter()))
+                .unwrap_err();
+        assert!(error.to_string().contains("isn't closed"));
+
+        #[cfg(feature = "std")]
+        {
+            use std::collections::HashMap;
+
+            let entries = vec![("*.rs", 1), ("*.py", 2)];
+            let map = HashMap::<GlobPattern, i32>::deserialize(MapDeserializer::<_, Error>::new(
+                entries.into_iter(),
+            ))
+            .unwrap();
+            assert_eq!(map[&GlobPattern::new("*.rs").unwrap()], 1);
+            assert_eq!(map.get(&GlobPattern::new("*.r\\s").unwrap()), Some(&1));
+        }
+    }
+}
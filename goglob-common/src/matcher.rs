@@ -0,0 +1,84 @@
+//! Abstraction over the different matching engines, so code can be generic
+//! over which one is used.
+
+/// Engine reporting whether a name matches a compiled pattern (or set of
+/// patterns).
+///
+/// Every engine must agree with golang's `path.Match` semantics, so they can be
+/// used interchangeably.
+pub trait PatternMatcher {
+    /// Report whether `name` matches.
+    fn is_match(&self, name: &str) -> bool;
+}
+
+impl<M: PatternMatcher + ?Sized> PatternMatcher for &M {
+    #[inline]
+    fn is_match(&self, name: &str) -> bool {
+        M::is_match(self, name)
+    }
+}
+#[cfg(feature = "alloc")]
+impl<M: PatternMatcher + ?Sized> PatternMatcher for alloc::boxed::Box<M> {
+    #[inline]
+    fn is_match(&self, name: &str) -> bool {
+        M::is_match(self, name)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PatternMatcher for crate::GlobPattern {
+    #[inline]
+    fn is_match(&self, name: &str) -> bool {
+        self.matches(name)
+    }
+}
+#[cfg(feature = "alloc")]
+impl PatternMatcher for crate::arc::ArcGlobPattern {
+    #[inline]
+    fn is_match(&self, name: &str) -> bool {
+        self.matches(name)
+    }
+}
+#[cfg(feature = "alloc")]
+impl PatternMatcher for crate::set::GlobSet {
+    #[inline]
+    fn is_match(&self, name: &str) -> bool {
+        self.matches(name)
+    }
+}
+impl<const TOKENS: usize, const BYTES: usize> PatternMatcher
+    for crate::fixed::FixedGlobPattern<TOKENS, BYTES>
+{
+    #[inline]
+    fn is_match(&self, name: &str) -> bool {
+        self.matches(name)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{fixed::FixedGlobPattern, matcher::PatternMatcher, set::GlobSet, GlobPattern};
+
+    fn count_matches<M: PatternMatcher>(matcher: M) -> usize {
+        ["lib.rs", "src/lib.rs", "README.md", "main.rs"]
+            .into_iter()
+            .filter(|name| matcher.is_match(name))
+            .count()
+    }
+
+    #[test]
+    fn pattern_matcher_engines() {
+        let pattern = GlobPattern::new("*.rs").unwrap();
+        let fixed = FixedGlobPattern::<2, 3>::new("*.rs").unwrap();
+        let set = GlobSet::compile(["*.rs", "*.md"]).unwrap();
+        assert_eq!(count_matches(&pattern), 2);
+        assert_eq!(count_matches(&fixed), 2);
+        assert_eq!(count_matches(&set), 3);
+        assert_eq!(count_matches(pattern.clone().shared()), 2);
+
+        let engines: Vec<Box<dyn PatternMatcher>> =
+            vec![Box::new(pattern), Box::new(fixed), Box::new(set)];
+        let counts: Vec<_> = engines.iter().map(count_matches).collect();
+        assert_eq!(counts, [2, 2, 3]);
+    }
+}
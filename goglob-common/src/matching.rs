@@ -0,0 +1,637 @@
+//! Matching algorithm shared by every pattern representation.
+
+use crate::{
+    options::{CaseFold, MatchOptions},
+    outcome::MismatchReason,
+};
+use core::iter::Peekable;
+
+/// Text being matched, either a `&str` or a `&[u8]` which may not be valid
+/// UTF-8.
+///
+/// Bytes that can't be decoded are each treated as a single character which
+/// isn't the separator and doesn't belong to any character class.
+pub(crate) trait Haystack: Copy {
+    /// Length in bytes.
+    fn len(self) -> usize;
+
+    #[inline]
+    fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    /// Split off the first character (`None` if undecodable), or return
+    /// `None` if empty.
+    fn next_char(self) -> Option<(Option<char>, Self)>;
+
+    /// Strip `literal`, which must be made of whole UTF-8 characters.
+    fn strip_literal(self, literal: &[u8]) -> Option<Self>;
+
+    /// Split at byte offset `mid`, which must be a character boundary.
+    fn split_at(self, mid: usize) -> (Self, Self);
+
+    /// Byte offset of the first occurrence of `c`, if any.
+    fn find_char(self, c: char) -> Option<usize>;
+
+    fn ends_with_char(self, c: char) -> bool;
+
+    /// Strip `literal` (made of whole UTF-8 characters), comparing characters
+    /// according to `fold`.
+    fn strip_literal_folded(self, literal: &[u8], fold: CaseFold) -> Option<Self> {
+        if fold == CaseFold::Sensitive {
+            return self.strip_literal(literal);
+        }
+        let literal = core::str::from_utf8(literal).ok()?;
+        let mut rest = self;
+        for expected in literal.chars() {
+            let (c, after) = rest.next_char()?;
+            if !c.is_some_and(|c| fold.eq(c, expected)) {
+                return None;
+            }
+            rest = after;
+        }
+        Some(rest)
+    }
+
+    /// Strip the first character if `pred` accepts it.
+    #[inline]
+    fn strip_char<F: FnOnce(Option<char>) -> bool>(self, pred: F) -> Option<Self> {
+        self.next_char()
+            .and_then(|(c, rest)| pred(c).then_some(rest))
+    }
+}
+impl Haystack for &str {
+    #[inline]
+    fn len(self) -> usize {
+        str::len(self)
+    }
+
+    #[inline]
+    fn next_char(self) -> Option<(Option<char>, Self)> {
+        let c = self.chars().next()?;
+        Some((Some(c), &self[c.len_utf8()..]))
+    }
+
+    #[inline]
+    fn strip_literal(self, literal: &[u8]) -> Option<Self> {
+        // `literal` is made of whole characters, so a matching prefix always
+        // ends at a character boundary of `self`.
+        self.as_bytes()
+            .starts_with(literal)
+            .then(|| &self[literal.len()..])
+    }
+
+    #[inline]
+    fn split_at(self, mid: usize) -> (Self, Self) {
+        str::split_at(self, mid)
+    }
+
+    #[inline]
+    fn find_char(self, c: char) -> Option<usize> {
+        self.find(c)
+    }
+
+    #[inline]
+    fn ends_with_char(self, c: char) -> bool {
+        self.ends_with(c)
+    }
+}
+impl Haystack for &[u8] {
+    #[inline]
+    fn len(self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn next_char(self) -> Option<(Option<char>, Self)> {
+        let width = match *self.first()? {
+            0x00..=0x7f => 1,
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => 0,
+        };
+        let c = self
+            .get(..width)
+            .and_then(|bytes| core::str::from_utf8(bytes).ok())
+            .and_then(|s| s.chars().next());
+        match c {
+            Some(c) => Some((Some(c), &self[width..])),
+            None => Some((None, &self[1..])),
+        }
+    }
+
+    #[inline]
+    fn strip_literal(self, literal: &[u8]) -> Option<Self> {
+        self.strip_prefix(literal)
+    }
+
+    #[inline]
+    fn split_at(self, mid: usize) -> (Self, Self) {
+        <[u8]>::split_at(self, mid)
+    }
+
+    fn find_char(self, c: char) -> Option<usize> {
+        let mut buf = [0; 4];
+        let c = c.encode_utf8(&mut buf).as_bytes();
+        self.windows(c.len()).position(|window| window == c)
+    }
+
+    fn ends_with_char(self, c: char) -> bool {
+        self.ends_with(c.encode_utf8(&mut [0; 4]).as_bytes())
+    }
+}
+
+/// Name made of ASCII characters alone, matched byte by byte without
+/// decoding any UTF-8. Only patterns made of ASCII characters alone may be
+/// matched against it, as literals are then compared byte by byte too.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy)]
+pub(crate) struct Ascii<'a>(pub(crate) &'a [u8]);
+#[cfg(feature = "alloc")]
+impl Haystack for Ascii<'_> {
+    #[inline]
+    fn len(self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    fn next_char(self) -> Option<(Option<char>, Self)> {
+        let (&b, rest) = self.0.split_first()?;
+        Some((Some(char::from(b)), Ascii(rest)))
+    }
+
+    #[inline]
+    fn strip_literal(self, literal: &[u8]) -> Option<Self> {
+        self.0.strip_prefix(literal).map(Ascii)
+    }
+
+    /// ASCII characters only have ASCII case variants, so both ways of
+    /// folding are the same.
+    #[inline]
+    fn strip_literal_folded(self, literal: &[u8], fold: CaseFold) -> Option<Self> {
+        if fold == CaseFold::Sensitive {
+            return self.strip_literal(literal);
+        }
+        let (prefix, rest) = self.0.split_at_checked(literal.len())?;
+        prefix.eq_ignore_ascii_case(literal).then_some(Ascii(rest))
+    }
+
+    #[inline]
+    fn split_at(self, mid: usize) -> (Self, Self) {
+        let (left, right) = self.0.split_at(mid);
+        (Ascii(left), Ascii(right))
+    }
+
+    #[inline]
+    fn find_char(self, c: char) -> Option<usize> {
+        let b = u8::try_from(c).ok()?;
+        self.0.iter().position(|&other| other == b)
+    }
+
+    #[inline]
+    fn ends_with_char(self, c: char) -> bool {
+        u8::try_from(c).is_ok_and(|b| self.0.last() == Some(&b))
+    }
+}
+
+/// Wildcard matching a variable amount of text, handled by
+/// [`match_tokens()`] itself.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Wildcard {
+    /// `*`, matching any sequence of non-`/` characters.
+    Seq,
+    /// `**/`, matching either nothing or any sequence of characters ending
+    /// with `/`. Fixed-capacity patterns never contain it.
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    AnyDirs,
+}
+
+/// Single element of a compiled pattern, as seen by [`match_tokens()`].
+pub(crate) trait MatchToken {
+    /// Match the start of `name`, returning the rest of it on success, or
+    /// `Err(wildcard)` if the token is a wildcard handled by the algorithm
+    /// itself.
+    fn try_matches_next<H: Haystack>(
+        &self,
+        name: H,
+        options: &MatchOptions,
+    ) -> Result<Option<H>, Wildcard>;
+
+    /// Why the token doesn't match the start of `name`, along with the rest
+    /// of `name` where matching failed. Only called after
+    /// [`try_matches_next()`][MatchToken::try_matches_next] failed.
+    fn mismatch<H: Haystack>(&self, name: H, options: &MatchOptions) -> (MismatchReason, H);
+
+    /// Whether the text matched by this token is reported as a capture.
+    fn is_capturing(&self) -> bool;
+}
+
+impl<T: MatchToken> MatchToken for &T {
+    #[inline]
+    fn try_matches_next<H: Haystack>(
+        &self,
+        name: H,
+        options: &MatchOptions,
+    ) -> Result<Option<H>, Wildcard> {
+        T::try_matches_next(self, name, options)
+    }
+
+    #[inline]
+    fn mismatch<H: Haystack>(&self, name: H, options: &MatchOptions) -> (MismatchReason, H) {
+        T::mismatch(self, name, options)
+    }
+
+    #[inline]
+    fn is_capturing(&self) -> bool {
+        T::is_capturing(self)
+    }
+}
+
+/// Receiver of the text matched by capturing tokens, which must support
+/// discarding captures when backtracking.
+pub(crate) trait CaptureSink<H> {
+    fn push(&mut self, capture: H);
+    fn len(&self) -> usize;
+    fn truncate(&mut self, len: usize);
+}
+impl<H> CaptureSink<H> for () {
+    #[inline]
+    fn push(&mut self, _: H) {}
+
+    #[inline]
+    fn len(&self) -> usize {
+        0
+    }
+
+    #[inline]
+    fn truncate(&mut self, _: usize) {}
+}
+#[cfg(feature = "alloc")]
+impl<H> CaptureSink<H> for alloc::vec::Vec<H> {
+    #[inline]
+    fn push(&mut self, capture: H) {
+        alloc::vec::Vec::push(self, capture)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        alloc::vec::Vec::len(self)
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        alloc::vec::Vec::truncate(self, len)
+    }
+}
+
+/// Why a literal doesn't match the start of `name`, along with the rest of
+/// `name` at the first character which differs.
+pub(crate) fn literal_mismatch<H: Haystack>(
+    name: H,
+    literal: &[u8],
+    fold: CaseFold,
+) -> (MismatchReason, H) {
+    let literal = core::str::from_utf8(literal).unwrap_or_default();
+    let mut rest = name;
+    for expected in literal.chars() {
+        match rest.next_char() {
+            Some((Some(c), after)) if fold.eq(c, expected) => rest = after,
+            Some(_) => return (MismatchReason::Literal, rest),
+            None => return (MismatchReason::TooShort, rest),
+        }
+    }
+    (MismatchReason::Literal, rest)
+}
+
+/// Why a token consuming a single character (`?` or a character class)
+/// doesn't match the start of `name`.
+pub(crate) fn char_mismatch<H: Haystack>(name: H, reason: MismatchReason) -> (MismatchReason, H) {
+    if name.is_empty() {
+        (MismatchReason::TooShort, name)
+    } else {
+        (reason, name)
+    }
+}
+
+/// Receiver of the reasons matching failed along the way. Matching backtracks,
+/// so there may be many of them.
+pub(crate) trait MismatchSink {
+    /// `reason` at byte `position` of the name, with `tokens_left` tokens left
+    /// (including the failing one).
+    fn mismatch(&mut self, reason: MismatchReason, position: usize, tokens_left: usize);
+}
+impl MismatchSink for () {
+    #[inline]
+    fn mismatch(&mut self, _: MismatchReason, _: usize, _: usize) {}
+}
+/// Keeps the failure which got the furthest into the name (the last one among
+/// equals), which is the most relevant one to report.
+impl MismatchSink for Option<(MismatchReason, usize, usize)> {
+    fn mismatch(&mut self, reason: MismatchReason, position: usize, tokens_left: usize) {
+        if !matches!(self, Some((_, furthest, _)) if position < *furthest) {
+            *self = Some((reason, position, tokens_left));
+        }
+    }
+}
+
+/// Whether `next` (the rest of `name`) starts with a `.` at the start of a
+/// segment, which must be matched explicitly if dotfiles are disabled.
+fn hidden_start<H: Haystack>(name: H, next: H, options: &MatchOptions) -> bool {
+    !options.dotfiles
+        && matches!(next.next_char(), Some((Some('.'), _)))
+        && (next.len() == name.len()
+            || name
+                .split_at(name.len() - next.len())
+                .0
+                .ends_with_char(options.effective_separator()))
+}
+
+/// Byte offset of `next` (the rest of `name`) within `name`.
+#[inline]
+fn position<H: Haystack>(name: H, next: H) -> usize {
+    name.len() - next.len()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn try_matches_capturing<H, T, C, M>(
+    token: &T,
+    name: H,
+    next: H,
+    options: &MatchOptions,
+    captures: &mut C,
+    mismatches: &mut M,
+    tokens_left: usize,
+) -> Result<Option<H>, Wildcard>
+where
+    H: Haystack,
+    T: MatchToken,
+    C: CaptureSink<H>,
+    M: MismatchSink,
+{
+    match token.try_matches_next(next, options)? {
+        Some(_) if token.is_capturing() && hidden_start(name, next, options) => {
+            mismatches.mismatch(MismatchReason::Hidden, position(name, next), tokens_left);
+            Ok(None)
+        }
+        Some(rest) => {
+            if token.is_capturing() {
+                captures.push(next.split_at(next.len() - rest.len()).0);
+            }
+            Ok(Some(rest))
+        }
+        None => {
+            let (reason, at) = token.mismatch(next, options);
+            mismatches.mismatch(reason, position(name, at), tokens_left);
+            Ok(None)
+        }
+    }
+}
+
+/// Number of token comparisons made while matching, out of a budget.
+pub(crate) struct Steps {
+    pub(crate) taken: usize,
+    limit: usize,
+    exhausted: bool,
+}
+impl Steps {
+    pub(crate) const fn unlimited() -> Self {
+        Self::limited(usize::MAX)
+    }
+
+    pub(crate) const fn limited(limit: usize) -> Self {
+        Self {
+            taken: 0,
+            limit,
+            exhausted: false,
+        }
+    }
+
+    /// Take a step, returning `false` if the budget is already spent.
+    fn take(&mut self) -> bool {
+        if self.taken == self.limit {
+            self.exhausted = true;
+            return false;
+        }
+        self.taken += 1;
+        true
+    }
+
+    /// Whether a step was needed past the budget.
+    pub(crate) fn exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
+
+/// Report whether `name` matches `tokens` according to `options`, recording
+/// the text matched by every capturing token into `captures`, the reasons
+/// matching failed into `mismatches` and counting the number of token
+/// comparisons into `steps`. Matching fails as soon as `steps` is exhausted.
+pub(crate) fn match_tokens<H, I, C, M>(
+    tokens: I,
+    name: H,
+    options: &MatchOptions,
+    captures: &mut C,
+    mismatches: &mut M,
+    steps: &mut Steps,
+) -> bool
+where
+    H: Haystack,
+    I: ExactSizeIterator + Clone,
+    I::Item: MatchToken + Clone,
+    C: CaptureSink<H>,
+    M: MismatchSink,
+{
+    match_peekable(
+        tokens.peekable(),
+        name,
+        name,
+        options,
+        captures,
+        mismatches,
+        steps,
+    )
+}
+fn match_peekable<H, I, C, M>(
+    mut tokens: Peekable<I>,
+    name: H,
+    mut next: H,
+    options: &MatchOptions,
+    captures: &mut C,
+    mismatches: &mut M,
+    steps: &mut Steps,
+) -> bool
+where
+    H: Haystack,
+    I: ExactSizeIterator + Clone,
+    I::Item: MatchToken + Clone,
+    C: CaptureSink<H>,
+    M: MismatchSink,
+{
+    let separator = options.effective_separator();
+    'outer: while let Some(token) = tokens.next() {
+        if !steps.take() {
+            return false;
+        }
+        // Tokens left, including this one
+        let tokens_left = tokens.len() + 1;
+        next = match try_matches_capturing(
+            &token,
+            name,
+            next,
+            options,
+            captures,
+            mismatches,
+            tokens_left,
+        ) {
+            Ok(Some(rest)) => rest,
+            Ok(None) => return false,
+            Err(Wildcard::AnyDirs) => {
+                // Unlike '*', the text matched by '**/' can contain '/', so
+                // the earliest position where the following tokens match
+                // isn't necessarily the right one. Try every directory
+                // boundary (including the very start) against the rest of the
+                // pattern instead.
+                let mut remaining = next;
+                loop {
+                    let (skipped, rest) = next.split_at(next.len() - remaining.len());
+                    let captured = captures.len();
+                    captures.push(skipped);
+                    if match_peekable(
+                        tokens.clone(),
+                        name,
+                        rest,
+                        options,
+                        captures,
+                        mismatches,
+                        steps,
+                    ) {
+                        return true;
+                    }
+                    if steps.exhausted() {
+                        return false;
+                    }
+                    captures.truncate(captured);
+
+                    // Advance past the next '/', unless the directory is
+                    // hidden
+                    if hidden_start(name, remaining, options) {
+                        let at = position(name, remaining);
+                        mismatches.mismatch(MismatchReason::Hidden, at, tokens_left);
+                        return false;
+                    }
+                    loop {
+                        match remaining.next_char() {
+                            Some((c, after)) => {
+                                remaining = after;
+                                if c == Some(separator) {
+                                    break;
+                                }
+                            }
+                            None => return false,
+                        }
+                    }
+                }
+            }
+            Err(Wildcard::Seq) => {
+                // SeqWildcard doesn't implement matches_next. However, it
+                // can match any number of non-'/' characters (even zero),
+                // so we must see what matches the remaining tokens up until
+                // the next SeqWildcard (or the end if no further SeqWildcards
+                // remain)
+
+                // A hidden name can only be matched by a literal '.' starting
+                // the segment in the pattern, not even with an empty '*'
+                // before it (as glob(7) does)
+                if hidden_start(name, next, options) {
+                    mismatches.mismatch(MismatchReason::Hidden, position(name, next), tokens_left);
+                    return false;
+                }
+
+                // If there are no more tokens left, make sure there is no '/'
+                // in the rest of the string
+                if tokens.peek().is_none() {
+                    captures.push(next);
+                    if let Some(found) = next.find_char(separator) {
+                        let at = position(name, next) + found;
+                        mismatches.mismatch(MismatchReason::Separator, at, tokens_left);
+                        return false;
+                    }
+                    return true;
+                };
+
+                // For every remaining position in next until '/', check if
+                // the remaining tokens until SeqWildcard match.
+                let mut remaining = next;
+                'star: while let Some((c, after)) = remaining.next_char() {
+                    let mut tokens_peek = tokens.clone();
+                    let (skipped, mut next_peek) = next.split_at(next.len() - remaining.len());
+                    let mut fail = false;
+                    let mut finished = true;
+                    // Captures of the tokens following this SeqWildcard are
+                    // only kept if the match succeeds at this position.
+                    let captured = captures.len();
+                    captures.push(skipped);
+                    'inner: while let Some(token_peek) = tokens_peek.peek().cloned() {
+                        if !steps.take() {
+                            return false;
+                        }
+                        let tokens_left = tokens_peek.len();
+                        next_peek = match try_matches_capturing(
+                            &token_peek,
+                            name,
+                            next_peek,
+                            options,
+                            captures,
+                            mismatches,
+                            tokens_left,
+                        ) {
+                            Ok(Some(rest)) => rest,
+                            Ok(None) => {
+                                fail = true;
+                                break 'inner;
+                            }
+                            Err(_) => {
+                                finished = false;
+                                break 'inner;
+                            }
+                        };
+                        tokens_peek.next();
+                    }
+
+                    if !fail && (!finished || next_peek.is_empty()) {
+                        // Either we correctly matched until the next SeqWildcard,
+                        // or there are no tokens left and the entirety of the
+                        // string is matched. In either case we continue
+                        tokens = tokens_peek;
+                        next = next_peek;
+                        continue 'outer;
+                    }
+                    if !fail {
+                        // The pattern ended before the name did
+                        mismatches.mismatch(MismatchReason::TooLong, position(name, next_peek), 0);
+                    }
+
+                    // Match failed, try from next position.
+                    captures.truncate(captured);
+
+                    if c == Some(separator) {
+                        // Found '/', abort
+                        let at = position(name, remaining);
+                        mismatches.mismatch(MismatchReason::Separator, at, tokens_left);
+                        break 'star;
+                    }
+                    remaining = after;
+                }
+
+                // Exhausted available positions without finding a match.
+                return false;
+            }
+        }
+    }
+    if !next.is_empty() {
+        // The pattern ended before the name did
+        mismatches.mismatch(MismatchReason::TooLong, position(name, next), 0);
+        return false;
+    }
+    true
+}
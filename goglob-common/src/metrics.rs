@@ -0,0 +1,87 @@
+//! Hooks reporting what patterns are compiled and matched, so that host
+//! applications can export metrics (e.g. to Prometheus or OpenTelemetry)
+//! without `goglob` depending on any metrics crate.
+
+use std::sync::OnceLock;
+
+static SINK: OnceLock<&'static dyn GlobMetricsSink> = OnceLock::new();
+
+/// Receiver of metrics events, registered with [`set_metrics_sink()`].
+///
+/// Every method has a no-op default implementation, so sinks only need to
+/// implement the events they care about. Methods are called synchronously from
+/// the thread compiling or matching, so they should be cheap (e.g. increment
+/// an atomic counter).
+pub trait GlobMetricsSink: Send + Sync {
+    /// A pattern was successfully compiled into `tokens` tokens.
+    fn compiled(&self, tokens: usize) {
+        let _ = tokens;
+    }
+
+    /// A pattern failed to compile because of a syntax error.
+    fn compile_failed(&self) {}
+
+    /// A name was matched against a pattern, taking `steps` token comparisons
+    /// (a rough measure of the cost of the match).
+    fn matched(&self, matched: bool, steps: usize) {
+        let _ = (matched, steps);
+    }
+
+    /// An already compiled pattern was reused by a cache instead of compiling
+    /// it again.
+    fn cache_hit(&self) {}
+}
+
+/// Register the sink receiving every metrics event of the process. Only one
+/// sink may ever be registered, so `sink` is given back if there already is
+/// one.
+pub fn set_metrics_sink(
+    sink: &'static dyn GlobMetricsSink,
+) -> Result<(), &'static dyn GlobMetricsSink> {
+    SINK.set(sink)
+}
+
+#[inline]
+pub(crate) fn sink() -> Option<&'static dyn GlobMetricsSink> {
+    SINK.get().copied()
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::metrics::{set_metrics_sink, GlobMetricsSink};
+    use crate::GlobPattern;
+    use std::cell::Cell;
+
+    thread_local! {
+        // Tests run in parallel, so only count the events of the current thread.
+        static EVENTS: Cell<(usize, usize, usize, usize)> = const { Cell::new((0, 0, 0, 0)) };
+    }
+
+    struct CountingSink;
+    impl GlobMetricsSink for CountingSink {
+        fn compiled(&self, _: usize) {
+            EVENTS.with(|e| e.set((e.get().0 + 1, e.get().1, e.get().2, e.get().3)));
+        }
+
+        fn compile_failed(&self) {
+            EVENTS.with(|e| e.set((e.get().0, e.get().1 + 1, e.get().2, e.get().3)));
+        }
+
+        fn matched(&self, matched: bool, steps: usize) {
+            let matched = matched as usize;
+            EVENTS.with(|e| e.set((e.get().0, e.get().1, e.get().2 + matched, e.get().3 + steps)));
+        }
+    }
+
+    #[test]
+    fn metrics_sink() {
+        let _ = set_metrics_sink(&CountingSink);
+        let pattern = GlobPattern::new("a*b").unwrap();
+        assert!(GlobPattern::new("a[").is_err());
+        assert!(pattern.matches("axxb"));
+        assert!(!pattern.matches("c"));
+        let (compiled, failed, matched, steps) = EVENTS.with(Cell::get);
+        assert_eq!((compiled, failed, matched), (1, 1, 1));
+        assert!(steps >= 4);
+    }
+}
@@ -0,0 +1,274 @@
+//! Shrinking of pattern sets without changing which names they match.
+
+use crate::{
+    automaton::is_exact,
+    charcls::{CharClass, CharClassType},
+    literal::Literal,
+    set::GlobSet,
+    GlobPattern, GlobToken,
+};
+use alloc::{string::String, vec::Vec};
+
+/// Smaller equivalent of a [`GlobSet`], as returned by
+/// [`GlobSet::minimize()`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Minimized {
+    set: GlobSet,
+    mapping: Vec<usize>,
+}
+impl Minimized {
+    /// The minimized set, matching exactly the same names as the original.
+    #[inline]
+    pub fn set(&self) -> &GlobSet {
+        &self.set
+    }
+
+    /// Take the minimized set.
+    #[inline]
+    pub fn into_set(self) -> GlobSet {
+        self.set
+    }
+
+    /// Index in the minimized set of the pattern standing for each pattern
+    /// of the original set, by original index.
+    #[inline]
+    pub fn mapping(&self) -> &[usize] {
+        &self.mapping
+    }
+
+    /// Index in the minimized set of the pattern standing for the pattern at
+    /// `index` in the original set (i.e. matching every name it matched), or
+    /// `None` if there's no such pattern.
+    #[inline]
+    pub fn new_index(&self, index: usize) -> Option<usize> {
+        self.mapping.get(index).copied()
+    }
+}
+
+impl GlobSet {
+    /// Shrink the set into one matching exactly the same names with fewer
+    /// patterns, for huge machine-generated pattern lists:
+    ///
+    /// - Every pattern is [simplified][GlobPattern::simplify].
+    /// - Patterns with the same options differing by a single character
+    ///   (possibly a class) are merged into one with a class there (`a.c`
+    ///   and `a.h` become `a.[ch]`), except for separators and, if dotfiles
+    ///   aren't matched by wildcards, `.`.
+    /// - Patterns matching a subset of the names another pattern matches (see
+    ///   [`GlobPattern::contains()`]) are removed.
+    ///
+    /// Patterns where a `*` is followed by a class matching the separator and
+    /// then another wildcard (e.g. `*[^x]*`) are only simplified and
+    /// deduplicated, as matching only tries the earliest position where such
+    /// a class matches. The surviving patterns keep their relative order.
+    /// Checking for subsumed patterns takes quadratic time in the number of
+    /// patterns.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::set::GlobSet;
+    ///
+    /// let set = GlobSet::compile(["*.c", "src/*", "*.h", "src/lib.rs"]).unwrap();
+    /// let minimized = set.minimize();
+    /// assert_eq!(minimized.set().len(), 2);
+    /// assert!(minimized.set().matches("lib.h"));
+    /// assert_eq!(minimized.mapping(), [0, 1, 0, 1]);
+    /// # }
+    /// ```
+    pub fn minimize(&self) -> Minimized {
+        let mut patterns: Vec<Option<GlobPattern>> =
+            self.iter().map(|p| Some(p.simplify())).collect();
+        // Index of the pattern standing for each original one, in `patterns`
+        let mut targets: Vec<usize> = (0..patterns.len()).collect();
+
+        let mut merged = true;
+        while merged {
+            merged = false;
+            for i in 0..patterns.len() {
+                for j in i + 1..patterns.len() {
+                    let (Some(left), Some(right)) = (&patterns[i], &patterns[j]) else {
+                        continue;
+                    };
+                    if let Some(pattern) = merge(left, right) {
+                        patterns[i] = Some(pattern);
+                        patterns[j] = None;
+                        redirect(&mut targets, j, i);
+                        merged = true;
+                    }
+                }
+            }
+        }
+
+        // Containment is only decided by the automatons of exact patterns
+        let exact: Vec<bool> = patterns
+            .iter()
+            .map(|p| p.as_ref().is_some_and(is_exact))
+            .collect();
+        for i in 0..patterns.len() {
+            let Some(pattern) = patterns[i].as_ref().filter(|_| exact[i]) else {
+                continue;
+            };
+            // Of equivalent patterns, the first one is kept
+            let by = (0..patterns.len()).find(|&j| {
+                j != i
+                    && exact[j]
+                    && patterns[j].as_ref().is_some_and(|other| {
+                        other.contains(pattern) && (j < i || !pattern.contains(other))
+                    })
+            });
+            if let Some(j) = by {
+                patterns[i] = None;
+                redirect(&mut targets, i, j);
+            }
+        }
+
+        let mut indices = Vec::with_capacity(patterns.len());
+        let mut set = GlobSet::default();
+        for pattern in patterns {
+            indices.push(pattern.map(|pattern| set.push(pattern)));
+        }
+        let mapping = targets
+            .into_iter()
+            .map(|t| indices[t].expect("targets always survive"))
+            .collect();
+        Minimized { set, mapping }
+    }
+}
+
+/// Make the original patterns standing for `from` stand for `to` instead.
+fn redirect(targets: &mut [usize], from: usize, to: usize) {
+    targets
+        .iter_mut()
+        .filter(|t| **t == from)
+        .for_each(|t| *t = to);
+}
+
+/// Merge two patterns differing by at most one character into one, if
+/// possible.
+fn merge(left: &GlobPattern, right: &GlobPattern) -> Option<GlobPattern> {
+    if left.options() != right.options() {
+        return None;
+    }
+    let (mut atoms, other) = (atoms(left), atoms(right));
+    if atoms.len() != other.len() {
+        return None;
+    }
+    let mut differing = atoms
+        .iter()
+        .zip(&other)
+        .enumerate()
+        .filter(|(_, (l, r))| l != r);
+    let Some((index, (l, r))) = differing.next() else {
+        return Some(left.clone());
+    };
+    if differing.next().is_some() || !is_exact(left) || !is_exact(right) {
+        return None;
+    }
+
+    let separator = left.options().effective_separator();
+    let dotfiles = left.options().dotfiles;
+    let mut entries = Vec::new();
+    for atom in [l, r] {
+        match atom {
+            // A class can't match a separator or start a hidden name, unlike
+            // the literal it would replace
+            GlobToken::Literal(c) => match c.as_ref().chars().next()? {
+                c if c == separator || (c == '.' && !dotfiles) => return None,
+                c => entries.push(CharClassType::from(c)),
+            },
+            GlobToken::CharClass(cc) if !cc.is_negated() => {
+                entries.extend(cc.types().iter().cloned())
+            }
+            _ => return None,
+        }
+    }
+    atoms[index] = GlobToken::CharClass(CharClass::new(false, entries));
+    Some(GlobPattern::from_token_vec(atoms, *left.options(), None).simplify())
+}
+
+/// Tokens of a pattern, with literals split into their characters.
+fn atoms(pattern: &GlobPattern) -> Vec<GlobToken> {
+    let mut atoms = Vec::with_capacity(pattern.tokens().len());
+    for token in pattern.tokens() {
+        match token {
+            GlobToken::Literal(l) => atoms.extend(
+                l.as_ref()
+                    .chars()
+                    .map(|c| GlobToken::Literal(Literal::new(String::from(c)))),
+            ),
+            token => atoms.push(token.clone()),
+        }
+    }
+    atoms
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{builder::GlobPatternBuilder, options::MatchOptions, set::GlobSet, GlobPattern};
+
+    #[test]
+    fn minimize_merges() {
+        let set = GlobSet::compile(["a.c", "a.h", "a.[ab]", "x/y", "xzy", "**", "*"]).unwrap();
+        let minimized = set.minimize();
+        let expected = GlobSet::compile(["x/y", "*"]).unwrap();
+        assert_eq!(minimized.set(), &expected);
+        assert_eq!(minimized.mapping(), [1, 1, 1, 0, 1, 1, 1]);
+        assert_eq!(minimized.new_index(7), None);
+
+        let set = GlobSet::compile(["a.c", "a.h", "a.[ab]", "x/y", "xzy"]).unwrap();
+        let expected = GlobSet::compile(["a.[a-ch]", "x/y", "xzy"]).unwrap();
+        assert_eq!(set.minimize().set(), &expected);
+        assert_eq!(set.minimize().mapping(), [0, 0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn minimize_subsumed() {
+        let set = GlobSet::compile(["src/*.rs", "*.md", "src/*", "src/*", "README.md"]).unwrap();
+        let minimized = set.minimize();
+        let expected = GlobSet::compile(["*.md", "src/*"]).unwrap();
+        assert_eq!(minimized.set(), &expected);
+        assert_eq!(minimized.mapping(), [1, 0, 1, 1, 0]);
+        assert_eq!(GlobSet::default().minimize().into_set(), GlobSet::default());
+    }
+
+    #[test]
+    fn minimize_keeps_inexact() {
+        // Matching "ab/c" commits "[^x]" to "a", leaving "b/c" to the last "*"
+        let set = GlobSet::new([
+            GlobPattern::new("*[^x]*").unwrap(),
+            GlobPattern::new("ab/c").unwrap(),
+        ]);
+        assert!(!set.get(0).unwrap().matches("ab/c"));
+        let minimized = set.minimize();
+        assert_eq!(minimized.set(), &set);
+        assert_eq!(minimized.mapping(), [0, 1]);
+        assert!(minimized.set().matches("ab/c"));
+    }
+
+    #[test]
+    fn minimize_keeps_hidden() {
+        let hidden = GlobPatternBuilder::new().options(MatchOptions::new().dotfiles(false));
+        let set = GlobSet::new([hidden.build(".a").unwrap(), hidden.build("xa").unwrap()]);
+        let minimized = set.minimize();
+        assert_eq!(minimized.set().len(), 2);
+        assert!(minimized.set().matches(".a"));
+    }
+
+    #[test]
+    fn minimize_is_equivalent() {
+        let patterns = [
+            "*.rs", "lib.r[s]", "[a-c]x", "dx", "?x", "a*", "b", "a/*", "a/b",
+        ];
+        let set = GlobSet::compile(patterns).unwrap();
+        let minimized = set.minimize();
+        for name in [
+            "lib.rs", "ax", "dx", "ex", "a/b", "a/c", "b", "ab", "x", ".rs",
+        ] {
+            assert_eq!(minimized.set().matches(name), set.matches(name), "{name}");
+            for i in set.matching(name) {
+                let pattern = minimized.set().get(minimized.mapping()[i]).unwrap();
+                assert!(pattern.matches(name), "{name}");
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! Options controlling how patterns are parsed and matched.
+
+use crate::syntax::Syntax;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Options shared by every API compiling patterns: [`GlobPattern::new_with()`][crate::GlobPattern::new_with],
+/// [`GlobSet::compile_with()`][crate::set::GlobSet::compile_with], the
+/// filesystem walker and the `glob!` macro.
+///
+/// With the `serde` feature, options can be (de)serialized as a map with the
+/// same names as the setters below (e.g. `{"case_fold": "ascii", "dotfiles":
+/// false}`), any missing option taking its default value.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct MatchOptions {
+    pub(crate) syntax: Syntax,
+    pub(crate) escapes: bool,
+    pub(crate) case_fold: CaseFold,
+    pub(crate) separator: Option<char>,
+    pub(crate) dotfiles: bool,
+    pub(crate) seq_wildcard: char,
+    pub(crate) single_wildcard: char,
+    pub(crate) escape_char: char,
+    pub(crate) char_classes: bool,
+    pub(crate) normalize_paths: bool,
+}
+impl MatchOptions {
+    /// Default options, matching golang's `path.Match` behaviour: the
+    /// [`GoPathMatch`][Syntax::GoPathMatch] syntax, `\` escapes, case-sensitive
+    /// matching, `/` as the separator and wildcards matching names starting
+    /// with `.`.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            syntax: Syntax::GoPathMatch,
+            escapes: true,
+            case_fold: CaseFold::Sensitive,
+            separator: None,
+            dotfiles: true,
+            seq_wildcard: '*',
+            single_wildcard: '?',
+            escape_char: '\\',
+            char_classes: true,
+            normalize_paths: false,
+        }
+    }
+
+    /// Dialect patterns are parsed as.
+    #[inline]
+    pub const fn syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Whether `\` escapes the next character. When disabled, `\` is a literal
+    /// character. Syntaxes without escapes (such as
+    /// [`GoFilepathMatch`][Syntax::GoFilepathMatch] on Windows) ignore this.
+    #[inline]
+    pub const fn escapes(mut self, escapes: bool) -> Self {
+        self.escapes = escapes;
+        self
+    }
+
+    /// How literals and character classes compare characters.
+    #[inline]
+    pub const fn case_fold(mut self, case_fold: CaseFold) -> Self {
+        self.case_fold = case_fold;
+        self
+    }
+
+    /// Character separating path segments, which `*` and `?` never match.
+    /// Defaults to `/` (or `\` for [`GoFilepathMatch`][Syntax::GoFilepathMatch]
+    /// on Windows).
+    #[inline]
+    pub const fn separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Whether wildcards and character classes may match a `.` at the start
+    /// of a name or path segment. When disabled, such names are only matched
+    /// by patterns with an explicit literal `.` starting the segment (e.g.
+    /// `.*` or `*/.config`, but not `*.config`), as with glob(7).
+    #[inline]
+    pub const fn dotfiles(mut self, dotfiles: bool) -> Self {
+        self.dotfiles = dotfiles;
+        self
+    }
+
+    /// Character acting as the `*` wildcard, matching any sequence of
+    /// non-separator characters (e.g. `%` for SQL `LIKE`-style patterns).
+    /// Metacharacters must all be different from each other, and wildcards
+    /// from the separator, or compiling patterns fails with
+    /// [`ErrorType::ConflictingMetachar`][crate::error::ErrorType::ConflictingMetachar].
+    #[inline]
+    pub const fn seq_wildcard(mut self, seq_wildcard: char) -> Self {
+        self.seq_wildcard = seq_wildcard;
+        self
+    }
+
+    /// Character acting as the `?` wildcard, matching a single non-separator
+    /// character (e.g. `_` for SQL `LIKE`-style patterns).
+    #[inline]
+    pub const fn single_wildcard(mut self, single_wildcard: char) -> Self {
+        self.single_wildcard = single_wildcard;
+        self
+    }
+
+    /// Character escaping the next one, `\` by default (see
+    /// [`escapes()`][Self::escapes]).
+    #[inline]
+    pub const fn escape_char(mut self, escape_char: char) -> Self {
+        self.escape_char = escape_char;
+        self
+    }
+
+    /// Whether `[` opens a character class. When disabled, `[` and `]` are
+    /// literal characters.
+    #[inline]
+    pub const fn char_classes(mut self, char_classes: bool) -> Self {
+        self.char_classes = char_classes;
+        self
+    }
+
+    /// Whether [`GlobPattern::matches_path()`][crate::GlobPattern::matches_path]
+    /// normalizes paths before matching them: dropping `.` components and
+    /// repeated separators (e.g. `./src//lib.rs` becomes `src/lib.rs`) and, on
+    /// Windows, turning both `\` and `/` into the separator.
+    #[inline]
+    pub const fn normalize_paths(mut self, normalize_paths: bool) -> Self {
+        self.normalize_paths = normalize_paths;
+        self
+    }
+
+    /// Whether `c` has a special meaning in patterns parsed with these
+    /// options, so it must be escaped (or put in a class) to be matched
+    /// literally.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn is_metachar(&self, c: char) -> bool {
+        c == self.seq_wildcard
+            || c == self.single_wildcard
+            || (self.char_classes && matches!(c, '[' | ']'))
+            || (self.effective_escapes() && c == self.escape_char)
+    }
+
+    /// A character with more than one meaning in patterns parsed with these
+    /// options (e.g. `?` if it's both the `*` and `?` wildcard), if any: the
+    /// wildcards and the escape character must differ from each other and,
+    /// if classes are enabled, from `[` and `]`, and the wildcards from the
+    /// separator (which an escape character may be, as it's then escaped).
+    pub(crate) fn conflicting_metachar(&self) -> Option<char> {
+        let escape = self.effective_escapes().then_some(self.escape_char);
+        let brackets = self.char_classes.then_some(['[', ']']);
+        let metachars = [
+            Some(self.seq_wildcard),
+            Some(self.single_wildcard),
+            escape,
+            brackets.map(|[open, _]| open),
+            brackets.map(|[_, close]| close),
+        ];
+        let repeated = metachars
+            .iter()
+            .enumerate()
+            .find_map(|(i, &c)| c.filter(|c| metachars[i + 1..].contains(&Some(*c))));
+        // Wildcards never match the separator, so they can't be it
+        let separator = Some(self.effective_separator())
+            .filter(|&c| c == self.seq_wildcard || c == self.single_wildcard);
+        repeated.or(separator)
+    }
+
+    pub(crate) fn effective_escapes(&self) -> bool {
+        self.escapes && self.syntax.escapes()
+    }
+
+    pub(crate) fn effective_separator(&self) -> char {
+        self.separator.unwrap_or(self.syntax.separator())
+    }
+}
+impl Default for MatchOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Case sensitivity of literals and character classes.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum CaseFold {
+    /// Characters must be identical.
+    #[default]
+    Sensitive,
+    /// ASCII letters match regardless of case (e.g. `a` and `A`).
+    Ascii,
+    /// Letters match regardless of case according to Unicode's case mappings
+    /// (e.g. `ä` and `Ä`).
+    ///
+    /// Without the `unicode` feature, which links the case mapping tables,
+    /// only ASCII letters match regardless of case (as with
+    /// [`Ascii`][Self::Ascii]).
+    Unicode,
+}
+impl CaseFold {
+    /// Whether `a` and `b` are the same character under this folding.
+    pub(crate) fn eq(self, a: char, b: char) -> bool {
+        match self {
+            CaseFold::Sensitive => a == b,
+            CaseFold::Ascii => a.eq_ignore_ascii_case(&b),
+            #[cfg(feature = "unicode")]
+            CaseFold::Unicode => a == b || a.to_lowercase().eq(b.to_lowercase()),
+            #[cfg(not(feature = "unicode"))]
+            CaseFold::Unicode => a.eq_ignore_ascii_case(&b),
+        }
+    }
+
+    /// `c` along with its lowercase and uppercase variants under this folding
+    /// (any of them possibly repeated).
+    pub(crate) fn variants(self, c: char) -> [char; 3] {
+        #[cfg(feature = "unicode")]
+        fn single(mut chars: impl Iterator<Item = char>, c: char) -> char {
+            match (chars.next(), chars.next()) {
+                (Some(single), None) => single,
+                _ => c,
+            }
+        }
+
+        match self {
+            CaseFold::Sensitive => [c; 3],
+            CaseFold::Ascii => [c, c.to_ascii_lowercase(), c.to_ascii_uppercase()],
+            #[cfg(feature = "unicode")]
+            CaseFold::Unicode => [c, single(c.to_lowercase(), c), single(c.to_uppercase(), c)],
+            #[cfg(not(feature = "unicode"))]
+            CaseFold::Unicode => [c, c.to_ascii_lowercase(), c.to_ascii_uppercase()],
+        }
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        options::{CaseFold, MatchOptions},
+        syntax::Syntax,
+        GlobPattern,
+    };
+
+    fn matches(options: &MatchOptions, pattern: &str, name: &str) -> bool {
+        GlobPattern::new_with(pattern, options)
+            .unwrap()
+            .matches(name)
+    }
+
+    #[test]
+    fn match_options_default() {
+        let options = MatchOptions::default();
+        assert_eq!(options, MatchOptions::new());
+        assert_eq!(
+            GlobPattern::new_with("a\\*[b-c]", &options).unwrap(),
+            GlobPattern::new("a\\*[b-c]").unwrap()
+        );
+        assert!(matches(&options, "*", ".hidden"));
+        assert!(!matches(&options, "A*", "abc"));
+    }
+
+    #[test]
+    fn match_options_case_fold() {
+        let ascii = MatchOptions::new().case_fold(CaseFold::Ascii);
+        assert!(matches(&ascii, "README.*", "readme.md"));
+        assert!(matches(&ascii, "[A-C]x", "bX"));
+        assert!(!matches(&ascii, "[^A-C]x", "bx"));
+        assert!(!matches(&ascii, "ä", "Ä"));
+
+        let unicode = MatchOptions::new().case_fold(CaseFold::Unicode);
+        assert!(matches(&unicode, "README.*", "readme.md"));
+        #[cfg(feature = "unicode")]
+        {
+            assert!(matches(&unicode, "ä*", "Äb"));
+            assert!(matches(&unicode, "[à-ä]", "Â"));
+            assert!(!matches(&unicode, "[^à-ä]", "Â"));
+        }
+        #[cfg(not(feature = "unicode"))]
+        assert!(!matches(&unicode, "ä", "Ä"));
+    }
+
+    #[test]
+    fn match_options_separator() {
+        let options = MatchOptions::new().separator(':');
+        assert!(matches(&options, "a/*", "a/b/c"));
+        assert!(!matches(&options, "a:*", "a:b:c"));
+        assert!(!matches(&options, "a?b", "a:b"));
+        let pattern = GlobPattern::new_with("a:*:c", &options).unwrap();
+        assert_eq!(pattern.segments().len(), 3);
+
+        let options = options.syntax(Syntax::Gitignore);
+        assert!(matches(&options, "a:**:c", "a:x:y:c"));
+        assert!(!matches(&options, "a:**c", "a:x:yc"));
+    }
+
+    #[test]
+    fn match_options_escapes() {
+        let options = MatchOptions::new().escapes(false);
+        assert!(matches(&options, "a\\*", "a\\b"));
+        assert!(matches(&options, "[\\]", "\\"));
+        assert!(GlobPattern::new_with("a\\", &options).is_ok());
+    }
+
+    #[test]
+    fn match_options_dotfiles() {
+        let options = MatchOptions::new().dotfiles(false);
+        assert!(!matches(&options, "*", ".hidden"));
+        assert!(!matches(&options, "?hidden", ".hidden"));
+        assert!(!matches(&options, "[.]hidden", ".hidden"));
+        assert!(!matches(&options, "*/*", "a/.hidden"));
+        assert!(matches(&options, ".*", ".hidden"));
+        assert!(matches(&options, "*/.*", "a/.hidden"));
+        assert!(matches(&options, "*.*", "a.b"));
+        assert!(matches(&options, "a*", "a.b"));
+        assert!(!matches(&options, "*.a", ".a"));
+        assert!(!matches(&options, "*.rs", ".rs"));
+        assert!(!matches(&options, "*.a*", ".ab"));
+        assert!(!matches(&options, "a/*.a", "a/.a"));
+        assert!(!matches(&options, "*.[a]", ".a"));
+        assert!(matches(&options, "*.a", "b.a"));
+
+        let options = options.syntax(Syntax::Gitignore);
+        assert!(!matches(&options, "**/b", "a/.x/b"));
+        assert!(matches(&options, "**/b", "a/x/b"));
+        assert!(matches(&options, "**/.x/b", "a/.x/b"));
+    }
+}
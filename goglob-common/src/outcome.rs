@@ -0,0 +1,225 @@
+//! Detailed match results explaining why a name doesn't match a pattern.
+
+use crate::fixed::FixedGlobPattern;
+#[cfg(feature = "alloc")]
+use crate::GlobPattern;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Result of [`GlobPattern::match_detailed()`], telling why the name doesn't
+/// match if it doesn't.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MatchOutcome {
+    Matched,
+    Mismatched(Mismatch),
+}
+impl MatchOutcome {
+    /// Whether the name matches, same as [`GlobPattern::matches()`].
+    #[inline]
+    pub fn is_match(&self) -> bool {
+        matches!(self, MatchOutcome::Matched)
+    }
+
+    /// Why the name doesn't match, if it doesn't.
+    #[inline]
+    pub fn mismatch(&self) -> Option<&Mismatch> {
+        match self {
+            MatchOutcome::Matched => None,
+            MatchOutcome::Mismatched(mismatch) => Some(mismatch),
+        }
+    }
+
+    fn from_sink(
+        matched: bool,
+        mismatch: Option<(MismatchReason, usize, usize)>,
+        token_count: usize,
+    ) -> Self {
+        if matched {
+            return MatchOutcome::Matched;
+        }
+        // Every failure is recorded, so there's always one when the name
+        // doesn't match.
+        let (reason, position, tokens_left) =
+            mismatch.unwrap_or((MismatchReason::TooShort, 0, token_count));
+        MatchOutcome::Mismatched(Mismatch {
+            reason,
+            position,
+            token: (tokens_left > 0).then(|| token_count - tokens_left),
+        })
+    }
+}
+
+/// Why a name doesn't match a pattern.
+///
+/// Matching may try several ways of splitting the name between wildcards, so
+/// this is the failure which got the furthest into the name.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Mismatch {
+    reason: MismatchReason,
+    position: usize,
+    token: Option<usize>,
+}
+impl Mismatch {
+    pub fn reason(&self) -> MismatchReason {
+        self.reason
+    }
+
+    /// Byte offset in the name where matching failed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Index of the token (see [`GlobPattern::tokens()`]) which failed to
+    /// match, or `None` if the whole pattern matched but the name is longer
+    /// ([`MismatchReason::TooLong`]).
+    pub fn token(&self) -> Option<usize> {
+        self.token
+    }
+}
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} at {}", self.reason, self.position)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MismatchReason {
+    /// The name differs from a literal part of the pattern.
+    Literal,
+    /// The character isn't part of a character class.
+    CharClass,
+    /// A wildcard would have to match the separator (`/` by default).
+    Separator,
+    /// A wildcard or character class would have to match the leading `.` of
+    /// a hidden name, which [`MatchOptions::dotfiles()`][crate::options::MatchOptions::dotfiles]
+    /// disallows.
+    Hidden,
+    /// The name ends before the pattern does.
+    TooShort,
+    /// The pattern ends before the name does.
+    TooLong,
+}
+impl MismatchReason {
+    pub fn desc(&self) -> &'static str {
+        match self {
+            MismatchReason::Literal => "mismatch at literal",
+            MismatchReason::CharClass => "character not in character class",
+            MismatchReason::Separator => "blocked by separator",
+            MismatchReason::Hidden => "hidden name not matched explicitly",
+            MismatchReason::TooShort => "name too short",
+            MismatchReason::TooLong => "name too long",
+        }
+    }
+}
+impl Display for MismatchReason {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.desc())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl GlobPattern {
+    /// Same as [`matches()`][GlobPattern::matches], telling why `name`
+    /// doesn't match if it doesn't (e.g. to explain why a path isn't covered
+    /// by a rule).
+    pub fn match_detailed<S: AsRef<str>>(&self, name: S) -> MatchOutcome {
+        let mut mismatch = None;
+        let matched = self.match_tokens_detailed(name.as_ref(), &mut mismatch);
+        MatchOutcome::from_sink(matched, mismatch, self.tokens().len())
+    }
+}
+
+impl<const TOKENS: usize, const BYTES: usize> FixedGlobPattern<TOKENS, BYTES> {
+    /// Same as [`matches()`][FixedGlobPattern::matches], telling why `name`
+    /// doesn't match if it doesn't.
+    pub fn match_detailed(&self, name: &str) -> MatchOutcome {
+        let mut mismatch = None;
+        let matched = self.match_tokens_detailed(name, &mut mismatch);
+        MatchOutcome::from_sink(matched, mismatch, self.token_count())
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        fixed::FixedGlobPattern,
+        options::MatchOptions,
+        outcome::{MatchOutcome, MismatchReason},
+        GlobPattern,
+    };
+    use goglob_testutil::GO_MATCH_VECTORS;
+
+    fn mismatch(pattern: &str, name: &str) -> (MismatchReason, usize, Option<usize>) {
+        let outcome = GlobPattern::new(pattern).unwrap().match_detailed(name);
+        let mismatch = outcome.mismatch().unwrap();
+        (mismatch.reason(), mismatch.position(), mismatch.token())
+    }
+
+    #[test]
+    fn match_detailed_go_match_test() {
+        for vector in GO_MATCH_VECTORS {
+            let Some(expected) = vector.expected else {
+                continue;
+            };
+            let pattern = GlobPattern::new(vector.pattern).unwrap();
+            let outcome = pattern.match_detailed(vector.name);
+            assert_eq!(
+                outcome.is_match(),
+                expected,
+                "({}, {})",
+                vector.pattern,
+                vector.name
+            );
+            let fixed = FixedGlobPattern::<16, 64>::new(vector.pattern).unwrap();
+            assert_eq!(fixed.match_detailed(vector.name), outcome);
+        }
+    }
+
+    #[test]
+    fn match_detailed_reasons() {
+        assert_eq!(
+            GlobPattern::new("a*").unwrap().match_detailed("abc"),
+            MatchOutcome::Matched
+        );
+        assert_eq!(
+            mismatch("abc", "abd"),
+            (MismatchReason::Literal, 2, Some(0))
+        );
+        assert_eq!(
+            mismatch("a[b-d]", "ax"),
+            (MismatchReason::CharClass, 1, Some(1))
+        );
+        assert_eq!(
+            mismatch("*.rs", "src/lib.rs"),
+            (MismatchReason::Separator, 3, Some(0))
+        );
+        assert_eq!(
+            mismatch("src/*", "src/a/b"),
+            (MismatchReason::Separator, 5, Some(1))
+        );
+        assert_eq!(
+            mismatch("a?c", "a/c"),
+            (MismatchReason::Separator, 1, Some(1))
+        );
+        assert_eq!(
+            mismatch("abc", "ab"),
+            (MismatchReason::TooShort, 2, Some(0))
+        );
+        assert_eq!(mismatch("a?", "a"), (MismatchReason::TooShort, 1, Some(1)));
+        assert_eq!(mismatch("a?", "abc"), (MismatchReason::TooLong, 2, None));
+        assert_eq!(
+            mismatch("a*b", "axbyc"),
+            (MismatchReason::Literal, 4, Some(2))
+        );
+
+        let options = MatchOptions::new().dotfiles(false);
+        let pattern = GlobPattern::new_with("src/*", &options).unwrap();
+        let mismatch = *pattern.match_detailed("src/.git").mismatch().unwrap();
+        assert_eq!(mismatch.reason(), MismatchReason::Hidden);
+        assert_eq!(mismatch.position(), 4);
+        assert_eq!(
+            mismatch.to_string(),
+            "hidden name not matched explicitly at 4"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Bucketing of names by the patterns of a set they match.
+
+use crate::set::GlobSet;
+use alloc::{vec, vec::Vec};
+
+/// Names bucketed by the patterns of a [`GlobSet`] they matched, as returned
+/// by [`GlobSet::partition()`].
+///
+/// A name matched by several patterns is in the bucket of each of them, and
+/// names within a bucket keep the order they were given in.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Partition<S> {
+    matched: Vec<Vec<S>>,
+    unmatched: Vec<S>,
+}
+impl<S> Partition<S> {
+    /// Names matched by each pattern, by pattern index.
+    #[inline]
+    pub fn matched(&self) -> &[Vec<S>] {
+        &self.matched
+    }
+
+    /// Names matched by the pattern at `index`, or `None` if there's no such
+    /// pattern.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&[S]> {
+        self.matched.get(index).map(Vec::as_slice)
+    }
+
+    /// Names matched by no pattern at all.
+    #[inline]
+    pub fn unmatched(&self) -> &[S] {
+        &self.unmatched
+    }
+
+    /// Names matched by each pattern (by pattern index) and names matched by
+    /// none.
+    #[inline]
+    pub fn into_parts(self) -> (Vec<Vec<S>>, Vec<S>) {
+        (self.matched, self.unmatched)
+    }
+}
+
+impl GlobSet {
+    /// Match every one of `names` against the set once, bucketing them by
+    /// the patterns they matched, instead of matching all of them against
+    /// each pattern in turn.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::set::GlobSet;
+    ///
+    /// let set = GlobSet::compile(["*.rs", "src/*", "*.py"]).unwrap();
+    /// let partition = set.partition(["build.rs", "src/lib.rs", "README.md"]);
+    /// assert_eq!(partition.get(0), Some(&["build.rs"][..]));
+    /// assert_eq!(partition.get(1), Some(&["src/lib.rs"][..]));
+    /// assert_eq!(partition.get(2), Some(&[][..]));
+    /// assert_eq!(partition.unmatched(), ["README.md"]);
+    /// # }
+    /// ```
+    pub fn partition<I, S>(&self, names: I) -> Partition<S>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str> + Clone,
+    {
+        let mut partition = Partition {
+            matched: vec![Vec::new(); self.len()],
+            unmatched: Vec::new(),
+        };
+        let mut matching = Vec::new();
+        for name in names {
+            let str_name = name.as_ref();
+            matching.clear();
+            matching.extend(
+                self.candidates(str_name)
+                    .filter(|(_, p)| p.matches(str_name))
+                    .map(|(i, _)| i),
+            );
+            match matching.split_last() {
+                Some((&last, others)) => {
+                    for &i in others {
+                        partition.matched[i].push(name.clone());
+                    }
+                    partition.matched[last].push(name);
+                }
+                None => partition.unmatched.push(name),
+            }
+        }
+        partition
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::set::GlobSet;
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn glob_set_partition() {
+        let set = GlobSet::compile(["src/*", "*.md", "target/*", "*"]).unwrap();
+        let names = [
+            "src/lib.rs",
+            "README.md",
+            "Cargo.toml",
+            "src/main.rs",
+            "a/b",
+        ];
+        let partition = set.partition(names.map(String::from));
+        for (i, bucket) in partition.matched().iter().enumerate() {
+            let expected: Vec<_> = names
+                .iter()
+                .copied()
+                .filter(|name| set.get(i).unwrap().matches(name))
+                .collect();
+            assert_eq!(
+                bucket.iter().map(String::as_str).collect::<Vec<_>>(),
+                expected
+            );
+        }
+        assert_eq!(partition.get(2), Some(&[][..]));
+        assert_eq!(partition.get(4), None);
+        assert_eq!(partition.unmatched(), ["a/b"]);
+
+        let (matched, unmatched) = GlobSet::default().partition(names).into_parts();
+        assert!(matched.is_empty());
+        assert_eq!(unmatched, names);
+    }
+}
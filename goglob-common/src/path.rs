@@ -0,0 +1,156 @@
+//! Matching [`Path`]s, normalized first if so configured.
+
+use crate::GlobPattern;
+use alloc::{borrow::Cow, string::String};
+use std::path::{self, Path};
+
+impl GlobPattern {
+    /// Report whether `path` matches the compiled shell pattern. Paths which
+    /// aren't valid UTF-8 never match.
+    ///
+    /// With [`MatchOptions::normalize_paths()`][crate::options::MatchOptions::normalize_paths],
+    /// `path` is normalized first, so that different spellings of the same
+    /// path match alike:
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::{options::MatchOptions, GlobPattern};
+    /// use std::path::Path;
+    ///
+    /// let path = Path::new("./src//lib.rs");
+    /// assert!(!GlobPattern::new("src/*.rs").unwrap().matches_path(path));
+    ///
+    /// let options = MatchOptions::new().normalize_paths(true);
+    /// let pattern = GlobPattern::new_with("src/*.rs", &options).unwrap();
+    /// assert!(pattern.matches_path(path));
+    /// # }
+    /// ```
+    ///
+    /// Normalizing also makes both `\` and `/` separators on Windows, so the
+    /// paths of directory entries are matched as they are, without
+    /// stringifying them first:
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::{options::MatchOptions, GlobPattern};
+    /// use std::fs;
+    ///
+    /// let options = MatchOptions::new().normalize_paths(true);
+    /// let pattern = GlobPattern::new_with("src/*.rs", &options).unwrap();
+    /// for entry in fs::read_dir("src").unwrap() {
+    ///     let path = entry.unwrap().path();
+    ///     if pattern.matches_path(&path) {
+    ///         println!("{}", path.display());
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn matches_path<P: AsRef<Path>>(&self, path: P) -> bool {
+        match path.as_ref().to_str() {
+            Some(path) => self.matches(self.normalize_path(path)),
+            None => false,
+        }
+    }
+
+    /// `path` as matched by the pattern, i.e. normalized if
+    /// [`MatchOptions::normalize_paths()`][crate::options::MatchOptions::normalize_paths]
+    /// is enabled.
+    pub(crate) fn normalize_path<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        match self.options.normalize_paths {
+            true => Cow::Owned(normalize(path, self.options.effective_separator())),
+            false => Cow::Borrowed(path),
+        }
+    }
+}
+
+/// `path` without `.` components nor repeated separators, and with its
+/// separators (both `\` and `/` on Windows) replaced with `separator`. Leading
+/// and trailing separators are kept, and a path made of `.` components only
+/// becomes `.`.
+fn normalize(path: &str, separator: char) -> String {
+    let is_separator = |c: char| c == separator || (cfg!(windows) && path::is_separator(c));
+    let mut normalized = String::with_capacity(path.len());
+    if path.starts_with(is_separator) {
+        normalized.push(separator);
+    }
+    for component in path
+        .split(is_separator)
+        .filter(|component| !component.is_empty() && *component != ".")
+    {
+        if !normalized.is_empty() && !normalized.ends_with(separator) {
+            normalized.push(separator);
+        }
+        normalized.push_str(component);
+    }
+    if normalized.is_empty() && !path.is_empty() {
+        normalized.push('.');
+    } else if path.ends_with(is_separator) && !normalized.ends_with(separator) {
+        normalized.push(separator);
+    }
+    normalized
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{options::MatchOptions, path::normalize, GlobPattern};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn path_normalize() {
+        for (path, normalized) in [
+            ("", ""),
+            (".", "."),
+            ("./", "."),
+            ("src/lib.rs", "src/lib.rs"),
+            ("./src//lib.rs", "src/lib.rs"),
+            ("src/./lib.rs", "src/lib.rs"),
+            ("//src///", "/src/"),
+            ("/", "/"),
+            ("src/../lib.rs", "src/../lib.rs"),
+            (".config/./", ".config/"),
+        ] {
+            assert_eq!(normalize(path, '/'), normalized, "{path}");
+        }
+        assert_eq!(normalize("a::.::b", ':'), "a:b");
+        #[cfg(windows)]
+        assert_eq!(normalize(r".\src\\lib.rs", '/'), "src/lib.rs");
+        #[cfg(not(windows))]
+        assert_eq!(normalize(r".\src\\lib.rs", '/'), r".\src\\lib.rs");
+    }
+
+    #[test]
+    fn matches_path() {
+        let pattern = GlobPattern::new("src/*.rs").unwrap();
+        assert!(pattern.matches_path(Path::new("src/lib.rs")));
+        assert!(pattern.matches_path(PathBuf::from("src/main.rs")));
+        assert!(!pattern.matches_path(Path::new("src//lib.rs")));
+
+        let options = MatchOptions::new().normalize_paths(true);
+        let pattern = GlobPattern::new_with("src/*.rs", &options).unwrap();
+        assert!(pattern.matches_path(Path::new("./src/./lib.rs")));
+        assert!(pattern.matches_path(Path::new("src//lib.rs")));
+        assert!(!pattern.matches_path(Path::new("src/bin/main.rs")));
+        // Only paths are normalized
+        assert!(!pattern.matches("./src/lib.rs"));
+    }
+
+    #[test]
+    fn matches_dir_entries() {
+        let dir = std::env::temp_dir().join(format!("goglob-path-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        for name in ["lib.rs", "main.c"] {
+            std::fs::write(dir.join("src").join(name), "").unwrap();
+        }
+
+        let options = MatchOptions::new().normalize_paths(true);
+        let pattern = GlobPattern::new_with("*/src/*.rs", &options).unwrap();
+        let mut matched: Vec<_> = std::fs::read_dir(dir.join("src"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| pattern.matches_path(path.strip_prefix(dir.parent().unwrap()).unwrap()))
+            .collect();
+        matched.sort();
+        assert_eq!(matched, [dir.join("src").join("lib.rs")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,115 @@
+//! Cheap checks ruling out names before matching them against a pattern.
+
+use crate::{options::CaseFold, GlobPattern};
+use alloc::boxed::Box;
+
+/// Necessary conditions for a name to match a pattern of a
+/// [`GlobSet`][crate::set::GlobSet], computed when the pattern is added so
+/// that most patterns of large sets are skipped with a length and a couple of
+/// string comparisons instead of going through the token loop.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Prefilter {
+    /// Minimum length of matching names in characters, which is compared
+    /// against their length in bytes (never shorter).
+    min_len: usize,
+    /// Case-sensitive literal every matching name starts with.
+    prefix: Option<Box<str>>,
+    /// Case-sensitive literal every matching name ends with.
+    suffix: Option<Box<str>>,
+}
+impl Prefilter {
+    pub(crate) fn new(pattern: &GlobPattern) -> Self {
+        let sensitive = pattern.options().case_fold == CaseFold::Sensitive;
+        let literal = |literal: Option<&str>| literal.filter(|_| sensitive).map(Box::from);
+        Self {
+            min_len: pattern.min_len(),
+            prefix: literal(pattern.literal_prefix()),
+            suffix: literal(pattern.literal_suffix()),
+        }
+    }
+
+    /// Whether `name` may match the pattern.
+    #[inline]
+    pub(crate) fn admits(&self, name: &str) -> bool {
+        admits(
+            self.min_len,
+            self.prefix.as_deref(),
+            self.suffix.as_deref(),
+            name,
+        )
+    }
+
+    /// Whether `name` may match `pattern`, checking the same conditions as
+    /// its prefilter without building one, for patterns only matched a few
+    /// times.
+    pub(crate) fn admits_once(pattern: &GlobPattern, name: &str) -> bool {
+        let sensitive = pattern.options().case_fold == CaseFold::Sensitive;
+        // Literals are checked first, as computing the minimum length goes
+        // through every token.
+        admits(
+            0,
+            pattern.literal_prefix().filter(|_| sensitive),
+            pattern.literal_suffix().filter(|_| sensitive),
+            name,
+        ) && name.len() >= pattern.min_len()
+    }
+}
+
+#[inline]
+fn admits(min_len: usize, prefix: Option<&str>, suffix: Option<&str>, name: &str) -> bool {
+    name.len() >= min_len
+        && prefix.is_none_or(|p| name.starts_with(p))
+        && suffix.is_none_or(|s| name.ends_with(s))
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        options::{CaseFold, MatchOptions},
+        prefilter::Prefilter,
+        GlobPattern,
+    };
+
+    #[test]
+    fn prefilter_admits() {
+        let names = [
+            "",
+            "a",
+            "src/lib.rs",
+            "src/ä.rs",
+            "SRC/LIB.RS",
+            "lib.rs",
+            "src/.rs",
+        ];
+        let options = [
+            MatchOptions::new(),
+            MatchOptions::new().case_fold(CaseFold::Ascii),
+            MatchOptions::new().dotfiles(false),
+        ];
+        for options in options {
+            for pattern in [
+                "src/*.rs",
+                "*.rs",
+                "src/?.rs",
+                "src/*",
+                "*",
+                "[a-z]*/lib.rs",
+            ] {
+                let pattern = GlobPattern::new_with(pattern, &options).unwrap();
+                let prefilter = Prefilter::new(&pattern);
+                for name in names {
+                    let admitted = prefilter.admits(name);
+                    assert_eq!(Prefilter::admits_once(&pattern, name), admitted);
+                    if pattern.matches(name) {
+                        assert!(admitted, "{pattern:?} {name}");
+                    }
+                }
+            }
+        }
+
+        let prefilter = Prefilter::new(&GlobPattern::new("src/*.rs").unwrap());
+        assert!(!prefilter.admits("lib.rs"));
+        assert!(!prefilter.admits("src/lib.c"));
+        assert!(!prefilter.admits("src.rs"));
+    }
+}
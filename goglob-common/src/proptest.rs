@@ -0,0 +1,140 @@
+//! [proptest](https://docs.rs/proptest) strategies for property-testing rule
+//! handling with valid patterns and names matching them.
+
+use crate::{
+    charcls::{CharClass, CharClassType},
+    convert::escape,
+    literal::Literal,
+    options::MatchOptions,
+    GlobPattern, GlobToken,
+};
+use proptest::{collection::vec, prelude::*};
+
+/// Maximum number of tokens of generated patterns.
+const MAX_TOKENS: usize = 8;
+/// Maximum number of characters generated for each `*`.
+const MAX_SEQ_LEN: usize = 8;
+
+fn token() -> impl Strategy<Value = GlobToken> {
+    let range = (any::<char>(), any::<char>())
+        .prop_map(|(a, b)| CharClassType::try_from(a.min(b)..=a.max(b)).unwrap());
+    prop_oneof![
+        vec(any::<char>(), 1..4)
+            .prop_map(|chars| GlobToken::Literal(Literal::new(chars.into_iter().collect()))),
+        (any::<bool>(), vec(range, 1..4))
+            .prop_map(|(negated, types)| GlobToken::CharClass(CharClass::new(negated, types))),
+        Just(GlobToken::SeqWildcard),
+        Just(GlobToken::SingleWildcard),
+    ]
+}
+
+/// Strategy generating valid compiled patterns.
+pub fn glob_pattern() -> impl Strategy<Value = GlobPattern> {
+    vec(token(), 1..=MAX_TOKENS)
+        .prop_map(|tokens| GlobPattern::normalized(tokens, MatchOptions::new()))
+}
+
+/// Strategy generating syntactically valid pattern strings (i.e. for which
+/// [`GlobPattern::new()`] always succeeds).
+pub fn pattern_string() -> impl Strategy<Value = String> {
+    glob_pattern().prop_map(|pattern| {
+        let mut string = String::new();
+        for token in pattern.tokens() {
+            match token {
+                GlobToken::Literal(l) => string.push_str(&escape(l.as_ref())),
+                GlobToken::CharClass(cc) => string.push_str(&cc.to_string()),
+                GlobToken::SeqWildcard => string.push('*'),
+                GlobToken::SingleWildcard => string.push('?'),
+                GlobToken::AnyDirs => string.push_str("**/"),
+            }
+        }
+        string
+    })
+}
+
+fn non_slash() -> impl Strategy<Value = char> {
+    any::<char>().prop_map(|c| if c == '/' { '_' } else { c })
+}
+
+fn matching_token(token: &GlobToken) -> BoxedStrategy<String> {
+    match token {
+        GlobToken::Literal(l) => Just(l.as_ref().to_string()).boxed(),
+        GlobToken::SingleWildcard => non_slash().prop_map(String::from).boxed(),
+        GlobToken::SeqWildcard => vec(non_slash(), 0..=MAX_SEQ_LEN)
+            .prop_map(|chars| chars.into_iter().collect())
+            .boxed(),
+        GlobToken::AnyDirs => vec(vec(non_slash(), 0..=MAX_SEQ_LEN), 0..=2)
+            .prop_map(|dirs| {
+                dirs.into_iter()
+                    .flat_map(|dir| dir.into_iter().chain(['/']))
+                    .collect()
+            })
+            .boxed(),
+        GlobToken::CharClass(cc) if !cc.is_negated() => {
+            let ranges: Vec<_> = cc
+                .types()
+                .iter()
+                .map(|cct| {
+                    let range = cct.as_range();
+                    proptest::char::range(*range.start(), *range.end())
+                })
+                .collect();
+            proptest::strategy::Union::new(ranges)
+                .prop_map(String::from)
+                .boxed()
+        }
+        GlobToken::CharClass(cc) => {
+            let cc = cc.clone();
+            any::<char>()
+                .prop_filter("character excluded by negated class", move |c| {
+                    cc.matches_next(c.encode_utf8(&mut [0; 4])).is_some()
+                })
+                .prop_map(String::from)
+                .boxed()
+        }
+    }
+}
+
+/// Strategy generating names guaranteed to match `pattern`.
+///
+/// Negated character classes are satisfied by rejection sampling, so patterns
+/// whose negated classes exclude almost every character may be rejected by
+/// proptest for generating too few names. Names are also rejected if the
+/// pattern doesn't match them after all, as matching commits to the earliest
+/// position where a class following a `*` matches (e.g. `ab/c` is built from
+/// the tokens of `*[^x]*`, but not matched by it).
+pub fn matching_name(pattern: &GlobPattern) -> impl Strategy<Value = String> {
+    let tokens: Vec<_> = pattern.tokens().iter().map(matching_token).collect();
+    let pattern = pattern.clone();
+    tokens
+        .prop_map(|parts| parts.concat())
+        .prop_filter("name skipped by matching", move |name| {
+            pattern.matches(name)
+        })
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::proptest::{glob_pattern, matching_name, pattern_string};
+    use crate::GlobPattern;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn proptest_pattern_string(pattern in pattern_string()) {
+            prop_assert!(GlobPattern::new(&pattern).is_ok());
+        }
+
+        #[test]
+        fn proptest_matching_name(
+            (pattern, name) in glob_pattern().prop_flat_map(|p| (Just(p.clone()), matching_name(&p)))
+        ) {
+            prop_assert!(pattern.matches(&name));
+        }
+
+        #[test]
+        fn proptest_display_round_trip(pattern in glob_pattern()) {
+            prop_assert_eq!(GlobPattern::new(pattern.to_string()).unwrap(), pattern);
+        }
+    }
+}
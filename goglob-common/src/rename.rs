@@ -0,0 +1,191 @@
+use crate::{
+    error::{Error, ErrorType},
+    GlobPattern, Result,
+};
+use alloc::{string::String, vec::Vec};
+
+/// Rewrites names matching a source pattern according to a destination
+/// template, filling it with the text captured by the source's wildcards (see
+/// [`GlobPattern::captures()`]).
+///
+/// The template syntax is:
+///
+/// ```text
+/// '*' or '?'    the next capture, in order (e.g. '*.jpeg' to '*.jpg')
+/// '{' n '}'     the n-th capture, starting at 1 ('{0}' is the whole name)
+/// '{' name '}'  the capture with the given name (see with_names())
+/// '\\' c        character c
+/// c             character c
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GlobRenamer {
+    source: GlobPattern,
+    template: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum TemplatePart {
+    Literal(String),
+    Capture(usize),
+    Whole,
+}
+
+impl GlobRenamer {
+    /// Parse `template` and pair it with `source`, returning an
+    /// [error][crate::error::Error] if the template is syntactically invalid or
+    /// refers to captures `source` doesn't have.
+    #[inline]
+    pub fn new<S: AsRef<str>>(source: GlobPattern, template: S) -> Result<Self> {
+        Self::with_names(source, core::iter::empty::<&str>(), template)
+    }
+
+    /// Same as [`new()`][Self::new], additionally naming the captures of
+    /// `source` in order so that the template can refer to them by name (e.g.
+    /// `{stem}`).
+    pub fn with_names<I, N, S>(source: GlobPattern, names: I, template: S) -> Result<Self>
+    where
+        I: IntoIterator<Item = N>,
+        N: AsRef<str>,
+        S: AsRef<str>,
+    {
+        let names: Vec<N> = names.into_iter().collect();
+        let names: Vec<&str> = names.iter().map(AsRef::as_ref).collect();
+        let template = parse_template(template.as_ref(), capture_count(&source), &names)?;
+        Ok(Self { source, template })
+    }
+
+    /// Source pattern names must match to be renamed.
+    #[inline]
+    pub fn source(&self) -> &GlobPattern {
+        &self.source
+    }
+
+    /// Rewrite `name` according to the template, or return `None` if it
+    /// doesn't match the source pattern.
+    pub fn rename<S: AsRef<str>>(&self, name: S) -> Option<String> {
+        let name = name.as_ref();
+        let captures = self.source.captures(name)?;
+        let mut result = String::with_capacity(name.len());
+        for part in &self.template {
+            match part {
+                TemplatePart::Literal(l) => result.push_str(l),
+                TemplatePart::Capture(i) => result.push_str(captures[*i]),
+                TemplatePart::Whole => result.push_str(name),
+            }
+        }
+        Some(result)
+    }
+}
+
+fn capture_count(pattern: &GlobPattern) -> usize {
+    pattern
+        .tokens()
+        .iter()
+        .filter(|token| !matches!(token, crate::GlobToken::Literal(_)))
+        .count()
+}
+
+fn parse_template(template: &str, captures: usize, names: &[&str]) -> Result<Vec<TemplatePart>> {
+    if template.is_empty() {
+        return Err(Error::empty_pattern());
+    }
+
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut next_capture = 0;
+    let mut template_iter = template.char_indices();
+    while let Some((i, c)) = template_iter.next() {
+        let part = match c {
+            '\\' => {
+                let (_, escaped_char) = template_iter
+                    .next()
+                    .ok_or_else(|| Error::new(ErrorType::IllegalEscape, i))?;
+                literal.push(escaped_char);
+                continue;
+            }
+            '*' | '?' => {
+                next_capture += 1;
+                (next_capture <= captures)
+                    .then_some(TemplatePart::Capture(next_capture - 1))
+                    .ok_or_else(|| Error::new(ErrorType::InvalidPlaceholder, i))?
+            }
+            '{' => {
+                let placeholder: String = template_iter
+                    .by_ref()
+                    .map(|(_, c)| c)
+                    .take_while(|c| *c != '}')
+                    .collect();
+                // take_while() consumes the closing '}', so check it was there.
+                if !template[i..].contains('}') {
+                    return Err(Error::new(ErrorType::UnclosedPlaceholder, i));
+                }
+                let index = match placeholder.parse::<usize>() {
+                    Ok(index) => Some(index),
+                    Err(_) => names
+                        .iter()
+                        .position(|name| *name == placeholder)
+                        .map(|index| index + 1),
+                };
+                match index {
+                    Some(0) => TemplatePart::Whole,
+                    Some(index) if index <= captures => TemplatePart::Capture(index - 1),
+                    _ => return Err(Error::new(ErrorType::InvalidPlaceholder, i)),
+                }
+            }
+            c => {
+                literal.push(c);
+                continue;
+            }
+        };
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(core::mem::take(&mut literal)));
+        }
+        parts.push(part);
+    }
+    if !literal.is_empty() {
+        parts.push(TemplatePart::Literal(literal));
+    }
+    Ok(parts)
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::rename::GlobRenamer;
+    use crate::GlobPattern;
+
+    fn renamer(source: &str, template: &str) -> GlobRenamer {
+        GlobRenamer::new(GlobPattern::new(source).unwrap(), template).unwrap()
+    }
+
+    #[test]
+    fn glob_renamer_rename() {
+        let renamer_jpg = renamer("*.jpeg", "*.jpg");
+        assert_eq!(renamer_jpg.rename("cat.jpeg"), Some("cat.jpg".into()));
+        assert_eq!(renamer_jpg.rename("cat.png"), None);
+
+        let swap = renamer("*_?[0-9].*", "{4}/{1}-{3}{2}.\\*");
+        assert_eq!(swap.rename("img_a1.png"), Some("png/img-1a.*".into()));
+
+        let whole = renamer("*.bak", "old/{0}");
+        assert_eq!(whole.rename("a.bak"), Some("old/a.bak".into()));
+
+        let source = GlobPattern::new("*.*").unwrap();
+        let named = GlobRenamer::with_names(source, ["stem", "ext"], "{ext}/{stem}").unwrap();
+        assert_eq!(named.rename("lib.rs"), Some("rs/lib".into()));
+    }
+
+    #[test]
+    fn glob_renamer_errors() {
+        let error = |template: &str| {
+            GlobRenamer::new(GlobPattern::new("*.*").unwrap(), template)
+                .err()
+                .unwrap()
+                .position()
+        };
+        assert_eq!(error("*/*/*"), 4);
+        assert_eq!(error("a{3}"), 1);
+        assert_eq!(error("a{name}"), 1);
+        assert_eq!(error("ab{1"), 2);
+        assert_eq!(error("ab\\"), 2);
+    }
+}
@@ -0,0 +1,260 @@
+//! Rule files attaching an action (or any other metadata) to each pattern,
+//! e.g. for policy files, loaded into a [`GlobMap`].
+
+use crate::{error::Error, map::GlobMap, GlobPattern};
+use alloc::string::{String, ToString};
+use core::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+#[cfg(feature = "json")]
+use serde::{de::DeserializeOwned, Deserialize};
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
+
+/// Error found while parsing a rule file, along with the (1-based) number of
+/// the offending line.
+#[derive(Debug)]
+pub struct RuleFileError {
+    line: usize,
+    kind: RuleFileErrorKind,
+}
+impl RuleFileError {
+    /// Number of the offending line, starting at 1.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    #[inline]
+    pub fn kind(&self) -> &RuleFileErrorKind {
+        &self.kind
+    }
+}
+impl Display for RuleFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+#[cfg(feature = "std")]
+impl StdError for RuleFileError {}
+
+/// What's wrong with a line of a rule file.
+#[derive(Debug)]
+pub enum RuleFileErrorKind {
+    /// The line has no tab separating the pattern from its action.
+    MissingAction,
+    /// The pattern is syntactically invalid.
+    Pattern(Error),
+    /// The action couldn't be parsed, described by its parser.
+    Action(String),
+    /// The line isn't a valid JSON object with a `pattern` field and the
+    /// action's fields, described by the JSON parser.
+    Json(String),
+}
+impl Display for RuleFileErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            RuleFileErrorKind::MissingAction => f.write_str("no tab before the action"),
+            RuleFileErrorKind::Pattern(e) => write!(f, "invalid pattern: {e}"),
+            RuleFileErrorKind::Action(e) => write!(f, "invalid action: {e}"),
+            RuleFileErrorKind::Json(e) => write!(f, "invalid JSON: {e}"),
+        }
+    }
+}
+
+/// Line of a JSON-lines rule file: the pattern, and the action made of the
+/// other fields.
+#[cfg(feature = "json")]
+#[derive(Deserialize)]
+struct JsonRule<A> {
+    pattern: String,
+    #[serde(flatten)]
+    action: A,
+}
+
+impl<A> GlobMap<A> {
+    /// Parse a rule file in which each line is a pattern and its action
+    /// separated by a tab (`pattern<TAB>action`), in order of precedence.
+    ///
+    /// Blank lines and lines starting with `#` are skipped. Actions are parsed
+    /// with [`FromStr`], and the first line which can't be parsed is reported
+    /// along with its number.
+    ///
+    /// ```
+    /// use goglob_common::map::GlobMap;
+    ///
+    /// let policy = "# Uploads\n/uploads/*.exe\tdeny\n/uploads/*\tallow\n";
+    /// let rules: GlobMap<String> = GlobMap::parse_rules(policy).unwrap();
+    /// assert_eq!(rules.get("/uploads/setup.exe").unwrap(), "deny");
+    /// assert_eq!(rules.get("/uploads/photo.jpg").unwrap(), "allow");
+    ///
+    /// let error = GlobMap::<String>::parse_rules("*.rs\tallow\n[a\tdeny\n").unwrap_err();
+    /// assert_eq!(error.line(), 2);
+    /// ```
+    pub fn parse_rules<S: AsRef<str>>(contents: S) -> Result<Self, RuleFileError>
+    where
+        A: FromStr,
+        A::Err: Display,
+    {
+        Self::parse_lines(contents.as_ref(), |line| {
+            let (pattern, action) = line
+                .split_once('\t')
+                .ok_or(RuleFileErrorKind::MissingAction)?;
+            let pattern = GlobPattern::new(pattern).map_err(RuleFileErrorKind::Pattern)?;
+            let action = action
+                .parse()
+                .map_err(|e: A::Err| RuleFileErrorKind::Action(e.to_string()))?;
+            Ok((pattern, action))
+        })
+    }
+
+    /// Parse a JSON-lines rule file, in which each line is an object with a
+    /// `pattern` field and the fields of its action, in order of precedence.
+    ///
+    /// Blank lines and lines starting with `#` are skipped, and the first line
+    /// which can't be parsed is reported along with its number.
+    ///
+    /// ```
+    /// use goglob_common::map::GlobMap;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Action {
+    ///     allow: bool,
+    /// }
+    ///
+    /// let policy = r#"{"pattern": "/uploads/*.exe", "allow": false}
+    /// {"pattern": "/uploads/*", "allow": true}"#;
+    /// let rules: GlobMap<Action> = GlobMap::parse_json_rules(policy).unwrap();
+    /// assert!(!rules.get("/uploads/setup.exe").unwrap().allow);
+    /// assert!(rules.get("/uploads/photo.jpg").unwrap().allow);
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn parse_json_rules<S: AsRef<str>>(contents: S) -> Result<Self, RuleFileError>
+    where
+        A: DeserializeOwned,
+    {
+        Self::parse_lines(contents.as_ref(), |line| {
+            let rule: JsonRule<A> =
+                serde_json::from_str(line).map_err(|e| RuleFileErrorKind::Json(e.to_string()))?;
+            let pattern = GlobPattern::new(rule.pattern).map_err(RuleFileErrorKind::Pattern)?;
+            Ok((pattern, rule.action))
+        })
+    }
+
+    /// Parse every line of `contents` which isn't blank nor a `#` comment into
+    /// an entry with `parse`.
+    fn parse_lines<F>(contents: &str, mut parse: F) -> Result<Self, RuleFileError>
+    where
+        F: FnMut(&str) -> Result<(GlobPattern, A), RuleFileErrorKind>,
+    {
+        let mut map = GlobMap::new();
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (pattern, action) = parse(line).map_err(|kind| RuleFileError {
+                line: index + 1,
+                kind,
+            })?;
+            map.insert(pattern, action);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        map::GlobMap,
+        rulefile::{RuleFileError, RuleFileErrorKind},
+    };
+    use alloc::string::{String, ToString};
+
+    #[derive(Debug, PartialEq)]
+    enum Action {
+        Allow,
+        Deny,
+    }
+    impl core::str::FromStr for Action {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "allow" => Ok(Action::Allow),
+                "deny" => Ok(Action::Deny),
+                _ => Err(alloc::format!("unknown action {s:?}")),
+            }
+        }
+    }
+
+    fn parse(contents: &str) -> Result<GlobMap<Action>, RuleFileError> {
+        GlobMap::parse_rules(contents)
+    }
+
+    #[test]
+    fn rule_file_parse() {
+        let rules = parse("# Policy\n\n*.exe\tdeny\r\n\n*\tallow\n").unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules.get("setup.exe"), Some(&Action::Deny));
+        assert_eq!(rules.get("photo.jpg"), Some(&Action::Allow));
+        assert_eq!(rules.get("a/b"), None);
+        // Only the first tab separates the action.
+        let rules: GlobMap<String> = GlobMap::parse_rules("a b\tc\td").unwrap();
+        assert_eq!(rules.get("a b").unwrap(), "c\td");
+    }
+
+    #[test]
+    fn rule_file_errors() {
+        let error = parse("*.exe\tdeny\n\n*.rs\n").unwrap_err();
+        assert_eq!(error.line(), 3);
+        assert!(matches!(error.kind(), RuleFileErrorKind::MissingAction));
+        let error = parse("[a\tdeny").unwrap_err();
+        assert_eq!(error.line(), 1);
+        assert!(matches!(error.kind(), RuleFileErrorKind::Pattern(_)));
+        let error = parse("*\tallow\n*\tmaybe\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: invalid action: unknown action \"maybe\""
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn rule_file_parse_json() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct JsonAction {
+            allow: bool,
+            #[serde(default)]
+            reason: Option<String>,
+        }
+
+        let contents = concat!(
+            r#"{"pattern": "*.exe", "allow": false, "reason": "executable"}"#,
+            "\n\n",
+            r#"{"allow": true, "pattern": "*"}"#,
+        );
+        let rules: GlobMap<JsonAction> = GlobMap::parse_json_rules(contents).unwrap();
+        assert_eq!(
+            rules.get("setup.exe"),
+            Some(&JsonAction {
+                allow: false,
+                reason: Some("executable".to_string())
+            })
+        );
+        assert!(rules.get("photo.jpg").unwrap().allow);
+
+        let error = GlobMap::<JsonAction>::parse_json_rules("{\"pattern\": \"*\"}").unwrap_err();
+        assert_eq!(error.line(), 1);
+        assert!(matches!(error.kind(), RuleFileErrorKind::Json(_)));
+        let error = GlobMap::<JsonAction>::parse_json_rules(concat!(
+            r#"{"pattern": "*", "allow": true}"#,
+            "\n",
+            r#"{"pattern": "[a", "allow": true}"#,
+        ))
+        .unwrap_err();
+        assert_eq!(error.line(), 2);
+        assert!(matches!(error.kind(), RuleFileErrorKind::Pattern(_)));
+    }
+}
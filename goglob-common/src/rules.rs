@@ -0,0 +1,395 @@
+//! Ordered rule lists in which the last matching rule wins, as in
+//! `.gitignore`.
+
+use crate::{coverage::Coverage, GlobPattern};
+#[cfg(feature = "parser")]
+use crate::{
+    error::{Error, ErrorType},
+    options::MatchOptions,
+    Result,
+};
+use alloc::vec::Vec;
+use core::slice::Iter;
+
+/// Single rule of a [`RuleSet`]: a pattern selecting the names it matches,
+/// or deselecting them if negated (`!pattern` in `.gitignore`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Rule {
+    pattern: GlobPattern,
+    negated: bool,
+}
+impl Rule {
+    /// Rule selecting (or deselecting if `negated`) the names matching
+    /// `pattern`.
+    #[inline]
+    pub fn new(pattern: GlobPattern, negated: bool) -> Self {
+        Self { pattern, negated }
+    }
+
+    #[inline]
+    pub fn pattern(&self) -> &GlobPattern {
+        &self.pattern
+    }
+
+    /// Whether the rule deselects the names it matches.
+    #[inline]
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+}
+
+/// Rule shadowed by a later one, as returned by
+/// [`RuleSet::find_shadowed()`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Shadowed {
+    rule: usize,
+    by: usize,
+}
+impl Shadowed {
+    /// Index of the rule which never takes effect.
+    #[inline]
+    pub fn rule(&self) -> usize {
+        self.rule
+    }
+
+    /// Index of the (first) later rule matching every name the shadowed rule
+    /// matches.
+    #[inline]
+    pub fn shadowed_by(&self) -> usize {
+        self.by
+    }
+}
+
+/// Ordered list of rules deciding whether names are selected (e.g. ignored):
+/// a name is selected if the last rule matching it isn't negated, so later
+/// rules take precedence over earlier ones.
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use goglob_common::{rules::{Rule, RuleSet}, GlobPattern};
+///
+/// let rules: RuleSet = [("*.log", false), ("keep.log", true)]
+///     .into_iter()
+///     .map(|(p, negated)| Rule::new(GlobPattern::new(p).unwrap(), negated))
+///     .collect();
+/// assert!(rules.matches("debug.log"));
+/// assert!(!rules.matches("keep.log"));
+/// assert!(!rules.matches("lib.rs"));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+impl RuleSet {
+    /// Empty rule set, selecting nothing.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of rules.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether there are no rules.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Rule at `index`, if any.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Rule> {
+        self.rules.get(index)
+    }
+
+    /// Iterate over the rules in order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, Rule> {
+        self.rules.iter()
+    }
+
+    /// Add a new rule after every other one (thus taking precedence over
+    /// them), returning its index.
+    pub fn push(&mut self, rule: Rule) -> usize {
+        self.rules.push(rule);
+        self.rules.len() - 1
+    }
+
+    /// Index of the rule deciding whether `name` is selected, i.e. the last
+    /// one matching it, if any.
+    pub fn matching_rule<S: AsRef<str>>(&self, name: S) -> Option<usize> {
+        let name = name.as_ref();
+        self.rules
+            .iter()
+            .rposition(|rule| rule.pattern.matches(name))
+    }
+
+    /// Same as [`matching_rule()`][Self::matching_rule] for the `name` of a
+    /// directory (if `is_dir`) or of any other file, directory-only rules
+    /// only matching directories (see [`GlobPattern::matches_entry()`]).
+    pub fn matching_rule_entry<S: AsRef<str>>(&self, name: S, is_dir: bool) -> Option<usize> {
+        let name = name.as_ref();
+        self.rules
+            .iter()
+            .rposition(|rule| rule.pattern.matches_entry(name, is_dir))
+    }
+
+    /// Report whether `name` is selected, i.e. the last rule matching it
+    /// isn't negated.
+    pub fn matches<S: AsRef<str>>(&self, name: S) -> bool {
+        self.matching_rule(name)
+            .is_some_and(|index| !self.rules[index].negated)
+    }
+
+    /// Same as [`matches()`][Self::matches] for the `name` of a directory (if
+    /// `is_dir`) or of any other file.
+    pub fn matches_entry<S: AsRef<str>>(&self, name: S, is_dir: bool) -> bool {
+        self.matching_rule_entry(name, is_dir)
+            .is_some_and(|index| !self.rules[index].negated)
+    }
+
+    /// Find the rules which never take effect because a later rule matches
+    /// every name they match (e.g. `*.log` after `debug.log`), in ascending
+    /// order.
+    ///
+    /// Rules are compared by the names their patterns match (see
+    /// [`GlobPattern::contains()`]), regardless of whether they're negated:
+    /// a shadowed rule is useless either way. As with `contains()`, rules
+    /// with a pattern such as `*[^x]*` only shadow or are shadowed by rules
+    /// with the same pattern. Each pair of rules is checked, so this takes
+    /// quadratic time in the number of rules.
+    pub fn find_shadowed(&self) -> Vec<Shadowed> {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter_map(|(rule, shadowed)| {
+                let by = self.rules[rule + 1..]
+                    .iter()
+                    .position(|later| later.pattern.contains(&shadowed.pattern))?;
+                Some(Shadowed {
+                    rule,
+                    by: rule + 1 + by,
+                })
+            })
+            .collect()
+    }
+
+    /// Count how many of `names` the pattern of each rule matches (see
+    /// [`GlobSet::coverage()`][crate::set::GlobSet::coverage]).
+    pub fn coverage<I, S>(&self, names: I) -> Coverage
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Coverage::of(self.rules.iter().map(Rule::pattern), names)
+    }
+}
+impl Extend<Rule> for RuleSet {
+    fn extend<T: IntoIterator<Item = Rule>>(&mut self, iter: T) {
+        self.rules.extend(iter)
+    }
+}
+impl FromIterator<Rule> for RuleSet {
+    fn from_iter<T: IntoIterator<Item = Rule>>(iter: T) -> Self {
+        let mut rules: Vec<_> = iter.into_iter().collect();
+        rules.shrink_to_fit();
+        Self { rules }
+    }
+}
+
+impl GlobPattern {
+    /// Parse a list of patterns separated by `delimiter` (e.g.
+    /// `"a,*.rs , !target/**"`), as commonly found in environment variables,
+    /// into a [`RuleSet`].
+    ///
+    /// Surrounding whitespace is trimmed from every item and empty items are
+    /// skipped. Items starting with `!` are negated rules. A `delimiter`
+    /// preceded by `\` is part of the pattern rather than separating it from
+    /// the next one (as is a leading `!`, the pattern then matching it
+    /// literally). Error positions are byte offsets into `list`.
+    ///
+    /// ```
+    /// use goglob_common::GlobPattern;
+    ///
+    /// let rules = GlobPattern::parse_list("*.rs , !target/*,", ',').unwrap();
+    /// assert_eq!(rules.len(), 2);
+    /// assert!(rules.matches("lib.rs"));
+    /// assert!(!rules.matches("target/lib.rs"));
+    ///
+    /// let error = GlobPattern::parse_list("*.rs, a[", ',').unwrap_err();
+    /// assert_eq!(error.position(), 7);
+    /// ```
+    #[cfg(feature = "parser")]
+    #[inline]
+    pub fn parse_list<S: AsRef<str>>(list: S, delimiter: char) -> Result<RuleSet> {
+        Self::parse_list_with(list, delimiter, &MatchOptions::new())
+    }
+
+    /// Same as [`parse_list()`][Self::parse_list], parsing and matching
+    /// every pattern according to `options`.
+    #[cfg(feature = "parser")]
+    pub fn parse_list_with<S: AsRef<str>>(
+        list: S,
+        delimiter: char,
+        options: &MatchOptions,
+    ) -> Result<RuleSet> {
+        let list = list.as_ref();
+        let escapes = options.effective_escapes();
+        let mut rules = RuleSet::new();
+        let mut start = 0;
+        let mut escaped = false;
+        for (i, c) in list.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == options.escape_char && escapes {
+                escaped = true;
+            } else if c == delimiter {
+                rules.extend(parse_item(list, start, i, options)?);
+                start = i + c.len_utf8();
+            }
+        }
+        rules.extend(parse_item(list, start, list.len(), options)?);
+        Ok(rules)
+    }
+}
+
+/// Parse the item at `start..end` of `list`, if it isn't empty.
+#[cfg(feature = "parser")]
+fn parse_item(
+    list: &str,
+    start: usize,
+    end: usize,
+    options: &MatchOptions,
+) -> Result<Option<Rule>> {
+    let item = &list[start..end];
+    let trimmed = item.trim_start();
+    let mut offset = start + item.len() - trimmed.len();
+    let mut pattern = trimmed.trim_end();
+    if pattern.is_empty() {
+        return Ok(None);
+    }
+    let negated = pattern.starts_with('!');
+    if negated {
+        pattern = &pattern[1..];
+        offset += 1;
+        if pattern.is_empty() {
+            return Err(Error::new(ErrorType::EmptyPattern, offset));
+        }
+    }
+    GlobPattern::new_with(pattern, options)
+        .map(|pattern| Some(Rule::new(pattern, negated)))
+        .map_err(|e| e.offset_by(offset))
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        error::ErrorType,
+        options::MatchOptions,
+        rules::{Rule, RuleSet},
+        GlobPattern,
+    };
+
+    fn rules(rules: &[&str]) -> RuleSet {
+        rules
+            .iter()
+            .map(|rule| match rule.strip_prefix('!') {
+                Some(pattern) => Rule::new(GlobPattern::new(pattern).unwrap(), true),
+                None => Rule::new(GlobPattern::new(rule).unwrap(), false),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rule_set_matches() {
+        let rules = rules(&["*.log", "!keep*.log", "keep-not.log", "build/"]);
+        assert!(rules.matches("debug.log"));
+        assert!(!rules.matches("keep.log"));
+        assert!(rules.matches("keep-not.log"));
+        assert!(!rules.matches("lib.rs"));
+        assert_eq!(rules.matching_rule("keep.log"), Some(1));
+        assert_eq!(rules.matching_rule("lib.rs"), None);
+
+        assert!(rules.matches_entry("build", true));
+        assert!(!rules.matches_entry("build", false));
+        assert_eq!(rules.matching_rule_entry("build", true), Some(3));
+        assert!(RuleSet::new().is_empty());
+        assert!(!RuleSet::new().matches("a"));
+    }
+
+    #[test]
+    fn parse_list() {
+        let rules = GlobPattern::parse_list(" a,*.rs , !target/*,,\t", ',').unwrap();
+        let expected = [("a", false), ("*.rs", false), ("target/*", true)];
+        assert_eq!(rules.len(), expected.len());
+        for (rule, (pattern, negated)) in rules.iter().zip(expected) {
+            assert_eq!(rule.pattern(), &GlobPattern::new(pattern).unwrap());
+            assert_eq!(rule.is_negated(), negated);
+        }
+        assert!(GlobPattern::parse_list(" , ", ',').unwrap().is_empty());
+
+        let rules = GlobPattern::parse_list("a\\;b;\\!c;d\\\\", ';').unwrap();
+        assert_eq!(rules.len(), 3);
+        assert!(rules.matches("a;b"));
+        assert!(rules.matches("!c"));
+        assert!(rules.matches("d\\"));
+    }
+
+    #[test]
+    fn parse_list_errors() {
+        let error = GlobPattern::parse_list("*.rs, !src/[a", ',').unwrap_err();
+        assert_eq!(
+            error.error_type().type_desc(),
+            ErrorType::UnclosedCharClass.type_desc()
+        );
+        assert_eq!(error.position(), 11);
+        let error = GlobPattern::parse_list("é,  ! ,a", ',').unwrap_err();
+        assert_eq!(error.position(), 6);
+        let error = GlobPattern::parse_list("a;b\\", ';').unwrap_err();
+        assert_eq!(error.position(), 3);
+
+        let options = MatchOptions::new().escapes(false);
+        let rules = GlobPattern::parse_list_with("a\\,b", ',', &options).unwrap();
+        assert_eq!(rules.len(), 2);
+        assert!(rules.matches("a\\"));
+    }
+
+    #[test]
+    fn rule_set_find_shadowed() {
+        let rules = rules(&[
+            "debug.log",
+            "!src/*.rs",
+            "*.log",
+            "src/*",
+            "!tmp/*",
+            "tmp/a",
+        ]);
+        let shadowed: Vec<_> = rules
+            .find_shadowed()
+            .iter()
+            .map(|s| (s.rule(), s.shadowed_by()))
+            .collect();
+        assert_eq!(shadowed, vec![(0, 2), (1, 3)]);
+        assert_eq!(
+            rules.coverage(["src/lib.rs", "a.log"]).hits(),
+            [0, 1, 1, 1, 0, 0]
+        );
+    }
+
+    #[test]
+    fn rule_set_find_shadowed_inexact() {
+        // Matching "ab/c" commits "[^x]" to "a", leaving "b/c" to the last "*"
+        let rules = rules(&["ab/c", "*[^x]*", "*[^x]*"]);
+        assert!(rules.matches("ab/c"));
+        let shadowed: Vec<_> = rules
+            .find_shadowed()
+            .iter()
+            .map(|s| (s.rule(), s.shadowed_by()))
+            .collect();
+        assert_eq!(shadowed, vec![(1, 2)]);
+    }
+}
@@ -0,0 +1,64 @@
+//! Pattern scanner shared by every pattern representation.
+//!
+//! The scanner runs the [`PatternLexer`] over the pattern and reports each
+//! element to a [`ScanSink`], which decides how (and whether) to store it.
+//! This keeps the scanner itself free of any allocation.
+
+use crate::{
+    error::Error,
+    lexer::{PatternLexer, Token},
+    options::MatchOptions,
+    Result,
+};
+
+/// Receiver of the elements of a pattern, in order, as it's scanned.
+///
+/// Consecutive calls to `literal_char()` form a single literal, terminated by
+/// `end_literal()`. Likewise, a character class starts with `start_class()`
+/// and ends with `end_class()`, with every entry reported in between.
+///
+/// Every method may fail (e.g. when running out of capacity), which aborts the
+/// scan with the given error.
+pub(crate) trait ScanSink {
+    fn seq_wildcard(&mut self, pos: usize) -> Result<()>;
+    /// `**/` in [`Syntax::Gitignore`], which matches any number of directories.
+    fn any_dirs(&mut self, pos: usize) -> Result<()>;
+    fn single_wildcard(&mut self, pos: usize) -> Result<()>;
+    fn literal_char(&mut self, c: char, pos: usize) -> Result<()>;
+    fn end_literal(&mut self) -> Result<()>;
+    fn start_class(&mut self, negated: bool, pos: usize) -> Result<()>;
+    fn class_char(&mut self, c: char, pos: usize) -> Result<()>;
+    fn class_range(&mut self, start: char, end: char, pos: usize) -> Result<()>;
+    fn end_class(&mut self, pos: usize) -> Result<()>;
+}
+
+/// Validate `pattern` according to `options`, reporting its elements to
+/// `sink`.
+pub(crate) fn scan<S: ScanSink>(pattern: &str, options: &MatchOptions, sink: &mut S) -> Result<()> {
+    if let Some(c) = options.conflicting_metachar() {
+        return Err(Error::conflicting_metachar(c));
+    }
+    let mut literal = false;
+    for token in PatternLexer::new_with(pattern, options) {
+        let (token, span) = token?;
+        if literal && !matches!(token, Token::Literal(_)) {
+            sink.end_literal()?;
+        }
+        literal = matches!(token, Token::Literal(_));
+        let pos = span.start;
+        match token {
+            Token::SeqWildcard => sink.seq_wildcard(pos)?,
+            Token::AnyDirs => sink.any_dirs(pos)?,
+            Token::SingleWildcard => sink.single_wildcard(pos)?,
+            Token::Literal(c) => sink.literal_char(c, pos)?,
+            Token::ClassStart { negated } => sink.start_class(negated, pos)?,
+            Token::ClassChar(c) => sink.class_char(c, pos)?,
+            Token::ClassRange(start, end) => sink.class_range(start, end, pos)?,
+            Token::ClassEnd => sink.end_class(pos)?,
+        }
+    }
+    if literal {
+        sink.end_literal()?;
+    }
+    Ok(())
+}
@@ -1,5 +1,10 @@
-use crate::GlobPattern;
-use serde::{de::Error, Deserialize, Deserializer};
+use crate::{map::GlobMap, set::GlobSet, GlobPattern};
+use alloc::{string::String, vec::Vec};
+use core::{fmt::Formatter, marker::PhantomData};
+use serde::{
+    de::{Error, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
 
 impl<'de> Deserialize<'de> for GlobPattern {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -10,3 +15,74 @@ impl<'de> Deserialize<'de> for GlobPattern {
         GlobPattern::new(string).map_err(D::Error::custom)
     }
 }
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for GlobMap<V> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(GlobMapVisitor(PhantomData))
+    }
+}
+
+/// Collects the entries of a map keyed by pattern strings in the order
+/// they're read.
+struct GlobMapVisitor<V>(PhantomData<V>);
+impl<'de, V: Deserialize<'de>> Visitor<'de> for GlobMapVisitor<V> {
+    type Value = GlobMap<V>;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("a map keyed by shell patterns")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut map = GlobMap::new();
+        while let Some((pattern, value)) = access.next_entry()? {
+            map.insert(pattern, value);
+        }
+        Ok(map)
+    }
+}
+
+/// Either a single pattern string or a sequence of them, as config formats
+/// use both shapes for the same field (e.g. `"*.rs"` or `["*.rs", "*.toml"]`).
+impl<'de> Deserialize<'de> for GlobSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(GlobSetVisitor)
+    }
+}
+
+/// Collects either a single pattern string or a sequence of them.
+struct GlobSetVisitor;
+impl<'de> Visitor<'de> for GlobSetVisitor {
+    type Value = GlobSet;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("a shell pattern or a sequence of shell patterns")
+    }
+
+    fn visit_str<E>(self, pattern: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        let pattern = GlobPattern::new(pattern).map_err(E::custom)?;
+        Ok(GlobSet::new(alloc::vec![pattern]))
+    }
+
+    fn visit_seq<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut patterns = Vec::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some(pattern) = access.next_element::<GlobPattern>()? {
+            patterns.push(pattern);
+        }
+        Ok(GlobSet::new(patterns))
+    }
+}
@@ -0,0 +1,620 @@
+#[cfg(feature = "prefilter")]
+use crate::prefilter::Prefilter;
+#[cfg(feature = "parser")]
+use crate::Result;
+use crate::{
+    charcls::ClassTables,
+    options::{CaseFold, MatchOptions},
+    GlobPattern, GlobToken,
+};
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::{BitOr, BitOrAssign},
+    slice::Iter,
+};
+#[cfg(feature = "std")]
+use std::{error::Error as StdError, path::Path};
+
+/// Collection of shell patterns matched together against a single name.
+///
+/// Patterns keep the position they were inserted in, which is used as their
+/// index when reporting which patterns matched. Character classes listing the
+/// same entries in different patterns share a single table (see
+/// [`intern_classes()`][Self::intern_classes]).
+///
+/// With the `prefilter` feature, names are first checked against the length
+/// and literal prefix and suffix of each pattern, skipping most patterns of
+/// large sets without matching them.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct GlobSet {
+    patterns: Vec<GlobPattern>,
+    /// Prefilter of the pattern at the same index.
+    #[cfg(feature = "prefilter")]
+    prefilters: Vec<Prefilter>,
+}
+impl GlobSet {
+    /// Build a [`GlobSet`][Self] from already compiled patterns.
+    #[inline]
+    pub fn new<I: IntoIterator<Item = GlobPattern>>(patterns: I) -> Self {
+        patterns.into_iter().collect()
+    }
+
+    /// Same as [`new()`][Self::new], checking that every pattern was compiled
+    /// with `options` (e.g. that a set meant to be case-insensitive doesn't
+    /// contain a case-sensitive pattern), or returning the first one which
+    /// wasn't.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::{
+    ///     options::{CaseFold, MatchOptions},
+    ///     set::GlobSet,
+    ///     GlobPattern,
+    /// };
+    ///
+    /// let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+    /// let patterns = [
+    ///     GlobPattern::new_with("*.rs", &options).unwrap(),
+    ///     GlobPattern::new("*.TOML").unwrap(),
+    /// ];
+    /// let err = GlobSet::new_with(patterns.clone(), &options).unwrap_err();
+    /// assert_eq!(err.index(), 1);
+    ///
+    /// let set = GlobSet::new_with(patterns[..1].to_vec(), &options).unwrap();
+    /// assert_eq!(set.options(), Some(&options));
+    /// # }
+    /// ```
+    pub fn new_with<I>(
+        patterns: I,
+        options: &MatchOptions,
+    ) -> core::result::Result<Self, OptionsMismatch>
+    where
+        I: IntoIterator<Item = GlobPattern>,
+    {
+        let patterns: Vec<_> = patterns.into_iter().collect();
+        if let Some(index) = patterns.iter().position(|p| p.options() != options) {
+            return Err(OptionsMismatch {
+                index,
+                options: *patterns[index].options(),
+            });
+        }
+        Ok(Self::from_patterns(patterns))
+    }
+
+    /// Compile every pattern in `patterns`, returning a [`GlobSet`][Self] on
+    /// success or the [error][crate::error::Error] of the first syntactically
+    /// invalid pattern.
+    #[cfg(feature = "parser")]
+    pub fn compile<I, S>(patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::compile_with(patterns, &MatchOptions::new())
+    }
+
+    /// Same as [`compile()`][Self::compile], compiling every pattern according
+    /// to `options`, which are then those of the whole set (see
+    /// [`options()`][Self::options]).
+    #[cfg(feature = "parser")]
+    pub fn compile_with<I, S>(patterns: I, options: &MatchOptions) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        patterns
+            .into_iter()
+            .map(|pattern| GlobPattern::new_with(pattern, options))
+            .collect::<Result<Vec<_>>>()
+            .map(Self::from_patterns)
+    }
+
+    /// Same as [`compile()`][Self::compile], compiling the patterns in
+    /// parallel (see [`par_compile_with()`][Self::par_compile_with]).
+    #[cfg(feature = "rayon")]
+    pub fn par_compile<I, S>(patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str> + Send,
+    {
+        Self::par_compile_with(patterns, &MatchOptions::new())
+    }
+
+    /// Same as [`compile_with()`][Self::compile_with], compiling the patterns
+    /// in parallel on rayon's global thread pool, which is much faster for
+    /// sets of thousands of patterns. Patterns keep their index, and the error
+    /// returned is still that of the first invalid one.
+    #[cfg(feature = "rayon")]
+    pub fn par_compile_with<I, S>(patterns: I, options: &MatchOptions) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str> + Send,
+    {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        let patterns: Vec<S> = patterns.into_iter().collect();
+        let compiled: Vec<Result<GlobPattern>> = patterns
+            .into_par_iter()
+            .map(|pattern| GlobPattern::new_with(pattern, options))
+            .collect();
+        compiled
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .map(Self::from_patterns)
+    }
+
+    fn from_patterns(patterns: Vec<GlobPattern>) -> Self {
+        let mut set = Self {
+            #[cfg(feature = "prefilter")]
+            prefilters: patterns.iter().map(Prefilter::new).collect(),
+            patterns,
+        };
+        set.patterns.shrink_to_fit();
+        set.intern_classes();
+        set
+    }
+
+    /// Make the character classes listing the same entries (e.g. the `[0-9]`
+    /// of thousands of machine-generated patterns) share a single table,
+    /// returning the number of distinct tables. Sets are interned when built,
+    /// so this is only worth calling after [`push()`][Self::push] or
+    /// [`merge()`][Self::merge] added many patterns.
+    ///
+    /// Classes compiled into the binary by the `glob!` macro are left alone.
+    pub fn intern_classes(&mut self) -> usize {
+        let mut tables = ClassTables::default();
+        for pattern in &mut self.patterns {
+            let runtime = pattern.tokens.iter().any(|token| match token {
+                GlobToken::CharClass(class) => !class.is_static(),
+                _ => false,
+            });
+            if !runtime {
+                continue;
+            }
+            for token in pattern.tokens.to_mut() {
+                if let GlobToken::CharClass(class) = token {
+                    tables.intern(class);
+                }
+            }
+        }
+        tables.len()
+    }
+
+    /// Number of patterns in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Whether the set contains no patterns at all.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Get the pattern at `index`, if any.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&GlobPattern> {
+        self.patterns.get(index)
+    }
+
+    /// Iterate over the patterns in insertion order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, GlobPattern> {
+        self.patterns.iter()
+    }
+
+    /// Add a new pattern to the end of the set, returning its index.
+    pub fn push(&mut self, pattern: GlobPattern) -> usize {
+        #[cfg(feature = "prefilter")]
+        self.prefilters.push(Prefilter::new(&pattern));
+        self.patterns.push(pattern);
+        self.patterns.len() - 1
+    }
+
+    /// Move every pattern of `other` to the end of the set, without compiling
+    /// them again, returning the index of the first one: the pattern at index
+    /// `i` in `other` ends up at index `offset + i`, while the indices of the
+    /// patterns already in the set don't change.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parser")] {
+    /// use goglob_common::set::GlobSet;
+    ///
+    /// let mut set = GlobSet::compile(["*.rs", "*.toml"]).unwrap();
+    /// let offset = set.merge(GlobSet::compile(["*.md"]).unwrap());
+    /// assert_eq!(offset, 2);
+    /// assert_eq!(set.matching("README.md"), vec![offset]);
+    /// # }
+    /// ```
+    pub fn merge(&mut self, other: GlobSet) -> usize {
+        let offset = self.patterns.len();
+        self.patterns.extend(other.patterns);
+        #[cfg(feature = "prefilter")]
+        self.prefilters.extend(other.prefilters);
+        offset
+    }
+
+    /// Options every pattern in the set was compiled with, or `None` if the
+    /// set is empty or its patterns were compiled with different options.
+    pub fn options(&self) -> Option<&MatchOptions> {
+        let (first, rest) = self.patterns.split_first()?;
+        let options = first.options();
+        rest.iter()
+            .all(|p| p.options() == options)
+            .then_some(options)
+    }
+
+    /// Report whether the `name` matches any pattern in the set.
+    #[inline]
+    pub fn matches<S: AsRef<str>>(&self, name: S) -> bool {
+        let name = name.as_ref();
+        self.candidates(name).any(|(_, p)| p.matches(name))
+    }
+
+    /// Report whether the byte string `name`, which may not be valid UTF-8,
+    /// matches any pattern in the set (see [`GlobPattern::matches_bytes()`]).
+    pub fn matches_bytes<B: AsRef<[u8]>>(&self, name: B) -> bool {
+        let name = name.as_ref();
+        match core::str::from_utf8(name) {
+            Ok(name) => self.matches(name),
+            Err(_) => self.iter().any(|p| p.matches_bytes(name)),
+        }
+    }
+
+    /// Report whether the `name` of a directory (if `is_dir`) or of any other
+    /// file matches any pattern in the set, directory-only patterns only
+    /// matching directories (see [`GlobPattern::matches_entry()`]).
+    #[inline]
+    pub fn matches_entry<S: AsRef<str>>(&self, name: S, is_dir: bool) -> bool {
+        let name = name.as_ref();
+        self.patterns.iter().any(|p| p.matches_entry(name, is_dir))
+    }
+
+    /// Report whether `path` matches any pattern in the set, as with
+    /// [`GlobPattern::matches_path()`].
+    #[cfg(feature = "std")]
+    pub fn matches_path<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        self.patterns.iter().any(|p| p.matches_path(path))
+    }
+
+    /// Deepest directory every pattern in the set is inside of, i.e. the
+    /// longest sequence of leading segments which are the same case-sensitive
+    /// literal in all of them (e.g. `src/bin` for `src/bin/*.rs` and
+    /// `src/bin/*/main.rs`), so a filesystem walker can start there instead of
+    /// at the current (or root) directory.
+    ///
+    /// Absolute patterns give an absolute root (`/` if that's all they
+    /// share). The last segment of a pattern is never part of the root as it
+    /// may not be a directory. Returns `None` if the set is empty or the
+    /// patterns share no directory at all.
+    pub fn longest_common_root(&self) -> Option<String> {
+        let mut patterns = self.patterns.iter();
+        let first = patterns.next()?;
+        let separator = first.options().effective_separator();
+        let mut common = literal_dirs(first);
+        for pattern in patterns {
+            let dirs = literal_dirs(pattern);
+            let shared = common.iter().zip(&dirs).take_while(|(a, b)| a == b).count();
+            common.truncate(shared);
+        }
+
+        match common[..] {
+            [] => None,
+            [ref root] if root.is_empty() => Some(separator.into()),
+            _ => Some(common.join(separator.encode_utf8(&mut [0; 4]))),
+        }
+    }
+
+    /// Report the indices of every pattern in the set matching `name`, in
+    /// ascending order.
+    pub fn matching<S: AsRef<str>>(&self, name: S) -> Vec<usize> {
+        let name = name.as_ref();
+        self.candidates(name)
+            .filter(|(_, p)| p.matches(name))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Report the indices of every pattern in the set matching the byte
+    /// string `name` (see [`matches_bytes()`][Self::matches_bytes]), in
+    /// ascending order.
+    pub fn matching_bytes<B: AsRef<[u8]>>(&self, name: B) -> Vec<usize> {
+        let name = name.as_ref();
+        match core::str::from_utf8(name) {
+            Ok(name) => self.matching(name),
+            Err(_) => self
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.matches_bytes(name))
+                .map(|(i, _)| i)
+                .collect(),
+        }
+    }
+
+    /// Patterns (along with their index) which `name` may match, i.e. those
+    /// admitted by their prefilter.
+    pub(crate) fn candidates<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a GlobPattern)> + 'a {
+        let patterns = self.patterns.iter().enumerate();
+        #[cfg(feature = "prefilter")]
+        let patterns = patterns.filter(move |(i, _)| self.prefilters[*i].admits(name));
+        #[cfg(not(feature = "prefilter"))]
+        let _ = name;
+        patterns
+    }
+}
+
+/// Leading segments of `pattern` which are case-sensitive literals followed by
+/// more segments, with an empty first one if it's absolute.
+fn literal_dirs(pattern: &GlobPattern) -> Vec<String> {
+    let segments = pattern.segments();
+    let mut dirs = Vec::new();
+    for (i, segment) in segments[..segments.len() - 1].iter().enumerate() {
+        match segment.tokens() {
+            // Leading '/', as repeated ones don't add any directory
+            [] if i == 0 => dirs.push(String::new()),
+            [] => {}
+            [GlobToken::Literal(l)] if segment.options().case_fold == CaseFold::Sensitive => {
+                dirs.push(l.as_ref().into())
+            }
+            _ => break,
+        }
+    }
+    dirs
+}
+
+impl FromIterator<GlobPattern> for GlobSet {
+    fn from_iter<T: IntoIterator<Item = GlobPattern>>(iter: T) -> Self {
+        Self::from_patterns(iter.into_iter().collect())
+    }
+}
+/// Same as [`GlobSet::merge()`], keeping the patterns of the left-hand side
+/// first.
+impl BitOr for GlobSet {
+    type Output = GlobSet;
+
+    fn bitor(mut self, rhs: GlobSet) -> GlobSet {
+        self.merge(rhs);
+        self
+    }
+}
+/// Same as [`GlobSet::merge()`], cloning the patterns of both sides.
+impl BitOr for &GlobSet {
+    type Output = GlobSet;
+
+    fn bitor(self, rhs: &GlobSet) -> GlobSet {
+        self.clone() | rhs.clone()
+    }
+}
+/// Same as [`GlobSet::merge()`].
+impl BitOrAssign for GlobSet {
+    fn bitor_assign(&mut self, rhs: GlobSet) {
+        self.merge(rhs);
+    }
+}
+impl IntoIterator for GlobSet {
+    type Item = GlobPattern;
+    type IntoIter = alloc::vec::IntoIter<GlobPattern>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.patterns.into_iter()
+    }
+}
+impl<'a> IntoIterator for &'a GlobSet {
+    type Item = &'a GlobPattern;
+    type IntoIter = Iter<'a, GlobPattern>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Error returned by [`GlobSet::new_with()`] when a pattern wasn't compiled
+/// with the options of the set.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OptionsMismatch {
+    index: usize,
+    options: MatchOptions,
+}
+impl OptionsMismatch {
+    /// Index of the first pattern compiled with other options.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Options the pattern was compiled with.
+    #[inline]
+    pub fn options(&self) -> &MatchOptions {
+        &self.options
+    }
+}
+impl Display for OptionsMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "pattern {} wasn't compiled with the options of the set",
+            self.index
+        )
+    }
+}
+#[cfg(feature = "std")]
+impl StdError for OptionsMismatch {}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        options::{CaseFold, MatchOptions},
+        set::GlobSet,
+        GlobPattern,
+    };
+
+    #[test]
+    fn glob_set_matches() {
+        let set = GlobSet::compile(["*.rs", "src/*", "Cargo.toml"]).unwrap();
+        assert_eq!(set.len(), 3);
+        assert!(set.matches("lib.rs"));
+        assert!(set.matches("src/lib.rs"));
+        assert!(set.matches("Cargo.toml"));
+        assert!(!set.matches("src/bin/main.rs"));
+
+        assert_eq!(set.matching("lib.rs"), vec![0]);
+        assert_eq!(set.matching("src/lib.rs"), vec![1]);
+        assert_eq!(set.matching("README.md"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn glob_set_matches_entry() {
+        let set = GlobSet::compile(["target/", "*.log"]).unwrap();
+        assert!(set.matches_entry("target", true));
+        assert!(!set.matches_entry("target", false));
+        assert!(set.matches_entry("build.log", false));
+        assert!(!set.matches_entry("src", true));
+    }
+
+    #[test]
+    fn glob_set_longest_common_root() {
+        let root = |patterns: &[&str]| GlobSet::compile(patterns).unwrap().longest_common_root();
+        assert_eq!(
+            root(&["src/bin/*.rs", "src/bin/*/main.rs"]),
+            Some("src/bin".into())
+        );
+        assert_eq!(root(&["src/lib.rs", "src/*/mod.rs"]), Some("src".into()));
+        assert_eq!(root(&["src/lib.rs"]), Some("src".into()));
+        assert_eq!(
+            root(&["/usr/lib/*.so", "/usr//lib/x/*"]),
+            Some("/usr/lib".into())
+        );
+        assert_eq!(root(&["/usr/*", "/etc/*"]), Some("/".into()));
+        assert_eq!(root(&["/usr/*", "usr/*"]), None);
+        assert_eq!(root(&["src/*", "tests/*"]), None);
+        assert_eq!(root(&["*/src/*"]), None);
+        assert_eq!(root(&["Cargo.toml"]), None);
+        assert_eq!(root(&[]), None);
+
+        let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+        let set = GlobSet::compile_with(["src/*"], &options).unwrap();
+        assert_eq!(set.longest_common_root(), None);
+    }
+
+    #[test]
+    fn glob_set_merge() {
+        let rust = GlobSet::compile(["*.rs", "Cargo.toml"]).unwrap();
+        let docs = GlobSet::compile(["*.md", "*.toml"]).unwrap();
+        let merged = &rust | &docs;
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged.matching("Cargo.toml"), vec![1, 3]);
+        assert_eq!(merged.matching("README.md"), vec![2]);
+        assert_eq!(merged.get(2), docs.get(0));
+        assert_eq!(rust.clone() | docs.clone(), merged);
+
+        let mut set = rust;
+        assert_eq!(set.merge(GlobSet::default()), 2);
+        set |= docs;
+        assert_eq!(set, merged);
+    }
+
+    #[test]
+    fn glob_set_intern_classes() {
+        use crate::{charcls::CharClass, GlobPattern, GlobToken};
+
+        fn classes(pattern: &GlobPattern) -> Vec<&CharClass> {
+            pattern
+                .tokens()
+                .iter()
+                .filter_map(|token| match token {
+                    GlobToken::CharClass(class) => Some(class),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        let mut set = GlobSet::compile(["log-[0-9][0-9].txt", "[^0-9]*", "[a-z]/[0-9]"]).unwrap();
+        let first = classes(set.get(0).unwrap());
+        assert!(first[0].shares_table(first[1]));
+        assert!(first[0].shares_table(classes(set.get(1).unwrap())[0]));
+        assert!(!first[0].shares_table(classes(set.get(2).unwrap())[0]));
+        assert!(first[0].shares_table(classes(set.get(2).unwrap())[1]));
+        assert!(set.matches("log-42.txt"));
+        assert!(!set.matches("0"));
+
+        let pushed = set.push(GlobPattern::new("[0-9]").unwrap());
+        let first = classes(set.get(0).unwrap());
+        assert!(!first[0].shares_table(classes(set.get(pushed).unwrap())[0]));
+        assert_eq!(set.intern_classes(), 2);
+        let first = classes(set.get(0).unwrap());
+        assert!(first[0].shares_table(classes(set.get(pushed).unwrap())[0]));
+        assert_eq!(GlobSet::compile(["*.rs"]).unwrap().intern_classes(), 0);
+    }
+
+    #[test]
+    fn glob_set_compile_error() {
+        let error = GlobSet::compile(["*.rs", "a[", "b["]).err().unwrap();
+        assert_eq!(error.position(), 1);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn glob_set_par_compile() {
+        let patterns: Vec<_> = (0..1000).map(|i| format!("src/{i}/*.rs")).collect();
+        let set = GlobSet::par_compile(&patterns).unwrap();
+        assert_eq!(set, GlobSet::compile(&patterns).unwrap());
+        assert_eq!(set.matching("src/42/lib.rs"), vec![42]);
+
+        let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+        let set = GlobSet::par_compile_with(["*.RS", "src/*"], &options).unwrap();
+        assert_eq!(set.matching("lib.rs"), vec![0]);
+
+        let mut patterns = patterns;
+        patterns[700] = "abc[".into();
+        patterns[300] = "a[".into();
+        let error = GlobSet::par_compile(&patterns).err().unwrap();
+        assert_eq!(error.position(), 1);
+    }
+
+    #[test]
+    fn glob_set_compile_with() {
+        let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+        let set = GlobSet::compile_with(["*.RS", "src/*"], &options).unwrap();
+        assert_eq!(set.matching("SRC/lib.rs"), vec![1]);
+        assert_eq!(set.matching("lib.rs"), vec![0]);
+        assert!(set.iter().all(|pattern| pattern.options() == &options));
+        assert_eq!(set.options(), Some(&options));
+    }
+
+    #[test]
+    fn glob_set_new_with() {
+        let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+        let folded = GlobPattern::new_with("*.RS", &options).unwrap();
+        let sensitive = GlobPattern::new("src/*").unwrap();
+        let set = GlobSet::new_with([folded.clone(), folded.clone()], &options).unwrap();
+        assert!(set.matches("lib.rs"));
+        assert_eq!(set.options(), Some(&options));
+
+        let err = GlobSet::new_with([folded.clone(), sensitive.clone()], &options).unwrap_err();
+        assert_eq!(err.index(), 1);
+        assert_eq!(err.options(), &MatchOptions::new());
+        assert_eq!(
+            err.to_string(),
+            "pattern 1 wasn't compiled with the options of the set"
+        );
+        assert_eq!(GlobSet::new([folded, sensitive]).options(), None);
+        assert_eq!(GlobSet::default().options(), None);
+        assert!(GlobSet::new_with([], &options).unwrap().is_empty());
+    }
+
+    #[test]
+    fn glob_set_matches_bytes() {
+        let set = GlobSet::compile(["*.rs", "src/*", "*"]).unwrap();
+        assert!(set.matches_bytes(b"lib\xff.rs"));
+        assert!(!set.matches_bytes(b"a/b\xff/c"));
+        assert_eq!(set.matching_bytes(b"src/m\xffin.rs"), vec![1]);
+        assert_eq!(set.matching_bytes("src/main.rs"), vec![1]);
+        assert_eq!(set.matching_bytes(b"a/b/c"), Vec::<usize>::new());
+    }
+}
@@ -0,0 +1,151 @@
+//! Dedicated matchers for the most common shapes of patterns.
+
+use crate::{
+    options::{CaseFold, MatchOptions},
+    GlobToken,
+};
+
+/// Shape of a pattern, recognized when it's compiled so that the most common
+/// ones (`README`, `*`, `src/*`, `*.rs`, `*test*`) are matched with plain
+/// string comparisons instead of going through the generic token loop.
+///
+/// Only case-sensitive patterns have a shape other than
+/// [`Generic`][Shape::Generic], and each matcher gives exactly the same
+/// results as the loop (including for hidden names and separators).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Shape {
+    /// Any other pattern.
+    Generic,
+    /// A single literal.
+    Literal,
+    /// `*`.
+    Any,
+    /// A literal followed by `*`.
+    Prefix,
+    /// `*` followed by a literal.
+    Suffix,
+    /// A literal between two `*`.
+    Infix,
+}
+impl Shape {
+    /// Shape of the pattern made of `tokens`, compiled with `options`.
+    pub(crate) const fn of(tokens: &[GlobToken], options: &MatchOptions) -> Shape {
+        if !matches!(options.case_fold, CaseFold::Sensitive) {
+            return Shape::Generic;
+        }
+        match tokens {
+            [GlobToken::Literal(_)] => Shape::Literal,
+            [GlobToken::SeqWildcard] => Shape::Any,
+            [GlobToken::Literal(_), GlobToken::SeqWildcard] => Shape::Prefix,
+            [GlobToken::SeqWildcard, GlobToken::Literal(_)] => Shape::Suffix,
+            [GlobToken::SeqWildcard, GlobToken::Literal(_), GlobToken::SeqWildcard] => Shape::Infix,
+            _ => Shape::Generic,
+        }
+    }
+
+    /// Report whether `name` matches the pattern made of `tokens` (of this
+    /// shape) and `options`, or `None` if it's [`Generic`][Shape::Generic].
+    pub(crate) fn matches(
+        self,
+        tokens: &[GlobToken],
+        name: &str,
+        options: &MatchOptions,
+    ) -> Option<bool> {
+        let separator = options.effective_separator();
+        // Whether a `*` matching at the start of `rest` would start a hidden
+        // name, which it can't do (not even matching nothing) if dotfiles are
+        // disabled.
+        let hidden = |rest: &str, at_segment_start: bool| {
+            !options.dotfiles && at_segment_start && rest.starts_with('.')
+        };
+        // Whether `*` matches all of `text`, which can't contain the
+        // separator.
+        let star = |text: &str, at_segment_start: bool| {
+            !text.contains(separator) && !hidden(text, at_segment_start)
+        };
+        let literal = |index: usize| match &tokens[index] {
+            GlobToken::Literal(l) => l.as_ref(),
+            _ => unreachable!("shapes are only made of literals and wildcards"),
+        };
+        let matched = match self {
+            Shape::Generic => return None,
+            Shape::Literal => name == literal(0),
+            Shape::Any => star(name, true),
+            Shape::Prefix => {
+                let prefix = literal(0);
+                name.strip_prefix(prefix)
+                    .is_some_and(|rest| star(rest, prefix.ends_with(separator)))
+            }
+            Shape::Suffix => name
+                .strip_suffix(literal(1))
+                .is_some_and(|head| !head.contains(separator) && !hidden(name, true)),
+            Shape::Infix => {
+                // Like the token loop, only the first occurrence of the
+                // literal is tried.
+                let infix = literal(1);
+                name.find(infix).is_some_and(|at| {
+                    let (head, rest) = (&name[..at], &name[at + infix.len()..]);
+                    !head.contains(separator)
+                        && !hidden(name, true)
+                        && star(rest, infix.ends_with(separator))
+                })
+            }
+        };
+        Some(matched)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{options::MatchOptions, shape::Shape, syntax::Syntax, GlobPattern};
+
+    #[test]
+    fn shape_of() {
+        let shape = |p: &str| {
+            let pattern = GlobPattern::new(p).unwrap();
+            Shape::of(pattern.tokens(), pattern.options())
+        };
+        assert_eq!(shape("README"), Shape::Literal);
+        assert_eq!(shape("*"), Shape::Any);
+        assert_eq!(shape("src/*"), Shape::Prefix);
+        assert_eq!(shape("*.rs"), Shape::Suffix);
+        assert_eq!(shape("*test*"), Shape::Infix);
+        assert_eq!(shape("*.r?"), Shape::Generic);
+        assert_eq!(shape("a*b*c"), Shape::Generic);
+        let options = MatchOptions::new().case_fold(crate::options::CaseFold::Ascii);
+        let pattern = GlobPattern::new_with("*.rs", &options).unwrap();
+        assert_eq!(Shape::of(pattern.tokens(), &options), Shape::Generic);
+    }
+
+    #[test]
+    fn shape_matches_like_tokens() {
+        let options = [
+            MatchOptions::new(),
+            MatchOptions::new().dotfiles(false),
+            MatchOptions::new().separator(':'),
+            MatchOptions::new()
+                .syntax(Syntax::Gitignore)
+                .dotfiles(false),
+        ];
+        let patterns = [
+            "a", "a/b", "*", "a*", "a/*", ".*", "*a", "*/a", "*.a", "*a*", "*/*", "*.*", "*a/*",
+            "*:*", "a:*",
+        ];
+        let names = [
+            "", "a", "b", ".", ".a", "a.a", "aa", "ba", "ab", "a/b", "a/.b", "a/", "/a", ".a/b",
+            "b/a", "a/a/a", "ba/.a", "a:.a", "b:a", "x.a.a", "é.a",
+        ];
+        for options in options {
+            for pattern in patterns {
+                let pattern = GlobPattern::new_with(pattern, &options).unwrap();
+                let shape = Shape::of(pattern.tokens(), &options);
+                assert_ne!(shape, Shape::Generic, "{pattern:?}");
+                for name in names {
+                    let by_shape = shape.matches(pattern.tokens(), name, &options);
+                    let by_tokens = pattern.match_tokens(name, None);
+                    assert_eq!(by_shape, Some(by_tokens), "{pattern:?} {name:?}");
+                }
+            }
+        }
+    }
+}
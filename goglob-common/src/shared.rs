@@ -0,0 +1,128 @@
+//! Pattern sets shared between threads which can be replaced while in use.
+
+use crate::set::GlobSet;
+use arc_swap::ArcSwap;
+use std::{sync::Arc, vec::Vec};
+
+/// Handle to a [`GlobSet`] shared between threads, which can be atomically
+/// replaced (e.g. after reloading rules from a config file) while other
+/// threads keep matching against it without taking any lock.
+///
+/// Matching always uses a single consistent set: calls running while a new
+/// set is [stored][Self::store] finish with the previous one, and later calls
+/// use the new one.
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use goglob_common::{set::GlobSet, shared::SharedGlobSet};
+/// use std::{sync::Arc, thread};
+///
+/// let shared = Arc::new(SharedGlobSet::new(GlobSet::compile(["*.rs"]).unwrap()));
+/// let reader = Arc::clone(&shared);
+/// thread::spawn(move || assert!(reader.matches("lib.rs")))
+///     .join()
+///     .unwrap();
+///
+/// shared.store(GlobSet::compile(["*.toml"]).unwrap());
+/// assert!(!shared.matches("lib.rs"));
+/// assert!(shared.matches("Cargo.toml"));
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct SharedGlobSet {
+    set: ArcSwap<GlobSet>,
+}
+impl SharedGlobSet {
+    /// Share `set` between threads.
+    #[inline]
+    pub fn new(set: GlobSet) -> Self {
+        Self {
+            set: ArcSwap::from_pointee(set),
+        }
+    }
+
+    /// Current set, which stays valid (and unchanged) even if another one is
+    /// stored afterwards.
+    #[inline]
+    pub fn load(&self) -> Arc<GlobSet> {
+        self.set.load_full()
+    }
+
+    /// Replace the current set with `set` for every later call.
+    #[inline]
+    pub fn store(&self, set: GlobSet) {
+        self.set.store(Arc::new(set))
+    }
+
+    /// Same as [`store()`][Self::store], returning the previous set.
+    #[inline]
+    pub fn swap(&self, set: GlobSet) -> Arc<GlobSet> {
+        self.set.swap(Arc::new(set))
+    }
+
+    /// Report whether the `name` matches any pattern in the current set.
+    #[inline]
+    pub fn matches<S: AsRef<str>>(&self, name: S) -> bool {
+        self.set.load().matches(name)
+    }
+
+    /// Report the indices of every pattern in the current set matching
+    /// `name`, in ascending order.
+    #[inline]
+    pub fn matching<S: AsRef<str>>(&self, name: S) -> Vec<usize> {
+        self.set.load().matching(name)
+    }
+}
+impl From<GlobSet> for SharedGlobSet {
+    #[inline]
+    fn from(set: GlobSet) -> Self {
+        Self::new(set)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{set::GlobSet, shared::SharedGlobSet};
+    use std::{sync::Arc, thread};
+
+    #[test]
+    fn shared_glob_set_store() {
+        let shared = SharedGlobSet::from(GlobSet::compile(["*.rs"]).unwrap());
+        let old = shared.load();
+        assert!(shared.matches("lib.rs"));
+
+        let previous = shared.swap(GlobSet::compile(["*.md", "*.rs"]).unwrap());
+        assert_eq!(previous, old);
+        assert!(old.matches("lib.rs"));
+        assert!(!old.matches("README.md"));
+        assert_eq!(shared.matching("lib.rs"), vec![1]);
+
+        shared.store(GlobSet::default());
+        assert!(!shared.matches("lib.rs"));
+        assert!(!SharedGlobSet::default().matches("lib.rs"));
+    }
+
+    #[test]
+    fn shared_glob_set_threads() {
+        let shared = Arc::new(SharedGlobSet::new(GlobSet::compile(["a*"]).unwrap()));
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        // Either set matches one name but never both
+                        let set = shared.load();
+                        assert_ne!(set.matches("ab"), set.matches("ba"));
+                    }
+                })
+            })
+            .collect();
+        for i in 0..100 {
+            let pattern = if i % 2 == 0 { "b*" } else { "a*" };
+            shared.store(GlobSet::compile([pattern]).unwrap());
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    }
+}
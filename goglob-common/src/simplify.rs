@@ -0,0 +1,132 @@
+//! Normalization of patterns into a canonical form.
+
+use crate::{charcls::CharClass, literal::Literal, GlobPattern, GlobToken};
+use alloc::{string::String, vec::Vec};
+
+impl GlobPattern {
+    /// Rewrite the pattern into an equivalent canonical form, so that patterns
+    /// matching the same names in the same way compare equal more often:
+    ///
+    /// - Runs of `*` and `?` become the `?`s followed by a single `*` (so `**`
+    ///   becomes `*` in the Go syntaxes, and `*?` and `?*` both become `?*`).
+    /// - Runs of `**/` (any number of directories) become a single one.
+    /// - Entries of character classes are sorted, with overlapping or adjacent
+    ///   ones merged into ranges (`[cab]` becomes `[a-c]`).
+    /// - Non-negated classes of a single character become literals (`[a]b`
+    ///   becomes `ab`), except for `[.]` if dotfiles aren't matched by
+    ///   wildcards, as it can't match a hidden name while a literal `.` can.
+    ///
+    /// The result matches exactly the same names as the original pattern, but
+    /// may capture different parts of them.
+    pub fn simplify(&self) -> GlobPattern {
+        let mut tokens: Vec<GlobToken> = Vec::with_capacity(self.tokens.len());
+        let mut singles = 0;
+        let mut seq = false;
+        for token in self.tokens.iter() {
+            let token = match token {
+                GlobToken::SingleWildcard => {
+                    singles += 1;
+                    continue;
+                }
+                GlobToken::SeqWildcard => {
+                    seq = true;
+                    continue;
+                }
+                GlobToken::CharClass(cc) => self.simplify_class(cc),
+                token => token.clone(),
+            };
+            flush_wildcards(&mut tokens, &mut singles, &mut seq);
+            match (tokens.last_mut(), token) {
+                (Some(GlobToken::AnyDirs), GlobToken::AnyDirs) => {}
+                (Some(GlobToken::Literal(last)), GlobToken::Literal(l)) => {
+                    let mut merged = String::from(last.as_ref());
+                    merged.push_str(l.as_ref());
+                    *last = Literal::new(merged);
+                }
+                (_, token) => tokens.push(token),
+            }
+        }
+        flush_wildcards(&mut tokens, &mut singles, &mut seq);
+        Self::from_token_vec(tokens, self.options, None)
+    }
+
+    fn simplify_class(&self, cc: &CharClass) -> GlobToken {
+        match cc.listed_ranges()[..] {
+            [(c, end)] if c == end && !cc.is_negated() && (c != '.' || self.options.dotfiles) => {
+                GlobToken::Literal(Literal::new(c.into()))
+            }
+            ref ranges => {
+                GlobToken::CharClass(CharClass::from_ranges(cc.is_negated(), ranges.to_vec()))
+            }
+        }
+    }
+}
+
+/// Push the pending run of wildcards in canonical order: the `?`s first, then
+/// at most one `*`.
+fn flush_wildcards(tokens: &mut Vec<GlobToken>, singles: &mut usize, seq: &mut bool) {
+    tokens.resize(tokens.len() + *singles, GlobToken::SingleWildcard);
+    if *seq {
+        tokens.push(GlobToken::SeqWildcard);
+    }
+    *singles = 0;
+    *seq = false;
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{builder::GlobPatternBuilder, options::MatchOptions, syntax::Syntax, GlobPattern};
+
+    fn simplified(pattern: &str) -> GlobPattern {
+        GlobPattern::new(pattern).unwrap().simplify()
+    }
+
+    #[test]
+    fn simplify_wildcards() {
+        assert_eq!(simplified("a**b"), GlobPattern::new("a*b").unwrap());
+        assert_eq!(simplified("a*?b"), GlobPattern::new("a?*b").unwrap());
+        assert_eq!(simplified("*?*?"), GlobPattern::new("??*").unwrap());
+        assert_eq!(simplified("a?*b"), simplified("a*?b"));
+        assert_eq!(simplified("*/?"), GlobPattern::new("*/?").unwrap());
+    }
+
+    #[test]
+    fn simplify_any_dirs() {
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        let pattern = gitignore.build("**/**/a").unwrap().simplify();
+        assert_eq!(pattern, gitignore.build("**/a").unwrap());
+        assert!(pattern.matches("x/y/a"));
+    }
+
+    #[test]
+    fn simplify_classes() {
+        assert_eq!(simplified("[a]b[c]"), GlobPattern::new("abc").unwrap());
+        assert_eq!(simplified("[a-a]"), GlobPattern::new("a").unwrap());
+        assert_eq!(simplified("[cab]"), GlobPattern::new("[a-c]").unwrap());
+        assert_eq!(simplified("[a-fc-hz]"), GlobPattern::new("[a-hz]").unwrap());
+        assert_eq!(simplified("[^b]"), GlobPattern::new("[^b]").unwrap());
+        assert_eq!(simplified("[^cb]"), GlobPattern::new("[^b-c]").unwrap());
+        assert_eq!(simplified("a\\*[*]"), GlobPattern::new("a\\*\\*").unwrap());
+    }
+
+    #[test]
+    fn simplify_keeps_hidden_class() {
+        let hidden = GlobPatternBuilder::new().options(MatchOptions::new().dotfiles(false));
+        let pattern = hidden.build("[.]a").unwrap();
+        assert_eq!(pattern.simplify(), pattern);
+        assert!(!pattern.simplify().matches(".a"));
+        assert_eq!(simplified("[.]a"), GlobPattern::new(".a").unwrap());
+    }
+
+    #[test]
+    fn simplify_is_idempotent_and_equivalent() {
+        for pattern in ["a*?[b]c", "[zyx]?**", "[^a-cb]*.rs", "?[.]*"] {
+            let simple = simplified(pattern);
+            assert_eq!(simple.simplify(), simple);
+            let original = GlobPattern::new(pattern).unwrap();
+            for name in ["abc", "axbc", "zqw", "d.rs", "a.rs", "x.y", "..", "abbc"] {
+                assert_eq!(simple.matches(name), original.matches(name), "{pattern}");
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Matching names against short lists of patterns without building a
+//! [`GlobSet`][crate::set::GlobSet].
+
+#[cfg(feature = "prefilter")]
+use crate::prefilter::Prefilter;
+use crate::GlobPattern;
+
+/// Report whether `name` matches any of `patterns`, stopping at the first
+/// one which does.
+///
+/// Unlike a [`GlobSet`][crate::set::GlobSet], nothing is computed ahead of
+/// time, which is faster for small lists of patterns only matched a few
+/// times. With the `prefilter` feature, each pattern is still ruled out with
+/// the same cheap checks as the prefilters of a set before going through its
+/// tokens. Prefer a set for large or long-lived lists.
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use goglob_common::{slice::any_match, GlobPattern};
+///
+/// let patterns = [
+///     GlobPattern::new("*.rs").unwrap(),
+///     GlobPattern::new("Cargo.*").unwrap(),
+/// ];
+/// assert!(any_match(&patterns, "Cargo.toml"));
+/// assert!(!any_match(&patterns, "README.md"));
+/// assert!(!any_match(&[], "README.md"));
+/// # }
+/// ```
+pub fn any_match<S: AsRef<str>>(patterns: &[GlobPattern], name: S) -> bool {
+    let name = name.as_ref();
+    patterns
+        .iter()
+        .any(|pattern| pattern_matches(pattern, name))
+}
+
+/// Report whether `name` matches every one of `patterns` (and so whether it
+/// does if there are none), stopping at the first one which doesn't.
+///
+/// See [`any_match()`] for when to prefer it over a
+/// [`GlobSet`][crate::set::GlobSet].
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use goglob_common::{slice::all_match, GlobPattern};
+///
+/// let patterns = [
+///     GlobPattern::new("src/*").unwrap(),
+///     GlobPattern::new("*/*.rs").unwrap(),
+/// ];
+/// assert!(all_match(&patterns, "src/lib.rs"));
+/// assert!(!all_match(&patterns, "src/README.md"));
+/// assert!(all_match(&[], "README.md"));
+/// # }
+/// ```
+pub fn all_match<S: AsRef<str>>(patterns: &[GlobPattern], name: S) -> bool {
+    let name = name.as_ref();
+    patterns
+        .iter()
+        .all(|pattern| pattern_matches(pattern, name))
+}
+
+#[inline]
+fn pattern_matches(pattern: &GlobPattern, name: &str) -> bool {
+    #[cfg(feature = "prefilter")]
+    if !Prefilter::admits_once(pattern, name) {
+        return false;
+    }
+    pattern.matches(name)
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        options::{CaseFold, MatchOptions},
+        slice::{all_match, any_match},
+        GlobPattern,
+    };
+    use alloc::vec::Vec;
+
+    #[test]
+    fn slice_match() {
+        let options = MatchOptions::new().case_fold(CaseFold::Ascii);
+        let patterns: Vec<_> = ["src/*.rs", "*.md", "[A-Z]*"]
+            .into_iter()
+            .map(|p| GlobPattern::new_with(p, &options).unwrap())
+            .collect();
+        for name in ["src/lib.rs", "SRC/LIB.RS", "readme.md", "Cargo.toml", ""] {
+            assert_eq!(
+                any_match(&patterns, name),
+                patterns.iter().any(|p| p.matches(name)),
+                "{name}"
+            );
+            assert_eq!(
+                all_match(&patterns, name),
+                patterns.iter().all(|p| p.matches(name)),
+                "{name}"
+            );
+        }
+        assert!(all_match(&patterns[..1], "SRC/MAIN.RS"));
+        assert!(!any_match(&patterns[..1], "src/main.c"));
+    }
+}
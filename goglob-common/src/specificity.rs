@@ -0,0 +1,160 @@
+//! Ranking of patterns by how specific they are.
+
+use crate::{GlobPattern, GlobToken};
+use core::cmp::{Ordering, Reverse};
+
+/// How specific a pattern is, as returned by [`GlobPattern::specificity()`].
+///
+/// Specificities are totally ordered, a greater one meaning a more specific
+/// pattern (so sorting rules by `Reverse(pattern.specificity())` puts the most
+/// specific first). They're compared by, in order:
+///
+/// 1. Fewer `**/` (any number of directories).
+/// 2. Fewer `*`.
+/// 3. More literal characters.
+/// 4. More path segments.
+/// 5. Fewer `?`.
+/// 6. Fewer character classes.
+///
+/// For example `a/b/c` > `a/*/c` > `a/*` > `a/**` (in
+/// [`Syntax::Gitignore`][crate::syntax::Syntax::Gitignore]), and `a[bc]` >
+/// `a?`. Patterns with the same counts are equal, even if they don't match the
+/// same names.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Specificity {
+    literal_chars: usize,
+    classes: usize,
+    single_wildcards: usize,
+    seq_wildcards: usize,
+    any_dirs: usize,
+    segments: usize,
+}
+impl Specificity {
+    /// Number of characters matched literally, including separators.
+    pub fn literal_chars(&self) -> usize {
+        self.literal_chars
+    }
+
+    /// Number of character classes (`[...]`).
+    pub fn classes(&self) -> usize {
+        self.classes
+    }
+
+    /// Number of `?`.
+    pub fn single_wildcards(&self) -> usize {
+        self.single_wildcards
+    }
+
+    /// Number of `*` (not counting the ones of `**/`).
+    pub fn seq_wildcards(&self) -> usize {
+        self.seq_wildcards
+    }
+
+    /// Number of `**/`.
+    pub fn any_dirs(&self) -> usize {
+        self.any_dirs
+    }
+
+    /// Number of path segments, as returned by [`GlobPattern::segments()`].
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    fn key(&self) -> impl Ord {
+        (
+            Reverse(self.any_dirs),
+            Reverse(self.seq_wildcards),
+            self.literal_chars,
+            self.segments,
+            Reverse(self.single_wildcards),
+            Reverse(self.classes),
+        )
+    }
+}
+impl Ord for Specificity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+impl PartialOrd for Specificity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl GlobPattern {
+    /// How specific the pattern is, for ranking patterns matching the same
+    /// name (see [`Specificity`] for the ordering).
+    pub fn specificity(&self) -> Specificity {
+        let separator = self.options().effective_separator();
+        let mut specificity = Specificity {
+            segments: 1,
+            ..Specificity::default()
+        };
+        for token in self.tokens() {
+            match token {
+                GlobToken::Literal(l) => {
+                    for c in l.as_ref().chars() {
+                        specificity.literal_chars += 1;
+                        if c == separator {
+                            specificity.segments += 1;
+                        }
+                    }
+                }
+                GlobToken::CharClass(_) => specificity.classes += 1,
+                GlobToken::SingleWildcard => specificity.single_wildcards += 1,
+                GlobToken::SeqWildcard => specificity.seq_wildcards += 1,
+                GlobToken::AnyDirs => {
+                    specificity.any_dirs += 1;
+                    specificity.segments += 1;
+                }
+            }
+        }
+        specificity
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{syntax::Syntax, GlobPattern};
+    use core::cmp::Reverse;
+
+    fn pattern(pattern: &str) -> GlobPattern {
+        GlobPattern::builder()
+            .syntax(Syntax::Gitignore)
+            .build(pattern)
+            .unwrap()
+    }
+
+    #[test]
+    fn specificity_counts() {
+        for p in ["a/b/c", "a/*/c", "src/**/*.rs", "**", "a[bc]?d*"] {
+            let pattern = pattern(p);
+            assert_eq!(
+                pattern.specificity().segments(),
+                pattern.segments().len(),
+                "{p}"
+            );
+        }
+        let specificity = pattern("src/**/[ab]?*.rs").specificity();
+        assert_eq!(specificity.literal_chars(), 7);
+        assert_eq!(specificity.classes(), 1);
+        assert_eq!(specificity.single_wildcards(), 1);
+        assert_eq!(specificity.seq_wildcards(), 1);
+        assert_eq!(specificity.any_dirs(), 1);
+        assert_eq!(specificity.segments(), 3);
+    }
+
+    #[test]
+    fn specificity_ordering() {
+        let ranked = ["a/b/c", "a/[bc]/c", "a/?/c", "a/*/c", "a/*", "a/**", "**"];
+        let mut patterns = ranked;
+        patterns.reverse();
+        patterns.sort_by_key(|p| Reverse(pattern(p).specificity()));
+        assert_eq!(patterns, ranked);
+
+        assert!(pattern("abc").specificity() > pattern("ab?").specificity());
+        assert!(pattern("a*bc").specificity() > pattern("a*b").specificity());
+        assert_eq!(pattern("a*").specificity(), pattern("*a").specificity());
+    }
+}
@@ -0,0 +1,224 @@
+//! Matching of names received in chunks.
+
+use crate::{
+    automaton::{is_exact, Automaton, States},
+    GlobPattern,
+};
+use alloc::string::String;
+
+/// Push-style matcher receiving the name in chunks, as returned by
+/// [`GlobPattern::stream_matcher()`], so names assembled from network buffers
+/// or ropes can be matched without concatenating them first.
+///
+/// Chunks may split the name anywhere, as long as each one is valid UTF-8.
+/// The result of [`finish()`][Self::finish] is always the same as
+/// [`GlobPattern::matches()`] on the whole name.
+///
+/// ```
+/// # #[cfg(feature = "parser")] {
+/// use goglob_common::GlobPattern;
+///
+/// let pattern = GlobPattern::new("src/*.rs").unwrap();
+/// let mut matcher = pattern.stream_matcher();
+/// for chunk in ["sr", "c/li", "b.", "rs"] {
+///     matcher.feed(chunk);
+/// }
+/// assert!(matcher.finish());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamMatcher<'p> {
+    state: State<'p>,
+}
+#[derive(Debug, Clone)]
+enum State<'p> {
+    /// States of the automaton after the characters fed so far, which are
+    /// never stored.
+    Automaton {
+        automaton: Automaton<'p>,
+        states: States,
+        segment_start: bool,
+        steps: usize,
+    },
+    /// The name fed so far, for patterns the automaton doesn't match exactly
+    /// like [`GlobPattern::matches()`] does.
+    Buffered {
+        pattern: &'p GlobPattern,
+        name: String,
+    },
+}
+impl<'p> StreamMatcher<'p> {
+    /// Matcher for `pattern`, which has been fed nothing yet.
+    pub fn new(pattern: &'p GlobPattern) -> Self {
+        let state = if is_exact(pattern) {
+            let automaton = Automaton::new(pattern);
+            State::Automaton {
+                states: automaton.start(),
+                automaton,
+                segment_start: true,
+                steps: 0,
+            }
+        } else {
+            State::Buffered {
+                pattern,
+                name: String::new(),
+            }
+        };
+        Self { state }
+    }
+
+    /// Append `chunk` to the name being matched.
+    pub fn feed(&mut self, chunk: &str) {
+        match &mut self.state {
+            State::Automaton {
+                automaton,
+                states,
+                segment_start,
+                steps,
+            } => {
+                for c in chunk.chars() {
+                    if states.is_empty() {
+                        // Nothing can match anymore
+                        return;
+                    }
+                    *steps += 1;
+                    *states = automaton.step(states, c, *segment_start && automaton.hides(c));
+                    *segment_start = c == automaton.separator;
+                }
+            }
+            State::Buffered { name, .. } => name.push_str(chunk),
+        }
+    }
+
+    /// Report whether the whole name fed so far matches the pattern.
+    pub fn finish(self) -> bool {
+        match self.state {
+            State::Automaton {
+                automaton,
+                states,
+                steps,
+                ..
+            } => {
+                let matched = automaton.accepts(&states);
+                automaton.pattern.record_match(matched, steps);
+                matched
+            }
+            State::Buffered { pattern, name } => pattern.matches(name),
+        }
+    }
+}
+
+impl GlobPattern {
+    /// Matcher receiving the name to match in chunks (see [`StreamMatcher`]).
+    #[inline]
+    pub fn stream_matcher(&self) -> StreamMatcher<'_> {
+        StreamMatcher::new(self)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{
+        automaton::is_exact, builder::GlobPatternBuilder, options::MatchOptions, syntax::Syntax,
+        GlobPattern,
+    };
+    use goglob_testutil::GO_MATCH_VECTORS;
+
+    /// Feed `name` split at every possible pair of positions.
+    fn assert_stream_matches(pattern: &GlobPattern, name: &str) {
+        let expected = pattern.matches(name);
+        let boundaries: Vec<_> = (0..=name.len())
+            .filter(|&i| name.is_char_boundary(i))
+            .collect();
+        for &i in &boundaries {
+            for &j in boundaries.iter().filter(|&&j| j >= i) {
+                let mut matcher = pattern.stream_matcher();
+                matcher.feed(&name[..i]);
+                matcher.feed(&name[i..j]);
+                matcher.feed(&name[j..]);
+                assert_eq!(matcher.finish(), expected, "{pattern:?} {name:?} {i} {j}");
+            }
+        }
+    }
+
+    #[test]
+    fn stream_matcher_go_match_test() {
+        for vector in GO_MATCH_VECTORS {
+            if let Ok(pattern) = GlobPattern::new(vector.pattern) {
+                assert_stream_matches(&pattern, vector.name);
+            }
+        }
+    }
+
+    #[test]
+    fn stream_matcher_options() {
+        let hidden = GlobPatternBuilder::new().options(MatchOptions::new().dotfiles(false));
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        let patterns = [
+            hidden.build("*").unwrap(),
+            hidden.build("a/?b").unwrap(),
+            hidden.build(".*").unwrap(),
+            gitignore.build("**/b").unwrap(),
+            gitignore.build("a/**/b/*.rs").unwrap(),
+            GlobPattern::new("*[^x]b*c").unwrap(),
+        ];
+        let names = [
+            ".a", "a", "a/.b", "a/xb", "b", "x/y/b", "a/b/c.rs", "ab/bc", "",
+        ];
+        for pattern in &patterns {
+            for name in names {
+                assert_stream_matches(pattern, name);
+            }
+        }
+    }
+
+    #[test]
+    fn stream_matcher_exhaustive() {
+        fn strings(alphabet: &[&str], len: usize) -> Vec<String> {
+            let mut strings = vec![String::new()];
+            for _ in 0..len {
+                let longer: Vec<_> = strings
+                    .iter()
+                    .flat_map(|s| alphabet.iter().map(move |a| format!("{s}{a}")))
+                    .collect();
+                strings.extend(longer);
+            }
+            strings.sort();
+            strings.dedup();
+            strings
+        }
+
+        let names = strings(&["a", "b", "/", "."], 4);
+        let hidden = GlobPatternBuilder::new().options(MatchOptions::new().dotfiles(false));
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        for pattern in strings(&["a", "/", "*", "?", "[^b]", ".", "**/"], 3) {
+            for builder in [&GlobPatternBuilder::new(), &hidden, &gitignore] {
+                let Ok(pattern) = builder.build(&pattern) else {
+                    continue;
+                };
+                for name in &names {
+                    let mut matcher = pattern.stream_matcher();
+                    name.split_inclusive(|_| true).for_each(|c| matcher.feed(c));
+                    assert_eq!(
+                        matcher.finish(),
+                        pattern.matches(name),
+                        "{pattern:?} {name:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stream_matcher_is_exact() {
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        assert!(is_exact(&GlobPattern::new("*a?b*c[^d]").unwrap()));
+        assert!(is_exact(&GlobPattern::new("a[^/]*").unwrap()));
+        assert!(is_exact(&gitignore.build("a/**/b/*.rs").unwrap()));
+        assert!(is_exact(&gitignore.build("*/**/b").unwrap()));
+        // Matching commits to "ab" for "[^x]b" in "ab/bc", missing "/b"
+        let pattern = GlobPattern::new("*[^x]b*c").unwrap();
+        assert!(!is_exact(&pattern));
+        assert!(!pattern.matches("ab/bc"));
+    }
+}
@@ -0,0 +1,71 @@
+//! Pattern dialects understood by the scanner.
+
+/// Dialect a pattern is parsed as, selected with
+/// [`GlobPatternBuilder::syntax()`][crate::builder::GlobPatternBuilder::syntax]
+/// or [`MatchOptions::syntax()`][crate::options::MatchOptions::syntax].
+///
+/// Every dialect shares the same `*`, `?` and `[...]` building blocks, with
+/// `*` and `?` never matching the separator (`/` by default). They differ in
+/// the details of escaping and character classes (and, for
+/// [`Gitignore`][Syntax::Gitignore], in `**`).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Syntax {
+    /// golang's `path.Match`, which is what [`GlobPattern::new()`][crate::GlobPattern::new]
+    /// uses.
+    #[default]
+    GoPathMatch,
+    /// golang's `filepath.Match`. Same as [`GoPathMatch`][Syntax::GoPathMatch],
+    /// except on Windows where `\` isn't an escape character but the path
+    /// separator (unless another one is set with
+    /// [`MatchOptions::separator()`][crate::options::MatchOptions::separator]).
+    GoFilepathMatch,
+    /// POSIX shell globs as described in `glob(7)`: classes are negated with
+    /// `!` (or `^`), a `]` right after the opening `[` (or `[!`) and a `-` at
+    /// the start or end of a class are literal, and an unclosed `[` or a lone
+    /// `]` are literal characters.
+    Glob7,
+    /// A single `.gitignore` pattern (without the surrounding rule syntax such
+    /// as comments, `!` negation or anchoring). Classes behave as in
+    /// [`Glob7`][Syntax::Glob7] except that an unclosed `[` is an error, and
+    /// `**` surrounded by separators (or the start/end of the pattern) matches
+    /// any number of directories: `**/a` matches `a` at any depth, `a/**/b`
+    /// matches `a/b` and `a/x/y/b`, and `a/**` matches everything inside `a`.
+    /// Any other `**` is a regular `*`.
+    Gitignore,
+}
+impl Syntax {
+    /// Whether `\` escapes the next character.
+    pub(crate) fn escapes(self) -> bool {
+        !(self == Syntax::GoFilepathMatch && cfg!(windows))
+    }
+
+    /// Default path separator.
+    pub(crate) fn separator(self) -> char {
+        if self == Syntax::GoFilepathMatch && cfg!(windows) {
+            '\\'
+        } else {
+            '/'
+        }
+    }
+
+    /// Whether character classes follow POSIX rules (`!` negation, literal
+    /// leading `]` and leading or trailing `-`).
+    pub(crate) fn posix_classes(self) -> bool {
+        matches!(self, Syntax::Glob7 | Syntax::Gitignore)
+    }
+
+    /// Whether an unclosed `[` and a lone `]` are literal characters.
+    pub(crate) fn lenient_brackets(self) -> bool {
+        self == Syntax::Glob7
+    }
+
+    /// Whether `**` between `/` matches any number of directories.
+    pub(crate) fn globstar(self) -> bool {
+        self == Syntax::Gitignore
+    }
+}
@@ -0,0 +1,244 @@
+//! Patterns with placeholders filled in at runtime.
+
+use crate::{
+    error::{Error, ErrorType},
+    options::MatchOptions,
+    GlobPattern, Result,
+};
+use alloc::{string::String, vec::Vec};
+
+/// Pattern with named placeholders (e.g. `{project}/src/*.rs`) which are
+/// filled in at runtime with values matched literally, so that user data can
+/// be inserted into a pattern without it ever being interpreted as wildcards.
+///
+/// The template syntax is that of the pattern, with the addition of:
+///
+/// ```text
+/// '{' name '}'  the value of placeholder name, escaped
+/// '{{'          character '{'
+/// '}}'          character '}'
+/// ```
+///
+/// ```
+/// use goglob_common::template::GlobTemplate;
+/// use std::collections::HashMap;
+///
+/// let template = GlobTemplate::new("{project}/src/*.rs").unwrap();
+/// let values = HashMap::from([("project", "my*app")]);
+/// let pattern = template.fill(|name| values.get(name)).unwrap();
+/// assert!(pattern.matches("my*app/src/lib.rs"));
+/// assert!(!pattern.matches("my-app/src/lib.rs"));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GlobTemplate {
+    parts: Vec<TemplatePart>,
+    options: MatchOptions,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum TemplatePart {
+    Pattern(String),
+    Placeholder(String, usize),
+}
+
+impl GlobTemplate {
+    /// Parse `template`, returning an [error][crate::error::Error] if a
+    /// placeholder isn't closed or has no name.
+    #[inline]
+    pub fn new<S: AsRef<str>>(template: S) -> Result<Self> {
+        Self::new_with(template, &MatchOptions::new())
+    }
+
+    /// Same as [`new()`][Self::new], filled-in patterns being parsed and
+    /// matched according to `options` instead of the defaults.
+    pub fn new_with<S: AsRef<str>>(template: S, options: &MatchOptions) -> Result<Self> {
+        let template = template.as_ref();
+        if template.is_empty() {
+            return Err(Error::empty_pattern());
+        }
+
+        let mut parts = Vec::new();
+        let mut pattern = String::new();
+        let mut template_iter = template.char_indices().peekable();
+        while let Some((i, c)) = template_iter.next() {
+            if c != '{' {
+                if c == '}' {
+                    template_iter.next_if(|(_, c)| *c == '}');
+                }
+                pattern.push(c);
+                continue;
+            }
+            if template_iter.next_if(|(_, c)| *c == '{').is_some() {
+                pattern.push('{');
+                continue;
+            }
+            let name = &template[i + 1..];
+            let end = name
+                .find('}')
+                .ok_or_else(|| Error::new(ErrorType::UnclosedPlaceholder, i))?;
+            if end == 0 {
+                return Err(Error::new(ErrorType::InvalidPlaceholder, i));
+            }
+            // Skip the name and the closing '}'
+            template_iter.nth(name[..end].chars().count());
+            if !pattern.is_empty() {
+                parts.push(TemplatePart::Pattern(core::mem::take(&mut pattern)));
+            }
+            parts.push(TemplatePart::Placeholder(String::from(&name[..end]), i));
+        }
+        if !pattern.is_empty() {
+            parts.push(TemplatePart::Pattern(pattern));
+        }
+        Ok(Self {
+            parts,
+            options: *options,
+        })
+    }
+
+    /// Options filled-in patterns are parsed and matched with.
+    #[inline]
+    pub fn options(&self) -> &MatchOptions {
+        &self.options
+    }
+
+    /// Iterate over the names of the placeholders, in order of appearance
+    /// (including repeated ones).
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> + '_ {
+        self.parts.iter().filter_map(|part| match part {
+            TemplatePart::Placeholder(name, _) => Some(name.as_str()),
+            TemplatePart::Pattern(_) => None,
+        })
+    }
+
+    /// Fill in every placeholder with the value `value` returns for its name
+    /// (e.g. `|name| map.get(name)`), escaped so that it only ever matches
+    /// itself, into pattern syntax.
+    ///
+    /// Returns an [error][crate::error::Error] at the placeholder's position
+    /// in the template if there is no value for it, or if the value can't be
+    /// escaped with the template's options (`]` without `\` escapes in the
+    /// [`GoPathMatch`][crate::syntax::Syntax::GoPathMatch] and
+    /// [`GoFilepathMatch`][crate::syntax::Syntax::GoFilepathMatch] syntaxes).
+    pub fn render<F, V>(&self, mut value: F) -> Result<String>
+    where
+        F: FnMut(&str) -> Option<V>,
+        V: AsRef<str>,
+    {
+        let mut rendered = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Pattern(pattern) => rendered.push_str(pattern),
+                TemplatePart::Placeholder(name, i) => {
+                    let value =
+                        value(name).ok_or_else(|| Error::new(ErrorType::MissingValue, *i))?;
+                    for c in value.as_ref().chars() {
+                        self.push_escaped(&mut rendered, c)
+                            .ok_or_else(|| Error::new(ErrorType::UnescapedChar(c), *i))?;
+                    }
+                }
+            }
+        }
+        Ok(rendered)
+    }
+
+    /// Same as [`render()`][Self::render], compiling the filled-in pattern.
+    /// Errors in the pattern itself have positions in the rendered pattern.
+    pub fn fill<F, V>(&self, value: F) -> Result<GlobPattern>
+    where
+        F: FnMut(&str) -> Option<V>,
+        V: AsRef<str>,
+    {
+        GlobPattern::new_with(self.render(value)?, &self.options)
+    }
+
+    /// Push `c` so that it's only matched literally, if possible.
+    fn push_escaped(&self, rendered: &mut String, c: char) -> Option<()> {
+        if !self.options.is_metachar(c) {
+            rendered.push(c);
+        } else if self.options.effective_escapes() {
+            rendered.push(self.options.escape_char);
+            rendered.push(c);
+        } else if !self.options.char_classes {
+            return None;
+        } else if c != ']' || self.options.syntax.posix_classes() {
+            // A class with a single character, which may be ']' right after
+            // the opening '[' with POSIX classes
+            rendered.push('[');
+            rendered.push(c);
+            rendered.push(']');
+        } else {
+            return None;
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ErrorType, options::MatchOptions, syntax::Syntax, template::GlobTemplate};
+
+    fn values<'a>(values: &'a [(&str, &'a str)]) -> impl FnMut(&str) -> Option<&'a str> {
+        move |name| values.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+    }
+
+    #[test]
+    fn glob_template_fill() {
+        let template = GlobTemplate::new("{dir}/{{{name}}}*.{ext}").unwrap();
+        let placeholders: Vec<_> = template.placeholders().collect();
+        assert_eq!(placeholders, ["dir", "name", "ext"]);
+
+        let filled = values(&[("dir", "a[1]"), ("name", "?\\"), ("ext", "rs")]);
+        assert_eq!(template.render(filled).unwrap(), "a\\[1\\]/{\\?\\\\}*.rs");
+        let pattern = template
+            .fill(values(&[("dir", "a[1]"), ("name", "?*"), ("ext", "rs")]))
+            .unwrap();
+        assert!(pattern.matches("a[1]/{?*}x.rs"));
+        assert!(!pattern.matches("a1/{xy}x.rs"));
+    }
+
+    #[test]
+    fn glob_template_options() {
+        let options = MatchOptions::new().syntax(Syntax::Glob7).escapes(false);
+        let template = GlobTemplate::new_with("{a}*", &options).unwrap();
+        assert_eq!(template.options(), &options);
+        let rendered = template.render(values(&[("a", "]*?[\\")])).unwrap();
+        assert_eq!(rendered, "[]][*][?][[]\\*");
+        let pattern = template.fill(values(&[("a", "]*?[\\")])).unwrap();
+        assert!(pattern.matches("]*?[\\x"));
+        assert!(!pattern.matches("]ab[\\x"));
+
+        let options = MatchOptions::new().escapes(false);
+        let template = GlobTemplate::new_with("x/{a}", &options).unwrap();
+        let error = template.render(values(&[("a", "b]")])).unwrap_err();
+        assert_eq!(error.position(), 2);
+        assert_eq!(template.render(values(&[("a", "b*")])).unwrap(), "x/b[*]");
+
+        let options = MatchOptions::new().seq_wildcard('%').escape_char('!');
+        let template = GlobTemplate::new_with("{a}%", &options).unwrap();
+        let rendered = template.render(values(&[("a", "5%!*")])).unwrap();
+        assert_eq!(rendered, "5!%!!*%");
+        let pattern = template.fill(values(&[("a", "5%!*")])).unwrap();
+        assert!(pattern.matches("5%!*.txt"));
+        assert!(!pattern.matches("5x!*.txt"));
+    }
+
+    #[test]
+    fn glob_template_errors() {
+        let error = |template: &str| GlobTemplate::new(template).err().unwrap().position();
+        assert_eq!(error("a{b"), 1);
+        assert_eq!(error("a/{}"), 2);
+        assert!(GlobTemplate::new("").is_err());
+
+        let template = GlobTemplate::new("é{a}/{b}").unwrap();
+        let error = template.render(values(&[("a", "x")])).unwrap_err();
+        assert_eq!(error.position(), 6);
+        assert_eq!(
+            error.error_type().type_desc(),
+            ErrorType::MissingValue.type_desc()
+        );
+        let error = GlobTemplate::new("[{a}")
+            .unwrap()
+            .fill(values(&[("a", "b")]));
+        assert!(error.is_err());
+    }
+}
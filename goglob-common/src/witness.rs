@@ -0,0 +1,219 @@
+//! Names telling two patterns apart.
+
+use crate::{
+    automaton::{alphabet, is_exact, Automaton},
+    GlobPattern,
+};
+use alloc::{
+    collections::{BTreeSet, VecDeque},
+    string::String,
+    vec::Vec,
+};
+
+/// Name matched by exactly one of two patterns, as returned by
+/// [`GlobPattern::witness()`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Witness {
+    name: String,
+    matched_by_left: bool,
+}
+impl Witness {
+    /// The name matched by one pattern but not the other.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the name is matched by the pattern `witness()` was called on
+    /// (and not by `other`).
+    pub fn matched_by_left(&self) -> bool {
+        self.matched_by_left
+    }
+
+    /// Whether the name is matched by the `other` pattern passed to
+    /// `witness()` (and not by the one it was called on).
+    pub fn matched_by_right(&self) -> bool {
+        !self.matched_by_left
+    }
+}
+
+impl GlobPattern {
+    /// Find a name matched by exactly one of `self` and `other`, or `None` if
+    /// they match exactly the same names.
+    ///
+    /// The witness is one of the shortest such names, built from the
+    /// characters appearing in either pattern (and their neighbours), so for
+    /// example `*.rs` and `*.r?` are told apart by `.ra`. It's always checked
+    /// against both patterns, but with case-insensitive matching a difference
+    /// only showing up for characters whose case variants aren't mentioned in
+    /// either pattern may go unnoticed, as may differences involving patterns
+    /// [`contains()`][Self::contains] can't decide on.
+    pub fn witness(&self, other: &GlobPattern) -> Option<Witness> {
+        self.search(other, |left, right| left != right)
+            .map(|name| Witness {
+                matched_by_left: self.matches(&name),
+                name,
+            })
+    }
+
+    /// Whether every name matched by `other` is also matched by `self` (e.g.
+    /// `src/*` contains `src/*.rs`), i.e. there's no name only `other`
+    /// matches.
+    ///
+    /// This has the same caveat as [`witness()`][Self::witness] with
+    /// case-insensitive matching, in which case a pattern may be deemed to
+    /// contain another one when it doesn't. Also, as matching commits to the
+    /// earliest position where the part of the pattern following a `*`
+    /// matches if other wildcards follow it, patterns where that part
+    /// contains a class matching the separator (e.g. `*[^x]*`, which doesn't
+    /// match `ab/c`) are only deemed to contain and be contained by equal
+    /// patterns.
+    pub fn contains(&self, other: &GlobPattern) -> bool {
+        if self == other {
+            return true;
+        }
+        if !is_exact(self) || !is_exact(other) {
+            return false;
+        }
+        self.search(other, |left, right| !left && right).is_none()
+    }
+
+    /// Find one of the shortest names for which `wanted(self matches it,
+    /// other matches it)` holds.
+    fn search<F: Fn(bool, bool) -> bool>(&self, other: &GlobPattern, wanted: F) -> Option<String> {
+        let left = Automaton::new(self);
+        let right = Automaton::new(other);
+        let alphabet = alphabet(&[&left, &right]);
+
+        // Each state is the states of both automatons along with whether
+        // they're at the start of a segment (as their separators may differ).
+        let start = (left.start(), right.start(), true, true);
+        let mut seen = BTreeSet::new();
+        seen.insert(start.clone());
+        // Index of the state each visited one was reached from, along with
+        // the character leading to it.
+        let mut parents: Vec<Option<(usize, char)>> = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((start, None));
+        while let Some(((l, r, l_start, r_start), parent)) = queue.pop_front() {
+            let index = parents.len();
+            parents.push(parent);
+            if wanted(left.accepts(&l), right.accepts(&r)) {
+                let name = path(&parents, index);
+                if wanted(self.matches(&name), other.matches(&name)) {
+                    return Some(name);
+                }
+            }
+            for &c in &alphabet {
+                let next_l = left.step(&l, c, l_start && left.hides(c));
+                let next_r = right.step(&r, c, r_start && right.hides(c));
+                if next_l.is_empty() && next_r.is_empty() {
+                    continue;
+                }
+                let next = (next_l, next_r, c == left.separator, c == right.separator);
+                if seen.insert(next.clone()) {
+                    queue.push_back((next, Some((index, c))));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Rebuild the name leading to the `index`th visited state.
+fn path(parents: &[Option<(usize, char)>], mut index: usize) -> String {
+    let mut chars = Vec::new();
+    while let Some((parent, c)) = parents[index] {
+        chars.push(c);
+        index = parent;
+    }
+    chars.into_iter().rev().collect()
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use crate::{builder::GlobPatternBuilder, options::MatchOptions, syntax::Syntax, GlobPattern};
+
+    fn witness(left: &str, right: &str) -> Option<(String, bool)> {
+        let left = GlobPattern::new(left).unwrap();
+        let right = GlobPattern::new(right).unwrap();
+        left.witness(&right)
+            .map(|w| (w.name().into(), w.matched_by_left()))
+    }
+
+    #[test]
+    fn witness_equivalent() {
+        assert_eq!(witness("a*b", "a*b"), None);
+        assert_eq!(witness("a**?b", "a?*b"), None);
+        assert_eq!(witness("[ab]c", "[ba]c"), None);
+        assert_eq!(witness("[a]*", "a*"), None);
+    }
+
+    #[test]
+    fn witness_different() {
+        assert_eq!(witness("*.rs", "*.r?"), Some((".ra".into(), false)));
+        assert_eq!(witness("a", "b"), Some(("a".into(), true)));
+        assert_eq!(witness("*", "?*"), Some(("".into(), true)));
+        assert_eq!(witness("*", "*/*"), Some(("".into(), true)));
+        assert_eq!(witness("a*", "a*/*"), Some(("a".into(), true)));
+        assert_eq!(witness("[a-c]", "[a-d]"), Some(("d".into(), false)));
+        assert_eq!(witness("[^a]", "?"), Some(("a".into(), false)));
+    }
+
+    #[test]
+    fn witness_checked() {
+        for (left, right) in [
+            ("a*", "*a"),
+            ("x?y", "x[^.]y"),
+            ("[!-~]", "?"),
+            ("*", "*/?"),
+        ] {
+            let (left, right) = (
+                GlobPattern::new(left).unwrap(),
+                GlobPattern::new(right).unwrap(),
+            );
+            let witness = left.witness(&right).unwrap();
+            assert_eq!(left.matches(witness.name()), witness.matched_by_left());
+            assert_eq!(right.matches(witness.name()), witness.matched_by_right());
+        }
+    }
+
+    #[test]
+    fn contains() {
+        let contains = |left: &str, right: &str| {
+            GlobPattern::new(left)
+                .unwrap()
+                .contains(&GlobPattern::new(right).unwrap())
+        };
+        assert!(contains("src/*", "src/*.rs"));
+        assert!(!contains("src/*.rs", "src/*"));
+        assert!(contains("*", "?*"));
+        assert!(contains("[a-z]*", "[b-c]x"));
+        assert!(!contains("*", "*/*"));
+        assert!(contains("a*b", "a*b"));
+        assert!(contains("a?*", "a*?"));
+        // Matching "ab/c" commits "[^x]" to "a", leaving "b/c" to the last "*"
+        assert!(!contains("*[^x]*", "ab/c"));
+        // Only equal patterns are deemed contained in such patterns
+        assert!(!contains("*[^x]*", "a"));
+        assert!(contains("*[^x]*", "*[^x]*"));
+    }
+
+    #[test]
+    fn witness_options() {
+        let hidden = GlobPatternBuilder::new().options(MatchOptions::new().dotfiles(false));
+        let left = hidden.build("*").unwrap();
+        let right = GlobPattern::new("*").unwrap();
+        let witness = left.witness(&right).unwrap();
+        assert_eq!(witness.name(), ".");
+        assert!(witness.matched_by_right());
+
+        let gitignore = GlobPatternBuilder::new().syntax(Syntax::Gitignore);
+        let left = gitignore.build("**/a").unwrap();
+        let right = gitignore.build("*/a").unwrap();
+        let witness = left.witness(&right).unwrap();
+        assert_eq!(witness.name(), "a");
+        assert!(witness.matched_by_left());
+        let right = gitignore.build("a").unwrap();
+        assert_eq!(left.witness(&right).unwrap().name(), "/a");
+    }
+}
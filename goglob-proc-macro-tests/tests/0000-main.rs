@@ -3,4 +3,7 @@ fn test_goglob_proc_macro() {
     let t = trybuild::TestCases::new();
     t.pass("tests/01-goglob-gotest-pass.rs");
     t.compile_fail("tests/02-goglob-gotest-fail.rs");
+    t.pass("tests/03-goglob-options-pass.rs");
+    t.compile_fail("tests/04-goglob-options-fail.rs");
+    t.pass("tests/05-goglob-fn-pass.rs");
 }
@@ -1,4 +1,4 @@
-use goglob::{glob, GlobPattern};
+use goglob::{glob, glob_fn, GlobPattern};
 
 const PATTERN_01: GlobPattern = glob!("[]a]");
 const PATTERN_02: GlobPattern = glob!("[-]");
@@ -12,4 +12,9 @@ const PATTERN_09: GlobPattern = glob!("[^bc");
 const PATTERN_10: GlobPattern = glob!("a[");
 const PATTERN_11: GlobPattern = glob!("a/b[");
 
-fn main() {}
\ No newline at end of file
+fn main() {
+    // The invalid patterns above still expand to a `GlobPattern`
+    assert!(!PATTERN_01.matches("a"));
+    let is_match: fn(&str) -> bool = glob_fn!("a[");
+    assert!(!is_match("a"));
+}
@@ -0,0 +1,35 @@
+use goglob::{glob, GlobPattern};
+
+const PATTERN_01: GlobPattern = glob!("README.*", case_fold = ascii);
+const PATTERN_02: GlobPattern = glob!("ä*", case_fold = unicode,);
+const PATTERN_03: GlobPattern = glob!("**/*.rs", syntax = gitignore, dotfiles = false);
+const PATTERN_04: GlobPattern = glob!("a:*", separator = ':');
+const PATTERN_05: GlobPattern = glob!("a\\*", escapes = false);
+const PATTERN_06: GlobPattern = glob!("[!a]", syntax = glob7);
+const PATTERN_07: GlobPattern = glob!("*.rs", dotfiles = false, syntax = gitignore);
+const PATTERN_08: GlobPattern =
+    glob!("*.rs", syntax = gitignore, escapes = true, dotfiles = false);
+const PATTERN_09: GlobPattern = glob!(r"a\*", escapes = false);
+const PATTERN_10: GlobPattern = glob!(r#"say "*""#);
+
+fn main() {
+    assert!(PATTERN_01.matches("readme.md"));
+    assert!(PATTERN_02.matches("Äb"));
+    assert!(PATTERN_03.matches("src/bin/main.rs"));
+    assert!(!PATTERN_03.matches("src/.bin/main.rs"));
+    assert!(PATTERN_04.matches("a:b/c"));
+    assert!(!PATTERN_04.matches("a:b:c"));
+    assert!(PATTERN_05.matches("a\\b"));
+    assert!(PATTERN_06.matches("b"));
+    assert_eq!(
+        PATTERN_06,
+        GlobPattern::builder()
+            .syntax(goglob::Syntax::Glob7)
+            .build("[!a]")
+            .unwrap()
+    );
+    assert_eq!(PATTERN_07, PATTERN_08);
+    assert!(!PATTERN_07.matches(".hidden.rs"));
+    assert_eq!(PATTERN_09, PATTERN_05);
+    assert!(PATTERN_10.matches("say \"hi\""));
+}
@@ -0,0 +1,11 @@
+use goglob::{glob, GlobPattern};
+
+const PATTERN_01: GlobPattern = glob!("*", case_fold = upper);
+const PATTERN_02: GlobPattern = glob!("*", dotfiles = 0);
+const PATTERN_03: GlobPattern = glob!("*", separator = "/");
+const PATTERN_04: GlobPattern = glob!("*", hidden = true);
+const PATTERN_05: GlobPattern = glob!("*", escapes = true, escapes = false);
+const PATTERN_06: GlobPattern = glob!("*" syntax = glob7);
+const PATTERN_07: GlobPattern = glob!("a[b", syntax = gitignore);
+
+fn main() {}
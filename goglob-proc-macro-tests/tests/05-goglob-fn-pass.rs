@@ -0,0 +1,80 @@
+use goglob::{glob, glob_fn, GlobPattern};
+
+type Matcher = fn(&str) -> bool;
+
+const CASES: &[(GlobPattern, Matcher)] = &[
+    (glob!("README"), glob_fn!("README")),
+    (glob!("*"), glob_fn!("*")),
+    (glob!("*.rs"), glob_fn!("*.rs")),
+    (glob!("src/*"), glob_fn!("src/*")),
+    (glob!("a*b*c"), glob_fn!("a*b*c")),
+    (glob!("*a?[b-d]*"), glob_fn!("*a?[b-d]*")),
+    (glob!("[^a-c]*x"), glob_fn!("[^a-c]*x")),
+    (glob!("a**"), glob_fn!("a**")),
+    (
+        glob!("*/*", dotfiles = false),
+        glob_fn!("*/*", dotfiles = false),
+    ),
+    (
+        glob!("*.rs", dotfiles = false),
+        glob_fn!("*.rs", dotfiles = false),
+    ),
+    (
+        glob!("a:*", separator = ':'),
+        glob_fn!("a:*", separator = ':'),
+    ),
+    (
+        glob!("*.RS", case_fold = ascii),
+        glob_fn!("*.RS", case_fold = ascii),
+    ),
+    (
+        glob!("**/*.rs", syntax = gitignore),
+        glob_fn!("**/*.rs", syntax = gitignore),
+    ),
+];
+
+const NAMES: &[&str] = &[
+    "",
+    "README",
+    "main.rs",
+    ".rs",
+    "src/main.rs",
+    "src/.git",
+    "src",
+    "abc",
+    "abbc",
+    "aXbc",
+    "axbxc",
+    "a",
+    "aa",
+    "za/b",
+    "x",
+    "dx",
+    "ax",
+    ".a/b",
+    "a/.b",
+    "a:b",
+    "a:b/c",
+    "a:b:c",
+    "LIB.RS",
+    "é.rs",
+    "src/bin/main.rs",
+];
+
+fn main() {
+    for (pattern, matches) in CASES {
+        for name in NAMES {
+            assert_eq!(
+                matches(name),
+                pattern.matches(name),
+                "{:?} {:?}",
+                pattern,
+                name
+            );
+        }
+    }
+    assert!(CASES[2].1("main.rs"));
+    assert!(!CASES[8].1(".a/b"));
+    assert!(!CASES[9].1(".rs"));
+    assert!(CASES[9].1("main.rs"));
+}
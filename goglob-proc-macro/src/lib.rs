@@ -13,32 +13,25 @@
 extern crate proc_macro;
 
 mod parse;
+mod scan;
 mod stream;
 
-use goglob_common::{
-    error::Error as GlobTokenError, literal::Literal as GlobTokenLiteral, scan_patterns, GlobToken,
-};
+use goglob_common::{literal::Literal as GlobTokenLiteral, GlobToken};
+use parse::MacroOption;
 use proc_macro::TokenStream as RawTokenStream;
-use proc_macro2::{Span, TokenStream};
-use quote::quote_spanned;
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use scan::{glob_tokens_from, Error};
 
 pub(crate) mod internal {
+    #[allow(unused_imports)]
     pub use goglob_common::*;
 }
 
-pub(crate) enum Error {
-    GlobTokenError(Span, GlobTokenError),
-    ParseError(parse::ParseError),
-}
-impl From<parse::ParseError> for Error {
-    fn from(pe: parse::ParseError) -> Self {
-        Self::ParseError(pe)
-    }
-}
-
 /// Compile the given `pattern` into tokens at code-compile time, emitting a
 /// `GlobPattern` on success or a compile-error if `pattern` is syntactically
-/// invalid.
+/// invalid. Along with the compile-error, it expands to the empty pattern so
+/// that the code using it still type-checks (and IDEs keep completing it).
 ///
 /// This is useful in contexts when the pattern is a known constant and can thus
 /// be declared as such:
@@ -50,30 +43,75 @@ impl From<parse::ParseError> for Error {
 /// That way, there is no runtime penalty when compiling the pattern for the first
 /// time as it will be pre-compiled into the resulting binary.
 ///
+/// Options may follow the pattern, each named after the corresponding
+/// `MatchOptions` setter:
+///
+/// ```no_compile
+/// const MY_PATTERN: GlobPattern = glob!("**/*.rs", syntax = gitignore, case_fold = ascii);
+/// ```
+///
+/// * `syntax = go_path_match | go_filepath_match | glob7 | gitignore`
+/// * `case_fold = sensitive | ascii | unicode`
+/// * `escapes = true | false`
+/// * `separator = '<CHAR>'`
+/// * `dotfiles = true | false`
+///
 /// # Further reading
 ///
 /// See the `goglob` crate's documentation for the appropriate syntax, as well
 /// as [goglob::error::Error] for possible syntax errors.
 #[proc_macro]
 pub fn glob(lit: RawTokenStream) -> RawTokenStream {
+    expand(lit, stream::glob_tokens_into_stream)
+}
+
+/// Same as [glob!()][glob], but emitting a `fn(&str) -> bool` specialized to
+/// the pattern instead of a `GlobPattern`, for hot patterns known at
+/// code-compile time:
+///
+/// ```no_compile
+/// const IS_RUST_FILE: fn(&str) -> bool = glob_fn!("*.rs");
+/// assert!(IS_RUST_FILE("main.rs"));
+/// ```
+///
+/// The function reports the same as `GlobPattern::matches()` would, comparing
+/// literals and classes inline and looping over the positions each `*` may
+/// end at rather than interpreting tokens at runtime. Patterns with case
+/// folding or `**/` are matched through their `GlobPattern` instead. Unlike
+/// `GlobPattern::matches()`, the function doesn't report to the metrics sink.
+///
+/// It takes the same options as [glob!()][glob].
+#[proc_macro]
+pub fn glob_fn(lit: RawTokenStream) -> RawTokenStream {
+    expand(lit, stream::glob_tokens_into_fn_stream)
+}
+
+fn expand(
+    lit: RawTokenStream,
+    into_stream: fn(Vec<GlobToken>, &str, &[MacroOption]) -> TokenStream,
+) -> RawTokenStream {
     let mut glob_tokens = Vec::new();
-    let result_tokens = if let Err(e) = glob_tokens_from(lit.into(), &mut glob_tokens) {
-        match e {
-            Error::GlobTokenError(span, gte) => {
-                let gte = format!("pattern malformed: {}", gte);
-                quote_spanned!(span => compile_error!(#gte))
-            }
-            Error::ParseError(parse::ParseError(span, msg)) => quote_spanned!(
-                span => compile_error!(#msg)
-            ),
+    let result_tokens = match glob_tokens_from(lit.into(), &mut glob_tokens) {
+        Err(e) => {
+            let compile_error = match e {
+                Error::GlobTokenError(span, gte) => {
+                    let gte = format!("pattern malformed: {}", gte);
+                    quote_spanned!(span => compile_error!(#gte);)
+                }
+                Error::ParseError(parse::ParseError(span, msg)) => quote_spanned!(
+                    span => compile_error!(#msg);
+                ),
+            };
+            // Still expand to a value of the right type (that of the empty
+            // pattern), so that the surrounding code keeps type-checking and
+            // IDEs keep completing it while the pattern is being fixed.
+            let placeholder = into_stream(Vec::new(), "", &[]);
+            quote!({
+                #compile_error
+                #placeholder
+            })
         }
-    } else {
-        stream::glob_tokens_into_stream(glob_tokens)
+        Ok((pattern, options)) => into_stream(glob_tokens, &pattern, &options),
     };
     result_tokens.into()
 }
-
-fn glob_tokens_from(lit: TokenStream, glob_tokens: &mut Vec<GlobToken>) -> Result<(), Error> {
-    let (pattern, span) = parse::parse_input(lit)?;
-    scan_patterns(&*pattern, glob_tokens).map_err(|gte| Error::GlobTokenError(span, gte))
-}
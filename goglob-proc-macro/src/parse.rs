@@ -1,4 +1,8 @@
-use proc_macro2::{Delimiter, Literal, Span, TokenStream, TokenTree};
+use goglob_common::{
+    options::{CaseFold, MatchOptions},
+    syntax::Syntax,
+};
+use proc_macro2::{Delimiter, Ident, Literal, Span, TokenStream, TokenTree};
 use std::char;
 
 macro_rules! unexpected_content {
@@ -9,7 +13,42 @@ macro_rules! unexpected_content {
 
 pub(crate) struct ParseError(pub(crate) Span, pub(crate) &'static str);
 
-pub(crate) fn parse_input(mut input: TokenStream) -> Result<(String, Span), ParseError> {
+/// Option given after the pattern (e.g. `glob!("*.rs", case_fold = ascii)`).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum MacroOption {
+    Syntax(Syntax),
+    CaseFold(CaseFold),
+    Escapes(bool),
+    Separator(char),
+    Dotfiles(bool),
+}
+impl MacroOption {
+    /// Set the option in `options`.
+    pub(crate) fn apply(self, options: MatchOptions) -> MatchOptions {
+        match self {
+            MacroOption::Syntax(syntax) => options.syntax(syntax),
+            MacroOption::CaseFold(case_fold) => options.case_fold(case_fold),
+            MacroOption::Escapes(escapes) => options.escapes(escapes),
+            MacroOption::Separator(separator) => options.separator(separator),
+            MacroOption::Dotfiles(dotfiles) => options.dotfiles(dotfiles),
+        }
+    }
+
+    /// Position of the option in the canonical order, that of the variants.
+    pub(crate) fn rank(self) -> usize {
+        match self {
+            MacroOption::Syntax(_) => 0,
+            MacroOption::CaseFold(_) => 1,
+            MacroOption::Escapes(_) => 2,
+            MacroOption::Separator(_) => 3,
+            MacroOption::Dotfiles(_) => 4,
+        }
+    }
+}
+
+pub(crate) fn parse_input(
+    mut input: TokenStream,
+) -> Result<(String, Span, Vec<MacroOption>), ParseError> {
     loop {
         let mut tokens = input.into_iter();
         let token = match tokens.next() {
@@ -21,30 +60,137 @@ pub(crate) fn parse_input(mut input: TokenStream) -> Result<(String, Span), Pars
                 ))
             }
         };
-        let span = token.span();
-        let result = match token {
+        match token {
             // Unwrap any empty group which may be created from macro expansion.
-            TokenTree::Group(group) if group.delimiter() == Delimiter::None => Err(group),
-            TokenTree::Literal(literal) => match parse_literal(literal) {
-                Ok(result) => Ok(result),
-                Err(msg) => return Err(ParseError(span, msg)),
-            },
-            _ => return Err(ParseError(span, unexpected_content!())),
-        };
-        if let Some(token) = tokens.next() {
-            return Err(ParseError(token.span(), "unexpected token"));
-        }
-        match result {
-            Ok(result) => return Ok((result, span)),
-            Err(group) =>
+            TokenTree::Group(group)
+                if group.delimiter() == Delimiter::None && tokens.clone().next().is_none() =>
             // input is wrapped in a group, unwrap and continue
             {
                 input = group.stream()
             }
+            token => {
+                let (pattern, span) = parse_pattern(token)?;
+                let options = parse_options(tokens)?;
+                return Ok((pattern, span, options));
+            }
         }
     }
 }
 
+fn parse_pattern(token: TokenTree) -> Result<(String, Span), ParseError> {
+    let span = token.span();
+    match token {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            let mut tokens = group.stream().into_iter();
+            match (tokens.next(), tokens.next()) {
+                (Some(token), None) => parse_pattern(token),
+                (_, Some(token)) => Err(ParseError(token.span(), "unexpected token")),
+                (None, None) => Err(ParseError(span, unexpected_content!())),
+            }
+        }
+        TokenTree::Literal(literal) => match parse_literal(literal) {
+            Ok(result) => Ok((result, span)),
+            Err(msg) => Err(ParseError(span, msg)),
+        },
+        _ => Err(ParseError(span, unexpected_content!())),
+    }
+}
+
+/// Parses the `, name = value` pairs following the pattern, which may end
+/// with a trailing comma.
+fn parse_options<I: Iterator<Item = TokenTree>>(
+    mut tokens: I,
+) -> Result<Vec<MacroOption>, ParseError> {
+    let mut options = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {}
+            token => return Err(ParseError(token.span(), "unexpected token")),
+        }
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(name)) => name,
+            Some(token) => return Err(ParseError(token.span(), "expected option name")),
+            None => break,
+        };
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {}
+            Some(token) => return Err(ParseError(token.span(), "expected '='")),
+            None => return Err(ParseError(name.span(), "missing option value")),
+        }
+        let value = tokens
+            .next()
+            .ok_or(ParseError(name.span(), "missing option value"))?;
+        let name_str = name.to_string();
+        if names.contains(&name_str) {
+            return Err(ParseError(name.span(), "duplicate option"));
+        }
+        options.push(parse_option(&name, &value)?);
+        names.push(name_str);
+    }
+    Ok(options)
+}
+
+fn parse_option(name: &Ident, value: &TokenTree) -> Result<MacroOption, ParseError> {
+    parse_option_value(&name.to_string(), value).map_err(|msg| match msg {
+        UNKNOWN_OPTION => ParseError(name.span(), msg),
+        msg => ParseError(value.span(), msg),
+    })
+}
+
+const UNKNOWN_OPTION: &str =
+    "unknown option, expected `syntax`, `case_fold`, `escapes`, `separator` or `dotfiles`";
+
+fn parse_option_value(name: &str, value: &TokenTree) -> Result<MacroOption, &'static str> {
+    let ident = match value {
+        TokenTree::Ident(ident) => Some(ident.to_string()),
+        _ => None,
+    };
+    let bool_value = || match ident.as_deref() {
+        Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        _ => Err("expected `true` or `false`"),
+    };
+    Ok(match name {
+        "syntax" => MacroOption::Syntax(match ident.as_deref() {
+            Some("go_path_match") => Syntax::GoPathMatch,
+            Some("go_filepath_match") => Syntax::GoFilepathMatch,
+            Some("glob7") => Syntax::Glob7,
+            Some("gitignore") => Syntax::Gitignore,
+            _ => {
+                return Err("expected `go_path_match`, `go_filepath_match`, `glob7` or `gitignore`")
+            }
+        }),
+        "case_fold" => MacroOption::CaseFold(match ident.as_deref() {
+            Some("sensitive") => CaseFold::Sensitive,
+            Some("ascii") => CaseFold::Ascii,
+            Some("unicode") => CaseFold::Unicode,
+            _ => return Err("expected `sensitive`, `ascii` or `unicode`"),
+        }),
+        "escapes" => MacroOption::Escapes(bool_value()?),
+        "dotfiles" => MacroOption::Dotfiles(bool_value()?),
+        "separator" => match value {
+            TokenTree::Literal(literal) => MacroOption::Separator(parse_char_literal(literal)?),
+            _ => return Err("expected character literal"),
+        },
+        _ => return Err(UNKNOWN_OPTION),
+    })
+}
+
+fn parse_char_literal(literal: &Literal) -> Result<char, &'static str> {
+    const EXPECTED: &str = "expected character literal";
+    let s = literal.to_string();
+    let content = s
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .ok_or(EXPECTED)?;
+    let mut chars = unescape(content)?.into_iter();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(EXPECTED),
+    }
+}
+
 fn parse_literal(literal: Literal) -> Result<String, &'static str> {
     let s = literal.to_string();
     let mut s_iter = s.char_indices();
@@ -69,13 +215,8 @@ fn parse_raw_content(s: &str) -> Result<String, &'static str> {
     let q_end = s
         .rfind('"')
         .ok_or(concat!("missing '\"' after 'r', ", unexpected_content!()))?;
-    let end_pounds = {
-        let e = &s[q_end..];
-        let mut eci = e.char_indices();
-        eci.next().ok_or("expected string literal")?;
-        let (i, _) = e.char_indices().next().ok_or("expected string literal")?;
-        &e[i..]
-    };
+    // Everything after the closing '"'.
+    let end_pounds = &s[q_end + 1..];
     let start = {
         let mut result: Option<usize> = None;
         for (i, _) in s.char_indices() {
@@ -87,18 +228,22 @@ fn parse_raw_content(s: &str) -> Result<String, &'static str> {
         result.ok_or(unexpected_content!())?
     };
     (all_pounds(&s[..q_start]) && all_pounds(end_pounds))
-        .then(|| (&s[start..q_end]).to_string())
+        .then(|| s[start..q_end].to_string())
         .ok_or(unexpected_content!())
 }
 
 /// Parses the cooked string / bytes content within quotes.
-fn parse_cooked_content(mut s: &str) -> Result<String, &'static str> {
+fn parse_cooked_content(s: &str) -> Result<String, &'static str> {
     let end = s
         .rfind('"')
         .ok_or(concat!("missing '\"' after 'r', ", unexpected_content!()))?;
-    s = &s[..end];
+    unescape(&s[..end]).map(String::from_iter)
+}
+
+/// Resolves the escapes of string or character literal content.
+fn unescape(s: &str) -> Result<Vec<char>, &'static str> {
     let mut s_iter = s.chars().peekable();
-    let mut result = String::new();
+    let mut result = Vec::new();
     while let Some(c) = s_iter.next() {
         if c != '\\' {
             result.push(c);
@@ -0,0 +1,38 @@
+//! Scanning of the macros' input into tokens.
+//!
+//! This module (along with [`parse`][crate::parse]) is also built into the
+//! `macro_parity` fuzz target, as a proc-macro crate can only export macros,
+//! so it must only depend on `proc_macro2` and not on `proc_macro`.
+
+use crate::parse::{self, MacroOption};
+use goglob_common::{
+    error::Error as GlobTokenError, options::MatchOptions, scan_patterns_with, GlobToken,
+};
+use proc_macro2::{Span, TokenStream};
+
+pub(crate) enum Error {
+    GlobTokenError(Span, GlobTokenError),
+    ParseError(parse::ParseError),
+}
+impl From<parse::ParseError> for Error {
+    fn from(pe: parse::ParseError) -> Self {
+        Self::ParseError(pe)
+    }
+}
+
+/// Parse the input of a macro, scanning its pattern into `glob_tokens` and
+/// returning the pattern along with the options given with it.
+pub(crate) fn glob_tokens_from(
+    lit: TokenStream,
+    glob_tokens: &mut Vec<GlobToken>,
+) -> Result<(String, Vec<MacroOption>), Error> {
+    let (pattern, span, options) = parse::parse_input(lit)?;
+    let match_options = options
+        .iter()
+        .fold(MatchOptions::new(), |match_options, option| {
+            option.apply(match_options)
+        });
+    scan_patterns_with(&pattern, &match_options, glob_tokens)
+        .map_err(|gte| Error::GlobTokenError(span, gte))?;
+    Ok((pattern, options))
+}
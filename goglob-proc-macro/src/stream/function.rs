@@ -0,0 +1,199 @@
+use crate::parse::MacroOption;
+use goglob_common::{
+    charcls::{CharClass as GlobTokenCharClass, CharClassType},
+    effective_separator,
+    options::{CaseFold, MatchOptions},
+    GlobToken,
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::ops::RangeInclusive;
+
+/// Expand to a `fn(&str) -> bool` reporting whether a name matches `pattern`,
+/// compiled into `glob_tokens` with `options`, as `GlobPattern::matches()`
+/// would.
+///
+/// Case-sensitive patterns without `**/` get straight-line code: each chunk of
+/// tokens after a `*` becomes a function comparing them in turn, and each `*`
+/// a loop over the positions the following chunk may start at, committing to
+/// the first one that matches just like the token loop. Any other pattern is
+/// matched through its `GlobPattern`.
+pub(crate) fn glob_tokens_into_fn_stream(
+    glob_tokens: Vec<GlobToken>,
+    pattern: &str,
+    options: &[MacroOption],
+) -> TokenStream {
+    let match_options = options
+        .iter()
+        .fold(MatchOptions::new(), |match_options, option| {
+            option.apply(match_options)
+        });
+    if match_options.case_fold(CaseFold::Sensitive) != match_options
+        || glob_tokens.contains(&GlobToken::AnyDirs)
+    {
+        let pattern = super::glob_tokens_into_stream(glob_tokens, pattern, options);
+        return quote! {{
+            #[inline]
+            fn matches(name: &str) -> bool {
+                const PATTERN: ::goglob::internal::GlobPattern = #pattern;
+                PATTERN.matches(name)
+            }
+            matches as fn(&str) -> bool
+        }};
+    }
+
+    let separator = effective_separator(&match_options);
+    // Whether the text at `next` would start a hidden name.
+    let hidden = if match_options.dotfiles(true) == match_options {
+        quote!(false)
+    } else {
+        quote!(
+            next.starts_with('.')
+                && (next.len() == name.len() || name[..name.len() - next.len()].ends_with(#separator))
+        )
+    };
+
+    let chunks: Vec<_> = glob_tokens
+        .split(|token| *token == GlobToken::SeqWildcard)
+        .collect();
+    let mut chunk_fns = quote!();
+    let mut body = quote!();
+    let mut ends_with_star = false;
+    for (i, chunk) in chunks.iter().enumerate() {
+        let last = i + 1 == chunks.len();
+        if chunk.is_empty() && (i == 0 || last) {
+            // Leading or trailing '*'
+            if last && i > 0 {
+                body = quote! {
+                    #body
+                    if #hidden {
+                        return false;
+                    }
+                    !next.contains(#separator)
+                };
+                ends_with_star = true;
+            } else {
+                body = quote!(let next = name;);
+            }
+            continue;
+        }
+
+        let chunk_fn = format_ident!("chunk_{}", i);
+        let stmts = chunk
+            .iter()
+            .map(|token| glob_token_into_stmt(token, separator));
+        chunk_fns = quote! {
+            #chunk_fns
+            #[inline(always)]
+            fn #chunk_fn(s: &str) -> Option<&str> {
+                #(#stmts)*
+                Some(s)
+            }
+        };
+        if i == 0 {
+            body = quote! {
+                let next = match #chunk_fn(name) {
+                    Some(next) => next,
+                    None => return false,
+                };
+            };
+            continue;
+        }
+
+        // The text matched by the last chunk must end the name.
+        let commit = if last {
+            quote!(rest.is_empty())
+        } else {
+            quote!(true)
+        };
+        // A `*` can't start a hidden name, not even matching nothing.
+        body = quote! {
+            #body
+            if #hidden {
+                return false;
+            }
+            let next = {
+                let mut remaining = next;
+                loop {
+                    let mut chars = remaining.chars();
+                    let c = match chars.next() {
+                        Some(c) => c,
+                        None => return false,
+                    };
+                    if let Some(rest) = #chunk_fn(remaining) {
+                        if #commit {
+                            break rest;
+                        }
+                    }
+                    if c == #separator {
+                        return false;
+                    }
+                    remaining = chars.as_str();
+                }
+            };
+        };
+    }
+    if !ends_with_star {
+        body = quote!(#body next.is_empty());
+    }
+
+    quote! {{
+        #[inline]
+        fn matches(name: &str) -> bool {
+            #chunk_fns
+            #body
+        }
+        matches as fn(&str) -> bool
+    }}
+}
+
+/// Statement matching `token` at the start of `s`, shadowing it with the rest
+/// or returning `None`.
+fn glob_token_into_stmt(token: &GlobToken, separator: char) -> TokenStream {
+    let condition = match token {
+        GlobToken::Literal(l) => {
+            let l = l.as_ref();
+            return quote!(let s = s.strip_prefix(#l)?;);
+        }
+        GlobToken::CharClass(cc) => char_class_into_condition(cc),
+        GlobToken::SingleWildcard => quote!(c != #separator),
+        GlobToken::SeqWildcard | GlobToken::AnyDirs => {
+            unreachable!("wildcards separate chunks")
+        }
+    };
+    quote! {
+        let mut chars = s.chars();
+        match chars.next() {
+            Some(c) if #condition => {}
+            _ => return None,
+        }
+        let s = chars.as_str();
+    }
+}
+
+fn char_class_into_condition(cc: &GlobTokenCharClass) -> TokenStream {
+    let negated = cc.is_negated();
+    if cc.types().is_empty() {
+        return quote!(#negated);
+    }
+    let alternatives = cc
+        .types()
+        .iter()
+        .map(|char_class_type| match char_class_type {
+            CharClassType::Single(_) => {
+                let c = *char_class_type.as_range().start();
+                quote!(#c)
+            }
+            CharClassType::Range(_) => {
+                let range: RangeInclusive<char> = char_class_type.as_range();
+                let (start, end) = (*range.start(), *range.end());
+                quote!(#start..=#end)
+            }
+        });
+    let condition = quote!(matches!(c, #(#alternatives)|*));
+    if negated {
+        quote!(!#condition)
+    } else {
+        condition
+    }
+}
@@ -1,11 +1,29 @@
 mod charcls;
+mod function;
 mod literal;
+mod options;
 
-use goglob_common::GlobToken;
+use crate::parse::MacroOption;
+use goglob_common::{options::MatchOptions, GlobToken};
 use proc_macro2::TokenStream;
 use quote::quote;
 
-pub(crate) fn glob_tokens_into_stream(glob_tokens: Vec<GlobToken>) -> TokenStream {
+pub(crate) use function::glob_tokens_into_fn_stream;
+
+/// Expand to the `GlobPattern` compiled from `pattern` into `glob_tokens` with
+/// `options`.
+///
+/// Every invocation of the same pattern compiling to the same tokens and
+/// options expands to the same code, however the options were written, so
+/// their token tables and pattern text are identical `const` data which LLVM
+/// and the linker can merge (e.g. with LTO or `--icf=all`). A `static`
+/// couldn't be, as its address is significant.
+pub(crate) fn glob_tokens_into_stream(
+    glob_tokens: Vec<GlobToken>,
+    pattern: &str,
+    options: &[MacroOption],
+) -> TokenStream {
+    let options = canonical_options(options);
     let mut inner_result = quote!();
     for glob_token in glob_tokens {
         let new_append = match glob_token {
@@ -13,6 +31,7 @@ pub(crate) fn glob_tokens_into_stream(glob_tokens: Vec<GlobToken>) -> TokenStrea
             GlobToken::CharClass(cc) => charcls::glob_token_char_class_into_stream(cc),
             GlobToken::SeqWildcard => glob_token_seq_wildcard_into_stream(),
             GlobToken::SingleWildcard => glob_token_single_wildcard_into_stream(),
+            GlobToken::AnyDirs => glob_token_any_dirs_into_stream(),
         };
         inner_result = quote![
             #inner_result
@@ -20,15 +39,35 @@ pub(crate) fn glob_tokens_into_stream(glob_tokens: Vec<GlobToken>) -> TokenStrea
         ]
     }
 
+    let from_tokens = if options.is_empty() {
+        quote!(::goglob::internal::glob_from_tokens(RESULTING_TOKENS, #pattern))
+    } else {
+        let options = options::match_options_into_stream(&options);
+        quote!(::goglob::internal::glob_from_tokens_with(RESULTING_TOKENS, #pattern, #options))
+    };
+
     let result = quote! {{
         const RESULTING_TOKENS: &'static [::goglob::internal::GlobToken] = &[
             #inner_result
         ];
-        ::goglob::internal::glob_from_tokens(RESULTING_TOKENS)
+        #from_tokens
     }};
     result
 }
 
+/// `options` without those set to their default value, in the canonical
+/// order (e.g. `case_fold = ascii, syntax = go_path_match` becomes
+/// `case_fold = ascii`).
+fn canonical_options(options: &[MacroOption]) -> Vec<MacroOption> {
+    let mut options: Vec<_> = options
+        .iter()
+        .copied()
+        .filter(|option| option.apply(MatchOptions::new()) != MatchOptions::new())
+        .collect();
+    options.sort_by_key(|option| option.rank());
+    options
+}
+
 pub(crate) fn glob_token_seq_wildcard_into_stream() -> TokenStream {
     quote!(::goglob::internal::GlobToken::SeqWildcard)
 }
@@ -36,3 +75,7 @@ pub(crate) fn glob_token_seq_wildcard_into_stream() -> TokenStream {
 pub(crate) fn glob_token_single_wildcard_into_stream() -> TokenStream {
     quote!(::goglob::internal::GlobToken::SingleWildcard)
 }
+
+pub(crate) fn glob_token_any_dirs_into_stream() -> TokenStream {
+    quote!(::goglob::internal::GlobToken::AnyDirs)
+}
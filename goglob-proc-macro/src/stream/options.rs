@@ -0,0 +1,42 @@
+use crate::parse::MacroOption;
+use goglob_common::{options::CaseFold, syntax::Syntax};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn match_options_into_stream(options: &[MacroOption]) -> TokenStream {
+    let mut result = quote!(::goglob::internal::options::MatchOptions::new());
+    for option in options {
+        let setter = match *option {
+            MacroOption::Syntax(syntax) => {
+                let syntax = syntax_into_stream(syntax);
+                quote!(syntax(#syntax))
+            }
+            MacroOption::CaseFold(case_fold) => {
+                let case_fold = case_fold_into_stream(case_fold);
+                quote!(case_fold(#case_fold))
+            }
+            MacroOption::Escapes(escapes) => quote!(escapes(#escapes)),
+            MacroOption::Separator(separator) => quote!(separator(#separator)),
+            MacroOption::Dotfiles(dotfiles) => quote!(dotfiles(#dotfiles)),
+        };
+        result = quote!(#result.#setter);
+    }
+    result
+}
+
+fn syntax_into_stream(syntax: Syntax) -> TokenStream {
+    match syntax {
+        Syntax::GoPathMatch => quote!(::goglob::internal::syntax::Syntax::GoPathMatch),
+        Syntax::GoFilepathMatch => quote!(::goglob::internal::syntax::Syntax::GoFilepathMatch),
+        Syntax::Glob7 => quote!(::goglob::internal::syntax::Syntax::Glob7),
+        Syntax::Gitignore => quote!(::goglob::internal::syntax::Syntax::Gitignore),
+    }
+}
+
+fn case_fold_into_stream(case_fold: CaseFold) -> TokenStream {
+    match case_fold {
+        CaseFold::Sensitive => quote!(::goglob::internal::options::CaseFold::Sensitive),
+        CaseFold::Ascii => quote!(::goglob::internal::options::CaseFold::Ascii),
+        CaseFold::Unicode => quote!(::goglob::internal::options::CaseFold::Unicode),
+    }
+}
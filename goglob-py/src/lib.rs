@@ -0,0 +1,97 @@
+//! Python bindings for `goglob`.
+//!
+//! Exposes `GlobPattern`, `GlobSet` and the `GlobError` exception as the
+//! `goglob` Python module, with the very same semantics as the Rust crate.
+
+use goglob::{error::Error, GlobPattern as RsGlobPattern, GlobSet as RsGlobSet};
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*};
+
+create_exception!(
+    goglob,
+    GlobError,
+    PyValueError,
+    "Raised when a pattern is syntactically invalid."
+);
+
+fn to_py_err(py: Python<'_>, e: Error) -> PyErr {
+    let err = GlobError::new_err(e.to_string());
+    let position = (e.position() != usize::MAX).then_some(e.position());
+    if let Err(attr_err) = err.value(py).setattr("position", position) {
+        return attr_err;
+    }
+    err
+}
+
+/// Shell pattern matching similar to golang's `path.Match`.
+#[pyclass(name = "GlobPattern", module = "goglob", frozen)]
+struct GlobPattern {
+    inner: RsGlobPattern,
+}
+#[pymethods]
+impl GlobPattern {
+    #[new]
+    fn new(py: Python<'_>, pattern: String) -> PyResult<Self> {
+        let inner = RsGlobPattern::new(&pattern).map_err(|e| to_py_err(py, e))?;
+        Ok(Self { inner })
+    }
+
+    /// Report whether the `name` matches the compiled shell pattern.
+    fn matches(&self, name: &str) -> bool {
+        self.inner.matches(name)
+    }
+
+    #[getter]
+    fn pattern(&self) -> &str {
+        self.inner.as_str()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("GlobPattern({:?})", self.inner.as_str())
+    }
+}
+
+/// Collection of shell patterns matched together against a single name.
+#[pyclass(name = "GlobSet", module = "goglob", frozen)]
+struct GlobSet {
+    inner: RsGlobSet,
+}
+#[pymethods]
+impl GlobSet {
+    #[new]
+    fn new(py: Python<'_>, patterns: Vec<String>) -> PyResult<Self> {
+        let inner = RsGlobSet::compile(&patterns).map_err(|e| to_py_err(py, e))?;
+        Ok(Self { inner })
+    }
+
+    /// Report whether the `name` matches any pattern in the set.
+    fn matches(&self, name: &str) -> bool {
+        self.inner.matches(name)
+    }
+
+    /// Report the indices of every pattern matching `name`, in ascending order.
+    fn matching(&self, name: &str) -> Vec<usize> {
+        self.inner.matching(name)
+    }
+
+    #[getter]
+    fn patterns(&self) -> Vec<&str> {
+        self.inner.iter().map(RsGlobPattern::as_str).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("GlobSet({:?})", self.patterns())
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "goglob")]
+fn goglob_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<GlobPattern>()?;
+    m.add_class::<GlobSet>()?;
+    m.add("GlobError", m.py().get_type::<GlobError>())?;
+    Ok(())
+}
@@ -0,0 +1,183 @@
+//! Differential testing against golang's own `path.Match`, run through a small
+//! Go program with the `go` toolchain (which must be in `PATH`, or given by the
+//! `GOGLOB_GO` environment variable).
+
+use crate::ConformanceMatcher;
+use std::{
+    env,
+    fmt::{self, Display, Formatter},
+    fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+/// Source of the Go program answering match queries.
+const REFERENCE_SOURCE: &str = include_str!("go/main.go");
+
+/// Characters random patterns are made of, weighted towards the special ones.
+const PATTERN_CHARS: &[char] = &[
+    'a', 'b', 'c', '/', '*', '*', '?', '[', ']', '^', '-', '\\', '.', 'é',
+];
+/// Characters random names are made of.
+const NAME_CHARS: &[char] = &['a', 'b', 'c', '/', '.', '-', '^', '\\', 'é'];
+
+/// Running instance of golang's `path.Match`.
+pub struct GoReference {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: ChildStdout,
+    dir: PathBuf,
+}
+impl GoReference {
+    /// Build and start the reference program.
+    pub fn spawn() -> io::Result<Self> {
+        let dir = env::temp_dir().join(format!("goglob-go-reference-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("main.go"), REFERENCE_SOURCE)?;
+
+        let go = env::var_os("GOGLOB_GO").unwrap_or_else(|| "go".into());
+        let mut child = Command::new(go)
+            .args(["run", "main.go"])
+            .current_dir(&dir)
+            .env("GO111MODULE", "off")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+            dir,
+        })
+    }
+
+    /// Whether `name` matches `pattern` according to `path.Match`, or `None`
+    /// if `pattern` is malformed.
+    pub fn matches(&mut self, pattern: &str, name: &str) -> io::Result<Option<bool>> {
+        if pattern.contains('\0') || name.contains('\0') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "NUL characters can't be sent to the reference",
+            ));
+        }
+        write!(self.stdin, "{pattern}\0{name}\0")?;
+        self.stdin.flush()?;
+        let mut answer = [0];
+        self.stdout.read_exact(&mut answer)?;
+        match answer[0] {
+            b't' => Ok(Some(true)),
+            b'f' => Ok(Some(false)),
+            b'e' => Ok(None),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unexpected answer from the reference",
+            )),
+        }
+    }
+}
+impl Drop for GoReference {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Random input whose result differs between a matcher and `path.Match`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Divergence {
+    pub pattern: String,
+    pub name: String,
+    pub expected: Option<bool>,
+    pub actual: Option<bool>,
+}
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({:?}, {:?}) expected {:?}, got {:?}",
+            self.pattern, self.name, self.expected, self.actual
+        )
+    }
+}
+
+/// Generate `count` random pattern and name pairs from `seed`, made of the
+/// characters most likely to expose differences.
+pub fn random_inputs(seed: u64, count: usize) -> Vec<(String, String)> {
+    // xorshift64*, whose state must not be zero
+    let mut state = seed | 1;
+    let mut next = move |bound: usize| {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        (state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 33) as usize % bound
+    };
+    (0..count)
+        .map(|_| {
+            let pattern_len = 1 + next(8);
+            let pattern = (0..pattern_len)
+                .map(|_| PATTERN_CHARS[next(PATTERN_CHARS.len())])
+                .collect();
+            let name_len = next(8);
+            let name = (0..name_len)
+                .map(|_| NAME_CHARS[next(NAME_CHARS.len())])
+                .collect();
+            (pattern, name)
+        })
+        .collect()
+}
+
+/// Compare `M` with `path.Match` on `count` random inputs generated from
+/// `seed`, returning the ones they disagree on.
+pub fn check_against_go<M: ConformanceMatcher>(
+    seed: u64,
+    count: usize,
+) -> io::Result<Vec<Divergence>> {
+    let mut reference = GoReference::spawn()?;
+    let mut divergences = Vec::new();
+    for (pattern, name) in random_inputs(seed, count) {
+        let expected = reference.matches(&pattern, &name)?;
+        let actual = M::compile(&pattern)
+            .ok()
+            .map(|matcher| matcher.matches(&name));
+        if actual != expected {
+            divergences.push(Divergence {
+                pattern,
+                name,
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(divergences)
+}
+
+/// Same as [`check_against_go()`], panicking with every divergence (or if the
+/// reference can't be run).
+pub fn assert_matches_go<M: ConformanceMatcher>(seed: u64, count: usize) {
+    let divergences = check_against_go::<M>(seed, count).expect("failed to run path.Match");
+    if !divergences.is_empty() {
+        let divergences: Vec<String> = divergences.iter().map(ToString::to_string).collect();
+        panic!(
+            "matcher diverges from path.Match (seed {seed}):\n{}",
+            divergences.join("\n")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::go::random_inputs;
+
+    #[test]
+    fn random_inputs_are_reproducible() {
+        let inputs = random_inputs(42, 100);
+        assert_eq!(inputs, random_inputs(42, 100));
+        assert_ne!(inputs, random_inputs(43, 100));
+        assert!(inputs.iter().all(|(pattern, _)| !pattern.is_empty()));
+        assert!(inputs.iter().any(|(pattern, _)| pattern.contains('*')));
+    }
+}
@@ -0,0 +1,35 @@
+// Reads NUL-terminated pattern and name pairs from stdin, and answers each
+// with 't' if the name matches, 'f' if it doesn't or 'e' if the pattern is
+// malformed, according to path.Match.
+package main
+
+import (
+	"bufio"
+	"os"
+	"path"
+)
+
+func main() {
+	in := bufio.NewReader(os.Stdin)
+	out := bufio.NewWriter(os.Stdout)
+	for {
+		pattern, err := in.ReadString(0)
+		if err != nil {
+			return
+		}
+		name, err := in.ReadString(0)
+		if err != nil {
+			return
+		}
+		matched, err := path.Match(pattern[:len(pattern)-1], name[:len(name)-1])
+		switch {
+		case err != nil:
+			out.WriteByte('e')
+		case matched:
+			out.WriteByte('t')
+		default:
+			out.WriteByte('f')
+		}
+		out.Flush()
+	}
+}
@@ -0,0 +1,203 @@
+//! Conformance suite checking shell pattern matchers against golang's
+//! `path.Match`.
+//!
+//! The vectors are the ones from Go's own `path.Match` tests, and can either be
+//! checked against any type implementing [`ConformanceMatcher`] or exported as
+//! JSON (see [`vectors_json()`]) for ports to other languages.
+//!
+//! With the `go-reference` feature, matchers can also be compared with Go's
+//! `path.Match` itself on random inputs (see [`go`]).
+
+#[cfg(feature = "go-reference")]
+pub mod go;
+
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+
+/// Single conformance test: whether `name` matches `pattern`, or `None` if
+/// `pattern` is syntactically invalid (regardless of `name`).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+pub struct MatchVector {
+    pub pattern: &'static str,
+    pub name: &'static str,
+    pub expected: Option<bool>,
+}
+
+const fn vector(pattern: &'static str, name: &'static str, expected: Option<bool>) -> MatchVector {
+    MatchVector {
+        pattern,
+        name,
+        expected,
+    }
+}
+
+/// Vectors from the test table of Go 1.18's `path.Match`.
+pub const GO_MATCH_VECTORS: &[MatchVector] = &[
+    vector("abc", "abc", Some(true)),
+    vector("*", "abc", Some(true)),
+    vector("*c", "abc", Some(true)),
+    vector("a*", "a", Some(true)),
+    vector("a*", "abc", Some(true)),
+    vector("a*", "ab/c", Some(false)),
+    vector("a*/b", "abc/b", Some(true)),
+    vector("a*/b", "a/c/b", Some(false)),
+    vector("a*b*c*d*e*/f", "axbxcxdxe/f", Some(true)),
+    vector("a*b*c*d*e*/f", "axbxcxdxexxx/f", Some(true)),
+    vector("a*b*c*d*e*/f", "axbxcxdxe/xxx/f", Some(false)),
+    vector("a*b*c*d*e*/f", "axbxcxdxexxx/fff", Some(false)),
+    vector("a*b?c*x", "abxbbxdbxebxczzx", Some(true)),
+    vector("a*b?c*x", "abxbbxdbxebxczzy", Some(false)),
+    vector("ab[c]", "abc", Some(true)),
+    vector("ab[b-d]", "abc", Some(true)),
+    vector("ab[e-g]", "abc", Some(false)),
+    vector("ab[^c]", "abc", Some(false)),
+    vector("ab[^b-d]", "abc", Some(false)),
+    vector("ab[^e-g]", "abc", Some(true)),
+    vector("a\\*b", "a*b", Some(true)),
+    vector("a\\*b", "ab", Some(false)),
+    vector("a?b", "a☺b", Some(true)),
+    vector("a[^a]b", "a☺b", Some(true)),
+    vector("a???b", "a☺b", Some(false)),
+    vector("a[^a][^a][^a]b", "a☺b", Some(false)),
+    vector("[a-ζ]*", "α", Some(true)),
+    vector("*[a-ζ]", "A", Some(false)),
+    vector("a?b", "a/b", Some(false)),
+    vector("a*b", "a/b", Some(false)),
+    vector("[\\]a]", "]", Some(true)),
+    vector("[\\-]", "-", Some(true)),
+    vector("[x\\-]", "x", Some(true)),
+    vector("[x\\-]", "-", Some(true)),
+    vector("[x\\-]", "z", Some(false)),
+    vector("[\\-x]", "x", Some(true)),
+    vector("[\\-x]", "-", Some(true)),
+    vector("[\\-x]", "a", Some(false)),
+    vector("[]a]", "]", None),
+    vector("[-]", "-", None),
+    vector("[x-]", "x", None),
+    vector("[x-]", "-", None),
+    vector("[x-]", "z", None),
+    vector("[-x]", "x", None),
+    vector("[-x]", "-", None),
+    vector("[-x]", "a", None),
+    vector("\\", "a", None),
+    vector("[a-b-c]", "a", None),
+    vector("[", "a", None),
+    vector("[^", "a", None),
+    vector("[^bc", "a", None),
+    vector("a[", "a", None),
+    vector("a[", "ab", None),
+    vector("a[", "x", None),
+    vector("a/b[", "x", None),
+    vector("*x", "xxx", Some(true)),
+];
+
+/// Pattern matcher which can be checked for conformance with golang's
+/// `path.Match`.
+pub trait ConformanceMatcher: Sized {
+    type Error;
+
+    /// Compile `pattern`, failing if it's syntactically invalid.
+    fn compile(pattern: &str) -> Result<Self, Self::Error>;
+
+    /// Report whether `name` matches the compiled pattern.
+    fn matches(&self, name: &str) -> bool;
+}
+
+/// Vector a matcher didn't conform to, along with its actual result.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ConformanceFailure {
+    pub index: usize,
+    pub vector: MatchVector,
+    pub actual: Option<bool>,
+}
+impl Display for ConformanceFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[Test {}]: ({}, {}) expected {:?}, got {:?}",
+            self.index, self.vector.pattern, self.vector.name, self.vector.expected, self.actual
+        )
+    }
+}
+
+/// Check `M` against every vector in [`GO_MATCH_VECTORS`], returning the ones
+/// it doesn't conform to.
+pub fn check_conformance<M: ConformanceMatcher>() -> Vec<ConformanceFailure> {
+    GO_MATCH_VECTORS
+        .iter()
+        .enumerate()
+        .filter_map(|(index, vector)| {
+            let actual = M::compile(vector.pattern)
+                .ok()
+                .map(|matcher| matcher.matches(vector.name));
+            (actual != vector.expected).then_some(ConformanceFailure {
+                index,
+                vector: *vector,
+                actual,
+            })
+        })
+        .collect()
+}
+
+/// Same as [`check_conformance()`], panicking with every failure if `M`
+/// doesn't conform.
+pub fn assert_conformance<M: ConformanceMatcher>() {
+    let failures = check_conformance::<M>();
+    if !failures.is_empty() {
+        let failures: Vec<String> = failures.iter().map(ToString::to_string).collect();
+        panic!(
+            "matcher doesn't conform to path.Match:\n{}",
+            failures.join("\n")
+        );
+    }
+}
+
+/// Export [`GO_MATCH_VECTORS`] as a JSON array of
+/// `{"pattern": ..., "name": ..., "expected": true | false | null}` objects,
+/// where `null` means the pattern is syntactically invalid.
+pub fn vectors_json() -> String {
+    serde_json::to_string_pretty(GO_MATCH_VECTORS).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{check_conformance, vectors_json, ConformanceMatcher, GO_MATCH_VECTORS};
+
+    /// Matcher which only supports literal patterns.
+    struct LiteralMatcher(String);
+    impl ConformanceMatcher for LiteralMatcher {
+        type Error = ();
+
+        fn compile(pattern: &str) -> Result<Self, ()> {
+            Ok(Self(pattern.to_string()))
+        }
+
+        fn matches(&self, name: &str) -> bool {
+            self.0 == name
+        }
+    }
+
+    #[test]
+    fn conformance_failures() {
+        let failures = check_conformance::<LiteralMatcher>();
+        assert!(!failures.is_empty());
+        assert!(failures.iter().all(|f| f.actual != f.vector.expected));
+        // "abc" matching "abc" is the first vector.
+        assert_ne!(failures[0].index, 0);
+        assert!(failures[0]
+            .to_string()
+            .starts_with(&format!("[Test {}]", failures[0].index)));
+    }
+
+    #[test]
+    fn conformance_vectors_json() {
+        let json: serde_json::Value = serde_json::from_str(&vectors_json()).unwrap();
+        let vectors = json.as_array().unwrap();
+        assert_eq!(vectors.len(), GO_MATCH_VECTORS.len());
+        assert_eq!(
+            vectors[0],
+            serde_json::json!({"pattern": "abc", "name": "abc", "expected": true})
+        );
+        assert!(vectors.iter().any(|v| v["expected"].is_null()));
+    }
+}
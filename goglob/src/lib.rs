@@ -21,12 +21,86 @@
 //!
 //! Match requires pattern to match all of name, not just a substring.
 //!
-//! Use [`GlobPattern::new(pattern)`][GlobPattern::new] to construct a new instance.
+//! Use [`GlobPattern::new(pattern)`][GlobPattern::new] to construct a new instance,
+//! or [`GlobPattern::builder()`][GlobPattern::builder] to parse it as another
+//! [Syntax] (such as `.gitignore` patterns) or with other [MatchOptions].
+//!
+//! Names can also be matched against many patterns at once with [GlobSet],
+//! [RuleSet] and [GlobMap], and files found with [fs]. [FixedGlobPattern]
+//! works without a heap allocator, and [PatternMatcher] is implemented by
+//! every kind of pattern.
 //!
 //! # Features
+//! * `std` (enabled by default): enables the [`std::error::Error`]
+//!   implementation of [error::Error]. Without it, `goglob` is `no_std`.
+//! * `alloc` (enabled by `std`): enables [GlobPattern] and everything built
+//!   on it. Without it, only the fixed-capacity [FixedGlobPattern] is
+//!   available, which never allocates.
+//! * `arbitrary`: implements `Arbitrary` for [GlobPattern] (generating valid
+//!   patterns) and allows generating names matching a pattern for fuzzing
+//!   (see [arbitrary]).
+//! * `arc-swap`: adds [SharedGlobSet], a [GlobSet] handle which can be
+//!   atomically replaced while other threads keep matching against it
+//!   lock-free, for reloading rules in long-running services.
+//! * `bstr`: adds [`GlobPattern::captures_bytes()`] returning bstr's `BStr`s,
+//!   along with [`GlobPattern::filter_byte_str_lines()`] and
+//!   `GlobPattern::filter_byte_lines()` for filtering bstr's byte lines.
+//! * `camino`: adds [`GlobPattern::matches_utf8_path()`] along with
+//!   [`fs::glob_utf8()`] and [`fs::glob_in_utf8()`], which return camino's
+//!   `Utf8PathBuf`s.
+//! * `clap`: allows parsing command-line arguments directly as a [GlobPattern]
+//!   with clap's `value_parser!(GlobPattern)`, reporting syntax errors with a
+//!   caret pointing at the offending character (see [clap]).
+//! * `defmt`: implements `defmt::Format` for [FixedGlobPattern], `GlobPattern`
+//!   (rendered back into pattern syntax), [error::Error] and
+//!   [error::ErrorType], for logging from firmware.
+//! * `diagnostics` (enabled by default): adds [`error::Error::render()`] and
+//!   [`error::Error::render_with()`], showing the offending pattern with a
+//!   caret under the error's position.
+//! * `encoding`: adds `GlobPattern::matches_bytes_with_encoding()` for names
+//!   in legacy encodings such as Windows-1252 or Shift_JIS (given as an
+//!   `encoding_rs::Encoding`), decoded on the fly without allocating.
+//! * `fs` (enabled by default): enables [fs], walking directories to find the
+//!   paths matching a pattern.
+//! * `idna`: adds [`HostPatternBuilder::idna()`], converting hostnames and
+//!   patterns to their ASCII (punycode) form so that `*.bücher.example` and
+//!   `*.xn--bcher-kva.example` match the same names.
+//! * `json`: adds `GlobMap::parse_json_rules()`, loading rule files in which
+//!   each line is a JSON object with a `pattern` field and the fields of its
+//!   action.
+//! * `metrics`: allows registering a [GlobMetricsSink][metrics::GlobMetricsSink]
+//!   counting compilations and matches (see [metrics]).
+//! * `parser` (enabled by default): allows compiling patterns from strings at
+//!   runtime. Without it, patterns can only be embedded pre-compiled (with
+//!   [`GlobPattern::from_tokens()`] or the `glob!()` macro), for firmware and
+//!   WASM deployments which never parse patterns. Features reading patterns
+//!   from strings (such as `serde`, `json` or `fs`) enable it.
+//! * `prefilter` (enabled by default): checks names against the length and
+//!   literal prefix and suffix of each pattern of a [GlobSet] (or of the
+//!   slices given to [any_match()] and [all_match()]) before matching them,
+//!   skipping most patterns of large sets.
 //! * `proc-macro`: allows using the `glob!("<PATTERN>")` procedural macro (see
-//!   [glob!()][glob]).
-//! * `serde`: enables serde deserialization of string patterns.
+//!   [glob!()][glob]), optionally followed by options such as
+//!   `glob!("*.rs", case_fold = ascii)`, and its [glob_fn!()][glob_fn] counterpart
+//!   emitting a matching function specialized to the pattern.
+//! * `proptest`: provides proptest strategies generating valid patterns,
+//!   pattern strings and names matching a pattern (see [proptest]).
+//! * `rayon`: adds `GlobSet::par_compile()` and `GlobSet::par_compile_with()`,
+//!   compiling large sets of patterns in parallel.
+//! * `serde`: enables serde deserialization of string patterns and of
+//!   [GlobMap]s from maps keyed by pattern strings (in the order they're
+//!   written), of [GlobSet]s from either a pattern string or a sequence of
+//!   them, as well as serialization and deserialization of [MatchOptions]
+//!   (e.g. from a config file).
+//! * `wasm`: exports `compile(pattern)` and `matches(handle, name)` to JavaScript
+//!   through `wasm-bindgen` (see [wasm]).
+//! * `unicode` (enabled by default): matches letters regardless of case
+//!   according to Unicode's case mappings with [`CaseFold::Unicode`]. Without
+//!   it, the mapping tables aren't linked and only ASCII letters match
+//!   regardless of case.
+//!
+//! Disabling the default features leaves only the core matcher (plus `alloc`
+//! for [GlobPattern]), which adds about 15 KB to a release binary.
 //!
 //! # License
 //! `BSD-3-Clause`.
@@ -41,14 +115,101 @@
 //! // license that can be found in the LICENSE file.
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "arbitrary")]
+pub use goglob_common::arbitrary;
+#[cfg(feature = "clap")]
+pub use goglob_common::clap;
+#[cfg(feature = "alloc")]
+pub use goglob_common::complexity;
+#[cfg(feature = "alloc")]
+pub use goglob_common::convert;
 pub use goglob_common::error;
+#[cfg(feature = "fs")]
+pub use goglob_common::fs;
+pub use goglob_common::lexer;
+#[cfg(feature = "alloc")]
+pub use goglob_common::limits;
+#[cfg(feature = "metrics")]
+pub use goglob_common::metrics;
+pub use goglob_common::outcome;
+#[cfg(feature = "proptest")]
+pub use goglob_common::proptest;
 pub use goglob_common::Result;
 
+#[cfg(feature = "alloc")]
+pub use goglob_common::arc::ArcGlobPattern;
+#[cfg(feature = "parser")]
+pub use goglob_common::builder::GlobPatternBuilder;
+#[cfg(feature = "parser")]
+pub use goglob_common::bytes::BytesGlobPattern;
+#[cfg(feature = "alloc")]
+pub use goglob_common::complete::PartialMatch;
+#[cfg(feature = "alloc")]
+pub use goglob_common::coverage::Coverage;
+#[cfg(feature = "alloc")]
+pub use goglob_common::dump::Dump;
+#[cfg(feature = "alloc")]
+pub use goglob_common::ext::GlobMatched;
+pub use goglob_common::fixed::FixedGlobPattern;
+#[cfg(feature = "parser")]
+pub use goglob_common::host::{HostPattern, HostPatternBuilder};
+#[cfg(all(feature = "std", feature = "parser"))]
+pub use goglob_common::ignore::IgnoreTree;
+#[cfg(feature = "parser")]
+pub use goglob_common::interner::{GlobHandle, GlobInterner};
+#[cfg(all(feature = "std", feature = "parser"))]
+pub use goglob_common::lazy::LazyGlob;
+#[cfg(feature = "std")]
+pub use goglob_common::lines::FilterLines;
+#[cfg(feature = "alloc")]
+pub use goglob_common::map::GlobMap;
+pub use goglob_common::matcher::PatternMatcher;
+#[cfg(feature = "alloc")]
+pub use goglob_common::minimize::Minimized;
+pub use goglob_common::options::{CaseFold, MatchOptions};
+#[cfg(feature = "alloc")]
+pub use goglob_common::partition::Partition;
+#[cfg(feature = "alloc")]
+pub use goglob_common::rename::GlobRenamer;
+#[cfg(feature = "parser")]
+pub use goglob_common::rulefile::{RuleFileError, RuleFileErrorKind};
+#[cfg(feature = "alloc")]
+pub use goglob_common::rules::{Rule, RuleSet, Shadowed};
+#[cfg(feature = "alloc")]
+pub use goglob_common::set::{GlobSet, OptionsMismatch};
+#[cfg(feature = "arc-swap")]
+pub use goglob_common::shared::SharedGlobSet;
+#[cfg(feature = "alloc")]
+pub use goglob_common::slice::{all_match, any_match};
+#[cfg(feature = "alloc")]
+pub use goglob_common::specificity::Specificity;
+#[cfg(feature = "alloc")]
+pub use goglob_common::stream::StreamMatcher;
+pub use goglob_common::syntax::Syntax;
+#[cfg(feature = "parser")]
+pub use goglob_common::template::GlobTemplate;
+#[cfg(feature = "alloc")]
+pub use goglob_common::witness::Witness;
+#[cfg(feature = "alloc")]
 pub use goglob_common::GlobPattern;
 
+/// Compiled pattern tokens, as returned by [`GlobPattern::tokens()`] (or
+/// [`GlobPattern::into_tokens()`], taking ownership of them).
+#[cfg(feature = "alloc")]
+pub mod token {
+    pub use goglob_common::charcls::{CharClass, CharClassType};
+    pub use goglob_common::literal::Literal;
+    pub use goglob_common::GlobToken;
+}
+
 #[cfg(feature = "proc-macro")]
 pub use goglob_proc_macro::*;
 
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 #[cfg(feature = "proc-macro")]
 #[doc(hidden)]
 pub mod internal {
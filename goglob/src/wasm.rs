@@ -0,0 +1,123 @@
+//! WebAssembly bindings exported through `wasm-bindgen`.
+//!
+//! These allow web frontends to validate and preview patterns with exactly the
+//! same rules as the Rust side:
+//!
+//! ```js
+//! import { compile, matches } from "goglob";
+//!
+//! try {
+//!     const handle = compile("a*/b");
+//!     matches(handle, "abc/b"); // true
+//! } catch (e) {
+//!     console.log(e.kind, e.position, e.message);
+//! }
+//! ```
+
+use crate::{error::ErrorType, GlobPattern};
+use wasm_bindgen::prelude::*;
+
+/// Compiled pattern handle returned by [`compile()`].
+#[wasm_bindgen]
+pub struct GlobHandle {
+    pattern: GlobPattern,
+}
+#[wasm_bindgen]
+impl GlobHandle {
+    /// Report whether the `name` matches the compiled shell pattern.
+    pub fn matches(&self, name: &str) -> bool {
+        self.pattern.matches(name)
+    }
+}
+
+/// Structured syntax error thrown by [`compile()`].
+#[wasm_bindgen]
+pub struct GlobError {
+    kind: &'static str,
+    message: String,
+    position: Option<usize>,
+}
+#[wasm_bindgen]
+impl GlobError {
+    /// Machine-readable error kind (e.g. `"UnclosedCharClass"`).
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        self.kind.to_string()
+    }
+
+    /// Human-readable error description.
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// Byte offset of the error in the pattern, or `undefined` if the error
+    /// isn't tied to any particular position (e.g. an empty pattern).
+    #[wasm_bindgen(getter)]
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+}
+impl From<crate::error::Error> for GlobError {
+    fn from(e: crate::error::Error) -> Self {
+        let kind = match e.error_type() {
+            ErrorType::CapacityExceeded => "CapacityExceeded",
+            ErrorType::ComplexityExceeded => "ComplexityExceeded",
+            ErrorType::ConflictingMetachar(_) => "ConflictingMetachar",
+            ErrorType::EmptyPattern => "EmptyPattern",
+            ErrorType::IllegalEscape => "IllegalEscape",
+            ErrorType::InvalidHostLabel => "InvalidHostLabel",
+            ErrorType::InvalidPlaceholder => "InvalidPlaceholder",
+            ErrorType::InvalidRangeValues(_, _) => "InvalidRangeValues",
+            ErrorType::MisplacedWildcard => "MisplacedWildcard",
+            ErrorType::MissingValue => "MissingValue",
+            ErrorType::PatternTooLong => "PatternTooLong",
+            ErrorType::TooManyClassEntries => "TooManyClassEntries",
+            ErrorType::TooManyTokens => "TooManyTokens",
+            ErrorType::UnclosedCharClass => "UnclosedCharClass",
+            ErrorType::UnclosedPlaceholder => "UnclosedPlaceholder",
+            ErrorType::UnescapedChar(_) => "UnescapedChar",
+        };
+        let position = (e.position() != usize::MAX).then_some(e.position());
+        Self {
+            kind,
+            message: e.to_string(),
+            position,
+        }
+    }
+}
+
+/// Compile the given `pattern`, returning a [`GlobHandle`] on success or
+/// throwing a [`GlobError`] if `pattern` is syntactically invalid.
+#[wasm_bindgen]
+pub fn compile(pattern: &str) -> Result<GlobHandle, GlobError> {
+    GlobPattern::new(pattern)
+        .map(|pattern| GlobHandle { pattern })
+        .map_err(GlobError::from)
+}
+
+/// Report whether the `name` matches the pattern compiled into `handle`.
+#[wasm_bindgen]
+pub fn matches(handle: &GlobHandle, name: &str) -> bool {
+    handle.matches(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wasm::{compile, matches};
+
+    #[test]
+    fn wasm_compile_and_match() {
+        let handle = compile("a*/b").ok().unwrap();
+        assert!(matches(&handle, "abc/b"));
+        assert!(!matches(&handle, "a/c/b"));
+
+        let error = compile("a[").err().unwrap();
+        assert_eq!(error.kind(), "UnclosedCharClass");
+        assert_eq!(error.position(), Some(1));
+
+        let error = compile("").err().unwrap();
+        assert_eq!(error.kind(), "EmptyPattern");
+        assert_eq!(error.position(), None);
+    }
+}